@@ -0,0 +1,51 @@
+//! Measures the throughput of uniform gain application.
+//!
+//! Compare the scalar and vectorized paths by running
+//! `cargo bench --bench gain` with and without `--features simd`. The
+//! `scalar_loop` baseline is only measured with `simd` enabled, since
+//! `process_in_place` runs the same scalar loop otherwise.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use sonex::process::{AudioNode, GainNode};
+use std::hint::black_box;
+
+fn gain(c: &mut Criterion) {
+    // One second of stereo audio at 48 kHz
+    let input: Vec<f32> = (0..48000 * 2)
+        .map(|i| (i as f32 * 0.001).sin())
+        .collect();
+    let node = GainNode::new(-3.0);
+
+    // Every iteration gets a fresh copy of the input; repeated attenuation would
+    // otherwise decay the buffer into subnormals and zeros
+    let mut group = c.benchmark_group("gain");
+    group.throughput(Throughput::Elements(input.len() as u64));
+    #[cfg(feature = "simd")]
+    group.bench_function("scalar_loop", |b| {
+        let linear_gain = 10.0_f32.powf(-3.0 / 20.0);
+        b.iter_batched(
+            || input.clone(),
+            |mut buffer| {
+                for sample in black_box(&mut buffer).iter_mut() {
+                    *sample *= linear_gain;
+                }
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("process_in_place", |b| {
+        b.iter_batched(
+            || input.clone(),
+            |mut buffer| {
+                node.process_in_place(black_box(&mut buffer));
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, gain);
+criterion_main!(benches);
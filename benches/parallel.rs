@@ -0,0 +1,44 @@
+//! Compares serial and rayon-parallel processing of stateless nodes.
+//!
+//! Run with `cargo bench --features rayon --bench parallel`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use sonex::process::{AudioNode, GainNode};
+use std::hint::black_box;
+
+fn gain(c: &mut Criterion) {
+    // 10 minutes of stereo audio at 48 kHz
+    let input: Vec<f32> = (0..48000 * 60 * 10 * 2)
+        .map(|i| (i as f32 * 0.001).sin())
+        .collect();
+    let node = GainNode::new(-3.0);
+
+    // Every iteration gets a fresh copy of the input; repeated attenuation would
+    // otherwise decay the buffer into subnormals and zeros
+    let mut group = c.benchmark_group("gain");
+    group.sample_size(20);
+    group.bench_function("serial", |b| {
+        b.iter_batched(
+            || input.clone(),
+            |mut buffer| {
+                node.process_in_place(black_box(&mut buffer));
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("parallel", |b| {
+        b.iter_batched(
+            || input.clone(),
+            |mut buffer| {
+                node.process_parallel(black_box(&mut buffer));
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, gain);
+criterion_main!(benches);
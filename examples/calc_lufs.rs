@@ -11,21 +11,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Sample rate: {} Hz", reader.sample_rate());
     println!("Channels: {}", reader.channels());
 
-    let mut all_samples = Vec::new();
+    let mut meter = Meter::new(reader.channels() as u32, reader.sample_rate())?;
+    let mut total_samples = 0;
     while let Ok(Some(samples)) = reader.read_packet() {
-        all_samples.extend(samples);
+        total_samples += samples.len();
+        meter.add_frames_f32(&samples)?;
     }
 
-    println!("\nTotal samples read: {}", all_samples.len());
-
-    let meter = Meter::new(&all_samples, reader.channels() as u32, reader.sample_rate());
+    println!("\nTotal samples read: {}", total_samples);
 
     let integrated_lufs: f64 = meter.lufs_integrated().unwrap();
     let short_term_lufs: f64 = meter.lufs_shortterm().unwrap();
+    let momentary_lufs: f64 = meter.lufs_momentary().unwrap();
     let true_peaks: Vec<f64> = meter.true_peaks().unwrap();
 
     println!("Integrated LUFS: {:?}", integrated_lufs);
     println!("Short-term LUFS: {:?}", short_term_lufs);
+    println!("Momentary LUFS: {:?}", momentary_lufs);
     println!("True peaks: {:?}", true_peaks); // This is a vec per channel
     Ok(())
 }
@@ -9,7 +9,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let sample_rate = reader.sample_rate();
     let channels = reader.channels();
-    let samples_per_window = (3.0 * sample_rate as f32) as usize;
     
     println!("Audio file info:");
     println!("Sample rate: {} Hz", sample_rate);
@@ -20,18 +19,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         all_samples.extend(samples);
     }
     
-    let mut lufs_values = Vec::new();
-    let mut time_points = Vec::new();
-    
-    for (i, chunk) in all_samples.chunks(samples_per_window).enumerate() {
-        let meter = Meter::new(chunk, channels as u32, sample_rate);
-        if let Some(lufs) = meter.lufs_shortterm() {
-            if lufs.is_finite() {  // LUFS can be -inf if the last window is too short
-                lufs_values.push(lufs);
-                time_points.push(i as f64 * 3.0);
-            }
-        }
-    }
+    // Short-term loudness of the sliding 3 s window, read every 0.5 s
+    let meter = Meter::from_samples(&all_samples, channels as u32, sample_rate)?;
+    let (time_points, lufs_values): (Vec<f64>, Vec<f64>) = meter
+        .shortterm_series(0.5)
+        .into_iter()
+        .filter(|(_, lufs)| lufs.is_finite())  // LUFS is -inf for silence
+        .unzip();
     println!("\nLUFS values:");
     for (time, lufs) in time_points.iter().zip(lufs_values.iter()) {
         println!("Time: {:.1}s, LUFS: {:.1}", time, lufs);
@@ -0,0 +1,135 @@
+//! Clipping detection.
+//!
+//! Audio that was driven past full scale during recording or mixing shows up as runs
+//! of samples stuck at the maximum value. A single full-scale sample is often a
+//! legitimate peak, so only runs of several consecutive samples are reported.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::detect_clipping;
+//!
+//! let samples = vec![0.5f32, -0.5, 1.0, -0.5, 1.0, 0.2, 1.0, 0.1];
+//! for event in detect_clipping(&samples, 2, 3) {
+//!     println!("channel {} clipped at frame {} for {} samples",
+//!         event.channel, event.start_frame, event.length);
+//! }
+//! ```
+
+/// Absolute sample value at or above which a sample counts as clipped.
+pub const CLIP_THRESHOLD: f32 = 0.999;
+
+/// A run of consecutive clipped samples in one channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipEvent {
+    /// Frame index of the first clipped sample
+    pub start_frame: usize,
+    /// Number of consecutive clipped samples
+    pub length: usize,
+    /// Channel the run occurred in
+    pub channel: usize,
+}
+
+/// Finds runs of clipped samples in interleaved audio.
+///
+/// A sample is clipped when its absolute value is at or above [`CLIP_THRESHOLD`].
+/// Each channel is scanned on its own.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+/// * `consecutive_threshold` - Minimum number of consecutive clipped samples for a run
+///   to be reported (e.g. 3)
+///
+/// # Returns
+///
+/// A vector of clip events ordered by start frame, events starting on the same frame
+/// are ordered by channel.
+pub fn detect_clipping(samples: &[f32], channels: usize, consecutive_threshold: usize) -> Vec<ClipEvent> {
+    let channels = channels.max(1);
+    let min_length = consecutive_threshold.max(1);
+    let mut events = Vec::new();
+
+    for channel in 0..channels {
+        let mut start = None;
+        let frames = samples.iter().skip(channel).step_by(channels);
+        // A trailing sentinel closes a run that reaches the end of the buffer
+        for (frame, &sample) in frames.chain(std::iter::once(&0.0)).enumerate() {
+            match (start, sample.abs() >= CLIP_THRESHOLD) {
+                (None, true) => start = Some(frame),
+                (Some(first), false) => {
+                    if frame - first >= min_length {
+                        events.push(ClipEvent { start_frame: first, length: frame - first, channel });
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events.sort_by_key(|event| (event.start_frame, event.channel));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn clipped() -> Vec<f32> {
+        // Stereo sine at 0.5 with clipped regions pasted in
+        let mut samples: Vec<f32> = (0..1000)
+            .flat_map(|i| {
+                let s = 0.5 * (i as f32 * 0.05).sin();
+                [s, s]
+            })
+            .collect();
+        // Left: 5 samples stuck at +1.0 from frame 100
+        (100..105).for_each(|f| samples[f * 2] = 1.0);
+        // Right: 10 samples stuck at -1.0 from frame 400
+        (400..410).for_each(|f| samples[f * 2 + 1] = -1.0);
+        // Both: 3 samples at 0.999 from frame 700
+        (1400..1406).for_each(|i| samples[i] = 0.999);
+        // Left: single full-scale peak at frame 900
+        samples[1800] = 1.0;
+        // Right: run reaching the end of the buffer
+        (997..1000).for_each(|f| samples[f * 2 + 1] = 1.2);
+        samples
+    }
+
+    #[rstest]
+    fn test_detects_clipped_regions(clipped: Vec<f32>) {
+        let events = detect_clipping(&clipped, 2, 3);
+        assert_eq!(
+            events,
+            vec![
+                ClipEvent { start_frame: 100, length: 5, channel: 0 },
+                ClipEvent { start_frame: 400, length: 10, channel: 1 },
+                ClipEvent { start_frame: 700, length: 3, channel: 0 },
+                ClipEvent { start_frame: 700, length: 3, channel: 1 },
+                ClipEvent { start_frame: 997, length: 3, channel: 1 },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(1, 6)]
+    #[case(4, 2)]
+    #[case(6, 1)]
+    #[case(11, 0)]
+    fn test_consecutive_threshold(clipped: Vec<f32>, #[case] threshold: usize, #[case] expected: usize) {
+        let events = detect_clipping(&clipped, 2, threshold);
+        assert_eq!(events.len(), expected);
+        assert!(events.iter().all(|event| event.length >= threshold));
+    }
+
+    #[rstest]
+    fn test_clean_audio() {
+        let samples: Vec<f32> = (0..1000).map(|i| 0.99 * (i as f32 * 0.05).sin()).collect();
+        assert!(detect_clipping(&samples, 1, 1).is_empty());
+        assert!(detect_clipping(&[], 2, 3).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Platform loudness compliance checks.
+//!
+//! Streaming and podcast platforms publish loudness targets and true peak ceilings
+//! for uploaded audio. Audio that is too loud gets turned down by the platform,
+//! audio that is too quiet may be turned up (and limited) or simply play back
+//! quieter than everything else. This module compares a measurement with the
+//! published specification of a platform.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::{check_compliance, Meter, Platform};
+//!
+//! let samples = vec![0.0f32; 48000 * 2];
+//! let meter = Meter::from_samples(&samples, 2, 48000).unwrap();
+//! let lufs = meter.lufs_integrated().unwrap();
+//! let peak = meter.true_peaks().unwrap().into_iter().fold(0.0, f64::max);
+//!
+//! let report = check_compliance(lufs, 20.0 * peak.log10(), Platform::Spotify);
+//! if !report.passes() {
+//!     println!("Adjust the gain by {:+.1} dB", report.gain_adjustment_db);
+//! }
+//! ```
+
+/// Deviation from the target loudness in LU that still counts as compliant.
+pub const LOUDNESS_TOLERANCE_LU: f64 = 1.0;
+
+/// A distribution platform with a published loudness specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// Spotify, -14 LUFS and -1 dBTP
+    Spotify,
+    /// Apple Podcasts, -16 LUFS and -1 dBTP
+    ApplePodcasts,
+    /// YouTube, -14 LUFS and -1 dBTP
+    YouTube,
+    /// Amazon Music, -14 LUFS and -2 dBTP
+    AmazonMusic,
+    /// EBU R128 broadcast, -23 LUFS and -1 dBTP
+    EbuR128,
+    /// ATSC A/85 US broadcast, -24 LUFS and -2 dBTP
+    AtscA85,
+}
+
+impl Platform {
+    /// Returns the target integrated loudness in LUFS.
+    pub fn target_lufs(&self) -> f64 {
+        match self {
+            Platform::Spotify | Platform::YouTube | Platform::AmazonMusic => -14.0,
+            Platform::ApplePodcasts => -16.0,
+            Platform::EbuR128 => -23.0,
+            Platform::AtscA85 => -24.0,
+        }
+    }
+
+    /// Returns the maximum allowed true peak in dBTP.
+    pub fn max_true_peak_dbtp(&self) -> f64 {
+        match self {
+            Platform::Spotify | Platform::ApplePodcasts | Platform::YouTube | Platform::EbuR128 => -1.0,
+            Platform::AmazonMusic | Platform::AtscA85 => -2.0,
+        }
+    }
+}
+
+/// Result of checking a loudness measurement against a platform specification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceReport {
+    /// The platform checked against
+    pub platform: Platform,
+    /// Whether the integrated loudness is within [`LOUDNESS_TOLERANCE_LU`] of the target
+    pub loudness_pass: bool,
+    /// Whether the true peak is at or below the platform ceiling
+    pub true_peak_pass: bool,
+    /// Gain in dB that brings the integrated loudness to the target
+    pub gain_adjustment_db: f64,
+    /// True peak in dBTP after applying `gain_adjustment_db`. If this exceeds the
+    /// ceiling, the gain has to be combined with limiting. Equals the measured true
+    /// peak when the gain is not finite, e.g. for silence.
+    pub true_peak_after_gain_dbtp: f64,
+}
+
+impl ComplianceReport {
+    /// Returns whether both the loudness and the true peak comply.
+    pub fn passes(&self) -> bool {
+        self.loudness_pass && self.true_peak_pass
+    }
+}
+
+/// Checks a loudness measurement against the specification of a platform.
+///
+/// # Arguments
+///
+/// * `integrated_lufs` - Integrated loudness in LUFS, e.g. from [`Meter::lufs_integrated`](super::Meter::lufs_integrated)
+/// * `true_peak_dbtp` - Highest true peak of all channels in dBTP
+/// * `target` - Platform to check against
+///
+/// # Returns
+///
+/// A [`ComplianceReport`] with the result per metric and the suggested gain
+/// adjustment. Silence (-inf LUFS) fails the loudness check with an infinite gain.
+pub fn check_compliance(integrated_lufs: f64, true_peak_dbtp: f64, target: Platform) -> ComplianceReport {
+    let gain_adjustment_db = target.target_lufs() - integrated_lufs;
+    // Infinite gain on silence would turn the -inf true peak into NaN
+    let true_peak_after_gain_dbtp = if gain_adjustment_db.is_finite() {
+        true_peak_dbtp + gain_adjustment_db
+    } else {
+        true_peak_dbtp
+    };
+    ComplianceReport {
+        platform: target,
+        loudness_pass: gain_adjustment_db.abs() <= LOUDNESS_TOLERANCE_LU,
+        true_peak_pass: true_peak_dbtp <= target.max_true_peak_dbtp(),
+        gain_adjustment_db,
+        true_peak_after_gain_dbtp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_broadcast_level_file_on_spotify() {
+        let report = check_compliance(-23.0, -10.0, Platform::Spotify);
+        assert!(!report.loudness_pass);
+        assert!(report.true_peak_pass);
+        assert!(!report.passes());
+        assert!((report.gain_adjustment_db - 9.0).abs() < 1e-9);
+        // Turning the file up by 9 dB would push it over the ceiling
+        assert!((report.true_peak_after_gain_dbtp + 1.0).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_true_peak_violation() {
+        let report = check_compliance(-14.2, -0.3, Platform::Spotify);
+        assert!(report.loudness_pass);
+        assert!(!report.true_peak_pass);
+        assert!(!report.passes());
+        assert!((report.gain_adjustment_db - 0.2).abs() < 1e-9);
+    }
+
+    #[rstest]
+    #[case(Platform::Spotify, -14.0, -1.0)]
+    #[case(Platform::ApplePodcasts, -16.0, -1.0)]
+    #[case(Platform::YouTube, -14.0, -1.0)]
+    #[case(Platform::AmazonMusic, -14.0, -2.0)]
+    #[case(Platform::EbuR128, -23.0, -1.0)]
+    #[case(Platform::AtscA85, -24.0, -2.0)]
+    fn test_on_target_passes(#[case] platform: Platform, #[case] lufs: f64, #[case] peak: f64) {
+        let report = check_compliance(lufs, peak, platform);
+        assert!(report.passes());
+        assert_eq!(report.gain_adjustment_db, 0.0);
+        assert_eq!(report.platform, platform);
+
+        assert!(!check_compliance(lufs, peak + 0.1, platform).true_peak_pass);
+        assert!(!check_compliance(lufs + 1.5, peak, platform).loudness_pass);
+    }
+
+    #[rstest]
+    fn test_silence_fails() {
+        let report = check_compliance(f64::NEG_INFINITY, f64::NEG_INFINITY, Platform::EbuR128);
+        assert!(!report.loudness_pass);
+        assert!(report.true_peak_pass);
+        assert_eq!(report.gain_adjustment_db, f64::INFINITY);
+        assert_eq!(report.true_peak_after_gain_dbtp, f64::NEG_INFINITY);
+    }
+}
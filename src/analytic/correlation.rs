@@ -0,0 +1,98 @@
+//! Stereo phase-correlation measurement.
+//!
+//! The phase correlation between the left and right channel indicates how well a
+//! stereo mix will survive being summed to mono, e.g. on phone speakers or smart
+//! speakers. Strongly negative values mean parts of the signal cancel out in mono.
+
+/// Computes the phase correlation coefficient between left and right channel.
+///
+/// The input is assumed to be 2-channel interleaved audio ([L,R,L,R,...]). The
+/// result is the Pearson correlation of the two channels (without mean removal, as
+/// in hardware correlation meters):
+///
+/// * `1.0` - fully correlated (mono compatible, e.g. identical channels)
+/// * `0.0` - decorrelated (e.g. independent noise, or one channel silent)
+/// * `-1.0` - fully out of phase (cancels completely in mono)
+///
+/// # Returns
+///
+/// A value in [-1.0, 1.0]. Returns `NaN` if the buffer cannot be stereo, i.e. it is
+/// empty or has an odd number of samples.
+pub fn phase_correlation(samples: &[f32]) -> f32 {
+    if samples.is_empty() || !samples.len().is_multiple_of(2) {
+        return f32::NAN;
+    }
+    let (mut lr, mut ll, mut rr) = (0.0f64, 0.0f64, 0.0f64);
+    for frame in samples.chunks_exact(2) {
+        let (l, r) = (frame[0] as f64, frame[1] as f64);
+        lr += l * r;
+        ll += l * l;
+        rr += r * r;
+    }
+    let norm = (ll * rr).sqrt();
+    if norm > 0.0 {
+        (lr / norm).clamp(-1.0, 1.0) as f32
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn noise(n: usize, seed: u32) -> Vec<f32> {
+        // Deterministic linear congruential generator
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    fn interleave(left: &[f32], right: &[f32]) -> Vec<f32> {
+        left.iter().zip(right).flat_map(|(&l, &r)| [l, r]).collect()
+    }
+
+    #[fixture]
+    fn tone() -> Vec<f32> {
+        (0..4800)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin())
+            .collect()
+    }
+
+    #[rstest]
+    fn test_identical_channels(tone: Vec<f32>) {
+        let stereo = interleave(&tone, &tone);
+        assert!((phase_correlation(&stereo) - 1.0).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_inverted_channel(tone: Vec<f32>) {
+        let inverted: Vec<f32> = tone.iter().map(|x| -x).collect();
+        let stereo = interleave(&tone, &inverted);
+        assert!((phase_correlation(&stereo) + 1.0).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_independent_noise() {
+        let stereo = interleave(&noise(48000, 1), &noise(48000, 2));
+        assert!(phase_correlation(&stereo).abs() < 0.05);
+    }
+
+    #[rstest]
+    fn test_silent_channel(tone: Vec<f32>) {
+        let stereo = interleave(&tone, &vec![0.0; tone.len()]);
+        assert_eq!(phase_correlation(&stereo), 0.0);
+    }
+
+    #[rstest]
+    #[case(vec![])]
+    #[case(vec![0.5, 0.5, 0.5])]
+    fn test_non_stereo_is_nan(#[case] samples: Vec<f32>) {
+        assert!(phase_correlation(&samples).is_nan());
+    }
+}
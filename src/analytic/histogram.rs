@@ -0,0 +1,138 @@
+//! Sample level histograms.
+//!
+//! A histogram of the absolute sample values shows how the level of a recording is
+//! distributed, e.g. for a levels display: heavily limited audio piles up in the
+//! top bins, while quiet or dynamic recordings spread over the lower ones.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::{level_histogram, HistogramScale};
+//!
+//! let samples = vec![0.5f32, -0.5, 0.25, -0.01];
+//!
+//! // 10 bins of 0.1 each
+//! let linear = level_histogram(&samples, 10, HistogramScale::Linear);
+//!
+//! // 12 bins of 5 dB each from -60 dBFS to 0 dBFS
+//! let db = level_histogram(&samples, 12, HistogramScale::Decibel { floor_dbfs: -60.0 });
+//! ```
+
+/// Spacing of the bins of a level histogram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistogramScale {
+    /// Equally wide bins of absolute sample value over [0.0, 1.0]
+    Linear,
+    /// Equally wide bins in dBFS over [`floor_dbfs`, 0.0]
+    Decibel {
+        /// Lower end of the first bin in dBFS (e.g. -60.0), must be negative
+        floor_dbfs: f32,
+    },
+}
+
+/// Counts the absolute sample values falling into each level bin.
+///
+/// Values at or above full scale are counted in the last bin. With
+/// [`HistogramScale::Decibel`] values below the floor, including digital silence,
+/// are counted in the first bin, so the counts always add up to the number of
+/// samples. A floor that is not negative leaves no range to divide into bins, so all
+/// samples are counted in the first bin.
+///
+/// # Arguments
+///
+/// * `samples` - Audio samples, for interleaved audio all channels are counted together
+/// * `bins` - Number of bins
+/// * `scale` - Spacing of the bins
+///
+/// # Returns
+///
+/// A vector with the number of samples in each bin, from the lowest to the highest
+/// level. Empty if `bins` is 0.
+pub fn level_histogram(samples: &[f32], bins: usize, scale: HistogramScale) -> Vec<u64> {
+    let mut counts = vec![0u64; bins];
+    if bins == 0 {
+        return counts;
+    }
+    if let HistogramScale::Decibel { floor_dbfs } = scale {
+        if floor_dbfs >= 0.0 || floor_dbfs.is_nan() {
+            counts[0] = samples.len() as u64;
+            return counts;
+        }
+    }
+
+    for sample in samples {
+        let position = match scale {
+            HistogramScale::Linear => sample.abs(),
+            HistogramScale::Decibel { floor_dbfs } => {
+                let db = 20.0 * sample.abs().log10();
+                1.0 - db / floor_dbfs
+            }
+        };
+        // Casting saturates, so negative positions (below the floor) land in bin 0
+        let bin = ((position * bins as f32) as usize).min(bins - 1);
+        counts[bin] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// A sine of the given amplitude, its magnitudes spread between 0 and the amplitude.
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len).map(|i| amplitude * (i as f32 * 0.1).sin()).collect()
+    }
+
+    #[rstest]
+    #[case(0.05, 0)]
+    #[case(0.35, 3)]
+    #[case(0.5, 5)]
+    #[case(-0.99, 9)]
+    #[case(1.5, 9)]
+    fn test_linear_constant_amplitude(#[case] amplitude: f32, #[case] bin: usize) {
+        let counts = level_histogram(&[amplitude; 1000], 10, HistogramScale::Linear);
+        assert_eq!(counts[bin], 1000);
+        assert_eq!(counts.iter().sum::<u64>(), 1000);
+    }
+
+    #[rstest]
+    #[case(0.5, 10)] // -6 dBFS
+    #[case(0.2, 9)] // -14 dBFS
+    #[case(0.02, 5)] // -34 dBFS
+    #[case(1e-5, 0)] // -100 dBFS, below the floor
+    #[case(0.0, 0)]
+    fn test_decibel_constant_amplitude(#[case] amplitude: f32, #[case] bin: usize) {
+        let scale = HistogramScale::Decibel { floor_dbfs: -60.0 };
+        let counts = level_histogram(&[amplitude, -amplitude], 12, scale);
+        assert_eq!(counts[bin], 2, "{:?}", counts);
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(6.0)]
+    #[case(f32::NAN)]
+    fn test_decibel_floor_not_negative(#[case] floor_dbfs: f32) {
+        let scale = HistogramScale::Decibel { floor_dbfs };
+        let counts = level_histogram(&[1.0, 0.5, 1e-5, 0.0], 4, scale);
+        assert_eq!(counts, vec![4, 0, 0, 0]);
+    }
+
+    #[rstest]
+    fn test_sine_stays_below_amplitude() {
+        let samples = sine(0.45, 10000);
+        let counts = level_histogram(&samples, 10, HistogramScale::Linear);
+        assert!(counts[..5].iter().all(|&c| c > 0));
+        assert!(counts[5..].iter().all(|&c| c == 0));
+        // A sine spends most of its time near the peaks
+        assert!(counts[4] > counts[0]);
+        assert_eq!(counts.iter().sum::<u64>(), 10000);
+    }
+
+    #[rstest]
+    fn test_no_bins() {
+        assert!(level_histogram(&[0.5], 0, HistogramScale::Linear).is_empty());
+        assert_eq!(level_histogram(&[], 3, HistogramScale::Linear), vec![0, 0, 0]);
+    }
+}
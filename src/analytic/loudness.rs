@@ -6,13 +6,97 @@
 //! The EBU R128 standard is widely used in broadcast and streaming to ensure consistent
 //! loudness levels across different audio content.
 
+use std::error::Error;
+use std::fmt;
+
 use ebur128::{EbuR128, Mode};
 
+use crate::buffer::AudioBuffer;
+
+/// Highest channel count supported by the meter.
+const MAX_CHANNELS: u32 = 64;
+/// Lowest and highest sample rate in Hz supported by the meter.
+const SAMPLE_RATE_RANGE: (u32, u32) = (16, 2_822_400);
+/// Blocks below this loudness in LUFS are always gated out.
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Blocks more than this many LU below the mean of the blocks above the absolute
+/// gate are gated out.
+const RELATIVE_GATE_LU: f64 = -10.0;
+/// Gating blocks are 400 ms long and start every 100 ms.
+const BLOCKS_PER_WINDOW: u64 = 4;
+
+/// Errors that can occur while creating or feeding a [`Meter`].
+#[derive(Debug)]
+pub enum MeterError {
+    /// The channel count is 0 or above 64
+    InvalidChannels(u32),
+    /// The sample rate is below 16 Hz or above 2822.4 kHz
+    InvalidSampleRate(u32),
+    /// The number of samples is not a multiple of the channel count
+    PartialFrame {
+        /// Number of samples passed
+        samples: usize,
+        /// Channel count of the meter
+        channels: u32,
+    },
+    /// The underlying EBU R128 implementation failed
+    Ebur128(ebur128::Error),
+}
+
+impl fmt::Display for MeterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeterError::InvalidChannels(channels) => {
+                write!(f, "invalid channel count {}, expected 1 to {}", channels, MAX_CHANNELS)
+            }
+            MeterError::InvalidSampleRate(rate) => write!(
+                f,
+                "invalid sample rate {} Hz, expected {} to {} Hz",
+                rate, SAMPLE_RATE_RANGE.0, SAMPLE_RATE_RANGE.1
+            ),
+            MeterError::PartialFrame { samples, channels } => write!(
+                f,
+                "{} samples are not a whole number of {}-channel frames",
+                samples, channels
+            ),
+            MeterError::Ebur128(e) => write!(f, "loudness measurement failed: {}", e),
+        }
+    }
+}
+
+impl Error for MeterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MeterError::Ebur128(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ebur128::Error> for MeterError {
+    fn from(e: ebur128::Error) -> Self {
+        MeterError::Ebur128(e)
+    }
+}
+
+/// Checks that the channel count and sample rate are supported by the EBU R128 meters.
+pub(crate) fn check_format(channels: u32, sample_rate: u32) -> Result<(), MeterError> {
+    if channels == 0 || channels > MAX_CHANNELS {
+        return Err(MeterError::InvalidChannels(channels));
+    }
+    if !(SAMPLE_RATE_RANGE.0..=SAMPLE_RATE_RANGE.1).contains(&sample_rate) {
+        return Err(MeterError::InvalidSampleRate(sample_rate));
+    }
+    Ok(())
+}
+
 /// A loudness meter implementing the EBU R128 standard.
 /// 
 /// This struct provides methods to measure various aspects of audio loudness:
 /// - Integrated LUFS (overall loudness)
 /// - Short-term LUFS (3-second window)
+/// - Momentary LUFS (400 ms window)
+/// - Loudness range (LRA)
 /// - True peak levels
 /// 
 /// # Example
@@ -21,7 +105,8 @@ use ebur128::{EbuR128, Mode};
 /// use sonex::analytic::Meter;
 /// 
 /// let samples = vec![0.0f32; 1000];
-/// let meter = Meter::new(&samples, 1, 44100);
+/// let mut meter = Meter::new(1, 44100).unwrap();
+/// meter.add_frames_f32(&samples).unwrap();
 /// 
 /// if let Some(lufs) = meter.lufs_integrated() {
 ///     println!("Integrated LUFS: {}", lufs);
@@ -30,33 +115,182 @@ use ebur128::{EbuR128, Mode};
 #[derive(Debug)]
 pub struct Meter {
     meter: EbuR128,
+    /// Mono meters measuring each channel on its own, None unless requested with
+    /// [`Meter::with_loudness_per_channel`] and empty for mono audio
+    channel_meters: Option<Vec<EbuR128>>,
     channels: u32,
-    #[allow(dead_code)]
-    sample_rate: u32
+    sample_rate: u32,
+    /// Frames added since the last reset
+    frames: u64,
+    /// Loudness of every 400 ms gating block so far, before gating
+    block_loudness: Vec<f64>,
+    /// Short-term loudness at the end of every 100 ms step so far
+    shortterm_loudness: Vec<f64>,
 }
 
 impl Meter {
-    /// Creates a new loudness meter for the given audio data.
+    /// Creates a new, empty loudness meter.
+    /// 
+    /// Audio is fed to the meter with [`Meter::add_frames_f32`], which can be called
+    /// repeatedly to measure a stream block by block.
     /// 
     /// # Arguments
     /// 
-    /// * `samples` - Interleaved audio samples
     /// * `channels` - Number of audio channels
     /// * `sample_rate` - Sample rate in Hz
     /// 
     /// # Returns
     /// 
-    /// Returns a new Meter instance configured for the given audio parameters.
-    pub fn new(samples: &[f32], channels: u32, sample_rate: u32) -> Self {
-        let modes = Mode::I | Mode::S | Mode::TRUE_PEAK;
-        let mut meter = EbuR128::new(channels, sample_rate, modes)
-            .expect("Failed to create EBU R128 meter");
-        meter.add_frames_f32(samples).expect("Failed to add frames to meter");
-        Self {
+    /// Returns a new Meter instance configured for the given audio parameters, or a
+    /// MeterError if the channel count or sample rate is not supported.
+    pub fn new(channels: u32, sample_rate: u32) -> Result<Self, MeterError> {
+        check_format(channels, sample_rate)?;
+
+        let modes = Mode::I | Mode::S | Mode::M | Mode::LRA | Mode::TRUE_PEAK;
+        let meter = EbuR128::new(channels, sample_rate, modes)?;
+        Ok(Self {
             meter,
+            channel_meters: None,
             channels,
-            sample_rate
+            sample_rate,
+            frames: 0,
+            block_loudness: Vec::new(),
+            shortterm_loudness: Vec::new(),
+        })
+    }
+
+    /// Enables measuring the integrated loudness of each channel on its own.
+    /// 
+    /// This adds a mono meter per channel, so it is off by default. Enable it before
+    /// adding audio; samples added earlier are not part of the per-channel measurement.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the Meter with [`Meter::loudness_per_channel`] enabled.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::analytic::Meter;
+    /// 
+    /// let mut meter = Meter::new(2, 48000).unwrap().with_loudness_per_channel();
+    /// meter.add_frames_f32(&[0.0f32; 9600]).unwrap();
+    /// println!("{:?}", meter.loudness_per_channel());
+    /// ```
+    pub fn with_loudness_per_channel(mut self) -> Self {
+        let channels = if self.channels > 1 { self.channels } else { 0 };
+        self.channel_meters = Some(
+            (0..channels)
+                // The format was checked when the meter was created
+                .map(|_| EbuR128::new(1, self.sample_rate, Mode::I).expect("valid meter format"))
+                .collect(),
+        );
+        self
+    }
+
+    /// Creates a new loudness meter and measures the given audio data in one go.
+    /// 
+    /// This is a convenience for [`Meter::new`] followed by [`Meter::add_frames_f32`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - Interleaved audio samples
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the Meter holding the measurement, or a MeterError if the parameters are
+    /// not supported or the samples are not a whole number of frames.
+    pub fn from_samples(samples: &[f32], channels: u32, sample_rate: u32) -> Result<Self, MeterError> {
+        let mut meter = Self::new(channels, sample_rate)?;
+        meter.add_frames_f32(samples)?;
+        Ok(meter)
+    }
+
+    /// Creates a meter for the format of an [`AudioBuffer`] and measures its samples.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buffer` - Audio to measure
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the Meter holding the measurement, or a MeterError if the format is not
+    /// supported or the samples are not a whole number of frames.
+    pub fn from_buffer(buffer: &AudioBuffer) -> Result<Self, MeterError> {
+        Self::from_samples(&buffer.samples, buffer.channels, buffer.sample_rate)
+    }
+
+    /// Adds interleaved audio samples to the measurement.
+    /// 
+    /// The samples are appended to everything the meter has seen so far, so a long
+    /// file can be measured in blocks without buffering it entirely.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - Interleaved audio samples, the length must be a multiple of the channel count
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Ok(()) if successful, or a MeterError if the samples are not a whole number
+    /// of frames. In that case none of the samples are added.
+    pub fn add_frames_f32(&mut self, samples: &[f32]) -> Result<(), MeterError> {
+        if !samples.len().is_multiple_of(self.channels as usize) {
+            return Err(MeterError::PartialFrame { samples: samples.len(), channels: self.channels });
+        }
+        self.add_blocks(samples)?;
+        let channel_meters = match self.channel_meters.as_mut() {
+            Some(meters) if !meters.is_empty() => meters,
+            _ => return Ok(()),
+        };
+
+        let channels = self.channels as usize;
+        let frames = samples.len() / channels;
+        let mut channel_samples = Vec::with_capacity(frames);
+        for (channel, meter) in channel_meters.iter_mut().enumerate() {
+            channel_samples.clear();
+            channel_samples.extend(samples.iter().skip(channel).step_by(channels).take(frames));
+            meter.add_frames_f32(&channel_samples)?;
+        }
+        Ok(())
+    }
+
+    /// Feeds the samples to the meter, recording the loudness of each gating block and
+    /// the short-term loudness every 100 ms.
+    /// 
+    /// The samples are split at the 100 ms boundaries where a block ends, so the
+    /// momentary loudness read there covers exactly that block.
+    fn add_blocks(&mut self, samples: &[f32]) -> Result<(), MeterError> {
+        let channels = self.channels as usize;
+        // The 100 ms step as rounded by the EBU R128 meter
+        let step = (self.sample_rate as u64 + 5) / 10;
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let until_boundary = (step - self.frames % step) as usize;
+            let (segment, tail) = rest.split_at(until_boundary.min(rest.len() / channels) * channels);
+            self.meter.add_frames_f32(segment)?;
+            self.frames += (segment.len() / channels) as u64;
+            if self.frames.is_multiple_of(step) {
+                self.shortterm_loudness.push(self.meter.loudness_shortterm()?);
+                if self.frames >= BLOCKS_PER_WINDOW * step {
+                    self.block_loudness.push(self.meter.loudness_momentary()?);
+                }
+            }
+            rest = tail;
         }
+        Ok(())
+    }
+
+    /// Clears all accumulated audio so the meter can be reused for a new measurement.
+    /// 
+    /// The channel count and sample rate are kept.
+    pub fn reset(&mut self) {
+        self.meter.reset();
+        self.frames = 0;
+        self.block_loudness.clear();
+        self.shortterm_loudness.clear();
+        self.channel_meters.iter_mut().flatten().for_each(EbuR128::reset);
     }
 
     /// Measures the integrated loudness (LUFS) of the entire audio.
@@ -71,10 +305,41 @@ impl Meter {
         self.meter.loudness_global().ok()
     }
 
+    /// Returns the loudness of the gating blocks that count toward the integrated loudness.
+    /// 
+    /// EBU R128 measures the integrated loudness over 400 ms blocks starting every
+    /// 100 ms. Blocks below -70 LUFS (absolute gate) and blocks more than 10 LU below
+    /// the mean of the remaining blocks (relative gate) are dropped, and
+    /// [`Meter::lufs_integrated`] is the mean power of the blocks that are left. Looking
+    /// at these blocks shows which parts of the audio a measurement is based on, e.g.
+    /// why quiet passages don't lower it.
+    /// 
+    /// # Returns
+    /// 
+    /// The LUFS value of each block surviving the gates, in the order of the audio.
+    /// Empty if no block is above the absolute gate or less than 400 ms were measured.
+    pub fn gating_blocks(&self) -> Vec<f64> {
+        let above_absolute: Vec<f64> = self.block_loudness
+            .iter()
+            .copied()
+            .filter(|&lufs| lufs >= ABSOLUTE_GATE_LUFS)
+            .collect();
+        if above_absolute.is_empty() {
+            return above_absolute;
+        }
+
+        let mean_energy = above_absolute.iter().map(|&lufs| loudness_to_energy(lufs)).sum::<f64>()
+            / above_absolute.len() as f64;
+        let relative_gate = energy_to_loudness(mean_energy) + RELATIVE_GATE_LU;
+        above_absolute.into_iter().filter(|&lufs| lufs >= relative_gate).collect()
+    }
+
     /// Measures the short-term loudness (LUFS) using a 3-second sliding window.
     /// 
     /// This measurement reflects more recent changes in loudness compared to the
-    /// integrated measurement.
+    /// integrated measurement. The window covers the last 3 seconds of all audio
+    /// added so far, so calling this after each [`Meter::add_frames_f32`] block yields
+    /// a short-term loudness trace over time.
     /// 
     /// # Returns
     /// 
@@ -83,6 +348,95 @@ impl Meter {
         self.meter.loudness_shortterm().ok()
     }
 
+    /// Returns the short-term loudness over time.
+    /// 
+    /// The 3-second short-term window slides over all audio added so far and is
+    /// read every `hop_sec`, as a loudness meter display would. Readings are taken on
+    /// the 100 ms grid of EBU R128, so the hop is rounded to a multiple of 100 ms
+    /// (at least 100 ms). Until 3 seconds have been measured the window is only
+    /// partly filled and reads lower than the audio in it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `hop_sec` - Time between readings in seconds
+    /// 
+    /// # Returns
+    /// 
+    /// A vector of (time in seconds, LUFS) pairs, where the time is the end of the
+    /// window. The first reading is at `hop_sec`, so audio of duration `d` gives
+    /// `floor(d / hop_sec)` readings. Silent windows read -inf LUFS.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::analytic::Meter;
+    /// 
+    /// let samples = vec![0.1f32; 48000 * 10];
+    /// let meter = Meter::from_samples(&samples, 1, 48000).unwrap();
+    /// for (time, lufs) in meter.shortterm_series(0.5) {
+    ///     println!("{:.1} s: {:.1} LUFS", time, lufs);
+    /// }
+    /// ```
+    pub fn shortterm_series(&self, hop_sec: f64) -> Vec<(f64, f64)> {
+        let steps_per_hop = ((hop_sec * 10.0).round() as usize).max(1);
+        let step = (self.sample_rate as u64 + 5) / 10;
+        self.shortterm_loudness
+            .iter()
+            .enumerate()
+            .skip(steps_per_hop - 1)
+            .step_by(steps_per_hop)
+            .map(|(i, &lufs)| ((i as u64 + 1) as f64 * step as f64 / self.sample_rate as f64, lufs))
+            .collect()
+    }
+
+    /// Measures the momentary loudness (LUFS) using a 400 ms sliding window.
+    /// 
+    /// This is the fastest-reacting loudness measurement defined by EBU R128 and is
+    /// suited for real-time level displays.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Some(value) with the LUFS value if successful, or None if the measurement failed.
+    pub fn lufs_momentary(&self) -> Option<f64> {
+        self.meter.loudness_momentary().ok()
+    }
+
+    /// Measures the loudness range (LRA) in LU.
+    /// 
+    /// The loudness range describes the variation of loudness over the audio as the
+    /// spread between the 10th and 95th percentile of the gated short-term loudness,
+    /// as defined by EBU Tech 3342. Speech podcasts typically measure 5 to 10 LU,
+    /// dynamic material more.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Some(value) with the LRA in LU if successful, or None if the measurement failed.
+    pub fn loudness_range(&self) -> Option<f64> {
+        self.meter.loudness_range().ok()
+    }
+
+    /// Measures the integrated loudness (LUFS) of each channel on its own.
+    /// 
+    /// Every channel is measured independently as if it were a mono signal, which helps
+    /// diagnosing an imbalance between channels: a channel 6 dB louder than another also
+    /// measures 6 LU higher. The values do not add up to [`Meter::lufs_integrated`], which
+    /// sums the power of all channels before gating.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Some(Vec) containing the LUFS value of each channel, or None if the
+    /// meter was not created with [`Meter::with_loudness_per_channel`] or the measurement failed.
+    pub fn loudness_per_channel(&self) -> Option<Vec<f64>> {
+        let channel_meters = self.channel_meters.as_ref()?;
+        if channel_meters.is_empty() {
+            return self.lufs_integrated().map(|lufs| vec![lufs]);
+        }
+        channel_meters
+            .iter()
+            .map(|meter| meter.loudness_global().ok())
+            .collect()
+    }
+
     /// Measures the true peak values for each channel.
     /// 
     /// True peak measurements take into account inter-sample peaks that may occur
@@ -90,12 +444,367 @@ impl Meter {
     /// 
     /// # Returns
     /// 
-    /// Returns Some(Vec) containing true peak values in dBTP for each channel,
-    /// or None if the measurement failed.
+    /// Returns Some(Vec) containing the true peak of each channel as a linear amplitude
+    /// (1.0 is full scale, use `20 * log10(peak)` for dBTP), or None if the measurement
+    /// failed.
     pub fn true_peaks(&self) -> Option<Vec<f64>> {
-        let true_peaks: Option<Vec<f64>> = (0..self.channels)
-            .map(|ch| self.meter.true_peak(ch as u32).ok())
-            .collect::<Option<Vec<f64>>>();
-        true_peaks
+        (0..self.channels)
+            .map(|ch| self.meter.true_peak(ch).ok())
+            .collect::<Option<Vec<f64>>>()
+    }
+}
+
+/// Converts a loudness in LUFS to the mean square energy it stands for.
+fn loudness_to_energy(lufs: f64) -> f64 {
+    10.0_f64.powf((lufs + 0.691) / 10.0)
+}
+
+/// Converts a mean square energy to a loudness in LUFS.
+fn energy_to_loudness(energy: f64) -> f64 {
+    10.0 * energy.log10() - 0.691
+}
+
+/// Calculates the gain in dB needed to bring audio to a target integrated loudness.
+/// 
+/// The integrated loudness of the samples is measured with a [`Meter`] and the
+/// difference to `target_lufs` is returned, e.g. a file measuring -20 LUFS with a
+/// target of -14 LUFS yields +6 dB.
+/// 
+/// # Arguments
+/// 
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+/// * `sample_rate` - Sample rate in Hz
+/// * `target_lufs` - Desired integrated loudness in LUFS (e.g. -14.0 for most streaming platforms)
+/// 
+/// # Returns
+/// 
+/// Returns Some(gain) in dB, or None if the loudness could not be measured (e.g. an
+/// unsupported channel count) or is -inf (e.g. silence or audio shorter than a single
+/// gating block).
+pub fn normalization_gain_db(
+    samples: &[f32],
+    channels: u32,
+    sample_rate: u32,
+    target_lufs: f64,
+) -> Option<f64> {
+    let meter = Meter::from_samples(samples, channels, sample_rate).ok()?;
+    let lufs = meter.lufs_integrated()?;
+    if lufs.is_finite() {
+        Some(target_lufs - lufs)
+    } else {
+        None
+    }
+}
+
+/// Measures the integrated loudness of several tracks played back to back.
+/// 
+/// All tracks are fed through a single [`Meter`], so the gating of EBU R128 applies
+/// to the album as a whole. This is the loudness used for album-mode normalization,
+/// where all tracks get the same gain and the loudness differences between them are
+/// kept.
+/// 
+/// # Arguments
+/// 
+/// * `tracks` - `(samples, channels, sample_rate)` of each track, in playback order
+/// 
+/// # Returns
+/// 
+/// Returns Some(value) with the LUFS value of the album, or None if there are no
+/// tracks, the tracks don't share the same channel count and sample rate, or the
+/// measurement failed.
+pub fn album_loudness(tracks: &[(&[f32], u32, u32)]) -> Option<f64> {
+    let &(_, channels, sample_rate) = tracks.first()?;
+    if tracks.iter().any(|&(_, c, r)| c != channels || r != sample_rate) {
+        return None;
+    }
+
+    let mut meter = Meter::new(channels, sample_rate).ok()?;
+    for (samples, _, _) in tracks {
+        meter.add_frames_f32(samples).ok()?;
+    }
+    meter.lufs_integrated()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::AudioReader;
+    use rstest::*;
+
+    #[fixture]
+    fn sine() -> (Vec<f32>, u32, u32) {
+        let mut reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        let mut samples = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            samples.extend(packet);
+        }
+        (samples, reader.channels() as u32, reader.sample_rate())
+    }
+
+    #[rstest]
+    #[case(1024)]
+    #[case(4800)]
+    #[case(44100)]
+    fn test_incremental_matches_one_shot(#[case] chunk_size: usize, sine: (Vec<f32>, u32, u32)) {
+        let (samples, channels, sample_rate) = sine;
+        let one_shot = Meter::from_samples(&samples, channels, sample_rate).unwrap();
+
+        let mut incremental = Meter::new(channels, sample_rate).unwrap();
+        for chunk in samples.chunks(chunk_size) {
+            incremental.add_frames_f32(chunk).unwrap();
+        }
+
+        let expected = one_shot.lufs_integrated().unwrap();
+        let actual = incremental.lufs_integrated().unwrap();
+        assert!(expected.is_finite());
+        assert!((expected - actual).abs() < 1e-9, "expected {}, got {}", expected, actual);
+        assert_eq!(one_shot.true_peaks(), incremental.true_peaks());
+    }
+
+    #[rstest]
+    fn test_from_buffer_matches_samples(sine: (Vec<f32>, u32, u32)) {
+        let (samples, channels, sample_rate) = sine;
+        let expected = Meter::from_samples(&samples, channels, sample_rate).unwrap();
+        let meter = Meter::from_buffer(&AudioBuffer::from_interleaved(samples, channels, sample_rate)).unwrap();
+        assert_eq!(meter.lufs_integrated(), expected.lufs_integrated());
+
+        let partial = AudioBuffer::from_interleaved(vec![0.0; 3], 2, 48000);
+        assert!(matches!(Meter::from_buffer(&partial), Err(MeterError::PartialFrame { .. })));
+    }
+
+    #[rstest]
+    fn test_momentary_close_to_shortterm_for_steady_tone() {
+        let sample_rate = 48000;
+        let tone: Vec<f32> = (0..sample_rate * 4)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+        let meter = Meter::from_samples(&tone, 1, sample_rate).unwrap();
+
+        let momentary = meter.lufs_momentary().unwrap();
+        let shortterm = meter.lufs_shortterm().unwrap();
+        assert!(momentary.is_finite());
+        assert!((momentary - shortterm).abs() < 0.1, "momentary {}, short-term {}", momentary, shortterm);
+    }
+
+    #[rstest]
+    fn test_shortterm_trace_with_single_meter() {
+        let sample_rate = 48000;
+        // A tone whose level steps up every second, 10 s in total
+        let signal: Vec<f32> = (0..sample_rate * 10)
+            .map(|i| {
+                let amp = 0.05 * (1 + i / sample_rate) as f32;
+                amp * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin()
+            })
+            .collect();
+        let block = (sample_rate * 3) as usize;
+
+        let mut meter = Meter::new(1, sample_rate).unwrap();
+        let mut streamed = Vec::new();
+        let mut fresh = Vec::new();
+        let mut reference = Vec::new();
+        for (i, chunk) in signal.chunks(block).enumerate() {
+            meter.add_frames_f32(chunk).unwrap();
+            streamed.push(meter.lufs_shortterm().unwrap());
+            fresh.push(Meter::from_samples(chunk, 1, sample_rate).unwrap().lufs_shortterm().unwrap());
+            let end = i * block + chunk.len();
+            reference.push(Meter::from_samples(&signal[..end], 1, sample_rate).unwrap().lufs_shortterm().unwrap());
+        }
+
+        // The accumulating meter always reports the true last 3 s of the signal
+        for (s, r) in streamed.iter().zip(reference.iter()) {
+            assert!((s - r).abs() < 1e-9);
+        }
+        // A fresh meter per block only sees the trailing 1 s block and under-reads it
+        let last = streamed.len() - 1;
+        assert!(streamed[last] - fresh[last] > 1.0);
+
+        meter.reset();
+        assert_eq!(meter.lufs_shortterm(), Some(f64::NEG_INFINITY));
+    }
+
+    #[rstest]
+    #[case(0.1, 1000)]
+    #[case(0.5, 4800)]
+    #[case(1.0, 16000)]
+    fn test_shortterm_series_of_sustained_tone(#[case] hop_sec: f64, #[case] chunk_size: usize) {
+        let sample_rate = 16000;
+        let duration_sec = 10.0;
+        let tone: Vec<f32> = (0..(sample_rate as f64 * duration_sec) as usize)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+        let mut meter = Meter::new(1, sample_rate).unwrap();
+        for chunk in tone.chunks(chunk_size) {
+            meter.add_frames_f32(chunk).unwrap();
+        }
+
+        let series = meter.shortterm_series(hop_sec);
+        assert_eq!(series.len(), (duration_sec / hop_sec).round() as usize);
+        assert!(series.iter().all(|(_, lufs)| lufs.is_finite()));
+        assert!((series[0].0 - hop_sec).abs() < 1e-9);
+        assert!((series.last().unwrap().0 - duration_sec).abs() < 1e-9);
+
+        // Each reading is the short-term loudness of the audio up to its time, which
+        // settles once the 3 s window is full
+        for &(time, lufs) in series.iter().step_by(series.len() / 5) {
+            let end = (time * sample_rate as f64).round() as usize;
+            let reference = Meter::from_samples(&tone[..end], 1, sample_rate).unwrap();
+            assert!((lufs - reference.lufs_shortterm().unwrap()).abs() < 1e-9, "{} s", time);
+        }
+        let settled: Vec<f64> = series.iter().filter(|(time, _)| *time >= 3.0).map(|&(_, lufs)| lufs).collect();
+        assert!(settled.iter().all(|lufs| (lufs - settled[0]).abs() < 0.01));
+
+        meter.reset();
+        assert!(meter.shortterm_series(hop_sec).is_empty());
+    }
+
+    #[rstest]
+    #[case(1024)]
+    #[case(48000)]
+    fn test_loudness_per_channel_shows_imbalance(#[case] chunk_size: usize) {
+        let sample_rate = 48000;
+        // Right channel at twice the amplitude of the left, i.e. 6 dB louder
+        let stereo: Vec<f32> = (0..sample_rate * 3)
+            .flat_map(|i| {
+                let s = 0.1 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin();
+                [s, 2.0 * s]
+            })
+            .collect();
+        let mut meter = Meter::new(2, sample_rate).unwrap().with_loudness_per_channel();
+        for chunk in stereo.chunks(chunk_size * 2) {
+            meter.add_frames_f32(chunk).unwrap();
+        }
+
+        let per_channel = meter.loudness_per_channel().unwrap();
+        assert_eq!(per_channel.len(), 2);
+        let difference = per_channel[1] - per_channel[0];
+        assert!((difference - 6.02).abs() < 0.05, "difference {}", difference);
+
+        // Each channel measures like the same signal measured as mono
+        let left: Vec<f32> = stereo.iter().step_by(2).cloned().collect();
+        let mut mono = Meter::new(1, sample_rate).unwrap().with_loudness_per_channel();
+        mono.add_frames_f32(&left).unwrap();
+        assert!((per_channel[0] - mono.lufs_integrated().unwrap()).abs() < 1e-9);
+        assert_eq!(mono.loudness_per_channel(), mono.lufs_integrated().map(|l| vec![l]));
+
+        // Without asking for it there are no per-channel meters
+        let combined = Meter::from_samples(&stereo, 2, sample_rate).unwrap();
+        assert_eq!(combined.loudness_per_channel(), None);
+        assert_eq!(combined.lufs_integrated(), meter.lufs_integrated());
+
+        meter.reset();
+        assert_eq!(meter.loudness_per_channel(), Some(vec![f64::NEG_INFINITY; 2]));
+    }
+
+    #[rstest]
+    #[case(0, 48000)]
+    #[case(65, 48000)]
+    #[case(2, 0)]
+    #[case(2, 3_000_000)]
+    fn test_invalid_parameters_are_errors(#[case] channels: u32, #[case] sample_rate: u32) {
+        let error = Meter::new(channels, sample_rate).unwrap_err();
+        match error {
+            MeterError::InvalidChannels(c) => assert_eq!(c, channels),
+            MeterError::InvalidSampleRate(r) => assert_eq!(r, sample_rate),
+            e => panic!("unexpected error {}", e),
+        }
+        assert!(Meter::from_samples(&[0.0; 96], channels, sample_rate).is_err());
+        assert_eq!(normalization_gain_db(&[0.5; 96000], channels, sample_rate, -14.0), None);
+    }
+
+    #[rstest]
+    fn test_partial_frame_is_rejected() {
+        let mut meter = Meter::new(2, 48000).unwrap().with_loudness_per_channel();
+        let error = meter.add_frames_f32(&[0.5; 4801]).unwrap_err();
+        assert!(matches!(error, MeterError::PartialFrame { samples: 4801, channels: 2 }));
+        assert_eq!(error.to_string(), "4801 samples are not a whole number of 2-channel frames");
+
+        // Nothing was added, neither to the combined nor to the per-channel meters
+        assert_eq!(meter.true_peaks(), Some(vec![0.0, 0.0]));
+        assert_eq!(meter.loudness_per_channel(), Some(vec![f64::NEG_INFINITY; 2]));
+    }
+
+    #[rstest]
+    fn test_album_of_identical_tracks(sine: (Vec<f32>, u32, u32)) {
+        let (samples, channels, sample_rate) = sine;
+        let track = (samples.as_slice(), channels, sample_rate);
+        let single = Meter::from_samples(&samples, channels, sample_rate).unwrap().lufs_integrated().unwrap();
+
+        let album = album_loudness(&[track, track]).unwrap();
+        assert!((album - single).abs() < 0.01, "album {}, single {}", album, single);
+        assert_eq!(album_loudness(&[track]), Some(single));
+    }
+
+    #[rstest]
+    fn test_album_gating_across_tracks() {
+        let sample_rate = 48000;
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..sample_rate * 5)
+                .map(|i| amplitude * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin())
+                .collect()
+        };
+        let loud = tone(0.5);
+        let quiet = tone(0.05);
+        let loud_lufs = Meter::from_samples(&loud, 1, sample_rate).unwrap().lufs_integrated().unwrap();
+
+        // The quiet track is 20 LU down and falls below the relative gate of the album,
+        // only the blocks spanning the transition between the tracks pull it down a bit
+        let album = album_loudness(&[(&loud, 1, sample_rate), (&quiet, 1, sample_rate)]).unwrap();
+        assert!((album - loud_lufs).abs() < 0.2, "album {}, loud {}", album, loud_lufs);
+    }
+
+    #[rstest]
+    #[case(2, 48000)]
+    #[case(1, 44100)]
+    fn test_album_rejects_mixed_formats(#[case] channels: u32, #[case] sample_rate: u32) {
+        let track = vec![0.1f32; 96000];
+        assert_eq!(album_loudness(&[(&track, 1, 48000), (&track, channels, sample_rate)]), None);
+        assert_eq!(album_loudness(&[]), None);
+    }
+
+    #[rstest]
+    #[case(48000)]
+    #[case(1000)]
+    fn test_gating_blocks_drop_quiet_and_silent(#[case] chunk_size: usize) {
+        // 2 s loud tone, 1 s at -50 dBFS and 1 s of silence
+        let sample_rate = 48000;
+        let tone = |amplitude: f32, seconds: usize| -> Vec<f32> {
+            (0..sample_rate * seconds)
+                .map(|i| amplitude * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin())
+                .collect()
+        };
+        let mut samples = tone(0.5, 2);
+        samples.extend(tone(0.00316, 1));
+        samples.extend(vec![0.0; sample_rate]);
+
+        let mut meter = Meter::new(1, sample_rate as u32).unwrap();
+        for chunk in samples.chunks(chunk_size) {
+            meter.add_frames_f32(chunk).unwrap();
+        }
+        // 37 blocks end every 100 ms from 0.4 s to 4 s. The 17 blocks within the tone
+        // and the 3 overlapping its end pass, the quiet blocks fall below the relative
+        // gate and the silent blocks below the absolute gate.
+        assert_eq!(meter.block_loudness.len(), 37);
+        let blocks = meter.gating_blocks();
+        assert_eq!(blocks.len(), 20);
+        let loud = blocks[0];
+        assert!(blocks[..17].iter().all(|&lufs| (lufs - loud).abs() < 0.01));
+        assert!(blocks[17..].windows(2).all(|w| w[1] < w[0]));
+        assert!(meter.block_loudness.iter().any(|&lufs| lufs > ABSOLUTE_GATE_LUFS && lufs < loud - 40.0));
+
+        // The integrated loudness is the mean power of the gated blocks
+        let mean_energy = blocks.iter().map(|&lufs| loudness_to_energy(lufs)).sum::<f64>() / blocks.len() as f64;
+        let integrated = meter.lufs_integrated().unwrap();
+        assert!((energy_to_loudness(mean_energy) - integrated).abs() < 1e-6, "{}", integrated);
+
+        meter.reset();
+        assert!(meter.gating_blocks().is_empty());
+    }
+
+    #[rstest]
+    fn test_empty_meter_is_silent() {
+        let meter = Meter::new(2, 48000).unwrap();
+        assert_eq!(meter.lufs_integrated(), Some(f64::NEG_INFINITY));
+        assert_eq!(meter.true_peaks(), Some(vec![0.0, 0.0]));
     }
 }
@@ -1,4 +1,37 @@
 // Analytic module
+mod clipping;
+mod compliance;
+mod correlation;
+mod histogram;
 mod loudness;
+mod onset;
+mod peak_meter;
+mod pitch;
+mod realtime;
+mod report;
+mod silence;
+pub mod spectrum;
+pub mod stats;
+mod tempo;
+mod true_peak;
+mod waveform;
+pub mod window;
 
-pub use loudness::Meter;
\ No newline at end of file
+pub use clipping::{detect_clipping, ClipEvent, CLIP_THRESHOLD};
+pub use compliance::{check_compliance, ComplianceReport, Platform, LOUDNESS_TOLERANCE_LU};
+pub use correlation::phase_correlation;
+pub use histogram::{level_histogram, HistogramScale};
+pub use loudness::{album_loudness, Meter, MeterError, normalization_gain_db};
+pub use onset::detect_onsets;
+pub use peak_meter::PeakMeter;
+pub use pitch::{estimate_pitch_autocorr, zero_crossing_rate};
+pub use realtime::RealtimeMeter;
+pub use report::{report_directory, report_file, LoudnessReport, ReportError};
+#[cfg(feature = "rayon")]
+pub use report::report_directory_parallel;
+pub use silence::detect_silence;
+pub(crate) use silence::silent_frames;
+pub use tempo::estimate_tempo;
+pub use true_peak::true_peak_dbtp;
+pub(crate) use true_peak::{polyphase_filter, TAPS_PER_PHASE};
+pub use waveform::waveform_peaks;
@@ -0,0 +1,205 @@
+//! Onset detection.
+//!
+//! Onsets are the starts of notes, hits and other transients, e.g. the drum hits
+//! used to slice a loop. They are found in three steps:
+//!
+//! 1. An onset detection function measures how much new energy appears in each
+//!    short frame, as the spectral flux of log-compressed magnitude spectra.
+//! 2. An adaptive threshold follows the local average of the detection function, so
+//!    loud passages don't drown out quiet ones.
+//! 3. Peaks of the detection function above the threshold are picked as onsets.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::detect_onsets;
+//!
+//! let samples = vec![0.0f32; 48000];
+//! for time in detect_onsets(&samples, 48000, 0.5) {
+//!     println!("Onset at {:.3} s", time);
+//! }
+//! ```
+
+use super::spectrum::{spectral_flux, windowed_magnitudes};
+use super::window::hann;
+use rustfft::FftPlanner;
+
+/// Approximate length of an analysis frame in seconds.
+const FRAME_SEC: f64 = 0.01;
+/// Number of frames an analysis frame advances by, as a fraction of its length.
+const HOP_DIVISOR: usize = 4;
+/// Compression applied to magnitudes before the flux, `ln(1 + COMPRESSION * m)`.
+const COMPRESSION: f32 = 100.0;
+/// Half-width in seconds of the window the adaptive threshold averages over.
+const THRESHOLD_WINDOW_SEC: f64 = 0.05;
+/// Minimum time in seconds between two onsets.
+const MIN_INTERVAL_SEC: f64 = 0.03;
+/// Flux the strongest frame must reach for the signal to have any onsets at all.
+/// Steady tones only reach a fraction of this through spectral leakage.
+const MIN_ONSET_STRENGTH: f32 = 1.0;
+
+/// Returns the FFT size and hop size in samples of the analysis frames.
+pub(super) fn frame_sizes(sample_rate: u32) -> (usize, usize) {
+    let fft_size = ((sample_rate as f64 * FRAME_SEC) as usize).next_power_of_two().max(16);
+    (fft_size, fft_size / HOP_DIVISOR)
+}
+
+/// Computes the onset detection function: the spectral flux between consecutive
+/// frames, normalized to a maximum of 1.0.
+///
+/// Returns None if no frame reaches [`MIN_ONSET_STRENGTH`], i.e. the signal is silent
+/// or steady.
+pub(super) fn onset_strength(samples: &[f32], fft_size: usize, hop: usize) -> Option<Vec<f32>> {
+    let fft = FftPlanner::new().plan_fft_forward(fft_size);
+    let window = hann(fft_size);
+
+    // Only full frames: zero padding would cut the signal off and spread its energy
+    // over all bins, which looks like an onset
+    let frames = (samples.len().checked_sub(fft_size)? / hop) + 1;
+    let mut previous: Option<Vec<f32>> = None;
+    let mut strength: Vec<f32> = (0..frames)
+        .map(|frame| {
+            let start = frame * hop;
+            let mut mag = windowed_magnitudes(fft.as_ref(), &samples[start..start + fft_size], &window);
+            mag.iter_mut().for_each(|m| *m = (1.0 + COMPRESSION * *m).ln());
+            let flux = previous.as_ref().map_or(0.0, |prev| spectral_flux(prev, &mag));
+            previous = Some(mag);
+            flux
+        })
+        .collect();
+
+    let max = strength.iter().fold(0.0f32, |max, &s| max.max(s));
+    if max < MIN_ONSET_STRENGTH {
+        return None;
+    }
+    strength.iter_mut().for_each(|s| *s /= max);
+    Some(strength)
+}
+
+/// Detects the onsets of notes and transients.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples; downmix multi-channel audio first
+/// * `sample_rate` - Sample rate in Hz
+/// * `sensitivity` - How readily onsets are reported, from 0.0 (only the strongest
+///   transients) to 1.0 (every local rise in energy). Clamped to [0.0, 1.0], 0.5 is
+///   a good start.
+///
+/// # Returns
+///
+/// The onset times in seconds, in chronological order. Onsets are at least 30 ms
+/// apart, and their times are accurate to a few milliseconds.
+pub fn detect_onsets(samples: &[f32], sample_rate: u32, sensitivity: f32) -> Vec<f64> {
+    let (fft_size, hop) = frame_sizes(sample_rate);
+    let Some(strength) = onset_strength(samples, fft_size, hop) else {
+        return Vec::new();
+    };
+    let frames_per_sec = sample_rate as f64 / hop as f64;
+    let threshold_frames = (THRESHOLD_WINDOW_SEC * frames_per_sec).ceil() as usize;
+    let min_interval_frames = (MIN_INTERVAL_SEC * frames_per_sec).ceil() as usize;
+    let delta = 0.5 * (1.0 - sensitivity.clamp(0.0, 1.0)) + 0.01;
+
+    let mut onsets: Vec<usize> = Vec::new();
+    for i in 1..strength.len() {
+        let from = i.saturating_sub(threshold_frames);
+        let to = (i + threshold_frames + 1).min(strength.len());
+        let local = &strength[from..to];
+        let threshold = local.iter().sum::<f32>() / local.len() as f32 + delta;
+
+        let peak_from = i.saturating_sub(min_interval_frames);
+        let peak_to = (i + min_interval_frames + 1).min(strength.len());
+        let is_peak = strength[peak_from..peak_to].iter().all(|&s| s <= strength[i])
+            && strength[i] > strength[i - 1];
+        let spaced = onsets.last().is_none_or(|&last| i - last >= min_interval_frames);
+        if strength[i] > threshold && is_peak && spaced {
+            onsets.push(i);
+        }
+    }
+
+    // The flux of frame i measures the change since frame i - 1, which happens
+    // between the centers of the two frames
+    let offset = (fft_size / 2) as f64 - hop as f64 / 2.0;
+    onsets
+        .into_iter()
+        .map(|i| (i as f64 * hop as f64 + offset) / sample_rate as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{sine, white_noise};
+    use rstest::*;
+
+    /// Clicks (short decaying noise bursts) every `interval_sec`, starting at `first_sec`.
+    fn click_train(sample_rate: u32, interval_sec: f64, first_sec: f64, count: usize) -> (Vec<f32>, Vec<f64>) {
+        let burst = white_noise(0.9, 0.005, sample_rate, 1);
+        let duration = first_sec + interval_sec * count as f64;
+        let mut samples = white_noise(0.001, duration as f32, sample_rate, 2);
+        let times: Vec<f64> = (0..count).map(|i| first_sec + i as f64 * interval_sec).collect();
+        for &time in &times {
+            let start = (time * sample_rate as f64) as usize;
+            for (j, s) in burst.iter().enumerate() {
+                samples[start + j] += s * (-(j as f32) / 50.0).exp();
+            }
+        }
+        (samples, times)
+    }
+
+    #[rstest]
+    #[case(48000, 0.25)]
+    #[case(44100, 0.125)]
+    fn test_click_train(#[case] sample_rate: u32, #[case] interval_sec: f64) {
+        let (samples, clicks) = click_train(sample_rate, interval_sec, 0.1, 12);
+        let onsets = detect_onsets(&samples, sample_rate, 0.5);
+        assert_eq!(onsets.len(), clicks.len(), "{:?}", onsets);
+        for (onset, click) in onsets.iter().zip(&clicks) {
+            assert!((onset - click).abs() < 0.005, "onset {} for click at {}", onset, click);
+        }
+    }
+
+    #[rstest]
+    fn test_tone_onsets() {
+        // Two notes separated by a pause. The notes fade out, as a hard cut would be
+        // a transient of its own.
+        let sample_rate = 48000;
+        let note = |freq: f32| -> Vec<f32> {
+            let mut note = sine(freq, 0.5, 0.5, sample_rate);
+            let fade = 4800;
+            let len = note.len();
+            note[len - fade..].iter_mut().enumerate().for_each(|(i, s)| *s *= 1.0 - i as f32 / fade as f32);
+            note
+        };
+        let mut samples = vec![0.0f32; 4800];
+        samples.extend(note(440.0));
+        samples.extend(vec![0.0; 24000]);
+        samples.extend(note(660.0));
+
+        let onsets = detect_onsets(&samples, sample_rate, 0.5);
+        assert_eq!(onsets.len(), 2, "{:?}", onsets);
+        assert!((onsets[0] - 0.1).abs() < 0.005);
+        assert!((onsets[1] - 1.1).abs() < 0.005);
+    }
+
+    #[rstest]
+    fn test_sensitivity() {
+        // Alternating loud and quiet clicks
+        let (mut samples, _) = click_train(48000, 0.25, 0.1, 8);
+        for (i, chunk) in samples.chunks_mut(12000).enumerate() {
+            if i % 2 == 1 {
+                chunk.iter_mut().for_each(|s| *s *= 0.01);
+            }
+        }
+        let strict = detect_onsets(&samples, 48000, 0.0);
+        let sensitive = detect_onsets(&samples, 48000, 1.0);
+        assert!(strict.len() < sensitive.len(), "{:?} vs {:?}", strict, sensitive);
+    }
+
+    #[rstest]
+    fn test_silence_and_short_input() {
+        assert!(detect_onsets(&vec![0.0; 48000], 48000, 1.0).is_empty());
+        assert!(detect_onsets(&sine(440.0, 0.5, 1.0, 48000), 48000, 1.0).is_empty());
+        assert!(detect_onsets(&[0.5; 100], 48000, 1.0).is_empty());
+    }
+}
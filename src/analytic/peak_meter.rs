@@ -0,0 +1,205 @@
+//! Peak-hold metering with falling ballistics.
+//!
+//! A raw peak reading jumps with every sample and is hard to follow on screen.
+//! Classic level meters instead hold each peak for a moment and then let the
+//! reading fall at a steady rate. [`PeakMeter`] models this behaviour per channel,
+//! so a UI only needs to draw [`PeakMeter::current_db`] on every refresh.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::PeakMeter;
+//!
+//! // Stereo meter holding peaks for 1.5 s, then falling at 20 dB/s
+//! let mut meter = PeakMeter::new(2, 48000, 1.5, 20.0).unwrap();
+//!
+//! let block = vec![0.25f32; 2 * 256];
+//! meter.push(&block).unwrap();
+//! let levels = meter.current_db();
+//! println!("L: {:.1} dBFS, R: {:.1} dBFS", levels[0], levels[1]);
+//! ```
+
+use super::loudness::{check_format, MeterError};
+
+/// Peak reading of one channel.
+#[derive(Debug, Clone, Copy)]
+struct ChannelPeak {
+    /// Level of the last peak in dBFS
+    peak_db: f32,
+    /// Frames since the last peak
+    frames_since_peak: u64,
+}
+
+impl ChannelPeak {
+    const SILENT: Self = Self { peak_db: f32::NEG_INFINITY, frames_since_peak: 0 };
+}
+
+/// A peak meter with peak hold and a constant fall rate, per channel.
+///
+/// A new peak is shown immediately. It is held for `hold_time_sec` and then falls
+/// by `decay_db_per_sec` until a sample at or above the falling reading starts a new
+/// peak. Unlike the window peak of [`RealtimeMeter`](super::RealtimeMeter), the
+/// reading never drops abruptly, which is how the eye expects a meter to move.
+#[derive(Debug, Clone)]
+pub struct PeakMeter {
+    channels: u32,
+    sample_rate: u32,
+    hold_frames: u64,
+    decay_db_per_sec: f32,
+    peaks: Vec<ChannelPeak>,
+}
+
+impl PeakMeter {
+    /// Creates a new peak meter.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `hold_time_sec` - How long a peak is held before it falls, in seconds
+    /// * `decay_db_per_sec` - Fall rate after the hold time in dB per second,
+    ///   negative values are treated as 0 (the peak is held until a louder one)
+    ///
+    /// # Returns
+    ///
+    /// Returns the new PeakMeter, or a MeterError if the channel count or sample
+    /// rate is not supported.
+    pub fn new(
+        channels: u32,
+        sample_rate: u32,
+        hold_time_sec: f32,
+        decay_db_per_sec: f32,
+    ) -> Result<Self, MeterError> {
+        check_format(channels, sample_rate)?;
+        Ok(Self {
+            channels,
+            sample_rate,
+            hold_frames: (hold_time_sec.max(0.0) * sample_rate as f32).round() as u64,
+            decay_db_per_sec: decay_db_per_sec.max(0.0),
+            peaks: vec![ChannelPeak::SILENT; channels as usize],
+        })
+    }
+
+    /// Returns the hold time in seconds.
+    pub fn hold_time_sec(&self) -> f32 {
+        self.hold_frames as f32 / self.sample_rate as f32
+    }
+
+    /// Returns the fall rate in dB per second.
+    pub fn decay_db_per_sec(&self) -> f32 {
+        self.decay_db_per_sec
+    }
+
+    /// Adds a block of interleaved samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - Interleaved audio samples, the length must be a multiple of the channel count
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or a MeterError if the block is not a whole number
+    /// of frames. Nothing is measured from a rejected block.
+    pub fn push(&mut self, block: &[f32]) -> Result<(), MeterError> {
+        if !block.len().is_multiple_of(self.channels as usize) {
+            return Err(MeterError::PartialFrame { samples: block.len(), channels: self.channels });
+        }
+        for frame in block.chunks_exact(self.channels as usize) {
+            for (channel, &sample) in frame.iter().enumerate() {
+                let mut peak = self.peaks[channel];
+                peak.frames_since_peak += 1;
+                let level_db = 20.0 * sample.abs().log10();
+                if level_db >= self.reading(&peak) {
+                    peak = ChannelPeak { peak_db: level_db, frames_since_peak: 0 };
+                }
+                self.peaks[channel] = peak;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the current reading of each channel in dBFS.
+    ///
+    /// Negative infinity before any audio has been pushed or for silence.
+    pub fn current_db(&self) -> Vec<f32> {
+        self.peaks.iter().map(|peak| self.reading(peak)).collect()
+    }
+
+    /// Forgets all peaks.
+    pub fn reset(&mut self) {
+        self.peaks.iter_mut().for_each(|peak| *peak = ChannelPeak::SILENT);
+    }
+
+    /// Level shown for a channel: the peak during the hold time, then falling.
+    fn reading(&self, peak: &ChannelPeak) -> f32 {
+        let falling_frames = peak.frames_since_peak.saturating_sub(self.hold_frames);
+        peak.peak_db - self.decay_db_per_sec * falling_frames as f32 / self.sample_rate as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0.5, 20.0)]
+    #[case(1.0, 6.0)]
+    fn test_single_peak_holds_then_decays(#[case] hold_time_sec: f32, #[case] decay_db_per_sec: f32) {
+        let sample_rate = 1000;
+        let mut meter = PeakMeter::new(1, sample_rate, hold_time_sec, decay_db_per_sec).unwrap();
+        meter.push(&[0.5]).unwrap();
+        let peak_db = meter.current_db()[0];
+        assert!((peak_db + 6.02).abs() < 0.01);
+
+        // Held for the hold time, fed with quieter audio
+        let hold_frames = (hold_time_sec * sample_rate as f32) as usize;
+        for _ in 0..hold_frames / 10 {
+            meter.push(&[0.01; 10]).unwrap();
+            assert_eq!(meter.current_db()[0], peak_db);
+        }
+
+        // Then falling at the configured rate
+        for step in 1..=10 {
+            meter.push(&[0.0; 100]).unwrap();
+            let expected = peak_db - decay_db_per_sec * step as f32 * 0.1;
+            assert!((meter.current_db()[0] - expected).abs() < 1e-3, "step {}", step);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_and_new_peaks() {
+        let mut meter = PeakMeter::new(2, 1000, 0.1, 10.0).unwrap();
+        meter.push(&[1.0, 0.25, 0.0, 0.0]).unwrap();
+        let levels = meter.current_db();
+        assert_eq!(levels[0], 0.0);
+        assert!((levels[1] + 12.04).abs() < 0.01);
+
+        // A louder sample replaces a held peak at once, a quieter one doesn't
+        meter.push(&[0.5, 0.5]).unwrap();
+        assert_eq!(meter.current_db()[0], 0.0);
+        assert!((meter.current_db()[1] + 6.02).abs() < 0.01);
+
+        // Once the left reading has fallen below a sample it restarts from there
+        meter.push(&vec![0.0; 2 * 1000]).unwrap();
+        assert!((meter.current_db()[0] + 9.0).abs() < 0.1);
+        meter.push(&[0.5, 0.0]).unwrap();
+        assert!((meter.current_db()[0] + 6.02).abs() < 0.01);
+
+        meter.reset();
+        assert_eq!(meter.current_db(), vec![f32::NEG_INFINITY; 2]);
+        assert!((meter.hold_time_sec() - 0.1).abs() < 1e-6);
+        assert_eq!(meter.decay_db_per_sec(), 10.0);
+    }
+
+    #[rstest]
+    fn test_invalid_input() {
+        assert!(matches!(PeakMeter::new(0, 48000, 1.0, 20.0), Err(MeterError::InvalidChannels(0))));
+        let mut meter = PeakMeter::new(2, 48000, 1.0, 20.0).unwrap();
+        assert!(matches!(
+            meter.push(&[0.5; 3]),
+            Err(MeterError::PartialFrame { samples: 3, channels: 2 })
+        ));
+        assert_eq!(meter.current_db(), vec![f32::NEG_INFINITY; 2]);
+    }
+}
@@ -0,0 +1,142 @@
+//! Zero-crossing rate and rough pitch estimation.
+//!
+//! These are cheap time-domain estimators intended for quick monophonic readouts,
+//! e.g. showing the fundamental frequency of a voice. They expect mono input.
+
+/// Minimum normalized autocorrelation for a pitch estimate to be considered voiced.
+const PITCH_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Returns the zero-crossing rate in crossings per second.
+///
+/// A pure tone crosses zero twice per period, so a 100 Hz sine yields roughly
+/// 200 crossings per second. Noisy or unvoiced signals have a high rate.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples
+/// * `sample_rate` - Sample rate in Hz
+pub fn zero_crossing_rate(samples: &[f32], sample_rate: u32) -> f32 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let crossings = samples
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+    crossings as f32 * sample_rate as f32 / (samples.len() - 1) as f32
+}
+
+/// Estimates the fundamental frequency of a monophonic signal using autocorrelation.
+///
+/// The normalized autocorrelation is searched for the strongest period between the
+/// lags corresponding to `fmax` and `fmin`. If the best correlation is too weak the
+/// signal is treated as noise or unvoiced.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples, should cover at least two periods of `fmin`
+/// * `sample_rate` - Sample rate in Hz
+/// * `fmin` - Lowest frequency to consider in Hz
+/// * `fmax` - Highest frequency to consider in Hz
+///
+/// # Returns
+///
+/// Returns Some(frequency) in Hz, or None if no sufficiently periodic component
+/// was found in the given band.
+pub fn estimate_pitch_autocorr(samples: &[f32], sample_rate: u32, fmin: f32, fmax: f32) -> Option<f32> {
+    if fmin <= 0.0 || fmax <= fmin {
+        return None;
+    }
+    let min_lag = ((sample_rate as f32 / fmax).floor() as usize).max(1);
+    let max_lag = (sample_rate as f32 / fmin).ceil() as usize;
+    if max_lag + 1 >= samples.len() {
+        return None;
+    }
+
+    let correlation = |lag: usize| -> f32 {
+        let (head, tail) = (&samples[..samples.len() - lag], &samples[lag..]);
+        let cross: f32 = head.iter().zip(tail).map(|(a, b)| a * b).sum();
+        let energy_head: f32 = head.iter().map(|x| x * x).sum();
+        let energy_tail: f32 = tail.iter().map(|x| x * x).sum();
+        let norm = (energy_head * energy_tail).sqrt();
+        if norm > 0.0 { cross / norm } else { 0.0 }
+    };
+
+    let scores: Vec<f32> = (min_lag - 1..=max_lag + 1).map(correlation).collect();
+    // scores[i] corresponds to lag min_lag - 1 + i
+    let max_score = scores[1..scores.len() - 1]
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max);
+    if max_score < PITCH_CONFIDENCE_THRESHOLD {
+        return None;
+    }
+    // Multiples of the period correlate almost as well as the period itself, so take
+    // the first local peak close to the maximum to avoid octave errors.
+    let best = (1..scores.len() - 1).find(|&i| {
+        scores[i] >= 0.9 * max_score && scores[i] >= scores[i - 1] && scores[i] >= scores[i + 1]
+    })?;
+    let best_score = scores[best];
+
+    // Refine the lag with parabolic interpolation around the peak
+    let (prev, next) = (scores[best - 1], scores[best + 1]);
+    let denom = prev - 2.0 * best_score + next;
+    let offset = if denom.abs() > f32::EPSILON { 0.5 * (prev - next) / denom } else { 0.0 };
+    let lag = (min_lag - 1 + best) as f32 + offset;
+
+    Some(sample_rate as f32 / lag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::f32::consts::PI;
+
+    fn sine(freq: f32, sample_rate: u32, n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| 0.5 * (2.0 * PI * freq * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    fn white_noise(n: usize) -> Vec<f32> {
+        // Deterministic linear congruential generator
+        let mut state: u32 = 12345;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(100.0)]
+    #[case(440.0)]
+    fn test_zero_crossing_rate(#[case] freq: f32) {
+        let samples = sine(freq, 48000, 48000);
+        let zcr = zero_crossing_rate(&samples, 48000);
+        assert!((zcr - 2.0 * freq).abs() < 2.0, "zcr {}", zcr);
+    }
+
+    #[rstest]
+    #[case(220.0)]
+    #[case(137.0)]
+    fn test_pitch_of_sine(#[case] freq: f32) {
+        let samples = sine(freq, 48000, 4800);
+        let pitch = estimate_pitch_autocorr(&samples, 48000, 60.0, 1000.0).unwrap();
+        assert!((pitch - freq).abs() < 1.0, "estimated {} Hz", pitch);
+    }
+
+    #[rstest]
+    fn test_noise_is_unvoiced() {
+        let samples = white_noise(4800);
+        assert_eq!(estimate_pitch_autocorr(&samples, 48000, 60.0, 1000.0), None);
+    }
+
+    #[rstest]
+    fn test_too_short_input() {
+        let samples = sine(220.0, 48000, 100);
+        assert_eq!(estimate_pitch_autocorr(&samples, 48000, 60.0, 1000.0), None);
+    }
+}
@@ -0,0 +1,248 @@
+//! Incremental level metering for live input.
+//!
+//! [`Meter`](super::Meter) accumulates a whole programme for integrated loudness.
+//! A live meter instead needs the level of the last moments, updated with every
+//! small buffer from the audio callback. [`RealtimeMeter`] keeps sliding windows
+//! over the most recent audio and doesn't allocate once it is created.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::RealtimeMeter;
+//!
+//! // Stereo input with peak and RMS over the last 300 ms
+//! let mut meter = RealtimeMeter::new(2, 48000, 0.3).unwrap();
+//!
+//! let block = vec![0.25f32; 2 * 256];
+//! meter.push(&block).unwrap();
+//! println!(
+//!     "Peak: {:.1} dBFS, RMS: {:.1} dBFS, momentary: {:.1} LUFS",
+//!     meter.peak_dbfs(),
+//!     meter.rms_dbfs(),
+//!     meter.momentary_lufs()
+//! );
+//! ```
+
+use std::collections::VecDeque;
+
+use ebur128::{EbuR128, Mode};
+
+use super::loudness::{check_format, MeterError};
+
+/// A level meter updated block by block for real-time displays.
+///
+/// Peak and RMS levels are measured over a sliding window of configurable length
+/// across all channels. The momentary loudness always uses the 400 ms window
+/// defined by EBU R128. Until a full window has been pushed, the levels cover the
+/// audio seen so far.
+///
+/// All buffers are allocated by [`RealtimeMeter::new`], pushing audio and reading
+/// the levels doesn't allocate.
+pub struct RealtimeMeter {
+    loudness: EbuR128,
+    channels: u32,
+    /// The most recent samples, interleaved, as a ring buffer
+    window: Vec<f32>,
+    position: usize,
+    filled: usize,
+    sum_of_squares: f64,
+    /// Candidates for the window peak as (sample index, magnitude), magnitudes decreasing
+    peaks: VecDeque<(u64, f32)>,
+    samples_seen: u64,
+}
+
+impl RealtimeMeter {
+    /// Creates a new real-time meter.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `window_sec` - Length of the peak and RMS window in seconds, at least one frame
+    ///
+    /// # Returns
+    ///
+    /// Returns the new RealtimeMeter, or a MeterError if the channel count or sample
+    /// rate is not supported.
+    pub fn new(channels: u32, sample_rate: u32, window_sec: f32) -> Result<Self, MeterError> {
+        check_format(channels, sample_rate)?;
+        let loudness = EbuR128::new(channels, sample_rate, Mode::M)?;
+
+        let window_frames = ((window_sec.max(0.0) * sample_rate as f32).round() as usize).max(1);
+        let window_len = window_frames * channels as usize;
+        Ok(Self {
+            loudness,
+            channels,
+            window: vec![0.0; window_len],
+            position: 0,
+            filled: 0,
+            sum_of_squares: 0.0,
+            peaks: VecDeque::with_capacity(window_len),
+            samples_seen: 0,
+        })
+    }
+
+    /// Returns the length of the peak and RMS window in frames.
+    pub fn window_frames(&self) -> usize {
+        self.window.len() / self.channels as usize
+    }
+
+    /// Adds a block of interleaved samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - Interleaved audio samples, the length must be a multiple of the channel count
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or a MeterError if the block is not a whole number
+    /// of frames. Nothing is measured from a rejected block.
+    pub fn push(&mut self, block: &[f32]) -> Result<(), MeterError> {
+        if !block.len().is_multiple_of(self.channels as usize) {
+            return Err(MeterError::PartialFrame { samples: block.len(), channels: self.channels });
+        }
+        self.loudness.add_frames_f32(block)?;
+
+        let window_len = self.window.len();
+        for &sample in block {
+            let oldest = std::mem::replace(&mut self.window[self.position], sample);
+            self.sum_of_squares += (sample as f64).powi(2) - (oldest as f64).powi(2);
+            self.position += 1;
+            if self.position == window_len {
+                // Recompute the sum once per window so rounding errors don't accumulate
+                self.position = 0;
+                self.sum_of_squares = self.window.iter().map(|&s| (s as f64).powi(2)).sum();
+            }
+            self.filled = (self.filled + 1).min(window_len);
+
+            let magnitude = sample.abs();
+            while self.peaks.back().is_some_and(|&(_, peak)| peak <= magnitude) {
+                self.peaks.pop_back();
+            }
+            self.peaks.push_back((self.samples_seen, magnitude));
+            while self.peaks.front().is_some_and(|&(index, _)| index + window_len as u64 <= self.samples_seen) {
+                self.peaks.pop_front();
+            }
+            self.samples_seen += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the highest absolute sample value within the window in dBFS.
+    ///
+    /// Negative infinity before any audio has been pushed or for silence.
+    pub fn peak_dbfs(&self) -> f32 {
+        let peak = self.peaks.front().map_or(0.0, |&(_, peak)| peak);
+        20.0 * peak.log10()
+    }
+
+    /// Returns the RMS level within the window in dBFS.
+    ///
+    /// Negative infinity before any audio has been pushed or for silence.
+    pub fn rms_dbfs(&self) -> f32 {
+        if self.filled == 0 {
+            return f32::NEG_INFINITY;
+        }
+        let mean_square = self.sum_of_squares.max(0.0) / self.filled as f64;
+        10.0 * mean_square.log10() as f32
+    }
+
+    /// Returns the loudness of the last 400 ms in LUFS.
+    ///
+    /// Negative infinity for silence.
+    pub fn momentary_lufs(&self) -> f64 {
+        self.loudness.loudness_momentary().unwrap_or(f64::NEG_INFINITY)
+    }
+
+    /// Forgets all audio pushed so far.
+    pub fn reset(&mut self) {
+        self.loudness.reset();
+        self.window.iter_mut().for_each(|s| *s = 0.0);
+        self.position = 0;
+        self.filled = 0;
+        self.sum_of_squares = 0.0;
+        self.peaks.clear();
+        self.samples_seen = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::stats::{rms_dbfs, sample_peak_dbfs};
+    use crate::analytic::Meter;
+    use crate::synth::{sine, white_noise};
+    use rstest::*;
+
+    #[fixture]
+    fn program() -> Vec<f32> {
+        // Quiet noise, a loud burst and a fading tone, interleaved as stereo
+        let mut mono = white_noise(0.1, 1.0, 48000, 5);
+        mono.extend(white_noise(0.9, 0.2, 48000, 6));
+        mono.extend(sine(440.0, 0.5, 1.0, 48000).iter().enumerate().map(|(i, s)| s * (1.0 - i as f32 / 48000.0)));
+        mono.iter().zip(mono.iter().rev()).flat_map(|(&l, &r)| [l, r * 0.5]).collect()
+    }
+
+    #[rstest]
+    fn test_running_peak_matches_full_buffer(program: Vec<f32>) {
+        let window_sec = 0.1;
+        let mut meter = RealtimeMeter::new(2, 48000, window_sec).unwrap();
+        let window_len = meter.window_frames() * 2;
+        assert_eq!(window_len, 9600);
+
+        let mut pushed = 0;
+        for block in program.chunks(2 * 64) {
+            meter.push(block).unwrap();
+            pushed += block.len();
+
+            let window = &program[pushed.saturating_sub(window_len)..pushed];
+            assert_eq!(meter.peak_dbfs(), sample_peak_dbfs(window));
+            assert!((meter.rms_dbfs() - rms_dbfs(window)).abs() < 1e-3);
+        }
+
+        // A window longer than the program holds the peak of the whole buffer
+        let mut meter = RealtimeMeter::new(2, 48000, 10.0).unwrap();
+        program.chunks(2 * 100).for_each(|block| meter.push(block).unwrap());
+        assert_eq!(meter.peak_dbfs(), sample_peak_dbfs(&program));
+        assert!((meter.rms_dbfs() - rms_dbfs(&program)).abs() < 1e-3);
+    }
+
+    #[rstest]
+    fn test_momentary_matches_meter(program: Vec<f32>) {
+        let mut meter = RealtimeMeter::new(2, 48000, 0.3).unwrap();
+        program.chunks(2 * 480).for_each(|block| meter.push(block).unwrap());
+        let expected = Meter::from_samples(&program, 2, 48000).unwrap().lufs_momentary().unwrap();
+        assert!((meter.momentary_lufs() - expected).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_silence_and_reset() {
+        let mut meter = RealtimeMeter::new(1, 44100, 0.05).unwrap();
+        assert_eq!(meter.peak_dbfs(), f32::NEG_INFINITY);
+        assert_eq!(meter.rms_dbfs(), f32::NEG_INFINITY);
+
+        meter.push(&[0.5; 1000]).unwrap();
+        assert!((meter.peak_dbfs() + 6.02).abs() < 0.01);
+
+        // The peak falls out of the window
+        meter.push(&[0.0; 44100]).unwrap();
+        assert_eq!(meter.peak_dbfs(), f32::NEG_INFINITY);
+        assert!(meter.momentary_lufs() < -100.0);
+
+        meter.push(&[0.25; 10]).unwrap();
+        meter.reset();
+        assert_eq!(meter.peak_dbfs(), f32::NEG_INFINITY);
+        assert_eq!(meter.rms_dbfs(), f32::NEG_INFINITY);
+    }
+
+    #[rstest]
+    fn test_invalid_input() {
+        assert!(matches!(RealtimeMeter::new(0, 48000, 0.1), Err(MeterError::InvalidChannels(0))));
+        let mut meter = RealtimeMeter::new(2, 48000, 0.1).unwrap();
+        assert!(matches!(
+            meter.push(&[0.5; 3]),
+            Err(MeterError::PartialFrame { samples: 3, channels: 2 })
+        ));
+        assert_eq!(meter.peak_dbfs(), f32::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,325 @@
+//! Loudness reports for files and directories.
+//!
+//! This module measures audio files on disk, e.g. to check a whole podcast
+//! back-catalog at once. Every file gets a [`LoudnessReport`] with the metrics that
+//! platforms publish targets for.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::report_directory;
+//!
+//! for (path, report) in report_directory("episodes", true) {
+//!     match report {
+//!         Ok(report) => println!(
+//!             "{}: {:.1} LUFS, LRA {:.1} LU, {:.1} dBTP",
+//!             path.display(), report.integrated_lufs, report.loudness_range_lu, report.true_peak_dbtp
+//!         ),
+//!         Err(e) => println!("{}: {}", path.display(), e),
+//!     }
+//! }
+//! ```
+//!
+//! With the `rayon` feature, [`report_directory_parallel`] measures several files at
+//! once.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::io::{AudioReader, ReaderError};
+use super::{Meter, MeterError};
+
+/// Loudness metrics of one audio file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessReport {
+    /// Integrated loudness in LUFS, -inf for silence
+    pub integrated_lufs: f64,
+    /// Loudness range (LRA) in LU
+    pub loudness_range_lu: f64,
+    /// Highest true peak of all channels in dBTP
+    pub true_peak_dbtp: f64,
+}
+
+/// Errors that can occur while measuring a file for a [`LoudnessReport`].
+#[derive(Debug)]
+pub enum ReportError {
+    /// The file or directory could not be accessed
+    Io(io::Error),
+    /// The file could not be opened or decoded as audio
+    Read(ReaderError),
+    /// The audio format is not supported by the loudness meter
+    Meter(MeterError),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::Io(e) => write!(f, "failed to access: {}", e),
+            ReportError::Read(e) => write!(f, "failed to read audio: {}", e),
+            ReportError::Meter(e) => write!(f, "failed to measure: {}", e),
+        }
+    }
+}
+
+impl Error for ReportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReportError::Io(e) => Some(e),
+            ReportError::Read(e) => Some(e),
+            ReportError::Meter(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReportError {
+    fn from(e: io::Error) -> Self {
+        ReportError::Io(e)
+    }
+}
+
+impl From<ReaderError> for ReportError {
+    fn from(e: ReaderError) -> Self {
+        ReportError::Read(e)
+    }
+}
+
+impl From<MeterError> for ReportError {
+    fn from(e: MeterError) -> Self {
+        ReportError::Meter(e)
+    }
+}
+
+/// Measures the loudness of an audio file.
+///
+/// The file is decoded packet by packet, so it is never held in memory entirely.
+///
+/// # Arguments
+///
+/// * `path` - Path to an audio file in any format supported by [`AudioReader`]
+///
+/// # Returns
+///
+/// The [`LoudnessReport`] of the file, or a ReportError if it cannot be decoded or
+/// measured.
+pub fn report_file<P: AsRef<Path>>(path: P) -> Result<LoudnessReport, ReportError> {
+    let mut reader = AudioReader::new(path).map_err(ReaderError::from)?;
+    let mut meter = Meter::new(reader.channels() as u32, reader.sample_rate())?;
+    while let Some(samples) = reader.read_packet()? {
+        meter.add_frames_f32(&samples)?;
+    }
+
+    let peak = meter.true_peaks().unwrap_or_default().into_iter().fold(0.0, f64::max);
+    Ok(LoudnessReport {
+        integrated_lufs: meter.lufs_integrated().unwrap_or(f64::NEG_INFINITY),
+        loudness_range_lu: meter.loudness_range().unwrap_or(0.0),
+        true_peak_dbtp: 20.0 * peak.log10(),
+    })
+}
+
+/// Measures the loudness of every file in a directory.
+///
+/// All files are measured regardless of their extension. A file that fails to
+/// decode produces an `Err` entry and the scan continues with the next file.
+///
+/// # Arguments
+///
+/// * `dir` - Directory to scan
+/// * `recursive` - Whether to descend into subdirectories
+///
+/// # Returns
+///
+/// One entry per file, sorted by path, holding the file path and its
+/// [`LoudnessReport`] or the ReportError it failed with. A directory that can't be
+/// listed appears as an entry with a `ReportError::Io`.
+pub fn report_directory<P: AsRef<Path>>(dir: P, recursive: bool) -> Vec<(PathBuf, Result<LoudnessReport, ReportError>)> {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    collect_files(dir.as_ref(), recursive, &mut files, &mut errors);
+
+    let reports = files
+        .into_iter()
+        .map(|path| {
+            let report = report_file(&path);
+            (path, report)
+        })
+        .collect();
+    sorted_with_errors(reports, errors)
+}
+
+/// Measures the loudness of every file in a directory, several files at a time.
+///
+/// Like [`report_directory`], but the files are decoded and measured concurrently on
+/// a rayon thread pool. Each file is still measured on its own, so a file that fails
+/// produces an `Err` entry without affecting the others, and the result is the same
+/// as that of [`report_directory`].
+///
+/// # Arguments
+///
+/// * `dir` - Directory to scan
+/// * `recursive` - Whether to descend into subdirectories
+/// * `threads` - Number of threads to measure on, or None for rayon's global pool
+///   (one thread per CPU core by default). If a pool of this size can't be created
+///   the global pool is used.
+///
+/// # Returns
+///
+/// One entry per file, sorted by path, as returned by [`report_directory`].
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::analytic::report_directory_parallel;
+///
+/// let reports = report_directory_parallel("episodes", true, Some(4));
+/// let failed = reports.iter().filter(|(_, report)| report.is_err()).count();
+/// println!("{} files, {} failed", reports.len(), failed);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn report_directory_parallel<P: AsRef<Path>>(
+    dir: P,
+    recursive: bool,
+    threads: Option<usize>,
+) -> Vec<(PathBuf, Result<LoudnessReport, ReportError>)> {
+    use rayon::prelude::*;
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    collect_files(dir.as_ref(), recursive, &mut files, &mut errors);
+
+    let measure = move || {
+        files
+            .into_par_iter()
+            .map(|path| {
+                let report = report_file(&path);
+                (path, report)
+            })
+            .collect()
+    };
+    let pool = threads.and_then(|threads| rayon::ThreadPoolBuilder::new().num_threads(threads).build().ok());
+    let reports = match pool {
+        Some(pool) => pool.install(measure),
+        None => measure(),
+    };
+    sorted_with_errors(reports, errors)
+}
+
+/// Adds an entry for each directory that couldn't be listed and sorts all entries by path.
+fn sorted_with_errors(
+    mut reports: Vec<(PathBuf, Result<LoudnessReport, ReportError>)>,
+    errors: Vec<(PathBuf, io::Error)>,
+) -> Vec<(PathBuf, Result<LoudnessReport, ReportError>)> {
+    reports.extend(errors.into_iter().map(|(path, e)| (path, Err(ReportError::Io(e)))));
+    reports.sort_by(|a, b| a.0.cmp(&b.0));
+    reports
+}
+
+/// Adds the files in `dir` to `files`, and directories that can't be listed to `errors`.
+fn collect_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>, errors: &mut Vec<(PathBuf, io::Error)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push((dir.to_path_buf(), e));
+            return;
+        }
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                errors.push((dir.to_path_buf(), e));
+                continue;
+            }
+        };
+        if path.is_dir() {
+            if recursive {
+                collect_files(&path, recursive, files, errors);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::AudioWriter;
+    use crate::synth::sine;
+    use rstest::*;
+
+    /// Creates a directory with a WAV at the top, a WAV in a subdirectory and a bogus file.
+    fn catalog(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sonex_report_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("season2")).unwrap();
+
+        for (path, amplitude) in [(dir.join("ep1.wav"), 0.5), (dir.join("season2").join("ep2.wav"), 0.1)] {
+            let mut writer = AudioWriter::new(path, 1, 48000).unwrap();
+            writer.write_samples(&sine(1000.0, amplitude, 3.0, 48000)).unwrap();
+            writer.finalize().unwrap();
+        }
+        fs::write(dir.join("notes.wav"), b"not audio at all").unwrap();
+        dir
+    }
+
+    #[rstest]
+    fn test_recursive_scan_reports_errors() {
+        let catalog = catalog("recursive");
+        let reports = report_directory(&catalog, true);
+        let names: Vec<_> = reports.iter().map(|(path, _)| path.strip_prefix(&catalog).unwrap()).collect();
+        assert_eq!(names, [Path::new("ep1.wav"), Path::new("notes.wav"), Path::new("season2/ep2.wav")]);
+
+        assert_eq!(reports.iter().filter(|(_, report)| report.is_ok()).count(), 2);
+        assert!(matches!(reports[1].1, Err(ReportError::Read(_))));
+
+        // A 1 kHz sine of amplitude 0.5 measures about -9 LUFS with a steady level
+        let loud = reports[0].1.as_ref().unwrap();
+        assert!((loud.integrated_lufs + 9.0).abs() < 0.5, "{:?}", loud);
+        assert!(loud.loudness_range_lu < 0.5);
+        assert!((loud.true_peak_dbtp + 6.02).abs() < 0.1);
+
+        let quiet = reports[2].1.as_ref().unwrap();
+        assert!((loud.integrated_lufs - quiet.integrated_lufs - 13.98).abs() < 0.1);
+
+        fs::remove_dir_all(catalog).unwrap();
+    }
+
+    #[rstest]
+    fn test_flat_scan_skips_subdirectories() {
+        let catalog = catalog("flat");
+        let reports = report_directory(&catalog, false);
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].1.is_ok());
+        assert!(reports[1].1.is_err());
+        fs::remove_dir_all(catalog).unwrap();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[rstest]
+    #[case(None)]
+    #[case(Some(1))]
+    #[case(Some(3))]
+    fn test_parallel_scan_matches_serial(#[case] threads: Option<usize>) {
+        let catalog = catalog(&format!("parallel_{:?}", threads));
+        let summary = |reports: Vec<(PathBuf, Result<LoudnessReport, ReportError>)>| -> Vec<_> {
+            reports.into_iter().map(|(path, report)| (path, report.ok())).collect()
+        };
+        let serial = summary(report_directory(&catalog, true));
+        let parallel = summary(report_directory_parallel(&catalog, true, threads));
+        assert_eq!(parallel.len(), 3);
+        assert_eq!(parallel, serial);
+
+        assert_eq!(report_directory_parallel("does/not/exist", true, threads).len(), 1);
+        fs::remove_dir_all(catalog).unwrap();
+    }
+
+    #[rstest]
+    fn test_missing_directory() {
+        let reports = report_directory("does/not/exist", true);
+        assert_eq!(reports.len(), 1);
+        assert!(matches!(reports[0].1, Err(ReportError::Io(_))));
+    }
+}
@@ -0,0 +1,106 @@
+//! Silence detection.
+//!
+//! This module finds regions of audio that stay below a level threshold, which is
+//! useful for auto-editing podcasts (e.g. finding pauses or dead air at the start
+//! and end of a recording).
+//!
+//! Detection operates on frames: a frame is considered silent only when every
+//! channel in it is below the threshold, so channel alignment is always preserved.
+
+/// Returns whether each frame of an interleaved buffer is below the threshold.
+pub(crate) fn silent_frames(samples: &[f32], channels: usize, threshold_dbfs: f32) -> Vec<bool> {
+    let threshold = 10.0_f32.powf(threshold_dbfs / 20.0);
+    samples
+        .chunks(channels.max(1))
+        .map(|frame| frame.iter().all(|sample| sample.abs() < threshold))
+        .collect()
+}
+
+/// Detects time ranges in which the audio stays below a level threshold.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+/// * `sample_rate` - Sample rate in Hz
+/// * `threshold_dbfs` - Level in dBFS below which a frame counts as silent (e.g. -60.0)
+/// * `min_duration_sec` - Minimum length of a silent region to be reported
+///
+/// # Returns
+///
+/// A vector of `(start, end)` times in seconds for every silent region at least
+/// `min_duration_sec` long, in chronological order.
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::analytic::detect_silence;
+///
+/// let samples = vec![0.0f32; 48000];
+/// let silence = detect_silence(&samples, 1, 48000, -60.0, 0.5);
+/// assert_eq!(silence, vec![(0.0, 1.0)]);
+/// ```
+pub fn detect_silence(
+    samples: &[f32],
+    channels: usize,
+    sample_rate: u32,
+    threshold_dbfs: f32,
+    min_duration_sec: f64,
+) -> Vec<(f64, f64)> {
+    let min_frames = (min_duration_sec * sample_rate as f64).ceil() as usize;
+    let to_sec = |frame: usize| frame as f64 / sample_rate as f64;
+
+    let silent = silent_frames(samples, channels, threshold_dbfs);
+    let mut regions = Vec::new();
+    let mut start = None;
+    for (i, &is_silent) in silent.iter().chain(std::iter::once(&false)).enumerate() {
+        match (is_silent, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                if i - s >= min_frames.max(1) {
+                    regions.push((to_sec(s), to_sec(i)));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// 0.5 s silence, 1 s of stereo tone, 0.25 s silence at 1 kHz sample rate
+    #[fixture]
+    fn padded() -> Vec<f32> {
+        let mut samples = vec![0.0f32; 500 * 2];
+        for i in 0..1000 {
+            let s = 0.5 * (2.0 * std::f32::consts::PI * 50.0 * (i as f32 + 0.5) / 1000.0).sin();
+            samples.extend([s, -s]);
+        }
+        samples.extend(vec![0.0f32; 250 * 2]);
+        samples
+    }
+
+    #[rstest]
+    fn test_detects_leading_and_trailing_silence(padded: Vec<f32>) {
+        let regions = detect_silence(&padded, 2, 1000, -60.0, 0.1);
+        assert_eq!(regions, vec![(0.0, 0.5), (1.5, 1.75)]);
+    }
+
+    #[rstest]
+    fn test_min_duration_filters_short_regions(padded: Vec<f32>) {
+        let regions = detect_silence(&padded, 2, 1000, -60.0, 0.3);
+        assert_eq!(regions, vec![(0.0, 0.5)]);
+    }
+
+    #[rstest]
+    fn test_frame_is_loud_if_any_channel_is_loud() {
+        // Left channel silent, right channel loud
+        let samples: Vec<f32> = (0..1000).flat_map(|_| [0.0, 0.5]).collect();
+        assert!(detect_silence(&samples, 2, 1000, -60.0, 0.1).is_empty());
+    }
+}
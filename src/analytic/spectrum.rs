@@ -0,0 +1,479 @@
+//! Frequency spectrum analysis based on the FFT.
+//!
+//! This module computes magnitude spectra of audio using [`rustfft`]. All functions
+//! expect mono input; multi-channel audio should be downmixed (e.g. by averaging the
+//! channels of each frame) before analysis.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::spectrum::{frequency_bins, magnitude_spectrum, WindowType};
+//!
+//! let samples = vec![0.0f32; 4096];
+//! let magnitudes = magnitude_spectrum(&samples, WindowType::Hann);
+//! let frequencies = frequency_bins(samples.len(), 48000);
+//!
+//! for (freq, mag) in frequencies.iter().zip(magnitudes.iter()) {
+//!     println!("{:.1} Hz: {:.3}", freq, mag);
+//! }
+//! ```
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+use super::window;
+
+/// Window functions applied to a block of samples before the FFT.
+///
+/// Windowing reduces spectral leakage caused by analysing a finite block of a
+/// signal. `Rectangular` applies no window, the others trade frequency resolution
+/// for lower side lobes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowType {
+    /// No windowing (all coefficients are 1.0)
+    Rectangular,
+    /// Hann (raised cosine) window, a good default for general analysis
+    Hann,
+    /// Hamming window
+    Hamming,
+    /// Blackman window, lowest side lobes of the available windows
+    Blackman,
+}
+
+impl WindowType {
+    /// Returns the window coefficients for a block of `n` samples.
+    ///
+    /// See the [`window`](super::window) module for the window functions themselves.
+    pub fn coefficients(&self, n: usize) -> Vec<f32> {
+        match self {
+            WindowType::Rectangular => vec![1.0; n],
+            WindowType::Hann => window::hann(n),
+            WindowType::Hamming => window::hamming(n),
+            WindowType::Blackman => window::blackman(n),
+        }
+    }
+}
+
+/// Computes the single-sided magnitude spectrum of a block of mono samples.
+///
+/// The whole slice is analysed as one FFT frame. The magnitudes are scaled by the
+/// window gain so that a sine of amplitude `A` shows a peak of roughly `A`.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples; downmix multi-channel audio first
+/// * `window` - Window function applied before the FFT
+///
+/// # Returns
+///
+/// A vector of `samples.len() / 2 + 1` magnitudes from DC up to the Nyquist
+/// frequency. Use [`frequency_bins`] to get the matching frequencies.
+pub fn magnitude_spectrum(samples: &[f32], window: WindowType) -> Vec<f32> {
+    let n = samples.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let fft = FftPlanner::new().plan_fft_forward(n);
+    windowed_magnitudes(fft.as_ref(), samples, &window.coefficients(n))
+}
+
+/// Windows `samples` (zero-padded to the FFT length) and returns the scaled
+/// single-sided magnitudes.
+pub(crate) fn windowed_magnitudes(fft: &dyn Fft<f32>, samples: &[f32], coefficients: &[f32]) -> Vec<f32> {
+    let n = fft.len();
+    let window_sum: f32 = coefficients.iter().sum();
+
+    let mut buffer: Vec<Complex<f32>> = coefficients
+        .iter()
+        .enumerate()
+        .map(|(i, &w)| Complex::new(samples.get(i).copied().unwrap_or(0.0) * w, 0.0))
+        .collect();
+    fft.process(&mut buffer);
+
+    buffer[..n / 2 + 1]
+        .iter()
+        .enumerate()
+        .map(|(k, c)| {
+            // DC and Nyquist have no mirrored counterpart in the discarded half
+            let scale = if k == 0 || 2 * k == n { 1.0 } else { 2.0 };
+            scale * c.norm() / window_sum
+        })
+        .collect()
+}
+
+/// Returns the center frequency in Hz of each bin of an `n`-point magnitude spectrum.
+///
+/// # Arguments
+///
+/// * `n` - FFT size, i.e. the number of samples passed to [`magnitude_spectrum`]
+/// * `sample_rate` - Sample rate in Hz
+pub fn frequency_bins(n: usize, sample_rate: u32) -> Vec<f32> {
+    if n == 0 {
+        return Vec::new();
+    }
+    (0..n / 2 + 1)
+        .map(|k| k as f32 * sample_rate as f32 / n as f32)
+        .collect()
+}
+
+/// Returns the frequency in Hz of the strongest bin of the Hann-windowed spectrum.
+#[cfg(test)]
+pub(crate) fn peak_frequency(samples: &[f32], sample_rate: u32) -> f32 {
+    let spectrum = magnitude_spectrum(samples, WindowType::Hann);
+    let bins = frequency_bins(samples.len(), sample_rate);
+    let peak = spectrum.iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap();
+    bins[peak]
+}
+
+/// Short-time magnitude spectra of a signal, e.g. for rendering a spectrogram.
+///
+/// `magnitudes[frame][bin]` holds the magnitude of frequency `frequencies[bin]` for
+/// the analysis frame starting at `times[frame]` seconds.
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    /// Magnitude spectrum of each frame, see [`magnitude_spectrum`] for the scaling
+    pub magnitudes: Vec<Vec<f32>>,
+    /// Start time of each frame in seconds
+    pub times: Vec<f32>,
+    /// Center frequency of each bin in Hz
+    pub frequencies: Vec<f32>,
+}
+
+/// Computes a spectrogram using the short-time Fourier transform (STFT).
+///
+/// The signal is split into frames of `fft_size` samples, advancing by `hop_size`
+/// samples each time. The final frame is zero-padded if the signal does not fill it.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples; downmix multi-channel audio first
+/// * `sample_rate` - Sample rate in Hz
+/// * `fft_size` - Number of samples per frame
+/// * `hop_size` - Number of samples between the start of consecutive frames
+/// * `window` - Window function applied to each frame
+///
+/// # Panics
+///
+/// Panics if `fft_size` or `hop_size` is zero.
+pub fn spectrogram(
+    samples: &[f32],
+    sample_rate: u32,
+    fft_size: usize,
+    hop_size: usize,
+    window: WindowType,
+) -> Spectrogram {
+    assert!(fft_size > 0, "fft_size must be greater than zero");
+    assert!(hop_size > 0, "hop_size must be greater than zero");
+
+    let fft = FftPlanner::new().plan_fft_forward(fft_size);
+    let coefficients = window.coefficients(fft_size);
+
+    let starts: Vec<usize> = (0..samples.len()).step_by(hop_size).collect();
+    let magnitudes = starts
+        .iter()
+        .map(|&start| {
+            let end = (start + fft_size).min(samples.len());
+            windowed_magnitudes(fft.as_ref(), &samples[start..end], &coefficients)
+        })
+        .collect();
+    let times = starts
+        .iter()
+        .map(|&start| start as f32 / sample_rate as f32)
+        .collect();
+
+    Spectrogram {
+        magnitudes,
+        times,
+        frequencies: frequency_bins(fft_size, sample_rate),
+    }
+}
+
+/// Returns the frequency in Hz of bin `k` of a single-sided spectrum with `bins` bins.
+fn bin_frequency(k: usize, bins: usize, sample_rate: u32) -> f32 {
+    if bins <= 1 {
+        return 0.0;
+    }
+    k as f32 * sample_rate as f32 / (2 * (bins - 1)) as f32
+}
+
+/// Computes the spectral centroid, the magnitude-weighted mean frequency.
+///
+/// The centroid is the "center of mass" of the spectrum and correlates with the
+/// perceived brightness of a sound.
+///
+/// # Arguments
+///
+/// * `mag` - Single-sided magnitude spectrum as returned by [`magnitude_spectrum`]
+/// * `sample_rate` - Sample rate in Hz of the analysed signal
+///
+/// # Returns
+///
+/// The centroid in Hz, 0.0 for an empty or all-zero spectrum.
+pub fn spectral_centroid(mag: &[f32], sample_rate: u32) -> f32 {
+    let total: f32 = mag.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let weighted: f32 = mag
+        .iter()
+        .enumerate()
+        .map(|(k, m)| bin_frequency(k, mag.len(), sample_rate) * m)
+        .sum();
+    weighted / total
+}
+
+/// Computes the spectral rolloff, the frequency below which a given share of the
+/// spectral energy lies.
+///
+/// # Arguments
+///
+/// * `mag` - Single-sided magnitude spectrum as returned by [`magnitude_spectrum`]
+/// * `sample_rate` - Sample rate in Hz of the analysed signal
+/// * `percent` - Share of the energy in percent, commonly 85.0 or 95.0. Clamped to
+///   [0.0, 100.0].
+///
+/// # Returns
+///
+/// The frequency in Hz of the first bin at which the cumulative energy reaches
+/// `percent` of the total, 0.0 for an empty or all-zero spectrum.
+pub fn spectral_rolloff(mag: &[f32], sample_rate: u32, percent: f32) -> f32 {
+    let total: f64 = mag.iter().map(|&m| (m as f64).powi(2)).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let threshold = total * percent.clamp(0.0, 100.0) as f64 / 100.0;
+
+    let mut cumulative = 0.0;
+    for (k, &m) in mag.iter().enumerate() {
+        cumulative += (m as f64).powi(2);
+        if cumulative >= threshold {
+            return bin_frequency(k, mag.len(), sample_rate);
+        }
+    }
+    bin_frequency(mag.len() - 1, mag.len(), sample_rate)
+}
+
+/// Computes the spectral flatness (Wiener entropy) of the power spectrum.
+///
+/// Flatness is the ratio of the geometric to the arithmetic mean of the power in each
+/// bin. It approaches 1.0 for noise-like spectra with equal power everywhere and 0.0
+/// for tonal spectra concentrated in a few bins. A single frame of white noise
+/// fluctuates from bin to bin, so average the spectra of several frames for a
+/// stable estimate.
+///
+/// # Arguments
+///
+/// * `mag` - Magnitude spectrum as returned by [`magnitude_spectrum`]
+///
+/// # Returns
+///
+/// The flatness in [0.0, 1.0]. 0.0 for an empty or all-zero spectrum, and for any
+/// spectrum with a bin of exactly zero.
+pub fn spectral_flatness(mag: &[f32]) -> f32 {
+    if mag.is_empty() {
+        return 0.0;
+    }
+    let n = mag.len() as f64;
+    let arithmetic = mag.iter().map(|&m| (m as f64).powi(2)).sum::<f64>() / n;
+    if arithmetic <= 0.0 {
+        return 0.0;
+    }
+    let geometric = (mag.iter().map(|&m| ((m as f64).powi(2)).ln()).sum::<f64>() / n).exp();
+    (geometric / arithmetic) as f32
+}
+
+/// Computes the spectral flux between two consecutive spectra.
+///
+/// Only increases in magnitude are counted (half-wave rectified flux), so the flux
+/// peaks at onsets where new energy appears and stays low while sounds decay.
+///
+/// # Arguments
+///
+/// * `prev_mag` - Magnitude spectrum of the previous frame
+/// * `mag` - Magnitude spectrum of the current frame, with the same number of bins
+///
+/// # Returns
+///
+/// The Euclidean norm of the positive magnitude differences, 0.0 if nothing got
+/// louder (including two all-zero spectra).
+///
+/// # Panics
+///
+/// Panics if the spectra have different lengths.
+pub fn spectral_flux(prev_mag: &[f32], mag: &[f32]) -> f32 {
+    assert_eq!(prev_mag.len(), mag.len(), "spectra must have the same number of bins");
+    prev_mag
+        .iter()
+        .zip(mag)
+        .map(|(prev, current)| (current - prev).max(0.0).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::white_noise;
+    use std::f32::consts::PI;
+    use rstest::*;
+
+    fn sine(freq: f32, amplitude: f32, sample_rate: u32, n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(WindowType::Rectangular)]
+    #[case(WindowType::Hann)]
+    #[case(WindowType::Hamming)]
+    #[case(WindowType::Blackman)]
+    fn test_peak_at_1khz(#[case] window: WindowType) {
+        let n = 4096;
+        let samples = sine(1000.0, 0.5, 48000, n);
+        let magnitudes = magnitude_spectrum(&samples, window);
+        let frequencies = frequency_bins(n, 48000);
+        assert_eq!(magnitudes.len(), frequencies.len());
+
+        let (peak_bin, peak) = magnitudes.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let bin_width = 48000.0 / n as f32;
+        assert!((frequencies[peak_bin] - 1000.0).abs() <= bin_width);
+        assert!(*peak > 0.3 && *peak <= 0.51, "peak magnitude {}", peak);
+    }
+
+    #[rstest]
+    fn test_frequency_bins() {
+        let bins = frequency_bins(8, 8000);
+        assert_eq!(bins, vec![0.0, 1000.0, 2000.0, 3000.0, 4000.0]);
+    }
+
+    #[rstest]
+    #[case(WindowType::Hann, 0.0, 0.0)]
+    #[case(WindowType::Hamming, 0.08, 0.08)]
+    #[case(WindowType::Rectangular, 1.0, 1.0)]
+    fn test_window_edges(#[case] window: WindowType, #[case] first: f32, #[case] last: f32) {
+        let coefficients = window.coefficients(16);
+        assert!((coefficients[0] - first).abs() < 1e-6);
+        assert!((coefficients[15] - last).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_spectrogram_chirp() {
+        let sample_rate = 16000;
+        let duration = 2.0;
+        let (f0, f1) = (200.0, 6000.0);
+        let n = (duration * sample_rate as f32) as usize;
+        // Linear chirp: instantaneous frequency rises from f0 to f1
+        let chirp: Vec<f32> = (0..n)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                (2.0 * PI * (f0 * t + (f1 - f0) / (2.0 * duration) * t * t)).sin()
+            })
+            .collect();
+
+        let spec = spectrogram(&chirp, sample_rate, 1024, 512, WindowType::Hann);
+        assert_eq!(spec.magnitudes.len(), spec.times.len());
+        assert_eq!(spec.frequencies.len(), 513);
+        assert!(spec.magnitudes.iter().all(|frame| frame.len() == 513));
+
+        let peak_bins: Vec<usize> = spec.magnitudes.iter()
+            .map(|frame| {
+                frame.iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .unwrap()
+                    .0
+            })
+            .collect();
+        // Skip the trailing frames which are mostly zero padding
+        let full_frames = (n - 1024) / 512 + 1;
+        for pair in peak_bins[..full_frames].windows(2) {
+            assert!(pair[1] > pair[0], "peak bins should increase: {:?}", peak_bins);
+        }
+    }
+
+    #[rstest]
+    fn test_spectrogram_zero_pads_last_frame() {
+        let samples = vec![1.0f32; 1000];
+        let spec = spectrogram(&samples, 1000, 256, 256, WindowType::Rectangular);
+        assert_eq!(spec.times, vec![0.0, 0.256, 0.512, 0.768]);
+        // The last frame only holds 232 samples, the rest is padding
+        assert!((spec.magnitudes[3][0] - 232.0 / 256.0).abs() < 1e-4);
+    }
+
+    fn averaged_spectrum(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+        let spec = spectrogram(samples, sample_rate, 1024, 1024, WindowType::Hann);
+        let frames = spec.magnitudes.len() as f32;
+        (0..spec.frequencies.len())
+            .map(|bin| spec.magnitudes.iter().map(|frame| frame[bin]).sum::<f32>() / frames)
+            .collect()
+    }
+
+    #[rstest]
+    fn test_bright_signal_has_higher_centroid_and_rolloff() {
+        let sample_rate = 48000;
+        let n = 4096;
+        let dull = sine(200.0, 0.5, sample_rate, n);
+        let bright: Vec<f32> = dull
+            .iter()
+            .zip(sine(6000.0, 0.5, sample_rate, n))
+            .map(|(a, b)| a + b)
+            .collect();
+
+        let dull_mag = magnitude_spectrum(&dull, WindowType::Hann);
+        let bright_mag = magnitude_spectrum(&bright, WindowType::Hann);
+        let dull_centroid = spectral_centroid(&dull_mag, sample_rate);
+        let bright_centroid = spectral_centroid(&bright_mag, sample_rate);
+        assert!((dull_centroid - 200.0).abs() < 20.0, "dull centroid {}", dull_centroid);
+        assert!((bright_centroid - 3100.0).abs() < 100.0, "bright centroid {}", bright_centroid);
+
+        assert!(spectral_rolloff(&dull_mag, sample_rate, 85.0) < 250.0);
+        let rolloff = spectral_rolloff(&bright_mag, sample_rate, 85.0);
+        assert!((rolloff - 6000.0).abs() < 50.0, "rolloff {}", rolloff);
+        // Half of the energy lies in the low tone
+        assert!(spectral_rolloff(&bright_mag, sample_rate, 40.0) < 250.0);
+    }
+
+    #[rstest]
+    fn test_flatness_of_noise_and_tone() {
+        let noise = white_noise(0.5, 4.0, 48000, 11);
+        let flatness = spectral_flatness(&averaged_spectrum(&noise, 48000)[1..512]);
+        assert!(flatness > 0.95, "noise flatness {}", flatness);
+
+        let tone = sine(1000.0, 0.5, 48000, 48000);
+        let flatness = spectral_flatness(&averaged_spectrum(&tone, 48000));
+        assert!(flatness < 0.01, "tone flatness {}", flatness);
+    }
+
+    #[rstest]
+    fn test_flux() {
+        let quiet = vec![0.1f32, 0.2, 0.3];
+        let onset = vec![0.5f32, 0.2, 0.0];
+        assert!((spectral_flux(&quiet, &onset) - 0.4).abs() < 1e-6);
+        // Only the third bin got louder, the decay of the first one doesn't count
+        assert!((spectral_flux(&onset, &quiet) - 0.3).abs() < 1e-6);
+        assert_eq!(spectral_flux(&quiet, &quiet), 0.0);
+    }
+
+    #[rstest]
+    fn test_features_of_silence() {
+        let zeros = vec![0.0f32; 513];
+        assert_eq!(spectral_centroid(&zeros, 48000), 0.0);
+        assert_eq!(spectral_rolloff(&zeros, 48000, 85.0), 0.0);
+        assert_eq!(spectral_flatness(&zeros), 0.0);
+        assert_eq!(spectral_flux(&zeros, &zeros), 0.0);
+        assert_eq!(spectral_centroid(&[], 48000), 0.0);
+        assert_eq!(spectral_flatness(&[]), 0.0);
+    }
+
+    #[rstest]
+    fn test_empty_input() {
+        assert!(magnitude_spectrum(&[], WindowType::Hann).is_empty());
+    }
+}
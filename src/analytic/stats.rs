@@ -0,0 +1,174 @@
+//! Basic level statistics: sample peak, RMS and crest factor.
+//!
+//! These are plain technical measurements that work directly on sample buffers and
+//! don't require constructing a full loudness [`Meter`](super::Meter). All levels are
+//! reported in dBFS, where 0 dBFS corresponds to a full-scale sample value of 1.0.
+//!
+//! The functions without a channel argument treat the interleaved buffer as a whole,
+//! the `_per_channel` variants return one value per channel.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::stats;
+//!
+//! let samples = vec![0.5f32, -0.5, 0.25, -0.25];
+//! println!("Peak: {} dBFS", stats::sample_peak_dbfs(&samples));
+//! println!("RMS: {} dBFS", stats::rms_dbfs(&samples));
+//! println!("Crest factor: {} dB", stats::crest_factor_db(&samples));
+//! ```
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.log10()
+}
+
+fn peak(samples: impl Iterator<Item = f32>) -> f32 {
+    samples.fold(0.0, |peak, sample| peak.max(sample.abs()))
+}
+
+fn rms(samples: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = samples.fold((0.0f64, 0usize), |(sum, count), sample| {
+        (sum + (sample as f64) * (sample as f64), count + 1)
+    });
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).sqrt() as f32
+    }
+}
+
+fn crest_factor(peak: f32, rms: f32) -> f32 {
+    if rms > 0.0 {
+        amplitude_to_db(peak / rms)
+    } else {
+        0.0
+    }
+}
+
+fn channel(samples: &[f32], channels: usize, ch: usize) -> impl Iterator<Item = f32> + '_ {
+    samples.iter().skip(ch).step_by(channels).copied()
+}
+
+/// Returns the highest absolute sample value in dBFS.
+///
+/// Silent or empty input returns negative infinity.
+pub fn sample_peak_dbfs(samples: &[f32]) -> f32 {
+    amplitude_to_db(peak(samples.iter().copied()))
+}
+
+/// Returns the RMS level in dBFS.
+///
+/// Note that a full-scale sine wave measures -3.01 dBFS RMS.
+/// Silent or empty input returns negative infinity.
+pub fn rms_dbfs(samples: &[f32]) -> f32 {
+    amplitude_to_db(rms(samples.iter().copied()))
+}
+
+/// Returns the crest factor (peak-to-RMS ratio) in dB.
+///
+/// A sine wave has a crest factor of about 3.01 dB and a square wave 0 dB.
+/// Silent or empty input returns 0 dB.
+pub fn crest_factor_db(samples: &[f32]) -> f32 {
+    crest_factor(peak(samples.iter().copied()), rms(samples.iter().copied()))
+}
+
+/// Returns the sample peak in dBFS for each channel of an interleaved buffer.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+pub fn sample_peak_dbfs_per_channel(samples: &[f32], channels: usize) -> Vec<f32> {
+    (0..channels)
+        .map(|ch| amplitude_to_db(peak(channel(samples, channels, ch))))
+        .collect()
+}
+
+/// Returns the RMS level in dBFS for each channel of an interleaved buffer.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+pub fn rms_dbfs_per_channel(samples: &[f32], channels: usize) -> Vec<f32> {
+    (0..channels)
+        .map(|ch| amplitude_to_db(rms(channel(samples, channels, ch))))
+        .collect()
+}
+
+/// Returns the crest factor in dB for each channel of an interleaved buffer.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+pub fn crest_factor_db_per_channel(samples: &[f32], channels: usize) -> Vec<f32> {
+    (0..channels)
+        .map(|ch| crest_factor(peak(channel(samples, channels, ch)), rms(channel(samples, channels, ch))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::f32::consts::PI;
+
+    #[fixture]
+    fn sine() -> Vec<f32> {
+        // 100 full periods of a 480 Hz sine at 48 kHz
+        (0..10000)
+            .map(|i| 0.5 * (2.0 * PI * 480.0 * i as f32 / 48000.0).sin())
+            .collect()
+    }
+
+    #[fixture]
+    fn square() -> Vec<f32> {
+        (0..10000)
+            .map(|i| if (i / 50) % 2 == 0 { 0.5 } else { -0.5 })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_sine(sine: Vec<f32>) {
+        assert!((sample_peak_dbfs(&sine) - -6.0206).abs() < 0.01);
+        assert!((rms_dbfs(&sine) - -9.0309).abs() < 0.01);
+        assert!((crest_factor_db(&sine) - 3.0103).abs() < 0.01);
+    }
+
+    #[rstest]
+    fn test_square(square: Vec<f32>) {
+        assert!((sample_peak_dbfs(&square) - -6.0206).abs() < 0.01);
+        assert!((rms_dbfs(&square) - -6.0206).abs() < 0.01);
+        assert!(crest_factor_db(&square).abs() < 0.01);
+    }
+
+    #[rstest]
+    fn test_silence() {
+        let silence = vec![0.0f32; 100];
+        assert_eq!(sample_peak_dbfs(&silence), f32::NEG_INFINITY);
+        assert_eq!(rms_dbfs(&silence), f32::NEG_INFINITY);
+        assert_eq!(crest_factor_db(&silence), 0.0);
+        assert_eq!(rms_dbfs(&[]), f32::NEG_INFINITY);
+    }
+
+    #[rstest]
+    fn test_per_channel(sine: Vec<f32>, square: Vec<f32>) {
+        let interleaved: Vec<f32> = sine.iter()
+            .zip(square.iter())
+            .flat_map(|(&l, &r)| [l, r])
+            .collect();
+
+        let crest = crest_factor_db_per_channel(&interleaved, 2);
+        assert!((crest[0] - 3.0103).abs() < 0.01);
+        assert!(crest[1].abs() < 0.01);
+
+        let rms = rms_dbfs_per_channel(&interleaved, 2);
+        assert!((rms[0] - rms_dbfs(&sine)).abs() < 1e-4);
+        assert!((rms[1] - rms_dbfs(&square)).abs() < 1e-4);
+
+        let peaks = sample_peak_dbfs_per_channel(&interleaved, 2);
+        assert_eq!(peaks.len(), 2);
+        assert!((peaks[0] - peaks[1]).abs() < 0.01);
+    }
+}
@@ -0,0 +1,142 @@
+//! Tempo estimation.
+//!
+//! The tempo is the dominant periodicity of the onset strength envelope computed for
+//! [`detect_onsets`](super::detect_onsets): a steady beat makes the envelope repeat
+//! every beat, so its autocorrelation peaks at the beat period.
+
+use super::onset::{frame_sizes, onset_strength};
+
+/// Slowest tempo considered in beats per minute.
+const MIN_BPM: f32 = 60.0;
+/// Fastest tempo considered in beats per minute.
+const MAX_BPM: f32 = 200.0;
+/// Minimum normalized autocorrelation of the envelope for the input to count as rhythmic.
+const TEMPO_CONFIDENCE_THRESHOLD: f32 = 0.3;
+/// Length in frames of the moving average smoothing the envelope, so that onsets
+/// falling into neighbouring frames still line up.
+const SMOOTHING_FRAMES: usize = 5;
+
+/// Estimates the tempo of rhythmic audio.
+///
+/// Multiples of the beat period correlate as well as the period itself, so the
+/// fastest tempo correlating almost as strongly as the best one is returned. Music
+/// with a strong off-beat may therefore be reported at double its tempo.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples covering several beats, ideally 10 seconds or more;
+///   downmix multi-channel audio first
+/// * `sample_rate` - Sample rate in Hz
+///
+/// # Returns
+///
+/// Returns Some(tempo) in beats per minute between 60 and 200, or None if the input
+/// is too short (less than two beats at 60 BPM) or has no clear beat.
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::analytic::estimate_tempo;
+///
+/// let samples = vec![0.0f32; 48000 * 10];
+/// match estimate_tempo(&samples, 48000) {
+///     Some(bpm) => println!("{:.1} BPM", bpm),
+///     None => println!("No beat found"),
+/// }
+/// ```
+pub fn estimate_tempo(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    let (fft_size, hop) = frame_sizes(sample_rate);
+    let frames_per_min = 60.0 * sample_rate as f32 / hop as f32;
+    let min_lag = ((frames_per_min / MAX_BPM).floor() as usize).max(1);
+    let max_lag = (frames_per_min / MIN_BPM).ceil() as usize;
+
+    let strength = onset_strength(samples, fft_size, hop)?;
+    if max_lag + 1 >= strength.len() / 2 {
+        return None;
+    }
+    let mut envelope: Vec<f32> = strength
+        .windows(SMOOTHING_FRAMES)
+        .map(|window| window.iter().sum::<f32>() / SMOOTHING_FRAMES as f32)
+        .collect();
+    let mean = envelope.iter().sum::<f32>() / envelope.len() as f32;
+    envelope.iter_mut().for_each(|e| *e -= mean);
+
+    let correlation = |lag: usize| -> f32 {
+        let (head, tail) = (&envelope[..envelope.len() - lag], &envelope[lag..]);
+        let cross: f32 = head.iter().zip(tail).map(|(a, b)| a * b).sum();
+        let energy_head: f32 = head.iter().map(|x| x * x).sum();
+        let energy_tail: f32 = tail.iter().map(|x| x * x).sum();
+        let norm = (energy_head * energy_tail).sqrt();
+        if norm > 0.0 { cross / norm } else { 0.0 }
+    };
+
+    let scores: Vec<f32> = (min_lag - 1..=max_lag + 1).map(correlation).collect();
+    // scores[i] corresponds to lag min_lag - 1 + i
+    let max_score = scores[1..scores.len() - 1]
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max);
+    if max_score < TEMPO_CONFIDENCE_THRESHOLD {
+        return None;
+    }
+    let best = (1..scores.len() - 1).find(|&i| {
+        scores[i] >= 0.9 * max_score && scores[i] >= scores[i - 1] && scores[i] >= scores[i + 1]
+    })?;
+    let best_score = scores[best];
+
+    // Refine the lag with parabolic interpolation around the peak
+    let (prev, next) = (scores[best - 1], scores[best + 1]);
+    let denom = prev - 2.0 * best_score + next;
+    let offset = if denom.abs() > f32::EPSILON { 0.5 * (prev - next) / denom } else { 0.0 };
+    let lag = (min_lag - 1 + best) as f32 + offset;
+
+    Some((frames_per_min / lag).clamp(MIN_BPM, MAX_BPM))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{sine, white_noise};
+    use rstest::*;
+
+    /// A click track: a short decaying noise burst on every beat, with accented downbeats.
+    fn click_track(bpm: f32, duration_sec: f32, sample_rate: u32) -> Vec<f32> {
+        let burst = white_noise(0.8, 0.01, sample_rate, 3);
+        let mut samples = vec![0.0f32; (duration_sec * sample_rate as f32) as usize];
+        let beat = 60.0 / bpm * sample_rate as f32;
+        let mut index = 0;
+        while (index as f32 * beat) as usize + burst.len() < samples.len() {
+            let start = (index as f32 * beat) as usize;
+            let accent = if index % 4 == 0 { 1.0 } else { 0.6 };
+            for (j, s) in burst.iter().enumerate() {
+                samples[start + j] += accent * s * (-(j as f32) / 80.0).exp();
+            }
+            index += 1;
+        }
+        samples
+    }
+
+    #[rstest]
+    #[case(120.0, 48000)]
+    #[case(120.0, 44100)]
+    #[case(90.0, 48000)]
+    #[case(174.0, 44100)]
+    fn test_click_track(#[case] bpm: f32, #[case] sample_rate: u32) {
+        let samples = click_track(bpm, 12.0, sample_rate);
+        let tempo = estimate_tempo(&samples, sample_rate).unwrap();
+        assert!((tempo - bpm).abs() <= 2.0, "estimated {} BPM for {} BPM", tempo, bpm);
+    }
+
+    #[rstest]
+    fn test_non_rhythmic_input() {
+        assert_eq!(estimate_tempo(&white_noise(0.5, 10.0, 48000, 9), 48000), None);
+        assert_eq!(estimate_tempo(&sine(440.0, 0.5, 10.0, 48000), 48000), None);
+        assert_eq!(estimate_tempo(&vec![0.0; 48000 * 10], 48000), None);
+    }
+
+    #[rstest]
+    fn test_too_short() {
+        assert_eq!(estimate_tempo(&click_track(120.0, 1.5, 48000), 48000), None);
+        assert_eq!(estimate_tempo(&[0.5; 10], 48000), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Standalone true peak measurement.
+//!
+//! The peak of the analog waveform reconstructed from the samples can lie between
+//! two samples and exceed the highest sample value. ITU-R BS.1770 estimates this
+//! true peak by oversampling the signal and taking the peak of the result.
+//!
+//! [`Meter::true_peaks`](super::Meter::true_peaks) measures the same value, but the
+//! meter also runs the loudness filters and gating. [`true_peak_dbtp`] only does the
+//! oversampling and is cheaper when the true peak is all that is needed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::true_peak_dbtp;
+//!
+//! let samples = vec![0.5f32; 48000 * 2];
+//! let peaks = true_peak_dbtp(&samples, 2, 48000);
+//! println!("Left: {:.1} dBTP, right: {:.1} dBTP", peaks[0], peaks[1]);
+//! ```
+
+use std::f64::consts::PI;
+
+/// Filter taps per oversampling phase at 4x oversampling, 2x uses twice as many.
+pub(crate) const TAPS_PER_PHASE: usize = 12;
+
+/// Returns the oversampling factor that brings the sample rate to at least 192 kHz,
+/// with at most 4x oversampling.
+fn oversampling_factor(sample_rate: u32) -> usize {
+    if sample_rate < 96000 {
+        4
+    } else if sample_rate < 192000 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Builds the polyphase interpolation filter.
+///
+/// Phase `p` holds the coefficients interpolating the point `p / factor` samples after
+/// a sample from its neighbours, taken from a Hann-windowed sinc.
+pub(crate) fn polyphase_filter(factor: usize) -> Vec<Vec<f32>> {
+    let half_width = (TAPS_PER_PHASE * 4 / factor / 2) as isize;
+    (0..factor)
+        .map(|phase| {
+            let offset = phase as f64 / factor as f64;
+            (1 - half_width..=half_width)
+                .map(|tap| {
+                    let t = offset - tap as f64;
+                    let window = 0.5 * (1.0 + (PI * t / half_width as f64).cos());
+                    let sinc = if t.abs() < 1e-9 { 1.0 } else { (PI * t).sin() / (PI * t) };
+                    (window * sinc) as f32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the highest absolute value of the oversampled signal of one channel.
+fn channel_true_peak(samples: &[f32], filter: &[Vec<f32>]) -> f32 {
+    let sample_peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let taps = filter[0].len() as isize;
+    let half_width = taps / 2;
+    let len = samples.len() as isize;
+
+    let mut peak = sample_peak;
+    for n in 0..len {
+        // Phase 0 reproduces the samples themselves
+        for coefficients in &filter[1..] {
+            let interpolated: f32 = coefficients
+                .iter()
+                .enumerate()
+                .filter_map(|(tap, c)| {
+                    let index = n + 1 - half_width + tap as isize;
+                    (0..len).contains(&index).then(|| c * samples[index as usize])
+                })
+                .sum();
+            peak = peak.max(interpolated.abs());
+        }
+    }
+    peak
+}
+
+/// Measures the true peak of each channel.
+///
+/// Below 96 kHz the signal is oversampled 4x with a polyphase interpolation filter
+/// as described in ITU-R BS.1770, at 96 kHz and above 2x, and from 192 kHz on the
+/// sample peak is returned.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+/// * `sample_rate` - Sample rate in Hz
+///
+/// # Returns
+///
+/// The true peak of each channel in dBTP, negative infinity for silent channels.
+pub fn true_peak_dbtp(samples: &[f32], channels: usize, sample_rate: u32) -> Vec<f32> {
+    let channels = channels.max(1);
+    let filter = polyphase_filter(oversampling_factor(sample_rate));
+    (0..channels)
+        .map(|channel| {
+            let channel_samples: Vec<f32> = samples.iter().skip(channel).step_by(channels).copied().collect();
+            20.0 * channel_true_peak(&channel_samples, &filter).log10()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::Meter;
+    use crate::io::AudioReader;
+    use rstest::*;
+
+    #[rstest]
+    fn test_matches_meter_on_file() {
+        let mut reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        let mut samples = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            samples.extend(packet);
+        }
+        let channels = reader.channels();
+        let sample_rate = reader.sample_rate();
+
+        let peaks = true_peak_dbtp(&samples, channels, sample_rate);
+        let meter = Meter::from_samples(&samples, channels as u32, sample_rate).unwrap();
+        let expected = meter.true_peaks().unwrap();
+        assert_eq!(peaks.len(), expected.len());
+        for (peak, linear) in peaks.iter().zip(expected.iter()) {
+            let expected_db = 20.0 * linear.log10() as f32;
+            assert!((peak - expected_db).abs() < 0.05, "{} vs {}", peak, expected_db);
+        }
+    }
+
+    #[rstest]
+    #[case(44100)]
+    #[case(48000)]
+    #[case(96000)]
+    fn test_finds_intersample_peak(#[case] sample_rate: u32) {
+        // A quarter of the sample rate at 45° phase: every sample lands at ±0.707
+        // while the waveform peaks at 1.0 between the samples
+        let samples: Vec<f32> = (0..sample_rate as usize / 10)
+            .map(|i| (PI / 2.0 * i as f64 + PI / 4.0).sin() as f32)
+            .collect();
+        let sample_peak = 20.0 * samples.iter().fold(0.0f32, |p, s| p.max(s.abs())).log10();
+        assert!((sample_peak + 3.01).abs() < 0.01);
+
+        let peak = true_peak_dbtp(&samples, 1, sample_rate)[0];
+        assert!(peak.abs() < 0.2, "true peak {}", peak);
+
+        let meter = Meter::from_samples(&samples, 1, sample_rate).unwrap();
+        let expected = 20.0 * meter.true_peaks().unwrap()[0].log10() as f32;
+        assert!((peak - expected).abs() < 0.2, "{} vs {}", peak, expected);
+    }
+
+    #[rstest]
+    fn test_per_channel_and_silence() {
+        let samples: Vec<f32> = (0..4800)
+            .flat_map(|i| [0.5 * (i as f32 * 0.01).sin(), 0.0])
+            .collect();
+        let peaks = true_peak_dbtp(&samples, 2, 48000);
+        assert!((peaks[0] + 6.02).abs() < 0.05);
+        assert_eq!(peaks[1], f32::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,97 @@
+//! Waveform overviews for display.
+//!
+//! Drawing every sample of a long recording is wasteful when the display has only a
+//! few hundred pixel columns. Instead, the audio is split into one bucket per column
+//! and each bucket is drawn as a vertical line from its lowest to its highest
+//! sample, which is how DAWs render waveform overviews.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::waveform_peaks;
+//!
+//! let samples = vec![0.5f32; 48000 * 2];
+//! // One (min, max) pair per pixel column of an 800 pixel wide view
+//! let peaks = waveform_peaks(&samples, 2, 800);
+//! for (column, (min, max)) in peaks[0].iter().enumerate() {
+//!     println!("{}: {:.2} to {:.2}", column, min, max);
+//! }
+//! ```
+
+/// Computes the minimum and maximum sample of evenly sized buckets of each channel.
+///
+/// The frames are divided into `points` consecutive buckets whose lengths differ by
+/// at most one frame. If the audio has fewer frames than `points`, every frame gets
+/// its own bucket.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples, a trailing partial frame is ignored
+/// * `channels` - Number of audio channels
+/// * `points` - Number of buckets, e.g. the width of the display in pixels
+///
+/// # Returns
+///
+/// One vector per channel holding the `(min, max)` sample value of each bucket, in
+/// order of time. The vectors are empty if there are no samples or `points` is 0.
+pub fn waveform_peaks(samples: &[f32], channels: usize, points: usize) -> Vec<Vec<(f32, f32)>> {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let points = points.min(frames);
+
+    (0..channels)
+        .map(|channel| {
+            (0..points)
+                .map(|point| {
+                    let start = point * frames / points;
+                    let end = (point + 1) * frames / points;
+                    samples[start * channels..end * channels]
+                        .iter()
+                        .skip(channel)
+                        .step_by(channels)
+                        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &s| (min.min(s), max.max(s)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(100, 0.8)]
+    #[case(73, 0.5)]
+    fn test_sine_buckets(#[case] points: usize, #[case] amplitude: f32) {
+        // 200 Hz over 2 seconds: every bucket spans at least one full period
+        let samples = sine(200.0, amplitude, 2.0, 48000);
+        let peaks = waveform_peaks(&samples, 1, points);
+        assert_eq!(peaks.len(), 1);
+        assert_eq!(peaks[0].len(), points);
+        for &(min, max) in &peaks[0] {
+            assert!(min < 0.0 && max > 0.0);
+            assert!(max <= amplitude && max > 0.99 * amplitude, "max {}", max);
+            assert!(min >= -amplitude && min < -0.99 * amplitude, "min {}", min);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_and_bucket_edges() {
+        // Left ramps up, right is constant
+        let samples: Vec<f32> = (0..10).flat_map(|i| [i as f32, -1.0]).collect();
+        let peaks = waveform_peaks(&samples, 2, 3);
+        assert_eq!(peaks[0], vec![(0.0, 2.0), (3.0, 5.0), (6.0, 9.0)]);
+        assert_eq!(peaks[1], vec![(-1.0, -1.0); 3]);
+    }
+
+    #[rstest]
+    fn test_fewer_frames_than_points() {
+        let peaks = waveform_peaks(&[0.1, -0.2, 0.3], 1, 10);
+        assert_eq!(peaks[0], vec![(0.1, 0.1), (-0.2, -0.2), (0.3, 0.3)]);
+        assert!(waveform_peaks(&[], 2, 10).iter().all(Vec::is_empty));
+        assert!(waveform_peaks(&[0.5; 8], 2, 0).iter().all(Vec::is_empty));
+    }
+}
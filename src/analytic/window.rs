@@ -0,0 +1,180 @@
+//! Window functions.
+//!
+//! Windows taper a block of samples towards its edges before it is analysed, which
+//! reduces the spectral leakage caused by cutting a finite block out of a signal.
+//! They are used by the FFT analysis in [`spectrum`](super::spectrum) and are useful
+//! for STFT and overlap-add processing.
+//!
+//! All windows are symmetric: the first and last coefficients are equal. A window of
+//! length 1 is `[1.0]` and a window of length 0 is empty.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::window::{apply_window, hann};
+//!
+//! let mut block = vec![0.5f32; 1024];
+//! let window = hann(block.len());
+//! apply_window(&mut block, &window);
+//! ```
+
+use std::f64::consts::PI;
+
+/// Builds a window of `n` coefficients from a function of the position in [0.0, 1.0].
+fn symmetric(n: usize, f: impl Fn(f64) -> f64) -> Vec<f32> {
+    if n <= 1 {
+        return vec![1.0; n];
+    }
+    let denom = (n - 1) as f64;
+    (0..n).map(|i| f(i as f64 / denom) as f32).collect()
+}
+
+/// Builds a generalized cosine window `a0 - a1 cos(2πx) + a2 cos(4πx) - a3 cos(6πx)`.
+fn cosine_sum(n: usize, coefficients: &[f64]) -> Vec<f32> {
+    symmetric(n, |x| {
+        coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, a)| {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                sign * a * (2.0 * PI * k as f64 * x).cos()
+            })
+            .sum()
+    })
+}
+
+/// Returns a Hann (raised cosine) window, a good default for general analysis.
+pub fn hann(n: usize) -> Vec<f32> {
+    cosine_sum(n, &[0.5, 0.5])
+}
+
+/// Returns a Hamming window.
+///
+/// Unlike the Hann window it doesn't reach zero at the edges (0.08), which lowers the
+/// first side lobe.
+pub fn hamming(n: usize) -> Vec<f32> {
+    cosine_sum(n, &[0.54, 0.46])
+}
+
+/// Returns a Blackman window.
+pub fn blackman(n: usize) -> Vec<f32> {
+    cosine_sum(n, &[0.42, 0.5, 0.08])
+}
+
+/// Returns a 4-term Blackman-Harris window, with side lobes below -92 dB.
+pub fn blackman_harris(n: usize) -> Vec<f32> {
+    cosine_sum(n, &[0.35875, 0.48829, 0.14128, 0.01168])
+}
+
+/// Returns a Kaiser window.
+///
+/// # Arguments
+///
+/// * `n` - Window length
+/// * `beta` - Shape parameter: 0.0 gives a rectangular window, larger values lower
+///   the side lobes and widen the main lobe (e.g. 8.6 is similar to Blackman)
+pub fn kaiser(n: usize, beta: f32) -> Vec<f32> {
+    let beta = beta as f64;
+    let norm = bessel_i0(beta);
+    symmetric(n, |x| {
+        let r = 2.0 * x - 1.0;
+        bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / norm
+    })
+}
+
+/// Modified Bessel function of the first kind of order zero, from its power series.
+fn bessel_i0(x: f64) -> f64 {
+    let quarter_x_squared = x * x / 4.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for k in 1..100 {
+        term *= quarter_x_squared / (k * k) as f64;
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+/// Multiplies a block of samples by a window in place.
+///
+/// # Arguments
+///
+/// * `samples` - Block of mono samples
+/// * `window` - Window coefficients, as long as the block
+///
+/// # Panics
+///
+/// Panics if the window and the block have different lengths.
+pub fn apply_window(samples: &mut [f32], window: &[f32]) {
+    assert_eq!(samples.len(), window.len(), "window length must match the block length");
+    samples.iter_mut().zip(window).for_each(|(sample, w)| *sample *= w);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn windows(n: usize) -> Vec<Vec<f32>> {
+        vec![hann(n), hamming(n), blackman(n), blackman_harris(n), kaiser(n, 8.6)]
+    }
+
+    #[rstest]
+    #[case(16)]
+    #[case(1023)]
+    fn test_symmetric_and_peak_in_center(#[case] n: usize) {
+        for window in windows(n) {
+            assert_eq!(window.len(), n);
+            for i in 0..n / 2 {
+                assert!((window[i] - window[n - 1 - i]).abs() < 1e-6);
+                assert!(window[i] <= window[i + 1] + 1e-6);
+            }
+            assert!(window.iter().all(|&w| (-1e-6..=1.0 + 1e-6).contains(&w)));
+        }
+    }
+
+    #[rstest]
+    fn test_edges() {
+        let window = hann(64);
+        assert!(window[0].abs() < 1e-6);
+        assert!(window[63].abs() < 1e-6);
+        assert!((hamming(64)[0] - 0.08).abs() < 1e-6);
+        assert!((blackman_harris(64)[0] - 6e-5).abs() < 1e-5);
+
+        // The middle of an odd-length window is exactly 1
+        assert!((hann(65)[32] - 1.0).abs() < 1e-6);
+        assert!((kaiser(65, 5.0)[32] - 1.0).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_kaiser_beta() {
+        assert!(kaiser(32, 0.0).iter().all(|&w| (w - 1.0).abs() < 1e-6));
+        // Larger beta tapers more
+        assert!(kaiser(32, 10.0)[0] < kaiser(32, 4.0)[0]);
+        assert!((bessel_i0(1.0) - 1.266_065_877_752_008).abs() < 1e-12);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    fn test_degenerate_lengths(#[case] n: usize) {
+        for window in windows(n) {
+            assert_eq!(window, vec![1.0; n]);
+        }
+    }
+
+    #[rstest]
+    fn test_apply_window() {
+        let mut samples = vec![2.0f32; 5];
+        apply_window(&mut samples, &hann(5));
+        assert_eq!(samples, vec![0.0, 1.0, 2.0, 1.0, 0.0]);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "window length")]
+    fn test_apply_window_length_mismatch() {
+        apply_window(&mut [1.0; 4], &hann(5));
+    }
+}
@@ -0,0 +1,161 @@
+//! Command-line interface for common sonex operations.
+//!
+//! Build with the `cli` feature:
+//!
+//! ```text
+//! cargo run --features cli --bin sonex -- info audio/sin_100Hz_-3dBFS_3s.wav
+//! sonex loudness in.wav
+//! sonex gain --db 6 in.wav out.wav
+//! sonex normalize --lufs -14 in.wav out.wav
+//! ```
+
+use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use sonex::analytic::Meter;
+use sonex::io::{AudioReader, AudioWriter};
+use sonex::process::{AudioNode, GainNode, LoudnessNormalizeNode};
+
+fn cli() -> Command {
+    let input = Arg::new("input").help("Input audio file").required(true);
+    let output = Arg::new("output").help("Output WAV file").required(true);
+    Command::new("sonex")
+        .about("Audio loudness analysis and processing")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("info")
+                .about("Prints the sample rate, channels, duration and loudness of a file")
+                .arg(input.clone()),
+        )
+        .subcommand(
+            Command::new("loudness")
+                .about("Prints the integrated loudness and true peak of a file")
+                .arg(input.clone()),
+        )
+        .subcommand(
+            Command::new("gain")
+                .about("Applies a gain in dB and writes the result")
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Gain in dB, negative values attenuate")
+                        .required(true)
+                        .allow_negative_numbers(true)
+                        .value_parser(value_parser!(f32)),
+                )
+                .arg(input.clone())
+                .arg(output.clone()),
+        )
+        .subcommand(
+            Command::new("normalize")
+                .about("Normalizes the integrated loudness and writes the result")
+                .arg(
+                    Arg::new("lufs")
+                        .long("lufs")
+                        .help("Target integrated loudness in LUFS")
+                        .required(true)
+                        .allow_negative_numbers(true)
+                        .value_parser(value_parser!(f64)),
+                )
+                .arg(input)
+                .arg(output),
+        )
+}
+
+/// Opens a file and reads all of its interleaved samples.
+fn read_all(path: &str) -> Result<(AudioReader, Vec<f32>), Box<dyn Error>> {
+    if !Path::new(path).is_file() {
+        return Err(format!("{} is not a file", path).into());
+    }
+    let mut reader = AudioReader::new(path)?;
+    let mut samples = Vec::new();
+    while let Some(packet) = reader.read_packet()? {
+        samples.extend(packet);
+    }
+    Ok((reader, samples))
+}
+
+fn measure(reader: &AudioReader, samples: &[f32]) -> Result<Meter, Box<dyn Error>> {
+    Ok(Meter::from_samples(samples, reader.channels() as u32, reader.sample_rate())?)
+}
+
+fn format_lufs(lufs: Option<f64>) -> String {
+    match lufs {
+        Some(lufs) if lufs.is_finite() => format!("{:.2} LUFS", lufs),
+        _ => "-inf LUFS".to_string(),
+    }
+}
+
+/// Writes processed samples with the channels, sample rate and sample depth of the input.
+fn write_all(path: &str, reader: &AudioReader, samples: &[f32]) -> Result<(), Box<dyn Error>> {
+    let mut writer = AudioWriter::from_reader(path, reader)?;
+    writer.write_samples(samples)?;
+    writer.finalize()?;
+    Ok(())
+}
+
+fn info(args: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let path = args.get_one::<String>("input").unwrap();
+    let (reader, samples) = read_all(path)?;
+    let frames = samples.len() / reader.channels();
+    let meter = measure(&reader, &samples)?;
+
+    println!("File: {}", path);
+    println!("Codec: {}", reader.codec_name());
+    println!("Sample rate: {} Hz", reader.sample_rate());
+    println!("Channels: {}", reader.channels());
+    println!("Duration: {:.3} s", frames as f64 / reader.sample_rate() as f64);
+    println!("Integrated loudness: {}", format_lufs(meter.lufs_integrated()));
+    Ok(())
+}
+
+fn loudness(args: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let path = args.get_one::<String>("input").unwrap();
+    let (reader, samples) = read_all(path)?;
+    let meter = measure(&reader, &samples)?;
+
+    println!("Integrated loudness: {}", format_lufs(meter.lufs_integrated()));
+    if let Some(peaks) = meter.true_peaks() {
+        let peak = peaks.iter().cloned().fold(0.0, f64::max);
+        println!("True peak: {:.2} dBTP", 20.0 * peak.log10());
+    }
+    Ok(())
+}
+
+fn gain(args: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let db = *args.get_one::<f32>("db").unwrap();
+    let (reader, samples) = read_all(args.get_one::<String>("input").unwrap())?;
+    let output = GainNode::new(db).process(&samples);
+    write_all(args.get_one::<String>("output").unwrap(), &reader, &output)
+}
+
+fn normalize(args: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let target_lufs = *args.get_one::<f64>("lufs").unwrap();
+    let (reader, samples) = read_all(args.get_one::<String>("input").unwrap())?;
+    let node = LoudnessNormalizeNode::new(target_lufs, reader.channels() as u32, reader.sample_rate());
+    let output = node.process(&samples);
+    write_all(args.get_one::<String>("output").unwrap(), &reader, &output)?;
+
+    let meter = measure(&reader, &output)?;
+    println!("Integrated loudness: {}", format_lufs(meter.lufs_integrated()));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match cli().get_matches().subcommand() {
+        Some(("info", args)) => info(args),
+        Some(("loudness", args)) => loudness(args),
+        Some(("gain", args)) => gain(args),
+        Some(("normalize", args)) => normalize(args),
+        _ => unreachable!("a subcommand is required"),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
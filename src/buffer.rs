@@ -0,0 +1,165 @@
+//! Audio samples together with their format.
+//!
+//! [`AudioBuffer`] keeps interleaved samples, the channel count and the sample rate
+//! in one value, so they can't get out of step when audio is passed from a reader
+//! through processing to a meter.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::analytic::Meter;
+//! use sonex::io::AudioReader;
+//! use sonex::process::{AudioNode, GainNode};
+//!
+//! let mut reader = AudioReader::new("audio.wav").unwrap();
+//! let buffer = reader.read_buffer().unwrap();
+//! println!("{} frames, {:.2} s", buffer.n_frames(), buffer.duration());
+//!
+//! let louder = GainNode::new(6.0).process_buffer(&buffer);
+//! let meter = Meter::from_buffer(&louder).unwrap();
+//! println!("{:?} LUFS", meter.lufs_integrated());
+//! ```
+
+/// Interleaved audio samples with their channel count and sample rate.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AudioBuffer {
+    /// Interleaved samples, e.g. [L,R,L,R,...] for stereo
+    pub samples: Vec<f32>,
+    /// Number of interleaved channels
+    pub channels: u32,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+}
+
+impl AudioBuffer {
+    /// Creates a buffer from interleaved samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Interleaved samples
+    /// * `channels` - Number of interleaved channels
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn from_interleaved(samples: Vec<f32>, channels: u32, sample_rate: u32) -> Self {
+        Self { samples, channels, sample_rate }
+    }
+
+    /// Creates a buffer by interleaving one sample vector per channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - Samples of each channel. Longer channels are cut to the length of
+    ///   the shortest one.
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn from_channels(channels: &[Vec<f32>], sample_rate: u32) -> Self {
+        let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+        let samples = (0..frames)
+            .flat_map(|frame| channels.iter().map(move |channel| channel[frame]))
+            .collect();
+        Self { samples, channels: channels.len() as u32, sample_rate }
+    }
+
+    /// Returns the number of frames, i.e. samples per channel.
+    ///
+    /// A trailing partial frame is not counted.
+    pub fn n_frames(&self) -> usize {
+        match self.channels {
+            0 => 0,
+            channels => self.samples.len() / channels as usize,
+        }
+    }
+
+    /// Returns the duration in seconds, or 0.0 for a sample rate of 0.
+    pub fn duration(&self) -> f64 {
+        match self.sample_rate {
+            0 => 0.0,
+            sample_rate => self.n_frames() as f64 / sample_rate as f64,
+        }
+    }
+
+    /// Returns true if the buffer holds no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Extracts the samples of one channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Channel index, starting at 0
+    ///
+    /// # Returns
+    ///
+    /// The samples of the channel, or None if `index` is not less than the channel count.
+    pub fn channel(&self, index: usize) -> Option<Vec<f32>> {
+        if index >= self.channels as usize {
+            return None;
+        }
+        let frames = self.n_frames() * self.channels as usize;
+        Some(self.samples[..frames].iter().skip(index).step_by(self.channels as usize).copied().collect())
+    }
+
+    /// Splits the buffer into one sample vector per channel.
+    pub fn to_channels(&self) -> Vec<Vec<f32>> {
+        (0..self.channels as usize).filter_map(|index| self.channel(index)).collect()
+    }
+
+    /// Returns the interleaved samples.
+    pub fn interleaved(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Consumes the buffer and returns the interleaved samples.
+    pub fn into_interleaved(self) -> Vec<f32> {
+        self.samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(vec![0.0; 8], 2, 48000, 4, 4.0 / 48000.0)]
+    #[case(vec![0.0; 9], 2, 1000, 4, 0.004)]
+    #[case(vec![0.0; 6], 1, 3, 6, 2.0)]
+    #[case(vec![0.0; 6], 0, 48000, 0, 0.0)]
+    #[case(vec![0.0; 6], 3, 0, 2, 0.0)]
+    #[case(Vec::new(), 2, 44100, 0, 0.0)]
+    fn test_frames_and_duration(
+        #[case] samples: Vec<f32>,
+        #[case] channels: u32,
+        #[case] sample_rate: u32,
+        #[case] frames: usize,
+        #[case] duration: f64,
+    ) {
+        let buffer = AudioBuffer::from_interleaved(samples, channels, sample_rate);
+        assert_eq!(buffer.n_frames(), frames);
+        assert!((buffer.duration() - duration).abs() < 1e-12);
+    }
+
+    #[rstest]
+    #[case(0, Some(vec![1.0, 4.0]))]
+    #[case(1, Some(vec![2.0, 5.0]))]
+    #[case(2, Some(vec![3.0, 6.0]))]
+    #[case(3, None)]
+    fn test_channel_extraction(#[case] index: usize, #[case] expected: Option<Vec<f32>>) {
+        // Two frames of three channels and a partial frame
+        let buffer = AudioBuffer::from_interleaved(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0], 3, 48000);
+        assert_eq!(buffer.channel(index), expected);
+    }
+
+    #[rstest]
+    fn test_channels_round_trip() {
+        let left = vec![0.1, 0.2, 0.3];
+        let right = vec![-0.1, -0.2, -0.3, -0.4];
+        let buffer = AudioBuffer::from_channels(&[left.clone(), right], 44100);
+        assert_eq!(buffer.channels, 2);
+        assert_eq!(buffer.interleaved(), &[0.1, -0.1, 0.2, -0.2, 0.3, -0.3]);
+        assert_eq!(buffer.to_channels(), vec![left, vec![-0.1, -0.2, -0.3]]);
+
+        let samples = buffer.clone().into_interleaved();
+        assert_eq!(AudioBuffer::from_interleaved(samples, 2, 44100), buffer);
+        assert!(AudioBuffer::from_channels(&[], 44100).is_empty());
+    }
+}
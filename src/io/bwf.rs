@@ -0,0 +1,135 @@
+//! Broadcast Wave Format (BWF) metadata.
+//!
+//! BWF files are WAV files with an additional `bext` chunk describing the
+//! recording, as specified in EBU Tech 3285. Broadcasters and post-production
+//! facilities often require it on delivered files.
+
+use crate::io::riff::{push_chunk, read_u32, ChunkId};
+
+/// Identifier of the broadcast extension chunk.
+pub(crate) const BEXT_CHUNK_ID: ChunkId = *b"bext";
+
+/// Length of the fixed part of the `bext` chunk preceding the coding history.
+const BEXT_FIXED_LEN: usize = 602;
+/// Byte offsets and lengths of the text fields of the `bext` chunk.
+const DESCRIPTION: (usize, usize) = (0, 256);
+const ORIGINATOR: (usize, usize) = (256, 32);
+const ORIGINATOR_REFERENCE: (usize, usize) = (288, 32);
+const ORIGINATION_DATE: (usize, usize) = (320, 10);
+const ORIGINATION_TIME: (usize, usize) = (330, 8);
+const TIME_REFERENCE_OFFSET: usize = 338;
+const VERSION_OFFSET: usize = 346;
+
+/// Metadata of the `bext` chunk of a Broadcast Wave file.
+///
+/// Text fields have a fixed length in the file and are truncated when written:
+/// 256 bytes for the description, 32 bytes for the originator and its reference.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BwfMetadata {
+    /// Free text description of the content
+    pub description: String,
+    /// Name of the organization or person that created the file
+    pub originator: String,
+    /// Unique reference assigned by the originator
+    pub originator_reference: String,
+    /// Creation date in the form `yyyy-mm-dd`
+    pub origination_date: String,
+    /// Creation time in the form `hh:mm:ss`
+    pub origination_time: String,
+    /// Position of the first sample as the number of samples since midnight
+    pub time_reference: u64,
+    /// Free text history of the coding processes applied to the audio
+    pub coding_history: String,
+}
+
+/// Parses a `bext` chunk, None if it is shorter than the fixed fields.
+pub(crate) fn parse_bext(data: &[u8]) -> Option<BwfMetadata> {
+    if data.len() < BEXT_FIXED_LEN {
+        return None;
+    }
+    let text = |(offset, len): (usize, usize)| {
+        let field = data[offset..offset + len].split(|&b| b == 0).next().unwrap_or_default();
+        String::from_utf8_lossy(field).into_owned()
+    };
+    let low = read_u32(data, TIME_REFERENCE_OFFSET)? as u64;
+    let high = read_u32(data, TIME_REFERENCE_OFFSET + 4)? as u64;
+
+    Some(BwfMetadata {
+        description: text(DESCRIPTION),
+        originator: text(ORIGINATOR),
+        originator_reference: text(ORIGINATOR_REFERENCE),
+        origination_date: text(ORIGINATION_DATE),
+        origination_time: text(ORIGINATION_TIME),
+        time_reference: high << 32 | low,
+        coding_history: text((BEXT_FIXED_LEN, data.len() - BEXT_FIXED_LEN)),
+    })
+}
+
+/// Encodes the metadata as a version 1 `bext` chunk.
+pub(crate) fn bext_chunk(metadata: &BwfMetadata) -> Vec<u8> {
+    let mut body = vec![0u8; BEXT_FIXED_LEN];
+    let mut write_text = |(offset, len): (usize, usize), text: &str| {
+        let mut end = text.len().min(len);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let bytes = &text.as_bytes()[..end];
+        body[offset..offset + bytes.len()].copy_from_slice(bytes);
+    };
+    write_text(DESCRIPTION, &metadata.description);
+    write_text(ORIGINATOR, &metadata.originator);
+    write_text(ORIGINATOR_REFERENCE, &metadata.originator_reference);
+    write_text(ORIGINATION_DATE, &metadata.origination_date);
+    write_text(ORIGINATION_TIME, &metadata.origination_time);
+
+    let time_reference = metadata.time_reference.to_le_bytes();
+    body[TIME_REFERENCE_OFFSET..TIME_REFERENCE_OFFSET + 8].copy_from_slice(&time_reference);
+    body[VERSION_OFFSET..VERSION_OFFSET + 2].copy_from_slice(&1u16.to_le_bytes());
+    body.extend(metadata.coding_history.as_bytes());
+
+    let mut chunk = Vec::new();
+    push_chunk(&mut chunk, &BEXT_CHUNK_ID, &body);
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn metadata() -> BwfMetadata {
+        BwfMetadata {
+            description: "Episode 12: Loudness".to_string(),
+            originator: "sonex".to_string(),
+            originator_reference: "EP12-MASTER".to_string(),
+            origination_date: "2024-03-01".to_string(),
+            origination_time: "14:30:00".to_string(),
+            time_reference: 48000 * 3600 * 5 + (1 << 33),
+            coding_history: "A=PCM,F=48000,W=24,M=stereo\r\n".to_string(),
+        }
+    }
+
+    #[rstest]
+    fn test_bext_round_trip(metadata: BwfMetadata) {
+        let chunk = bext_chunk(&metadata);
+        assert_eq!(&chunk[..4], b"bext");
+        let size = read_u32(&chunk, 4).unwrap() as usize;
+        assert_eq!(size, BEXT_FIXED_LEN + metadata.coding_history.len());
+        assert_eq!(parse_bext(&chunk[8..8 + size]), Some(metadata));
+    }
+
+    #[rstest]
+    fn test_long_text_is_truncated(mut metadata: BwfMetadata) {
+        metadata.originator = "x".repeat(40);
+        let chunk = bext_chunk(&metadata);
+        let parsed = parse_bext(&chunk[8..]).unwrap();
+        assert_eq!(parsed.originator, "x".repeat(32));
+        assert_eq!(parsed.originator_reference, metadata.originator_reference);
+    }
+
+    #[rstest]
+    fn test_short_chunk() {
+        assert_eq!(parse_bext(&[0; 100]), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Joining decoded clips.
+//!
+//! Decoded samples carry no format information, so nothing stops a 44.1 kHz mono
+//! clip from being glued onto a 48 kHz stereo one, which plays back at the wrong
+//! speed with the channels scrambled. [`concat`] checks the formats before joining.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::io::concat;
+//!
+//! let intro = vec![0.0f32; 48000 * 2];
+//! let episode = vec![0.0f32; 48000 * 60 * 2];
+//!
+//! // Both clips are 48 kHz stereo
+//! let joined = concat(&[(&intro, 2, 48000), (&episode, 2, 48000)]).unwrap();
+//!
+//! // A 44.1 kHz mono clip is rejected
+//! let jingle = vec![0.0f32; 44100];
+//! assert!(concat(&[(&joined, 2, 48000), (&jingle, 1, 44100)]).is_err());
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors that can occur while concatenating clips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConcatError {
+    /// A clip has a different channel count than the first clip
+    ChannelMismatch {
+        /// Index of the offending clip
+        index: usize,
+        /// Channel count of the first clip
+        expected: u32,
+        /// Channel count of the offending clip
+        found: u32,
+    },
+    /// A clip has a different sample rate than the first clip
+    SampleRateMismatch {
+        /// Index of the offending clip
+        index: usize,
+        /// Sample rate of the first clip in Hz
+        expected: u32,
+        /// Sample rate of the offending clip in Hz
+        found: u32,
+    },
+}
+
+impl fmt::Display for ConcatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcatError::ChannelMismatch { index, expected, found } => write!(
+                f,
+                "clip {} has {} channels, expected {}",
+                index, found, expected
+            ),
+            ConcatError::SampleRateMismatch { index, expected, found } => write!(
+                f,
+                "clip {} has a sample rate of {} Hz, expected {} Hz",
+                index, found, expected
+            ),
+        }
+    }
+}
+
+impl Error for ConcatError {}
+
+/// Concatenates interleaved clips that share the same format.
+///
+/// # Arguments
+///
+/// * `clips` - `(samples, channels, sample_rate)` of each clip, in playback order
+///
+/// # Returns
+///
+/// The samples of all clips joined end to end, or an error naming the first clip
+/// whose channel count or sample rate differs from the first clip. An empty list
+/// of clips gives an empty vector.
+pub fn concat(clips: &[(&[f32], u32, u32)]) -> Result<Vec<f32>, ConcatError> {
+    let Some(&(_, channels, sample_rate)) = clips.first() else {
+        return Ok(Vec::new());
+    };
+
+    for (index, &(_, clip_channels, clip_rate)) in clips.iter().enumerate().skip(1) {
+        if clip_channels != channels {
+            return Err(ConcatError::ChannelMismatch { index, expected: channels, found: clip_channels });
+        }
+        if clip_rate != sample_rate {
+            return Err(ConcatError::SampleRateMismatch { index, expected: sample_rate, found: clip_rate });
+        }
+    }
+
+    let total = clips.iter().map(|(samples, _, _)| samples.len()).sum();
+    let mut output = Vec::with_capacity(total);
+    for (samples, _, _) in clips {
+        output.extend_from_slice(samples);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_concatenates_matching_clips() {
+        let a = [0.1, 0.2, 0.3, 0.4];
+        let b = [0.5, 0.6];
+        let c = [0.7, 0.8];
+        let joined = concat(&[(&a, 2, 48000), (&b, 2, 48000), (&c, 2, 48000)]).unwrap();
+        assert_eq!(joined, vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8]);
+
+        assert_eq!(concat(&[(&a, 1, 44100)]).unwrap(), a.to_vec());
+        assert!(concat(&[]).unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case(2, 48000, ConcatError::ChannelMismatch { index: 1, expected: 1, found: 2 })]
+    #[case(1, 48000, ConcatError::SampleRateMismatch { index: 1, expected: 44100, found: 48000 })]
+    #[case(2, 22050, ConcatError::ChannelMismatch { index: 1, expected: 1, found: 2 })]
+    fn test_rejects_mismatched_clip(
+        #[case] channels: u32,
+        #[case] sample_rate: u32,
+        #[case] expected: ConcatError,
+    ) {
+        let mono = [0.0; 100];
+        let other = [0.0; 200];
+        let result = concat(&[(&mono, 1, 44100), (&other, channels, sample_rate)]);
+        assert_eq!(result, Err(expected));
+    }
+
+    #[rstest]
+    fn test_error_names_offending_clip() {
+        let clip = [0.0; 10];
+        let error = concat(&[(&clip, 2, 44100), (&clip, 2, 44100), (&clip, 2, 48000)])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ConcatError::SampleRateMismatch { index: 2, expected: 44100, found: 48000 }
+        );
+        assert_eq!(error.to_string(), "clip 2 has a sample rate of 48000 Hz, expected 44100 Hz");
+    }
+}
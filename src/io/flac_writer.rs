@@ -0,0 +1,324 @@
+//! FLAC file writing functionality.
+//!
+//! This module provides the ability to write audio data to losslessly compressed
+//! FLAC files. Floating-point input is quantized to 24-bit integer samples, the
+//! highest resolution commonly supported by FLAC decoders.
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use flacenc::bitsink::{BitSink, ByteSink};
+use flacenc::component::{BitRepr, Stream, StreamInfo};
+use flacenc::constant::MIN_BLOCK_SIZE;
+use flacenc::error::Verify;
+use flacenc::source::{Fill, FrameBuf};
+
+use crate::io::writer::quantize;
+
+/// Bit depth of the written FLAC samples.
+const BITS_PER_SAMPLE: usize = 24;
+
+/// Errors that can occur while writing a FLAC file.
+#[derive(Debug)]
+pub enum FlacWriterError {
+    /// The encoded file could not be written
+    Io(std::io::Error),
+    /// The encoder rejected the configuration or the samples
+    Encode(String),
+}
+
+impl fmt::Display for FlacWriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlacWriterError::Io(e) => write!(f, "failed to write FLAC file: {}", e),
+            FlacWriterError::Encode(msg) => write!(f, "failed to encode FLAC: {}", msg),
+        }
+    }
+}
+
+impl Error for FlacWriterError {}
+
+impl From<std::io::Error> for FlacWriterError {
+    fn from(e: std::io::Error) -> Self {
+        FlacWriterError::Io(e)
+    }
+}
+
+/// A high-level audio file writer for FLAC files.
+///
+/// The interface mirrors [`AudioWriter`](crate::io::AudioWriter): interleaved
+/// floating-point samples are passed to `write_samples` and the file is completed by
+/// `finalize`. The encoder works on the complete stream, so samples are buffered in
+/// memory and the file is only written by `finalize`.
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::io::FlacWriter;
+///
+/// let mut writer = FlacWriter::new("output.flac", 2, 44100).unwrap();
+/// let samples = vec![0.0f32; 1000];
+/// writer.write_samples(&samples).unwrap();
+/// writer.finalize().unwrap();
+/// ```
+pub struct FlacWriter {
+    path: PathBuf,
+    channels: u16,
+    sample_rate: u32,
+    samples: Vec<i32>,
+}
+
+impl FlacWriter {
+    /// Creates a new FlacWriter with specified parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Output file path
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    ///
+    /// # Returns
+    ///
+    /// Returns a Result containing the FlacWriter if successful, or an error if the
+    /// encoder does not support the channel count or sample rate.
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        channels: u16,
+        sample_rate: u32,
+    ) -> Result<Self, FlacWriterError> {
+        if channels == 0 || channels > 8 {
+            return Err(FlacWriterError::Encode(format!(
+                "unsupported channel count {}, FLAC supports 1 to 8 channels",
+                channels
+            )));
+        }
+        if sample_rate == 0 {
+            return Err(FlacWriterError::Encode("sample rate must be positive".to_string()));
+        }
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            channels,
+            sample_rate,
+            samples: Vec::new(),
+        })
+    }
+
+    /// Writes a slice of audio samples.
+    ///
+    /// The samples should be interleaved if multi-channel (e.g., [L,R,L,R,...] for stereo).
+    /// Each sample should be in the range [-1.0, 1.0], values outside are clipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Slice of floating-point samples to write
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful.
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), FlacWriterError> {
+        self.samples.extend(
+            samples.iter().map(|&sample| quantize(sample, BITS_PER_SAMPLE as u32) as i32),
+        );
+        Ok(())
+    }
+
+    /// Encodes the buffered samples and writes the FLAC file.
+    ///
+    /// This method must be called when you're done writing samples, nothing is written
+    /// to disk before. The writer cannot be used after calling this method.
+    ///
+    /// FLAC blocks hold at least 64 frames, so a stream shorter than that is padded
+    /// with silence.
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or an error if encoding or writing the file failed.
+    pub fn finalize(self) -> Result<(), FlacWriterError> {
+        let config = flacenc::config::Encoder::default()
+            .into_verified()
+            .map_err(|(_, e)| FlacWriterError::Encode(e.to_string()))?;
+        let channels = self.channels as usize;
+        let mut stream = Stream::new(self.sample_rate as usize, channels, BITS_PER_SAMPLE)
+            .map_err(|e| FlacWriterError::Encode(e.to_string()))?;
+
+        let total_frames = self.samples.len() / channels;
+        let block_size = choose_block_size(total_frames, config.block_size);
+        let blocks = self.samples[..total_frames * channels].chunks(block_size * channels);
+        for (frame_number, block) in blocks.enumerate() {
+            let size = (block.len() / channels).max(MIN_BLOCK_SIZE);
+            let mut framebuf = FrameBuf::with_size(channels, size)
+                .map_err(|e| FlacWriterError::Encode(e.to_string()))?;
+            framebuf
+                .fill_interleaved(block)
+                .map_err(|e| FlacWriterError::Encode(e.to_string()))?;
+            let frame = flacenc::encode_fixed_size_frame(
+                &config,
+                &framebuf,
+                frame_number,
+                stream.stream_info(),
+            )
+            .map_err(|e| FlacWriterError::Encode(format!("{:?}", e)))?;
+            stream.add_frame(frame);
+        }
+
+        // The encoder takes the smallest block, usually the shorter last one, as the
+        // minimum block size. Decoders only treat the stream as fixed-size when the
+        // minimum equals the maximum, so both are set to the nominal block size.
+        let mut stream_info = stream.stream_info().clone();
+        stream_info
+            .set_block_sizes(block_size, block_size)
+            .map_err(|e| FlacWriterError::Encode(e.to_string()))?;
+
+        let mut sink = ByteSink::new();
+        write_stream(&mut sink, &stream, &stream_info)?;
+        std::fs::write(&self.path, sink.as_slice())?;
+        Ok(())
+    }
+}
+
+/// Chooses a block size of at most `preferred` frames whose last block is not shorter
+/// than `MIN_BLOCK_SIZE`, the smallest block the encoder accepts.
+///
+/// This keeps the decoded length equal to the input. Only a stream shorter than
+/// `MIN_BLOCK_SIZE` frames is padded with silence to a single block of that size.
+fn choose_block_size(total_frames: usize, preferred: usize) -> usize {
+    if total_frames <= preferred {
+        return total_frames.max(MIN_BLOCK_SIZE);
+    }
+    (MIN_BLOCK_SIZE..=preferred)
+        .rev()
+        .find(|&size| {
+            let remainder = total_frames % size;
+            remainder == 0 || remainder >= MIN_BLOCK_SIZE
+        })
+        .unwrap_or(preferred)
+}
+
+/// Writes the stream like `Stream::write`, but with the given stream info.
+fn write_stream(
+    sink: &mut ByteSink,
+    stream: &Stream,
+    stream_info: &StreamInfo,
+) -> Result<(), FlacWriterError> {
+    let encode_error = |e: &dyn fmt::Display| FlacWriterError::Encode(e.to_string());
+
+    sink.write_bytes_aligned(b"fLaC").map_err(|e| encode_error(&e))?;
+    // Metadata block header: last block flag with type 0 (STREAMINFO) and its length
+    sink.write(0x80_u8).map_err(|e| encode_error(&e))?;
+    sink.write_lsbs((stream_info.count_bits() / 8) as u32, 24)
+        .map_err(|e| encode_error(&e))?;
+    stream_info.write(sink).map_err(|e| encode_error(&e))?;
+    for frame in (0..stream.frame_count()).filter_map(|n| stream.frame(n)) {
+        frame.write(sink).map_err(|e| encode_error(&e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::AudioReader;
+    use rstest::*;
+
+    fn read_all(path: &Path) -> (AudioReader, Vec<f32>) {
+        let mut reader = AudioReader::new(path).unwrap();
+        let mut samples = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            samples.extend(packet);
+        }
+        (reader, samples)
+    }
+
+    #[rstest]
+    #[case(1, 10_000)]
+    #[case(2, 10_000)]
+    #[case(2, 8200)]
+    fn test_sine_round_trip(#[case] channels: u16, #[case] frames: usize) {
+        let path = std::env::temp_dir()
+            .join(format!("sonex_flac_writer_{}ch_{}.flac", channels, frames));
+        let input: Vec<f32> = (0..frames)
+            .flat_map(|i| {
+                let sample = 0.8 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin();
+                (0..channels).map(move |ch| sample * (1.0 - 0.5 * ch as f32))
+            })
+            .collect();
+
+        let mut writer = FlacWriter::new(&path, channels, 44100).unwrap();
+        let (first, second) = input.split_at(input.len() / 3);
+        writer.write_samples(first).unwrap();
+        writer.write_samples(second).unwrap();
+        writer.finalize().unwrap();
+
+        let (reader, output) = read_all(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(reader.channels(), channels as usize);
+        assert_eq!(reader.sample_rate(), 44100);
+        assert_eq!(output.len(), input.len());
+        // Lossless apart from the 24-bit quantization
+        let tolerance = 1.0 / (1 << 24) as f32;
+        for (out, inp) in output.iter().zip(input.iter()) {
+            assert!((out - inp).abs() <= tolerance, "{} vs {}", out, inp);
+        }
+
+        // Audio that is already 24-bit survives the round trip bit for bit
+        let writer_path = path.with_extension("requantized.flac");
+        let mut writer = FlacWriter::new(&writer_path, channels, 44100).unwrap();
+        writer.write_samples(&output).unwrap();
+        writer.finalize().unwrap();
+        let (_, requantized) = read_all(&writer_path);
+        std::fs::remove_file(&writer_path).unwrap();
+        assert_eq!(requantized, output);
+    }
+
+    #[rstest]
+    fn test_empty_stream() {
+        let path = std::env::temp_dir().join("sonex_flac_writer_empty.flac");
+        FlacWriter::new(&path, 2, 48000).unwrap().finalize().unwrap();
+
+        // Just the stream marker and the STREAMINFO block without any audio frames
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes[..4], b"fLaC");
+        assert_eq!(bytes.len(), 4 + 4 + 34);
+    }
+
+    #[rstest]
+    fn test_out_of_range_is_clipped() {
+        let path = std::env::temp_dir().join("sonex_flac_writer_clip.flac");
+        let mut writer = FlacWriter::new(&path, 1, 8000).unwrap();
+        writer.write_samples(&[1.5, -2.0, 0.25]).unwrap();
+        writer.finalize().unwrap();
+
+        let (_, output) = read_all(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let tolerance = 2.0 / (1 << 23) as f32;
+        assert!((output[0] - 1.0).abs() <= tolerance);
+        assert!((output[1] + 1.0).abs() <= tolerance);
+        assert!((output[2] - 0.25).abs() <= tolerance);
+    }
+
+    #[rstest]
+    #[case(10, 4096, 64)]
+    #[case(1000, 4096, 1000)]
+    #[case(8192, 4096, 4096)]
+    #[case(10_000, 4096, 4096)]
+    #[case(8200, 4096, 4068)]
+    fn test_choose_block_size(#[case] total: usize, #[case] preferred: usize, #[case] expected: usize) {
+        let size = choose_block_size(total, preferred);
+        assert_eq!(size, expected);
+        let remainder = total % size;
+        assert!(total < MIN_BLOCK_SIZE || remainder == 0 || remainder >= MIN_BLOCK_SIZE);
+    }
+
+    #[rstest]
+    #[case(0, 44100)]
+    #[case(9, 44100)]
+    #[case(2, 0)]
+    fn test_invalid_spec(#[case] channels: u16, #[case] sample_rate: u32) {
+        let result = FlacWriter::new("unused.flac", channels, sample_rate);
+        assert!(matches!(result, Err(FlacWriterError::Encode(_))));
+    }
+}
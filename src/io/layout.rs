@@ -0,0 +1,155 @@
+//! Channel layouts.
+//!
+//! The channel count alone doesn't say which speaker a channel belongs to: four
+//! channels could be quad (front and rear pairs) or LCRS (left, center, right and
+//! surround). A [`ChannelLayout`] lists the speaker position of each channel.
+
+use symphonia::core::audio::Channels;
+
+/// Speaker position of an audio channel.
+///
+/// The first 18 positions follow the order of Microsoft's `WAVEFORMATEXTENSIBLE`
+/// channel mask, which is also the order channels are interleaved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelPosition {
+    /// Front left, also used for mono
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    /// Low-frequency effects (subwoofer)
+    Lfe,
+    RearLeft,
+    RearRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    RearCenter,
+    SideLeft,
+    SideRight,
+    TopCenter,
+    TopFrontLeft,
+    TopFrontCenter,
+    TopFrontRight,
+    TopRearLeft,
+    TopRearCenter,
+    TopRearRight,
+    RearLeftOfCenter,
+    RearRightOfCenter,
+    FrontLeftWide,
+    FrontRightWide,
+    FrontLeftHigh,
+    FrontCenterHigh,
+    FrontRightHigh,
+    /// Second low-frequency effects channel
+    Lfe2,
+}
+
+impl ChannelPosition {
+    fn from_channel(channel: Channels) -> Option<Self> {
+        let position = match channel {
+            Channels::FRONT_LEFT => ChannelPosition::FrontLeft,
+            Channels::FRONT_RIGHT => ChannelPosition::FrontRight,
+            Channels::FRONT_CENTRE => ChannelPosition::FrontCenter,
+            Channels::LFE1 => ChannelPosition::Lfe,
+            Channels::REAR_LEFT => ChannelPosition::RearLeft,
+            Channels::REAR_RIGHT => ChannelPosition::RearRight,
+            Channels::FRONT_LEFT_CENTRE => ChannelPosition::FrontLeftOfCenter,
+            Channels::FRONT_RIGHT_CENTRE => ChannelPosition::FrontRightOfCenter,
+            Channels::REAR_CENTRE => ChannelPosition::RearCenter,
+            Channels::SIDE_LEFT => ChannelPosition::SideLeft,
+            Channels::SIDE_RIGHT => ChannelPosition::SideRight,
+            Channels::TOP_CENTRE => ChannelPosition::TopCenter,
+            Channels::TOP_FRONT_LEFT => ChannelPosition::TopFrontLeft,
+            Channels::TOP_FRONT_CENTRE => ChannelPosition::TopFrontCenter,
+            Channels::TOP_FRONT_RIGHT => ChannelPosition::TopFrontRight,
+            Channels::TOP_REAR_LEFT => ChannelPosition::TopRearLeft,
+            Channels::TOP_REAR_CENTRE => ChannelPosition::TopRearCenter,
+            Channels::TOP_REAR_RIGHT => ChannelPosition::TopRearRight,
+            Channels::REAR_LEFT_CENTRE => ChannelPosition::RearLeftOfCenter,
+            Channels::REAR_RIGHT_CENTRE => ChannelPosition::RearRightOfCenter,
+            Channels::FRONT_LEFT_WIDE => ChannelPosition::FrontLeftWide,
+            Channels::FRONT_RIGHT_WIDE => ChannelPosition::FrontRightWide,
+            Channels::FRONT_LEFT_HIGH => ChannelPosition::FrontLeftHigh,
+            Channels::FRONT_CENTRE_HIGH => ChannelPosition::FrontCenterHigh,
+            Channels::FRONT_RIGHT_HIGH => ChannelPosition::FrontRightHigh,
+            Channels::LFE2 => ChannelPosition::Lfe2,
+            _ => return None,
+        };
+        Some(position)
+    }
+}
+
+/// The speaker positions of the channels of an audio stream, in interleaving order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelLayout {
+    positions: Vec<ChannelPosition>,
+}
+
+impl ChannelLayout {
+    /// Converts a Symphonia channel mask.
+    pub(crate) fn from_channels(channels: Channels) -> Self {
+        Self {
+            positions: channels.iter().filter_map(ChannelPosition::from_channel).collect(),
+        }
+    }
+
+    /// Returns the position of each channel, in interleaving order.
+    pub fn positions(&self) -> &[ChannelPosition] {
+        &self.positions
+    }
+
+    /// Returns the number of channels.
+    pub fn count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the index of the channel at the given position, if present.
+    pub fn index_of(&self, position: ChannelPosition) -> Option<usize> {
+        self.positions.iter().position(|&p| p == position)
+    }
+
+    /// Returns whether the layout has a low-frequency effects channel.
+    pub fn has_lfe(&self) -> bool {
+        self.positions
+            .iter()
+            .any(|&p| p == ChannelPosition::Lfe || p == ChannelPosition::Lfe2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::Layout;
+    use rstest::*;
+
+    #[rstest]
+    fn test_five_point_one() {
+        let layout = ChannelLayout::from_channels(Layout::FivePointOne.into_channels());
+        assert_eq!(
+            layout.positions(),
+            &[
+                ChannelPosition::FrontLeft,
+                ChannelPosition::FrontRight,
+                ChannelPosition::FrontCenter,
+                ChannelPosition::Lfe,
+                ChannelPosition::RearLeft,
+                ChannelPosition::RearRight,
+            ]
+        );
+        assert!(layout.has_lfe());
+        assert_eq!(layout.index_of(ChannelPosition::Lfe), Some(3));
+    }
+
+    #[rstest]
+    fn test_quad_and_lcrs_differ() {
+        let quad = Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::REAR_LEFT | Channels::REAR_RIGHT;
+        let lcrs = Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::FRONT_CENTRE | Channels::REAR_CENTRE;
+        let quad = ChannelLayout::from_channels(quad);
+        let lcrs = ChannelLayout::from_channels(lcrs);
+        assert_eq!(quad.count(), 4);
+        assert_eq!(lcrs.count(), 4);
+        assert_ne!(quad, lcrs);
+        assert!(!quad.has_lfe());
+        assert_eq!(lcrs.index_of(ChannelPosition::RearCenter), Some(3));
+        assert_eq!(quad.index_of(ChannelPosition::FrontCenter), None);
+    }
+}
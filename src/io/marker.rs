@@ -0,0 +1,150 @@
+//! WAV cue markers.
+//!
+//! WAV files store markers in two chunks: the `cue ` chunk holds the position of
+//! each cue point, and the `labl` entries of a `LIST` chunk of type `adtl` attach a
+//! label to a cue point by its id.
+
+use crate::io::riff::{push_chunk, read_u32, ChunkId};
+
+/// Chunks holding the cue points and their labels.
+pub(crate) const CUE_CHUNK_IDS: [ChunkId; 2] = [*b"cue ", *b"LIST"];
+
+/// A labeled position in an audio file, e.g. the start of a podcast chapter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    /// Position of the marker in frames from the start of the audio
+    pub frame: u64,
+    /// Position of the marker in seconds from the start of the audio
+    pub time_sec: f64,
+    /// Label of the marker, empty if the file has none
+    pub label: String,
+}
+
+/// Collects the cue points of a WAV file as `(frame, label)` pairs ordered by frame.
+///
+/// # Arguments
+///
+/// * `chunks` - Chunks read with [`CUE_CHUNK_IDS`], other chunks are ignored
+pub(crate) fn parse_cue_points(chunks: &[(ChunkId, Vec<u8>)]) -> Vec<(u64, String)> {
+    let mut positions: Vec<(u32, u64)> = Vec::new();
+    let mut labels: Vec<(u32, String)> = Vec::new();
+    for (id, data) in chunks {
+        match id {
+            b"cue " => positions.extend(parse_cue(data)),
+            b"LIST" if data.starts_with(b"adtl") => labels.extend(parse_labels(&data[4..])),
+            _ => {}
+        }
+    }
+
+    let mut cue_points: Vec<(u64, String)> = positions
+        .into_iter()
+        .map(|(id, frame)| {
+            let label = labels.iter().find(|(label_id, _)| *label_id == id);
+            (frame, label.map(|(_, text)| text.clone()).unwrap_or_default())
+        })
+        .collect();
+    cue_points.sort_by_key(|(frame, _)| *frame);
+    cue_points
+}
+
+/// Parses the `(id, frame)` pairs of a `cue ` chunk.
+fn parse_cue(data: &[u8]) -> Vec<(u32, u64)> {
+    let count = read_u32(data, 0).unwrap_or(0) as usize;
+    data.get(4..)
+        .unwrap_or_default()
+        .chunks_exact(24)
+        .take(count)
+        .filter_map(|point| Some((read_u32(point, 0)?, read_u32(point, 20)? as u64)))
+        .collect()
+}
+
+/// Parses the `(id, label)` pairs of the `labl` entries of an `adtl` list.
+fn parse_labels(mut data: &[u8]) -> Vec<(u32, String)> {
+    let mut labels = Vec::new();
+    while data.len() >= 8 {
+        let size = read_u32(data, 4).unwrap_or(0) as usize;
+        let Some(body) = data.get(8..8 + size) else {
+            break;
+        };
+        if &data[..4] == b"labl" {
+            if let Some(id) = read_u32(body, 0) {
+                let text = body[4..].split(|&b| b == 0).next().unwrap_or_default();
+                labels.push((id, String::from_utf8_lossy(text).into_owned()));
+            }
+        }
+        data = data.get(8 + size + size % 2..).unwrap_or_default();
+    }
+    labels
+}
+
+/// Encodes cue points as a `cue ` chunk followed by a `LIST`/`adtl` chunk of labels.
+///
+/// Cue points are numbered from 1 in the given order.
+pub(crate) fn cue_chunks(cue_points: &[(u64, String)]) -> Vec<u8> {
+    let mut cue = Vec::with_capacity(4 + 24 * cue_points.len());
+    cue.extend((cue_points.len() as u32).to_le_bytes());
+    for (id, (frame, _)) in (1u32..).zip(cue_points) {
+        let frame = (*frame).min(u32::MAX as u64) as u32;
+        cue.extend(id.to_le_bytes());
+        cue.extend(frame.to_le_bytes());
+        cue.extend(b"data");
+        cue.extend(0u32.to_le_bytes());
+        cue.extend(0u32.to_le_bytes());
+        cue.extend(frame.to_le_bytes());
+    }
+
+    let mut list = b"adtl".to_vec();
+    for (id, (_, label)) in (1u32..).zip(cue_points) {
+        let mut body = id.to_le_bytes().to_vec();
+        body.extend(label.as_bytes());
+        body.push(0);
+        push_chunk(&mut list, b"labl", &body);
+    }
+
+    let mut chunks = Vec::new();
+    push_chunk(&mut chunks, b"cue ", &cue);
+    push_chunk(&mut chunks, b"LIST", &list);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::riff::read_chunks;
+    use std::io::Cursor;
+    use rstest::*;
+
+    fn wav_with_chunks(chunks: &[u8]) -> Vec<u8> {
+        let mut wav = b"RIFF".to_vec();
+        wav.extend((4 + chunks.len() as u32).to_le_bytes());
+        wav.extend(b"WAVE");
+        wav.extend(chunks);
+        wav
+    }
+
+    #[rstest]
+    fn test_chunks_round_trip() {
+        let cue_points = vec![(48000, "Intro".to_string()), (96000, "Odd".to_string()), (10, String::new())];
+        let wav = wav_with_chunks(&cue_chunks(&cue_points));
+        let chunks = read_chunks(Cursor::new(wav), &CUE_CHUNK_IDS);
+        assert_eq!(
+            parse_cue_points(&chunks),
+            vec![(10, String::new()), (48000, "Intro".to_string()), (96000, "Odd".to_string())]
+        );
+    }
+
+    #[rstest]
+    fn test_unlabeled_and_foreign_lists() {
+        let mut chunks = Vec::new();
+        push_chunk(&mut chunks, b"LIST", b"INFOISFT\x04\x00\x00\x00test");
+        let mut cue = 1u32.to_le_bytes().to_vec();
+        cue.extend([7, 0, 0, 0, 0, 0, 0, 0]);
+        cue.extend(b"data");
+        cue.extend([0; 8]);
+        cue.extend(5u32.to_le_bytes());
+        push_chunk(&mut chunks, b"cue ", &cue);
+
+        let chunks = read_chunks(Cursor::new(wav_with_chunks(&chunks)), &CUE_CHUNK_IDS);
+        assert_eq!(parse_cue_points(&chunks), vec![(5, String::new())]);
+    }
+}
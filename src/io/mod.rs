@@ -2,6 +2,23 @@
 
 mod reader;
 mod writer;
+mod flac_writer;
+mod concat;
+mod marker;
+mod riff;
+mod bwf;
+mod layout;
+mod raw;
+#[cfg(feature = "opus")]
+mod opus_writer;
 
-pub use reader::AudioReader;
-pub use writer::AudioWriter;
\ No newline at end of file
+pub use reader::{AudioReader, AudioReaderOptions, ReaderError};
+pub use writer::{AudioWriter, SampleDepth};
+pub use flac_writer::{FlacWriter, FlacWriterError};
+pub use concat::{concat, ConcatError};
+pub use marker::Marker;
+pub use bwf::BwfMetadata;
+pub use layout::{ChannelLayout, ChannelPosition};
+pub use raw::{ByteOrder, RawPcmSpec, RawSampleFormat};
+#[cfg(feature = "opus")]
+pub use opus_writer::{OpusWriter, OpusWriterError};
@@ -0,0 +1,521 @@
+//! Ogg Opus file writing functionality.
+//!
+//! This module writes compressed lossy audio for web delivery, encoded with libopus
+//! and packed into an Ogg container as described in RFC 7845. It is only available
+//! with the `opus` feature. The files can be read back with
+//! [`AudioReader`](crate::io::AudioReader), which decodes Opus at 48 kHz.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::sync::Mutex;
+
+use audiopus::coder::{Decoder as RawOpusDecoder, Encoder};
+use audiopus::{Application, Bitrate, Channels, SampleRate};
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use symphonia::core::audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal, SignalSpec};
+use symphonia::core::codecs::{
+    CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult, CODEC_TYPE_OPUS,
+};
+use symphonia::core::errors::{Error as SymphoniaError, Result as SymphoniaResult};
+use symphonia::core::formats::Packet;
+
+/// Sample rate of the granule positions and of decoded Opus audio.
+const OPUS_RATE: u32 = 48000;
+/// Input sample rates supported by the encoder.
+const SUPPORTED_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+/// Frames per second of encoded audio, i.e. 20 ms per packet.
+const PACKETS_PER_SEC: u32 = 50;
+/// Largest Opus packet, as recommended by the libopus documentation.
+const MAX_PACKET_BYTES: usize = 4000;
+/// Longest Opus packet in frames at 48 kHz (120 ms).
+const MAX_PACKET_FRAMES: usize = 5760;
+/// Serial number of the single logical Ogg stream.
+const STREAM_SERIAL: u32 = 0x736f_6e78;
+
+/// Errors that can occur while writing an Ogg Opus file.
+#[derive(Debug)]
+pub enum OpusWriterError {
+    /// The encoded file could not be written
+    Io(io::Error),
+    /// Opus can't encode audio at this sample rate, the audio must be resampled to
+    /// one of 8, 12, 16, 24 or 48 kHz first
+    UnsupportedSampleRate(u32),
+    /// Opus can't encode this many channels, only mono and stereo are supported
+    UnsupportedChannels(u16),
+    /// The encoder rejected the configuration or the samples
+    Encode(String),
+}
+
+impl fmt::Display for OpusWriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpusWriterError::Io(e) => write!(f, "failed to write Opus file: {}", e),
+            OpusWriterError::UnsupportedSampleRate(rate) => write!(
+                f,
+                "unsupported sample rate {} Hz, Opus supports 8000, 12000, 16000, 24000 and 48000 Hz",
+                rate
+            ),
+            OpusWriterError::UnsupportedChannels(channels) => write!(
+                f,
+                "unsupported channel count {}, Opus supports 1 or 2 channels",
+                channels
+            ),
+            OpusWriterError::Encode(msg) => write!(f, "failed to encode Opus: {}", msg),
+        }
+    }
+}
+
+impl Error for OpusWriterError {}
+
+impl From<io::Error> for OpusWriterError {
+    fn from(e: io::Error) -> Self {
+        OpusWriterError::Io(e)
+    }
+}
+
+impl From<audiopus::Error> for OpusWriterError {
+    fn from(e: audiopus::Error) -> Self {
+        OpusWriterError::Encode(e.to_string())
+    }
+}
+
+/// A high-level audio file writer for Ogg Opus files.
+///
+/// The interface mirrors [`AudioWriter`](crate::io::AudioWriter): interleaved
+/// floating-point samples are passed to `write_samples` and the file is completed by
+/// `finalize`. Audio is encoded in 20 ms packets as it arrives.
+///
+/// Opus encodes mono or stereo audio at 8, 12, 16, 24 or 48 kHz. Audio at other
+/// rates, e.g. 44.1 kHz, must be resampled first, for example with
+/// [`ResampleNode`](crate::process::ResampleNode). Decoders always output 48 kHz;
+/// the original rate is stored in the header for reference.
+///
+/// The encoder delays the audio by a few milliseconds. The delay is stored in the
+/// header as the pre-skip, which players following RFC 7845 skip.
+/// [`AudioReader`](crate::io::AudioReader) doesn't: its decoded audio starts with the
+/// pre-skip and ends with the padding of the last packet.
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::io::OpusWriter;
+///
+/// // Stereo at 48 kHz, encoded at 96 kbit/s
+/// let mut writer = OpusWriter::new("output.opus", 2, 48000, 96_000).unwrap();
+/// let samples = vec![0.0f32; 1000];
+/// writer.write_samples(&samples).unwrap();
+/// writer.finalize().unwrap();
+/// ```
+pub struct OpusWriter {
+    writer: PacketWriter<BufWriter<File>>,
+    encoder: Encoder,
+    channels: usize,
+    /// Frames per packet at the input rate
+    packet_frames: usize,
+    /// Duration of an input frame in 48 kHz granules
+    granule_scale: u64,
+    /// Encoder delay in 48 kHz granules, stored as the pre-skip of the header
+    pre_skip: u64,
+    /// Samples waiting for a complete packet
+    pending: Vec<f32>,
+    /// Samples received by `write_samples`
+    samples_written: u64,
+    /// Frames passed to the encoder, including padding
+    frames_encoded: u64,
+    /// The last encoded packet with its granule position. It is held back until the
+    /// next one arrives, because the last packet of the stream has to be marked.
+    held_packet: Option<(Vec<u8>, u64)>,
+}
+
+impl OpusWriter {
+    /// Creates a new OpusWriter with specified parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Output file path
+    /// * `channels` - Number of audio channels, 1 or 2
+    /// * `sample_rate` - Sample rate in Hz, one of 8000, 12000, 16000, 24000 or 48000
+    /// * `bitrate` - Target bitrate in bits per second, from 6000 to 510000
+    ///
+    /// # Returns
+    ///
+    /// Returns a Result containing the OpusWriter if successful, or an error if the
+    /// format is not supported or the file can't be created.
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        channels: u16,
+        sample_rate: u32,
+        bitrate: u32,
+    ) -> Result<Self, OpusWriterError> {
+        let opus_channels = match channels {
+            1 => Channels::Mono,
+            2 => Channels::Stereo,
+            _ => return Err(OpusWriterError::UnsupportedChannels(channels)),
+        };
+        if !SUPPORTED_RATES.contains(&sample_rate) {
+            return Err(OpusWriterError::UnsupportedSampleRate(sample_rate));
+        }
+        if !(6000..=510_000).contains(&bitrate) {
+            return Err(OpusWriterError::Encode(format!(
+                "unsupported bitrate {} bit/s, Opus supports 6000 to 510000 bit/s",
+                bitrate
+            )));
+        }
+
+        let rate = SampleRate::try_from(sample_rate as i32)?;
+        let mut encoder = Encoder::new(rate, opus_channels, Application::Audio)?;
+        encoder.set_bitrate(Bitrate::BitsPerSecond(bitrate as i32))?;
+        let granule_scale = (OPUS_RATE / sample_rate) as u64;
+        let pre_skip = encoder.lookahead()? as u64 * granule_scale;
+
+        let mut writer = PacketWriter::new(BufWriter::new(File::create(path)?));
+        // The identification and comment headers each need a page of their own
+        writer.write_packet(
+            opus_head(channels as u8, pre_skip as u16, sample_rate),
+            STREAM_SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+        writer.write_packet(opus_tags(), STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)?;
+
+        Ok(Self {
+            writer,
+            encoder,
+            channels: channels as usize,
+            packet_frames: (sample_rate / PACKETS_PER_SEC) as usize,
+            granule_scale,
+            pre_skip,
+            pending: Vec::new(),
+            samples_written: 0,
+            frames_encoded: 0,
+            held_packet: None,
+        })
+    }
+
+    /// Writes a slice of audio samples.
+    ///
+    /// The samples should be interleaved if multi-channel (e.g., [L,R,L,R,...] for stereo).
+    /// Each sample should be in the range [-1.0, 1.0].
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Slice of floating-point samples to write
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or an error if encoding or writing failed.
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), OpusWriterError> {
+        self.pending.extend_from_slice(samples);
+        self.samples_written += samples.len() as u64;
+
+        let packet_len = self.packet_frames * self.channels;
+        let complete = self.pending.len() / packet_len * packet_len;
+        let pending = std::mem::take(&mut self.pending);
+        for packet in pending[..complete].chunks_exact(packet_len) {
+            self.encode_packet(packet)?;
+        }
+        self.pending = pending[complete..].to_vec();
+        Ok(())
+    }
+
+    /// Encodes the remaining samples and completes the Ogg stream.
+    ///
+    /// This method must be called when you're done writing samples. The writer cannot
+    /// be used after calling this method. The last packet is padded with silence,
+    /// which players following RFC 7845 trim using the stream length stored in the
+    /// last page.
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or an error if encoding or writing the file failed.
+    pub fn finalize(mut self) -> Result<(), OpusWriterError> {
+        // Feed silence until the encoder has passed its lookahead, so the end of the
+        // input makes it into a packet
+        let packet_len = self.packet_frames * self.channels;
+        let lookahead_frames = self.pre_skip / self.granule_scale;
+        let frames_written = self.samples_written / self.channels as u64;
+        let mut pending = std::mem::take(&mut self.pending);
+        while !pending.is_empty() || self.frames_encoded < frames_written + lookahead_frames {
+            pending.resize(packet_len, 0.0);
+            self.encode_packet(&pending)?;
+            pending.clear();
+        }
+
+        let end_granule = self.pre_skip + frames_written * self.granule_scale;
+        if let Some((packet, _)) = self.held_packet.take() {
+            self.writer.write_packet(
+                packet.into_boxed_slice(),
+                STREAM_SERIAL,
+                PacketWriteEndInfo::EndStream,
+                end_granule,
+            )?;
+        }
+        let mut file = self.writer.into_inner();
+        io::Write::flush(&mut file)?;
+        Ok(())
+    }
+
+    /// Encodes one packet of `packet_frames` frames and writes the previous one.
+    fn encode_packet(&mut self, samples: &[f32]) -> Result<(), OpusWriterError> {
+        let mut output = vec![0u8; MAX_PACKET_BYTES];
+        let len = self.encoder.encode_float(samples, &mut output)?;
+        output.truncate(len);
+        self.frames_encoded += self.packet_frames as u64;
+
+        // Decoded samples up to the end of this packet, the pre-skip included
+        let granule = self.frames_encoded * self.granule_scale;
+        if let Some((packet, granule)) = self.held_packet.replace((output, granule)) {
+            self.writer.write_packet(
+                packet.into_boxed_slice(),
+                STREAM_SERIAL,
+                PacketWriteEndInfo::NormalPacket,
+                granule,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the identification header of RFC 7845, section 5.1.
+fn opus_head(channels: u8, pre_skip: u16, input_sample_rate: u32) -> Box<[u8]> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1);
+    head.push(channels);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_sample_rate.to_le_bytes());
+    // Output gain and channel mapping family 0 (mono or stereo)
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+    head.into_boxed_slice()
+}
+
+/// Builds the comment header of RFC 7845, section 5.2, without user comments.
+fn opus_tags() -> Box<[u8]> {
+    let vendor = concat!("sonex ", env!("CARGO_PKG_VERSION"));
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags.into_boxed_slice()
+}
+
+/// Symphonia decoder for Opus packets, backed by libopus.
+///
+/// Symphonia demuxes Ogg Opus but has no Opus decoder of its own, so
+/// [`AudioReader`](crate::io::AudioReader) registers this one when the `opus`
+/// feature is enabled.
+pub(crate) struct OpusDecoder {
+    params: CodecParameters,
+    // The libopus decoder is not Sync, which Symphonia decoders must be
+    decoder: Mutex<RawOpusDecoder>,
+    channels: Channels,
+    buffer: AudioBuffer<f32>,
+    interleaved: Vec<f32>,
+}
+
+impl Decoder for OpusDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> SymphoniaResult<Self> {
+        let layout = params.channels.ok_or(SymphoniaError::Unsupported("opus: channel count is missing"))?;
+        let channels = match layout.count() {
+            1 => Channels::Mono,
+            2 => Channels::Stereo,
+            _ => return Err(SymphoniaError::Unsupported("opus: only mono and stereo streams are supported")),
+        };
+        let decoder = RawOpusDecoder::new(SampleRate::Hz48000, channels)
+            .map_err(|_| SymphoniaError::DecodeError("opus: failed to create decoder"))?;
+
+        let mut params = params.clone();
+        params.with_sample_rate(OPUS_RATE);
+        Ok(Self {
+            params,
+            decoder: Mutex::new(decoder),
+            channels,
+            buffer: AudioBuffer::new(MAX_PACKET_FRAMES as u64, SignalSpec::new(OPUS_RATE, layout)),
+            interleaved: vec![0.0; MAX_PACKET_FRAMES * layout.count()],
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[CodecDescriptor {
+            codec: CODEC_TYPE_OPUS,
+            short_name: "opus",
+            long_name: "Opus",
+            inst_func: |params, options| Ok(Box::new(OpusDecoder::try_new(params, options)?)),
+        }]
+    }
+
+    fn reset(&mut self) {
+        if let Ok(decoder) = RawOpusDecoder::new(SampleRate::Hz48000, self.channels) {
+            self.decoder = Mutex::new(decoder);
+        }
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> SymphoniaResult<AudioBufferRef<'_>> {
+        let invalid = |_| SymphoniaError::DecodeError("opus: invalid packet");
+        let decoder = self.decoder.get_mut().unwrap_or_else(|e| e.into_inner());
+        let frames = decoder
+            .decode_float(
+                Some(packet.buf().try_into().map_err(invalid)?),
+                self.interleaved.as_mut_slice().try_into().map_err(invalid)?,
+                false,
+            )
+            .map_err(invalid)?;
+
+        let channels = self.channels as usize;
+        self.buffer.clear();
+        self.buffer.render_reserved(Some(frames));
+        for channel in 0..channels {
+            let samples = self.interleaved[..frames * channels].iter().skip(channel).step_by(channels);
+            self.buffer.chan_mut(channel).iter_mut().zip(samples).for_each(|(out, &s)| *out = s);
+        }
+        Ok(self.buffer.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buffer.as_audio_buffer_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::Meter;
+    use crate::io::AudioReader;
+    use crate::synth::sine;
+    use rstest::*;
+
+    fn lufs(samples: &[f32], channels: usize, sample_rate: u32) -> f64 {
+        Meter::from_samples(samples, channels as u32, sample_rate)
+            .unwrap()
+            .lufs_integrated()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(1, 48000, 64_000)]
+    #[case(2, 48000, 128_000)]
+    #[case(1, 16000, 32_000)]
+    fn test_sine_round_trip(#[case] channels: u16, #[case] sample_rate: u32, #[case] bitrate: u32) {
+        let path = std::env::temp_dir()
+            .join(format!("sonex_opus_writer_{}ch_{}.opus", channels, sample_rate));
+        let input: Vec<f32> = sine(1000.0, 0.5, 3.0, sample_rate)
+            .into_iter()
+            .flat_map(|s| std::iter::repeat_n(s, channels as usize))
+            .collect();
+
+        let mut writer = OpusWriter::new(&path, channels, sample_rate, bitrate).unwrap();
+        // Blocks that don't line up with the packets
+        for block in input.chunks(1001 * channels as usize) {
+            writer.write_samples(block).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut reader = AudioReader::new(&path).unwrap();
+        let mut output = Vec::new();
+        while let Some(packet) = reader.read_packet().unwrap() {
+            output.extend(packet);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(reader.codec_name(), "opus");
+        assert_eq!(reader.channels(), channels as usize);
+        assert_eq!(reader.sample_rate(), 48000);
+        // Decoded at 48 kHz, with up to one packet and the encoder delay extra
+        let expected_frames = 3 * 48000;
+        let frames = output.len() / channels as usize;
+        assert!(frames >= expected_frames && frames < expected_frames + 2 * 960, "{} frames", frames);
+
+        let difference = lufs(&output, channels as usize, 48000) - lufs(&input, channels as usize, sample_rate);
+        assert!(difference.abs() < 0.5, "loudness differs by {} LU", difference);
+    }
+
+    #[rstest]
+    #[case(48000)]
+    #[case(16000)]
+    fn test_granule_positions(#[case] sample_rate: u32) {
+        let path = std::env::temp_dir().join(format!("sonex_opus_writer_granule_{}.opus", sample_rate));
+        // A quarter second of silence, then a tone, long enough for several pages
+        let mut input = vec![0.0; sample_rate as usize / 4];
+        input.extend(sine(440.0, 0.5, 5.75, sample_rate));
+        let mut writer = OpusWriter::new(&path, 1, sample_rate, 64_000).unwrap();
+        writer.write_samples(&input).unwrap();
+        writer.finalize().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let decoded = AudioReader::new(&path).unwrap().read_all().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Granule position and number of completed packets of each page
+        let mut pages = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            assert_eq!(&bytes[offset..offset + 4], b"OggS");
+            let granule = u64::from_le_bytes(bytes[offset + 6..offset + 14].try_into().unwrap());
+            let segments = &bytes[offset + 27..offset + 27 + bytes[offset + 26] as usize];
+            pages.push((granule, segments.iter().filter(|&&len| len < 255).count()));
+            offset += 27 + segments.len() + segments.iter().map(|&len| len as usize).sum::<usize>();
+        }
+        let pre_skip = u16::from_le_bytes(bytes[38..40].try_into().unwrap()) as u64;
+
+        // Audio pages count every decoded sample, the pre-skip included, and the last
+        // one ends the stream pre-skip samples after the input
+        let (last, audio) = pages[2..].split_last().unwrap();
+        assert!(!audio.is_empty());
+        let mut packets = 0;
+        for &(granule, completed) in audio {
+            packets += completed as u64;
+            assert_eq!(granule, packets * 960);
+        }
+        assert_eq!(last.0, pre_skip + 6 * 48000);
+
+        // AudioReader keeps the pre-skip, so the tone starts that much later
+        let onset = decoded.iter().position(|s| s.abs() > 0.1).unwrap() as u64;
+        assert!(onset.abs_diff(12000 + pre_skip) < 48, "onset at {}, pre-skip {}", onset, pre_skip);
+    }
+
+    #[rstest]
+    fn test_header_pages() {
+        let path = std::env::temp_dir().join("sonex_opus_writer_header.opus");
+        OpusWriter::new(&path, 2, 24000, 64_000).unwrap().finalize().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&bytes[..4], b"OggS");
+        // The identification header follows the 27 byte page header and 1 segment
+        let head = &bytes[28..47];
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head[9], 2);
+        assert_eq!(u32::from_le_bytes(head[12..16].try_into().unwrap()), 24000);
+        assert!(bytes.windows(8).any(|w| w == b"OpusTags"));
+    }
+
+    #[rstest]
+    #[case::rate(1, 44100, 64_000)]
+    #[case::channels(6, 48000, 64_000)]
+    #[case::bitrate(2, 48000, 1_000_000)]
+    fn test_invalid_spec(#[case] channels: u16, #[case] sample_rate: u32, #[case] bitrate: u32) {
+        let result = OpusWriter::new("unused.opus", channels, sample_rate, bitrate);
+        match result {
+            Err(OpusWriterError::UnsupportedSampleRate(rate)) => {
+                assert_eq!(rate, 44100);
+                assert!(OpusWriterError::UnsupportedSampleRate(rate).to_string().contains("48000 Hz"));
+            }
+            Err(OpusWriterError::UnsupportedChannels(count)) => {
+                assert_eq!(count, 6);
+                assert!(OpusWriterError::UnsupportedChannels(count).to_string().contains("1 or 2"));
+            }
+            Err(OpusWriterError::Encode(_)) => assert_eq!(bitrate, 1_000_000),
+            _ => panic!("invalid spec accepted"),
+        }
+    }
+}
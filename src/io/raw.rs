@@ -0,0 +1,307 @@
+//! Headerless PCM input.
+//!
+//! Raw PCM dumps carry no header, so the probe can't detect their format. The caller
+//! describes the samples with a [`RawPcmSpec`] and [`RawPcmFormat`] cuts the bytes
+//! into packets for Symphonia's PCM decoder, taking the place of a container format.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use symphonia::core::audio::Channels;
+use symphonia::core::codecs::{
+    CodecParameters, CodecType, CODEC_TYPE_PCM_F32BE, CODEC_TYPE_PCM_F32LE, CODEC_TYPE_PCM_S16BE,
+    CODEC_TYPE_PCM_S16LE, CODEC_TYPE_PCM_S24BE, CODEC_TYPE_PCM_S24LE, CODEC_TYPE_PCM_S32BE,
+    CODEC_TYPE_PCM_S32LE,
+};
+use symphonia::core::errors::{Error as SymphoniaError, Result};
+use symphonia::core::formats::{Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::{Metadata, MetadataLog};
+use symphonia::core::units::TimeBase;
+
+/// Number of frames per packet.
+const PACKET_FRAMES: u64 = 4096;
+
+/// Number of channel positions in symphonia's `Channels` bit set.
+const MAX_CHANNELS: usize = 26;
+
+/// Sample encoding of raw PCM data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawSampleFormat {
+    /// 16-bit signed integer
+    Int16,
+    /// 24-bit signed integer, packed in 3 bytes
+    Int24,
+    /// 32-bit signed integer
+    Int32,
+    /// 32-bit IEEE float, nominally in [-1.0, 1.0]
+    Float32,
+}
+
+impl RawSampleFormat {
+    /// Returns the size of one sample in bytes.
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            RawSampleFormat::Int16 => 2,
+            RawSampleFormat::Int24 => 3,
+            RawSampleFormat::Int32 | RawSampleFormat::Float32 => 4,
+        }
+    }
+}
+
+/// Byte order of multi-byte samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Least significant byte first, as on x86 and ARM and in WAV files
+    #[default]
+    LittleEndian,
+    /// Most significant byte first, as in AIFF files
+    BigEndian,
+}
+
+/// Layout of headerless PCM data, see [`AudioReader::from_raw_pcm`](crate::io::AudioReader::from_raw_pcm).
+///
+/// Samples are interleaved, i.e. a frame holds one sample of each channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawPcmSpec {
+    /// Encoding of each sample
+    pub sample_format: RawSampleFormat,
+    /// Byte order of each sample
+    pub byte_order: ByteOrder,
+    /// Number of interleaved channels, 1 to 26 (the positions symphonia can describe)
+    pub channels: usize,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+}
+
+impl RawPcmSpec {
+    fn frame_bytes(&self) -> u64 {
+        (self.sample_format.bytes_per_sample() * self.channels) as u64
+    }
+
+    fn codec(&self) -> CodecType {
+        let little = self.byte_order == ByteOrder::LittleEndian;
+        match (self.sample_format, little) {
+            (RawSampleFormat::Int16, true) => CODEC_TYPE_PCM_S16LE,
+            (RawSampleFormat::Int16, false) => CODEC_TYPE_PCM_S16BE,
+            (RawSampleFormat::Int24, true) => CODEC_TYPE_PCM_S24LE,
+            (RawSampleFormat::Int24, false) => CODEC_TYPE_PCM_S24BE,
+            (RawSampleFormat::Int32, true) => CODEC_TYPE_PCM_S32LE,
+            (RawSampleFormat::Int32, false) => CODEC_TYPE_PCM_S32BE,
+            (RawSampleFormat::Float32, true) => CODEC_TYPE_PCM_F32LE,
+            (RawSampleFormat::Float32, false) => CODEC_TYPE_PCM_F32BE,
+        }
+    }
+}
+
+/// A format reader for headerless PCM, producing one track of fixed-size packets.
+pub(crate) struct RawPcmFormat {
+    source: MediaSourceStream,
+    spec: RawPcmSpec,
+    tracks: Vec<Track>,
+    metadata: MetadataLog,
+    n_frames: Option<u64>,
+    position: u64,
+}
+
+impl RawPcmFormat {
+    /// Creates a reader for the whole source.
+    ///
+    /// Fails with `SymphoniaError::Unsupported` if the channel count or sample rate of
+    /// the spec is invalid. A trailing partial frame is ignored.
+    pub(crate) fn new(source: Box<dyn MediaSource>, spec: RawPcmSpec) -> Result<Self> {
+        if spec.channels == 0 || spec.channels > MAX_CHANNELS {
+            return Err(SymphoniaError::Unsupported("raw pcm: channel count must be 1 to 26"));
+        }
+        if spec.sample_rate == 0 {
+            return Err(SymphoniaError::Unsupported("raw pcm: sample rate must be positive"));
+        }
+
+        let n_frames = source.byte_len().map(|len| len / spec.frame_bytes());
+        let bits = (spec.sample_format.bytes_per_sample() * 8) as u32;
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(spec.codec())
+            .with_sample_rate(spec.sample_rate)
+            .with_time_base(TimeBase::new(1, spec.sample_rate))
+            .with_channels(Channels::from_bits_truncate(((1u64 << spec.channels) - 1) as u32))
+            .with_bits_per_sample(bits)
+            .with_bits_per_coded_sample(bits)
+            .with_max_frames_per_packet(PACKET_FRAMES);
+        if let Some(n_frames) = n_frames {
+            params.with_n_frames(n_frames);
+        }
+
+        Ok(Self {
+            source: MediaSourceStream::new(source, Default::default()),
+            spec,
+            tracks: vec![Track::new(0, params)],
+            metadata: MetadataLog::default(),
+            n_frames,
+            position: 0,
+        })
+    }
+}
+
+impl FormatReader for RawPcmFormat {
+    /// The layout of raw PCM can't be detected, use [`RawPcmFormat::new`] instead.
+    fn try_new(_source: MediaSourceStream, _options: &FormatOptions) -> Result<Self> {
+        Err(SymphoniaError::Unsupported("raw pcm: the sample layout must be specified"))
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &[]
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
+        let required_ts = match to {
+            SeekTo::TimeStamp { ts, .. } => ts,
+            SeekTo::Time { time, .. } => {
+                ((time.seconds as f64 + time.frac) * self.spec.sample_rate as f64).round() as u64
+            }
+        };
+        let actual_ts = self.n_frames.map_or(required_ts, |n_frames| required_ts.min(n_frames));
+        self.source.seek(SeekFrom::Start(actual_ts * self.spec.frame_bytes()))?;
+        self.position = actual_ts;
+        Ok(SeekedTo { track_id: 0, required_ts, actual_ts })
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn next_packet(&mut self) -> Result<Packet> {
+        let frame_bytes = self.spec.frame_bytes() as usize;
+        let mut data = vec![0u8; PACKET_FRAMES as usize * frame_bytes];
+        let mut filled = 0;
+        while filled < data.len() {
+            match self.source.read(&mut data[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+
+        let frames = filled / frame_bytes;
+        if frames == 0 {
+            return Err(SymphoniaError::IoError(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        data.truncate(frames * frame_bytes);
+        let packet = Packet::new_from_boxed_slice(0, self.position, frames as u64, data.into_boxed_slice());
+        self.position += frames as u64;
+        Ok(packet)
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::AudioReader;
+    use rstest::*;
+
+    const SAMPLES: [f32; 6] = [0.5, -1.0, 0.25, 0.0, -0.125, 0.75];
+
+    /// Encodes samples that are exactly representable in every format.
+    fn encode(samples: &[f32], format: RawSampleFormat, byte_order: ByteOrder) -> Vec<u8> {
+        let n = format.bytes_per_sample();
+        samples
+            .iter()
+            .flat_map(|&s| {
+                let mut bytes = match format {
+                    RawSampleFormat::Float32 => s.to_le_bytes().to_vec(),
+                    _ => {
+                        let value = (s as f64 * (1u64 << (n * 8 - 1)) as f64) as i64;
+                        value.to_le_bytes()[..n].to_vec()
+                    }
+                };
+                if byte_order == ByteOrder::BigEndian {
+                    bytes.reverse();
+                }
+                bytes
+            })
+            .collect()
+    }
+
+    fn spec(sample_format: RawSampleFormat, byte_order: ByteOrder, channels: usize) -> RawPcmSpec {
+        RawPcmSpec { sample_format, byte_order, channels, sample_rate: 8000 }
+    }
+
+    fn read_all(reader: &mut AudioReader) -> Vec<f32> {
+        let mut samples = Vec::new();
+        while let Some(packet) = reader.read_packet().unwrap() {
+            samples.extend(packet);
+        }
+        samples
+    }
+
+    #[rstest]
+    #[case(RawSampleFormat::Int16, ByteOrder::LittleEndian)]
+    #[case(RawSampleFormat::Int16, ByteOrder::BigEndian)]
+    #[case(RawSampleFormat::Int24, ByteOrder::LittleEndian)]
+    #[case(RawSampleFormat::Int24, ByteOrder::BigEndian)]
+    #[case(RawSampleFormat::Int32, ByteOrder::LittleEndian)]
+    #[case(RawSampleFormat::Int32, ByteOrder::BigEndian)]
+    #[case(RawSampleFormat::Float32, ByteOrder::LittleEndian)]
+    #[case(RawSampleFormat::Float32, ByteOrder::BigEndian)]
+    fn test_read_known_samples(#[case] format: RawSampleFormat, #[case] byte_order: ByteOrder) {
+        let bytes = encode(&SAMPLES, format, byte_order);
+        assert_eq!(bytes.len(), SAMPLES.len() * format.bytes_per_sample());
+
+        let mut reader = AudioReader::from_raw_pcm_bytes(bytes, spec(format, byte_order, 1)).unwrap();
+        assert_eq!(reader.sample_rate(), 8000);
+        assert_eq!(reader.channels(), 1);
+        assert_eq!(reader.bits_per_sample(), Some(format.bytes_per_sample() as u32 * 8));
+        assert_eq!(read_all(&mut reader), SAMPLES);
+    }
+
+    #[rstest]
+    fn test_read_file_stereo_range() {
+        // 10000 stereo frames span several packets
+        let samples: Vec<f32> = (0..20000).map(|i| (i % 256) as f32 / 256.0 - 0.5).collect();
+        let format = RawSampleFormat::Int16;
+        let path = std::env::temp_dir().join("sonex_raw_stereo.pcm");
+        std::fs::write(&path, encode(&samples, format, ByteOrder::BigEndian)).unwrap();
+
+        let spec = spec(format, ByteOrder::BigEndian, 2);
+        let mut reader = AudioReader::from_raw_pcm(&path, spec).unwrap();
+        assert_eq!(reader.channels(), 2);
+        assert_eq!(read_all(&mut reader), samples);
+
+        // Frames 5000 to 6000
+        let mut reader = AudioReader::from_raw_pcm(&path, spec).unwrap();
+        let range = reader.read_range(0.625, 0.75).unwrap();
+        assert_eq!(range, samples[10000..12000]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[rstest]
+    fn test_partial_frame_and_invalid_spec() {
+        // The odd trailing byte of a 16-bit stream is ignored
+        let mut bytes = encode(&SAMPLES, RawSampleFormat::Int16, ByteOrder::LittleEndian);
+        bytes.push(0x7f);
+        let spec = spec(RawSampleFormat::Int16, ByteOrder::LittleEndian, 1);
+        let mut reader = AudioReader::from_raw_pcm_bytes(bytes, spec).unwrap();
+        assert_eq!(read_all(&mut reader), SAMPLES);
+
+        assert!(AudioReader::from_raw_pcm_bytes(vec![0; 4], RawPcmSpec { channels: 0, ..spec }).is_err());
+        assert!(AudioReader::from_raw_pcm_bytes(vec![0; 4], RawPcmSpec { sample_rate: 0, ..spec }).is_err());
+    }
+
+    #[rstest]
+    fn test_channel_limit() {
+        let spec = spec(RawSampleFormat::Int16, ByteOrder::LittleEndian, 26);
+        let samples: Vec<f32> = (0..26 * 3).map(|i| i as f32 / 128.0).collect();
+        let bytes = encode(&samples, RawSampleFormat::Int16, ByteOrder::LittleEndian);
+        let mut reader = AudioReader::from_raw_pcm_bytes(bytes.clone(), spec).unwrap();
+        assert_eq!(reader.channels(), 26);
+        assert_eq!(read_all(&mut reader), samples);
+
+        assert!(AudioReader::from_raw_pcm_bytes(bytes, RawPcmSpec { channels: 27, ..spec }).is_err());
+    }
+}
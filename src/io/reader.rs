@@ -4,18 +4,126 @@
 //! It supports reading common formats like WAV, FLAC, MP3, etc., and converts them to normalized
 //! floating-point samples.
 
+use std::error::Error;
+use std::fmt;
 use std::fs::File;
+use std::io::{BufReader, Cursor, ErrorKind};
 use std::path::Path;
 
-use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL, CodecParameters};
-use symphonia::core::formats::{FormatOptions, FormatReader, Track};
+use symphonia::core::codecs::{CodecRegistry, Decoder, DecoderOptions, CODEC_TYPE_NULL, CodecParameters};
+use symphonia::core::formats::{FormatOptions, FormatReader, Packet, SeekMode, SeekTo, Track};
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::io::MediaSourceStream;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::probe::Hint;
+use symphonia::core::units::Time;
 use symphonia::default::get_probe;
 
+use crate::buffer::AudioBuffer;
+use crate::io::layout::ChannelLayout;
+use crate::io::bwf::{parse_bext, BwfMetadata, BEXT_CHUNK_ID};
+use crate::io::marker::{parse_cue_points, Marker, CUE_CHUNK_IDS};
+use crate::io::raw::{RawPcmFormat, RawPcmSpec};
+use crate::io::riff::{read_chunks, ChunkId};
+
+/// WAV chunks holding metadata that Symphonia skips.
+pub(crate) const METADATA_CHUNK_IDS: [ChunkId; 3] = [CUE_CHUNK_IDS[0], CUE_CHUNK_IDS[1], BEXT_CHUNK_ID];
+
+/// Returns the registry of the codecs Symphonia decodes and those enabled by features.
+fn codecs() -> &'static CodecRegistry {
+    #[cfg(feature = "opus")]
+    {
+        static CODECS: std::sync::LazyLock<CodecRegistry> = std::sync::LazyLock::new(|| {
+            let mut registry = CodecRegistry::new();
+            symphonia::default::register_enabled_codecs(&mut registry);
+            registry.register_all::<crate::io::opus_writer::OpusDecoder>();
+            registry
+        });
+        &CODECS
+    }
+    #[cfg(not(feature = "opus"))]
+    symphonia::default::get_codecs()
+}
+
+/// Errors that can occur while reading audio from an [`AudioReader`].
+#[derive(Debug)]
+pub enum ReaderError {
+    /// Seeking to the start of the range failed, e.g. because the source is not seekable
+    Seek(SymphoniaError),
+    /// Reading or decoding a packet failed
+    Decode(SymphoniaError),
+    /// A packet decoded to a different channel count or sample rate than the stream
+    /// started with, so its samples can't be interleaved with the previous ones
+    FormatChanged {
+        /// Channel count reported by [`AudioReader::channels`]
+        expected_channels: usize,
+        /// Channel count of the packet
+        found_channels: usize,
+        /// Sample rate reported by [`AudioReader::sample_rate`]
+        expected_sample_rate: u32,
+        /// Sample rate of the packet
+        found_sample_rate: u32,
+    },
+    /// The stream ended before the number of frames given by its header, e.g. because
+    /// the file was cut off
+    Truncated {
+        /// Number of frames given by the header
+        expected_frames: u64,
+        /// Frame position at which the stream ended
+        found_frames: u64,
+    },
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReaderError::Seek(e) => write!(f, "failed to seek: {}", e),
+            ReaderError::Decode(e) => write!(f, "failed to decode: {}", e),
+            ReaderError::FormatChanged {
+                expected_channels,
+                found_channels,
+                expected_sample_rate,
+                found_sample_rate,
+            } => write!(
+                f,
+                "format changed mid-stream from {} channels at {} Hz to {} channels at {} Hz",
+                expected_channels, expected_sample_rate, found_channels, found_sample_rate
+            ),
+            ReaderError::Truncated { expected_frames, found_frames } => write!(
+                f,
+                "stream ended after {} of {} frames",
+                found_frames, expected_frames
+            ),
+        }
+    }
+}
+
+impl Error for ReaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReaderError::Seek(e) | ReaderError::Decode(e) => Some(e),
+            ReaderError::FormatChanged { .. } | ReaderError::Truncated { .. } => None,
+        }
+    }
+}
+
+impl From<SymphoniaError> for ReaderError {
+    fn from(e: SymphoniaError) -> Self {
+        ReaderError::Decode(e)
+    }
+}
+
+/// Options controlling how an [`AudioReader`] decodes a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioReaderOptions {
+    /// Stop [`AudioReader::read_packet`] after this many frames, None to read the whole file
+    pub max_frames: Option<u64>,
+    /// Trim the encoder delay and padding of formats that store it (e.g. MP3 with a
+    /// LAME header), so the decoded audio has its original length
+    pub enable_gapless: bool,
+}
+
 /// A high-level audio file reader that provides easy access to audio samples.
 /// 
 /// This struct handles the complexities of audio decoding and provides a simple interface
@@ -39,6 +147,12 @@ pub struct AudioReader {
     decoder: Box<dyn Decoder>,
     track: Track,
     spec: CodecParameters,
+    cue_points: Vec<(u64, String)>,
+    bwf: Option<BwfMetadata>,
+    max_frames: Option<u64>,
+    frames_read: u64,
+    /// Timestamp following the last decoded packet, relative to the start of the track
+    position: u64,
 }
 
 impl AudioReader {
@@ -61,40 +175,170 @@ impl AudioReader {
     /// * No supported audio tracks are found
     /// * The codec is not supported
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, SymphoniaError> {
+        Self::new_with_options(path, AudioReaderOptions::default())
+    }
+
+    /// Creates a new audio reader for the specified file path with decoding options.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Path to the audio file
+    /// * `options` - Decoding options, see [`AudioReaderOptions`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioReader if successful, or a SymphoniaError if the file
+    /// cannot be opened or decoded.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::{AudioReader, AudioReaderOptions};
+    /// 
+    /// // Preview the first 10 seconds
+    /// let options = AudioReaderOptions { max_frames: Some(441000), ..Default::default() };
+    /// let mut reader = AudioReader::new_with_options("audio.wav", options).unwrap();
+    /// while let Ok(Some(samples)) = reader.read_packet() {
+    ///     // Process samples...
+    /// }
+    /// ```
+    pub fn new_with_options<P: AsRef<Path>>(path: P, options: AudioReaderOptions) -> Result<Self, SymphoniaError> {
         // Provide hints to the probe to speed up the process.
         let mut hint = Hint::new();
         if let Some(ext) = path.as_ref().extension().and_then(|s| s.to_str()) {
             hint.with_extension(ext);
         }
 
-        let src = File::open(path).expect("failed to open file");
+        let src = File::open(&path)?;
+        let chunks = File::open(&path)
+            .map(|file| read_chunks(BufReader::new(file), &METADATA_CHUNK_IDS))
+            .unwrap_or_default();
+
+        Self::from_source(Box::new(src), hint, chunks, options)
+    }
+
+    /// Creates a new audio reader for audio held in memory.
+    /// 
+    /// The format is detected from the content, so the bytes must hold a complete
+    /// audio file such as the output of [`AudioWriter::from_writer`](crate::io::AudioWriter::from_writer).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bytes` - Encoded audio file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioReader if successful, or a SymphoniaError if the
+    /// bytes cannot be decoded.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::AudioReader;
+    /// 
+    /// let bytes = std::fs::read("audio.wav").unwrap();
+    /// let mut reader = AudioReader::from_bytes(bytes).unwrap();
+    /// ```
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, SymphoniaError> {
+        let chunks = read_chunks(Cursor::new(&bytes), &METADATA_CHUNK_IDS);
+        Self::from_source(Box::new(Cursor::new(bytes)), Hint::new(), chunks, AudioReaderOptions::default())
+    }
+
+    /// Creates a new audio reader for a headerless PCM file.
+    /// 
+    /// Raw PCM has no header to detect the format from, so the layout of the samples
+    /// is given by `spec`. A trailing partial frame is ignored.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Path to the raw PCM file
+    /// * `spec` - Sample format, byte order, channel count and sample rate of the data
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioReader if successful, or a SymphoniaError if the file
+    /// cannot be opened or the spec is invalid.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::{AudioReader, ByteOrder, RawPcmSpec, RawSampleFormat};
+    /// 
+    /// let spec = RawPcmSpec {
+    ///     sample_format: RawSampleFormat::Int16,
+    ///     byte_order: ByteOrder::LittleEndian,
+    ///     channels: 2,
+    ///     sample_rate: 44100,
+    /// };
+    /// let mut reader = AudioReader::from_raw_pcm("audio.pcm", spec).unwrap();
+    /// while let Ok(Some(samples)) = reader.read_packet() {
+    ///     // Process samples...
+    /// }
+    /// ```
+    pub fn from_raw_pcm<P: AsRef<Path>>(path: P, spec: RawPcmSpec) -> Result<Self, SymphoniaError> {
+        let format = RawPcmFormat::new(Box::new(File::open(path)?), spec)?;
+        Self::from_format(Box::new(format), Vec::new(), AudioReaderOptions::default())
+    }
+
+    /// Creates a new audio reader for headerless PCM held in memory.
+    /// 
+    /// See [`AudioReader::from_raw_pcm`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bytes` - Interleaved raw PCM samples
+    /// * `spec` - Sample format, byte order, channel count and sample rate of the data
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioReader if successful, or a SymphoniaError if the
+    /// spec is invalid.
+    pub fn from_raw_pcm_bytes(bytes: Vec<u8>, spec: RawPcmSpec) -> Result<Self, SymphoniaError> {
+        let format = RawPcmFormat::new(Box::new(Cursor::new(bytes)), spec)?;
+        Self::from_format(Box::new(format), Vec::new(), AudioReaderOptions::default())
+    }
 
-        let mss = MediaSourceStream::new(Box::new(src), Default::default());
+    fn from_source(
+        source: Box<dyn MediaSource>,
+        hint: Hint,
+        metadata_chunks: Vec<(ChunkId, Vec<u8>)>,
+        options: AudioReaderOptions,
+    ) -> Result<Self, SymphoniaError> {
+        let mss = MediaSourceStream::new(source, Default::default());
+        let format_options = FormatOptions {
+            enable_gapless: options.enable_gapless,
+            ..Default::default()
+        };
 
         // Probe the media for a compatible format reader
         let probed = get_probe().format(
             &hint,
             mss,
-            &FormatOptions::default(),
+            &format_options,
             &MetadataOptions::default(),
         )?;
 
-        let format = probed.format;
+        Self::from_format(probed.format, metadata_chunks, options)
+    }
 
+    fn from_format(
+        format: Box<dyn FormatReader>,
+        metadata_chunks: Vec<(ChunkId, Vec<u8>)>,
+        options: AudioReaderOptions,
+    ) -> Result<Self, SymphoniaError> {
         let track = format
             .tracks()
             .iter()
             .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-            .expect("no supported audio tracks")
+            .ok_or(SymphoniaError::Unsupported("no supported audio tracks"))?
             .clone();
         
 
         // Create a decoder
         let dec_opts: DecoderOptions = Default::default();
 
-        let decoder = symphonia::default::get_codecs()
-            .make(&track.codec_params, &dec_opts)
-            .expect("unsupported codec");
+        let decoder = codecs()
+            .make(&track.codec_params, &dec_opts)?;
 
         let spec = decoder.codec_params().clone();
 
@@ -103,6 +347,14 @@ impl AudioReader {
             decoder,
             track,
             spec,
+            cue_points: parse_cue_points(&metadata_chunks),
+            bwf: metadata_chunks
+                .iter()
+                .find(|(id, _)| *id == BEXT_CHUNK_ID)
+                .and_then(|(_, data)| parse_bext(data)),
+            max_frames: options.max_frames,
+            frames_read: 0,
+            position: 0,
         })
     }
 
@@ -116,6 +368,61 @@ impl AudioReader {
         self.spec.channels.unwrap().count()
     }
 
+    /// Returns the speaker position of each channel, or None if the file doesn't
+    /// specify the channels.
+    /// 
+    /// WAV files without an explicit channel mask get the default layout for their
+    /// channel count, e.g. 6 channels are read as 5.1.
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        self.spec.channels.map(ChannelLayout::from_channels)
+    }
+
+    /// Returns the bit depth of the source samples, or None if the format doesn't
+    /// specify one.
+    /// 
+    /// Lossy formats like MP3 decode to floating-point and usually have no bit depth.
+    pub fn bits_per_sample(&self) -> Option<u32> {
+        self.spec.bits_per_sample
+    }
+
+    /// Returns the short name of the codec, e.g. "pcm_s16le", "flac" or "mp3".
+    /// 
+    /// Returns "unknown" if the codec is not registered with the decoder.
+    pub fn codec_name(&self) -> &'static str {
+        codecs()
+            .get_codec(self.spec.codec)
+            .map(|descriptor| descriptor.short_name)
+            .unwrap_or("unknown")
+    }
+
+    /// Returns the cue markers of a WAV file, ordered by position.
+    /// 
+    /// Markers are read from the `cue ` chunk and labeled from the `labl` entries of
+    /// the `LIST` chunk, as written by [`AudioWriter::add_marker`](crate::io::AudioWriter::add_marker)
+    /// and most audio editors. Formats without cue support return an empty list.
+    pub fn markers(&self) -> Vec<Marker> {
+        let sample_rate = self.sample_rate() as f64;
+        self.cue_points
+            .iter()
+            .map(|(frame, label)| Marker {
+                frame: *frame,
+                time_sec: *frame as f64 / sample_rate,
+                label: label.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the Broadcast Wave metadata of the `bext` chunk, or None if the file
+    /// has none.
+    pub fn bwf_metadata(&self) -> Option<BwfMetadata> {
+        self.bwf.clone()
+    }
+
+    /// Returns the codec parameters of the decoded track.
+    pub(crate) fn codec_params(&self) -> &CodecParameters {
+        &self.spec
+    }
+
     /// Reads and decodes the next packet of audio samples.
     /// 
     /// Returns a vector of interleaved floating-point samples normalized to the range [-1.0, 1.0].
@@ -125,32 +432,625 @@ impl AudioReader {
     /// # Returns
     /// 
     /// * `Ok(Some(samples))` - Successfully read samples
-    /// * `Ok(None)` - End of file reached
-    /// * `Err(e)` - An error occurred during reading or decoding
-    pub fn read_packet(&mut self) -> Result<Option<Vec<f32>>, SymphoniaError> {
-        loop {
-            let packet = match self.format.next_packet() {
-                Ok(packet) => packet,
-                Err(e) => match e {
-                    SymphoniaError::IoError(_) => return Ok(None),
-                    _ => return Err(e),
+    /// * `Ok(None)` - End of file reached, or the `max_frames` limit of the
+    ///   [`AudioReaderOptions`] has been read
+    /// * `Err(e)` - An error occurred during reading or decoding, the packet has a
+    ///   different channel count or sample rate than the stream (`ReaderError::FormatChanged`),
+    ///   or the stream ended before the length given by its header (`ReaderError::Truncated`)
+    pub fn read_packet(&mut self) -> Result<Option<Vec<f32>>, ReaderError> {
+        Ok(self.read_packet_timed()?.map(|(_, samples)| samples))
+    }
+
+    /// Reads and decodes all remaining audio samples.
+    /// 
+    /// # Returns
+    /// 
+    /// The interleaved samples from the current position to the end of the file, or
+    /// to the `max_frames` limit of the [`AudioReaderOptions`]. Errors are returned
+    /// as by [`AudioReader::read_packet`].
+    pub fn read_all(&mut self) -> Result<Vec<f32>, ReaderError> {
+        let mut samples = Vec::new();
+        while let Some(packet) = self.read_packet()? {
+            samples.extend(packet);
+        }
+        Ok(samples)
+    }
+
+    /// Reads and decodes all remaining audio samples into an [`AudioBuffer`].
+    /// 
+    /// Like [`AudioReader::read_all`], but the samples are returned together with the
+    /// channel count and sample rate of the file.
+    pub fn read_buffer(&mut self) -> Result<AudioBuffer, ReaderError> {
+        Ok(AudioBuffer::from_interleaved(self.read_all()?, self.channels() as u32, self.sample_rate()))
+    }
+
+    /// Reads and decodes the next packet of audio samples together with its timestamp.
+    /// 
+    /// Like [`AudioReader::read_packet`], but also returns the time of the first frame
+    /// of the packet in seconds, computed from the packet timestamp and the time base of
+    /// the track. This relates the decoded audio to external events, e.g. a transcript.
+    /// 
+    /// PCM formats stamp every packet exactly. Compressed formats stamp whole codec
+    /// frames (e.g. 1152 samples for MP3), and some containers store coarser
+    /// timestamps, so the granularity depends on the format. With gapless playback
+    /// enabled the first packet can start after 0 s where the encoder delay is trimmed.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Some((time_sec, samples)))` - Timestamp in seconds and interleaved samples
+    /// * `Ok(None)` - End of file reached, or the `max_frames` limit of the
+    ///   [`AudioReaderOptions`] has been read
+    /// * `Err(e)` - An error occurred during reading or decoding, see [`AudioReader::read_packet`]
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::AudioReader;
+    /// 
+    /// let mut reader = AudioReader::new("audio.wav").unwrap();
+    /// while let Ok(Some((time_sec, samples))) = reader.read_packet_timed() {
+    ///     println!("{:.3} s: {} samples", time_sec, samples.len());
+    /// }
+    /// ```
+    pub fn read_packet_timed(&mut self) -> Result<Option<(f64, Vec<f32>)>, ReaderError> {
+        let remaining = self.max_frames.map(|max| max.saturating_sub(self.frames_read));
+        if remaining == Some(0) {
+            return Ok(None);
+        }
+
+        let Some(packet) = self.next_packet()? else {
+            return Ok(None);
+        };
+        let mut samples = self.decode_packet(&packet)?;
+        let channels = self.channels();
+        if let Some(remaining) = remaining {
+            samples.truncate((remaining as usize).saturating_mul(channels));
+        }
+        self.frames_read += (samples.len() / channels) as u64;
+        Ok(Some((self.timestamp_to_seconds(packet.ts()), samples)))
+    }
+
+    /// Reads and decodes the next packet of audio as frames.
+    /// 
+    /// Like [`AudioReader::read_packet`], but each frame holds one sample per channel,
+    /// so the length of the returned vector is the number of frames rather than
+    /// frames × channels.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Some(frames))` - Successfully read frames, each of length [`AudioReader::channels`]
+    /// * `Ok(None)` - End of file reached, or the `max_frames` limit of the
+    ///   [`AudioReaderOptions`] has been read
+    /// * `Err(e)` - An error occurred during reading or decoding, see [`AudioReader::read_packet`]
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::AudioReader;
+    /// 
+    /// let mut reader = AudioReader::new("audio.wav").unwrap();
+    /// while let Ok(Some(frames)) = reader.read_frames() {
+    ///     for frame in &frames {
+    ///         let left = frame[0];
+    ///     }
+    /// }
+    /// ```
+    pub fn read_frames(&mut self) -> Result<Option<Vec<Vec<f32>>>, ReaderError> {
+        let channels = self.channels();
+        Ok(self
+            .read_packet()?
+            .map(|samples| samples.chunks_exact(channels).map(<[f32]>::to_vec).collect()))
+    }
+
+    /// Returns the number of frames [`AudioReader::read_packet`] yields in total.
+    /// 
+    /// This is the length of the stream, capped by the `max_frames` limit of the
+    /// [`AudioReaderOptions`]. Multiply by [`AudioReader::channels`] for the number of
+    /// interleaved samples.
+    /// 
+    /// # Returns
+    /// 
+    /// The number of frames, or None if the format doesn't store the length of the
+    /// stream and no `max_frames` limit is set.
+    pub fn num_frames(&self) -> Option<u64> {
+        match (self.track.codec_params.n_frames, self.max_frames) {
+            (Some(n_frames), Some(max)) => Some(n_frames.min(max)),
+            (n_frames, max) => n_frames.or(max),
+        }
+    }
+
+    /// Reads the samples between two points in time.
+    /// 
+    /// The reader seeks to `start_sec` and decodes until `end_sec`, so only the packets
+    /// around the range are decoded. The first and last packets are trimmed to return
+    /// exactly the frames in `[start_sec, end_sec)`. The range is clamped to the length
+    /// of the file and an empty vector is returned if `start_sec >= end_sec`.
+    /// 
+    /// Subsequent calls to `read_packet` continue after the last decoded packet.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start_sec` - Start of the range in seconds
+    /// * `end_sec` - End of the range in seconds
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the interleaved samples of the range, or a ReaderError if seeking or
+    /// decoding failed.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::AudioReader;
+    /// 
+    /// let mut reader = AudioReader::new("audio.wav").unwrap();
+    /// // Seconds 30 to 35
+    /// let samples = reader.read_range(30.0, 35.0).unwrap();
+    /// ```
+    pub fn read_range(&mut self, start_sec: f64, end_sec: f64) -> Result<Vec<f32>, ReaderError> {
+        let sample_rate = self.sample_rate() as f64;
+        let channels = self.channels();
+        let start_frame = (start_sec.max(0.0) * sample_rate).round() as u64;
+        let mut end_frame = (end_sec.max(0.0) * sample_rate).round() as u64;
+        if let Some(n_frames) = self.track.codec_params.n_frames {
+            end_frame = end_frame.min(n_frames);
+        }
+        if start_frame >= end_frame {
+            return Ok(Vec::new());
+        }
+
+        self.format
+            .seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time: Time::from(start_frame as f64 / sample_rate),
+                    track_id: Some(self.track.id),
                 },
-            };
+            )
+            .map_err(ReaderError::Seek)?;
+        self.decoder.reset();
 
-            // If the packet doesn't match our chosen track, skip it
-            if packet.track_id() != self.track.id {
+        let mut samples = Vec::with_capacity((end_frame - start_frame) as usize * channels);
+        while let Some(packet) = self.next_packet()? {
+            let packet_start = self.timestamp_to_frame(packet.ts());
+            if packet_start >= end_frame {
+                break;
+            }
+            let decoded = self.decode_packet(&packet)?;
+            let packet_end = packet_start + (decoded.len() / channels) as u64;
+            if packet_end <= start_frame {
                 continue;
             }
 
-            let decoded = self.decoder.decode(&packet)?;
-            // Create a sample buffer
-            let spec = *decoded.spec();
-            let duration = decoded.capacity() as u64;
-            let mut sample_buf = SampleBuffer::<f32>::new(duration, spec);
+            let from = start_frame.saturating_sub(packet_start) as usize * channels;
+            let to = (end_frame.min(packet_end) - packet_start) as usize * channels;
+            samples.extend_from_slice(&decoded[from..to]);
+            if packet_end >= end_frame {
+                break;
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Reads the next packet of the decoded track, skipping those of other tracks.
+    /// 
+    /// Returns None at the end of the stream. Symphonia reports the end as an
+    /// unexpected-EOF I/O error, which a file cut off mid-stream produces as well, so
+    /// the stream must also have reached the frame count of its header, if it has one.
+    /// Any other error is returned as `ReaderError::Decode`.
+    fn next_packet(&mut self) -> Result<Option<Packet>, ReaderError> {
+        loop {
+            match self.format.next_packet() {
+                Ok(packet) if packet.track_id() == self.track.id => return Ok(Some(packet)),
+                Ok(_) => continue,
+                Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                    // Containers differ in whether the frame count includes the encoder
+                    // delay and padding, so neither counts as missing
+                    let params = &self.track.codec_params;
+                    let slack = params.delay.unwrap_or(0) as u64 + params.padding.unwrap_or(0) as u64;
+                    return match params.n_frames {
+                        Some(expected_frames) if self.position + slack < expected_frames => {
+                            Err(ReaderError::Truncated { expected_frames, found_frames: self.position })
+                        }
+                        _ => Ok(None),
+                    };
+                }
+                // The track list changed, e.g. at the start of a chained Ogg stream
+                Err(SymphoniaError::ResetRequired) => return Ok(None),
+                Err(e) => return Err(ReaderError::Decode(e)),
+            }
+        }
+    }
+
+    /// Converts a timestamp of the track to seconds from the start of the stream.
+    fn timestamp_to_seconds(&self, ts: u64) -> f64 {
+        match self.track.codec_params.time_base {
+            Some(time_base) => {
+                let time = time_base.calc_time(ts);
+                time.seconds as f64 + time.frac
+            }
+            None => ts as f64 / self.sample_rate() as f64,
+        }
+    }
+
+    /// Converts a timestamp of the track to a frame index.
+    fn timestamp_to_frame(&self, ts: u64) -> u64 {
+        match self.track.codec_params.time_base {
+            Some(time_base) => {
+                let time = time_base.calc_time(ts);
+                ((time.seconds as f64 + time.frac) * self.sample_rate() as f64).round() as u64
+            }
+            None => ts,
+        }
+    }
+
+    /// Decodes a packet into interleaved samples.
+    /// 
+    /// Fails with `ReaderError::FormatChanged` if the packet doesn't match the stream's
+    /// channel count and sample rate.
+    fn decode_packet(&mut self, packet: &Packet) -> Result<Vec<f32>, ReaderError> {
+        let expected_channels = self.channels();
+        let expected_sample_rate = self.sample_rate();
+        let decoded = self.decoder.decode(packet)?;
+        // Create a sample buffer
+        let spec = *decoded.spec();
+        if spec.channels.count() != expected_channels || spec.rate != expected_sample_rate {
+            return Err(ReaderError::FormatChanged {
+                expected_channels,
+                found_channels: spec.channels.count(),
+                expected_sample_rate,
+                found_sample_rate: spec.rate,
+            });
+        }
+        let duration = decoded.capacity() as u64;
+        let mut sample_buf = SampleBuffer::<f32>::new(duration, spec);
+
+        sample_buf.copy_interleaved_ref(decoded);
+        // The container's duration leaves out padding the decoder still outputs, the
+        // decoded length leaves out frames missing from a cut-off packet
+        let frames = (sample_buf.len() / expected_channels) as u64;
+        let frames = match packet.dur() {
+            0 => frames,
+            dur => dur.min(frames),
+        };
+        self.position = (packet.ts() + frames).saturating_sub(self.track.codec_params.start_ts);
+
+        Ok(sample_buf.samples().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::ChannelPosition;
+    use std::borrow::Cow;
+    use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec};
+    use symphonia::core::codecs::{CodecDescriptor, FinalizeResult};
+    use std::io::Cursor;
+    use rstest::*;
+
+    /// Encodes a short 24-bit WAV file in memory.
+    fn wav_24_bit() -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+        for i in 0..960 {
+            writer.write_sample(i * 1000).unwrap();
+        }
+        writer.finalize().unwrap();
+        buffer.into_inner()
+    }
+
+    /// Builds a silent MPEG-1 Layer III stream: mono, 128 kbit/s at 44.1 kHz.
+    ///
+    /// With all side information zero every frame decodes to silence, so the frames
+    /// are just the header followed by zeros.
+    fn mp3_silence(frames: usize) -> Vec<u8> {
+        // 144 * bitrate / sample_rate bytes per frame without padding
+        let frame_len = 144 * 128_000 / 44100;
+        let mut frame = vec![0u8; frame_len];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0xC0]);
+        frame.repeat(frames)
+    }
+
+    /// Decoder returning silent buffers, switching to another format after the
+    /// first packet.
+    struct SwitchingDecoder {
+        params: CodecParameters,
+        first: AudioBuffer<f32>,
+        switched: AudioBuffer<f32>,
+        decoded: usize,
+    }
+
+    impl SwitchingDecoder {
+        fn new(params: CodecParameters, channels: usize, sample_rate: u32) -> Self {
+            let buffer = |channels: usize, rate: u32| {
+                let channels = Channels::from_bits_truncate((1 << channels) - 1);
+                let mut buffer = AudioBuffer::new(64, SignalSpec::new(rate, channels));
+                buffer.render_reserved(Some(64));
+                buffer
+            };
+            let first = buffer(params.channels.unwrap().count(), params.sample_rate.unwrap());
+            Self { params, first, switched: buffer(channels, sample_rate), decoded: 0 }
+        }
+    }
+
+    impl Decoder for SwitchingDecoder {
+        fn try_new(_: &CodecParameters, _: &DecoderOptions) -> symphonia::core::errors::Result<Self> {
+            Err(symphonia::core::errors::Error::Unsupported("test decoder"))
+        }
+
+        fn supported_codecs() -> &'static [CodecDescriptor] {
+            &[]
+        }
+
+        fn reset(&mut self) {
+            self.decoded = 0;
+        }
+
+        fn codec_params(&self) -> &CodecParameters {
+            &self.params
+        }
+
+        fn decode(&mut self, _: &Packet) -> symphonia::core::errors::Result<AudioBufferRef<'_>> {
+            self.decoded += 1;
+            Ok(self.last_decoded())
+        }
+
+        fn finalize(&mut self) -> FinalizeResult {
+            FinalizeResult::default()
+        }
+
+        fn last_decoded(&self) -> AudioBufferRef<'_> {
+            let buffer = if self.decoded > 1 { &self.switched } else { &self.first };
+            AudioBufferRef::F32(Cow::Borrowed(buffer))
+        }
+    }
+
+    fn read_all(reader: &mut AudioReader) -> Vec<f32> {
+        let mut samples = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            samples.extend(packet);
+        }
+        samples
+    }
+
+    #[rstest]
+    fn test_read_buffer() {
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let mut reader = AudioReader::new(path).unwrap();
+        let buffer = reader.read_buffer().unwrap();
+
+        assert_eq!((buffer.channels, buffer.sample_rate), (1, 44100));
+        assert_eq!(buffer.n_frames(), buffer.samples.len());
+        assert!((buffer.duration() - 3.0).abs() < 1e-3);
+        assert_eq!(buffer.samples, read_all(&mut AudioReader::new(path).unwrap()));
+        assert!(reader.read_all().unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case(1.0, 2.0)]
+    #[case(0.0, 1.0)]
+    #[case(0.25, 1.25)]
+    fn test_read_one_second(#[case] start_sec: f64, #[case] end_sec: f64) {
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let all = read_all(&mut AudioReader::new(path).unwrap());
+
+        let mut reader = AudioReader::new(path).unwrap();
+        let range = reader.read_range(start_sec, end_sec).unwrap();
+
+        let sample_rate = reader.sample_rate() as usize;
+        assert_eq!(range.len(), sample_rate * reader.channels());
+        let start = (start_sec * sample_rate as f64) as usize;
+        assert_eq!(range, all[start..start + range.len()]);
+    }
+
+    #[rstest]
+    fn test_read_range_stereo() {
+        let mut reader = AudioReader::from_bytes(wav_24_bit()).unwrap();
+        let all = read_all(&mut AudioReader::from_bytes(wav_24_bit()).unwrap());
+
+        // 100 to 300 frames of the 480 frame file
+        let range = reader.read_range(100.0 / 48000.0, 300.0 / 48000.0).unwrap();
+        assert_eq!(range.len(), 200 * 2);
+        assert_eq!(range, all[200..600]);
+    }
+
+    #[rstest]
+    fn test_read_range_is_clamped() {
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let all = read_all(&mut AudioReader::new(path).unwrap());
+
+        let mut reader = AudioReader::new(path).unwrap();
+        let tail = reader.read_range(2.5, 10.0).unwrap();
+        assert_eq!(tail, all[110250..]);
+
+        assert!(reader.read_range(2.0, 1.0).unwrap().is_empty());
+        assert!(reader.read_range(5.0, 6.0).unwrap().is_empty());
+
+        // Reading again after the end of the file seeks back
+        assert_eq!(reader.read_range(-1.0, 0.5).unwrap().len(), 22050);
+    }
+
+    #[rstest]
+    fn test_five_point_one_layout() {
+        let spec = hound::WavSpec {
+            channels: 6,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+        for _ in 0..480 * 6 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let reader = AudioReader::from_bytes(buffer.into_inner()).unwrap();
+        let layout = reader.channel_layout().unwrap();
+        assert_eq!(layout.count(), 6);
+        assert!(layout.has_lfe());
+        assert_eq!(layout.index_of(ChannelPosition::Lfe), Some(3));
+        assert_eq!(layout.index_of(ChannelPosition::RearRight), Some(5));
+
+        let plain = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap().channel_layout().unwrap();
+        assert!(!plain.has_lfe());
+    }
+
+    #[rstest]
+    #[case(66150)]
+    #[case(12345)]
+    #[case(1)]
+    #[case(0)]
+    fn test_max_frames(#[case] max_frames: u64) {
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let all = read_all(&mut AudioReader::new(path).unwrap());
+
+        let options = AudioReaderOptions { max_frames: Some(max_frames), ..Default::default() };
+        let mut reader = AudioReader::new_with_options(path, options).unwrap();
+        let capped = read_all(&mut reader);
+        assert_eq!(capped, all[..max_frames as usize]);
+        assert!(reader.read_packet().unwrap().is_none());
+    }
+
+    #[rstest]
+    #[case::wav(AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap())]
+    #[case::mp3(AudioReader::from_bytes(mp3_silence(40)).unwrap())]
+    fn test_packet_timestamps(#[case] mut reader: AudioReader) {
+        let sample_rate = reader.sample_rate() as f64;
+        let channels = reader.channels();
+        let mut packets = Vec::new();
+        while let Some(packet) = reader.read_packet_timed().unwrap() {
+            packets.push(packet);
+        }
+        assert!(packets.len() > 1);
+        assert!(packets[0].0.abs() < 1e-9, "first packet at {} s", packets[0].0);
+
+        // Each packet starts where the previous one ended
+        for pair in packets.windows(2) {
+            let (start, samples) = &pair[0];
+            let duration = (samples.len() / channels) as f64 / sample_rate;
+            assert!(pair[1].0 > *start);
+            assert!((pair[1].0 - (start + duration)).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    fn test_open_errors_are_returned() {
+        assert!(matches!(AudioReader::new("does_not_exist.wav"), Err(SymphoniaError::IoError(_))));
+        assert!(AudioReader::from_bytes(b"not audio at all".to_vec()).is_err());
+    }
+
+    #[rstest]
+    fn test_truncated_stream_is_reported() {
+        let mut bytes = wav_24_bit();
+        // Cut off the file in the middle of the data chunk
+        bytes.truncate(bytes.len() - 1200);
+        let mut reader = AudioReader::from_bytes(bytes).unwrap();
+        assert_eq!(reader.num_frames(), Some(480));
+
+        assert_eq!(reader.read_packet().unwrap().map(|samples| samples.len()), Some(560));
+        match reader.read_packet() {
+            Err(ReaderError::Truncated { expected_frames, found_frames }) => {
+                assert_eq!((expected_frames, found_frames), (480, 280));
+            }
+            other => panic!("expected a truncation error, got {:?}", other),
+        }
+        assert!(matches!(reader.read_all(), Err(ReaderError::Truncated { .. })));
+
+        // The complete file ends without an error
+        let mut reader = AudioReader::from_bytes(wav_24_bit()).unwrap();
+        assert_eq!(reader.read_all().unwrap().len(), 960);
+        assert!(reader.read_packet().unwrap().is_none());
+    }
 
-            sample_buf.copy_interleaved_ref(decoded);
+    #[rstest]
+    fn test_read_frames_stereo() {
+        let all = read_all(&mut AudioReader::from_bytes(wav_24_bit()).unwrap());
+        let mut reader = AudioReader::from_bytes(wav_24_bit()).unwrap();
+        assert_eq!(reader.num_frames(), Some(480));
 
-            return Ok(Some(sample_buf.samples().to_vec()));
+        let mut frames = Vec::new();
+        while let Some(packet) = reader.read_frames().unwrap() {
+            frames.extend(packet);
         }
+        assert_eq!(frames.len(), 480);
+        assert!(frames.iter().all(|frame| frame.len() == 2));
+        assert_eq!(frames.concat(), all);
+    }
+
+    #[rstest]
+    fn test_num_frames_capped() {
+        let options = AudioReaderOptions { max_frames: Some(100), ..Default::default() };
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let reader = AudioReader::new_with_options(path, options).unwrap();
+        assert_eq!(reader.num_frames(), Some(100));
+    }
+
+    #[rstest]
+    fn test_max_frames_beyond_end() {
+        let options = AudioReaderOptions { max_frames: Some(10_000_000), enable_gapless: true };
+        let path = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let all = read_all(&mut AudioReader::new(path).unwrap());
+        let mut reader = AudioReader::new_with_options(path, options).unwrap();
+        assert_eq!(read_all(&mut reader), all);
+    }
+
+    #[rstest]
+    fn test_16_bit_wav_info() {
+        let reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        assert_eq!(reader.bits_per_sample(), Some(16));
+        assert_eq!(reader.codec_name(), "pcm_s16le");
+    }
+
+    #[rstest]
+    fn test_24_bit_wav_info() {
+        let reader = AudioReader::from_bytes(wav_24_bit()).unwrap();
+        assert_eq!(reader.bits_per_sample(), Some(24));
+        assert_eq!(reader.codec_name(), "pcm_s24le");
+        assert_eq!(reader.channels(), 2);
+    }
+
+    #[rstest]
+    fn test_mp3_info() {
+        let mut reader = AudioReader::from_bytes(mp3_silence(20)).unwrap();
+        assert_eq!(reader.bits_per_sample(), None);
+        assert_eq!(reader.codec_name(), "mp3");
+        assert_eq!(reader.sample_rate(), 44100);
+
+        let samples = reader.read_packet().unwrap().unwrap();
+        assert!(samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[rstest]
+    #[case(2, 44100, "from 1 channels at 44100 Hz to 2 channels at 44100 Hz")]
+    #[case(6, 44100, "from 1 channels at 44100 Hz to 6 channels at 44100 Hz")]
+    #[case(1, 48000, "from 1 channels at 44100 Hz to 1 channels at 48000 Hz")]
+    fn test_format_change(#[case] channels: usize, #[case] sample_rate: u32, #[case] message: &str) {
+        let mut reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        let params = reader.codec_params().clone();
+        reader.decoder = Box::new(SwitchingDecoder::new(params, channels, sample_rate));
+
+        // The first packet matches the stream, the second one doesn't
+        assert_eq!(reader.read_packet().unwrap().unwrap().len(), 64);
+        let error = reader.read_packet().unwrap_err();
+        match error {
+            ReaderError::FormatChanged { expected_channels, found_channels, expected_sample_rate, found_sample_rate } => {
+                assert_eq!((expected_channels, found_channels), (1, channels));
+                assert_eq!((expected_sample_rate, found_sample_rate), (44100, sample_rate));
+            }
+            ref e => panic!("unexpected error {}", e),
+        }
+        assert_eq!(error.to_string(), format!("format changed mid-stream {}", message));
+
+        // Ranges are checked in the same way
+        reader.decoder = Box::new(SwitchingDecoder::new(reader.codec_params().clone(), channels, sample_rate));
+        assert!(matches!(reader.read_range(0.0, 1.0), Err(ReaderError::FormatChanged { .. })));
     }
 }
@@ -0,0 +1,118 @@
+//! Low-level RIFF chunk access for WAV metadata.
+//!
+//! Symphonia and hound only handle the chunks describing the audio itself. WAV
+//! metadata such as cue markers or the Broadcast Wave `bext` chunk lives in
+//! additional chunks, which are read and written with these helpers.
+
+use std::io::{Read, Seek, SeekFrom};
+
+/// A chunk identifier, e.g. `*b"cue "`.
+pub(crate) type ChunkId = [u8; 4];
+
+/// Reads the chunks with the given identifiers from a WAV file, in file order.
+///
+/// All other chunks, including the audio data, are skipped without reading them.
+/// Sources that are not WAV files give an empty list, and reading stops at the first
+/// malformed or truncated chunk, keeping the chunks read up to that point.
+pub(crate) fn read_chunks<R: Read + Seek>(mut source: R, ids: &[ChunkId]) -> Vec<(ChunkId, Vec<u8>)> {
+    let mut header = [0u8; 12];
+    if source.read_exact(&mut header).is_err() || &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut chunk_header = [0u8; 8];
+    while source.read_exact(&mut chunk_header).is_ok() {
+        let id = [chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]];
+        let size = u32::from_le_bytes([chunk_header[4], chunk_header[5], chunk_header[6], chunk_header[7]]);
+        if ids.contains(&id) {
+            // The size comes from the file, so only what is actually there gets allocated
+            let mut data = Vec::new();
+            if (&mut source).take(size as u64).read_to_end(&mut data).is_err() || data.len() < size as usize {
+                break;
+            }
+            chunks.push((id, data));
+            if size % 2 == 1 && source.seek(SeekFrom::Current(1)).is_err() {
+                break;
+            }
+        } else if source.seek(SeekFrom::Current(size as i64 + (size % 2) as i64)).is_err() {
+            break;
+        }
+    }
+    chunks
+}
+
+/// Reads a little-endian `u32` at the given offset, None if the bytes are too short.
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Appends a RIFF chunk, padded to an even length.
+pub(crate) fn push_chunk(out: &mut Vec<u8>, id: &ChunkId, body: &[u8]) {
+    out.extend(id);
+    out.extend((body.len() as u32).to_le_bytes());
+    out.extend(body);
+    if body.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use rstest::*;
+
+    fn wav_with_chunks(chunks: &[u8]) -> Vec<u8> {
+        let mut wav = b"RIFF".to_vec();
+        wav.extend((4 + chunks.len() as u32).to_le_bytes());
+        wav.extend(b"WAVE");
+        wav.extend(chunks);
+        wav
+    }
+
+    #[rstest]
+    fn test_reads_requested_chunks() {
+        let mut chunks = Vec::new();
+        // Odd-sized chunks exercise the padding
+        push_chunk(&mut chunks, b"junk", &[1]);
+        push_chunk(&mut chunks, b"cue ", &[2, 3, 4]);
+        push_chunk(&mut chunks, b"data", &[0; 8]);
+        push_chunk(&mut chunks, b"LIST", &[5, 6]);
+        let wav = wav_with_chunks(&chunks);
+
+        let read = read_chunks(Cursor::new(wav), &[*b"LIST", *b"cue "]);
+        assert_eq!(read, vec![(*b"cue ", vec![2, 3, 4]), (*b"LIST", vec![5, 6])]);
+    }
+
+    #[rstest]
+    #[case(b"fLaC and more bytes".to_vec())]
+    #[case(Vec::new())]
+    #[case(wav_with_chunks(&[]))]
+    fn test_no_chunks(#[case] bytes: Vec<u8>) {
+        assert!(read_chunks(Cursor::new(bytes), &[*b"cue "]).is_empty());
+    }
+
+    #[rstest]
+    fn test_truncated_chunk() {
+        let mut chunks = Vec::new();
+        push_chunk(&mut chunks, b"cue ", &[1, 2]);
+        push_chunk(&mut chunks, b"LIST", &[0; 16]);
+        let mut wav = wav_with_chunks(&chunks);
+        wav.truncate(wav.len() - 4);
+        assert_eq!(read_chunks(Cursor::new(wav), &[*b"cue ", *b"LIST"]), vec![(*b"cue ", vec![1, 2])]);
+    }
+
+    #[rstest]
+    fn test_oversized_chunk_size() {
+        let mut chunks = Vec::new();
+        push_chunk(&mut chunks, b"cue ", &[1, 2]);
+        // A LIST chunk claiming almost 4 GB, followed by only a few bytes
+        chunks.extend(b"LIST");
+        chunks.extend(0xFFFF_FFF0u32.to_le_bytes());
+        chunks.extend([0; 6]);
+        let wav = wav_with_chunks(&chunks);
+        assert_eq!(read_chunks(Cursor::new(wav), &[*b"cue ", *b"LIST"]), vec![(*b"cue ", vec![1, 2])]);
+    }
+}
@@ -1,18 +1,101 @@
 //! Audio file writing functionality.
 //! 
 //! This module provides the ability to write audio data to WAV files.
-//! It supports writing 32-bit floating-point samples and can create files
-//! with various channel configurations and sample rates.
+//! It supports writing 32-bit floating-point or 16, 24 and 32-bit integer samples and
+//! can create files with various channel configurations and sample rates, optionally
+//! with labeled cue markers and Broadcast Wave metadata.
 
+use std::cell::RefCell;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::rc::Rc;
 use hound::{WavWriter, WavSpec, SampleFormat};
+use symphonia::core::sample::SampleFormat as SourceFormat;
 use crate::io::AudioReader;
+use crate::io::bwf::{bext_chunk, parse_bext, BwfMetadata, BEXT_CHUNK_ID};
+use crate::io::marker::{cue_chunks, parse_cue_points};
+use crate::io::reader::METADATA_CHUNK_IDS;
+use crate::io::riff::read_chunks;
+
+/// Sample format of the written WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleDepth {
+    /// 16-bit integer PCM
+    Int16,
+    /// 24-bit integer PCM
+    Int24,
+    /// 32-bit integer PCM
+    Int32,
+    /// 32-bit floating-point
+    Float32,
+}
+
+impl SampleDepth {
+    /// Returns the number of bits per sample.
+    pub fn bits_per_sample(&self) -> u16 {
+        match self {
+            SampleDepth::Int16 => 16,
+            SampleDepth::Int24 => 24,
+            SampleDepth::Int32 | SampleDepth::Float32 => 32,
+        }
+    }
+
+    /// Chooses the depth matching the source of the reader.
+    /// 
+    /// Integer sources keep their bit depth, 8-bit sources are widened to 16 bits.
+    /// Floating-point sources and formats without a bit depth, like MP3, use
+    /// `Float32`.
+    pub fn from_reader(reader: &AudioReader) -> Self {
+        let params = reader.codec_params();
+        match params.sample_format {
+            Some(SourceFormat::F32 | SourceFormat::F64) => SampleDepth::Float32,
+            Some(SourceFormat::U8 | SourceFormat::S8 | SourceFormat::U16 | SourceFormat::S16) => {
+                SampleDepth::Int16
+            }
+            Some(SourceFormat::U24 | SourceFormat::S24) => SampleDepth::Int24,
+            Some(SourceFormat::U32 | SourceFormat::S32) => SampleDepth::Int32,
+            None => match reader.bits_per_sample() {
+                Some(8 | 16) => SampleDepth::Int16,
+                Some(24) => SampleDepth::Int24,
+                Some(32) => SampleDepth::Int32,
+                _ => SampleDepth::Float32,
+            },
+        }
+    }
+
+    /// Returns the depth of a WAV spec, None for formats the writer can't produce.
+    fn from_spec(spec: &WavSpec) -> Option<Self> {
+        match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Float, 32) => Some(SampleDepth::Float32),
+            (SampleFormat::Int, 16) => Some(SampleDepth::Int16),
+            (SampleFormat::Int, 24) => Some(SampleDepth::Int24),
+            (SampleFormat::Int, 32) => Some(SampleDepth::Int32),
+            _ => None,
+        }
+    }
+
+    fn spec(&self, channels: u16, sample_rate: u32) -> WavSpec {
+        WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: self.bits_per_sample(),
+            sample_format: match self {
+                SampleDepth::Float32 => SampleFormat::Float,
+                _ => SampleFormat::Int,
+            },
+        }
+    }
+}
 
 /// A high-level audio file writer for WAV files.
 /// 
-/// This struct provides a simple interface for writing audio samples to WAV files.
-/// It supports 32-bit floating-point samples and can be configured for different
-/// channel counts and sample rates.
+/// This struct provides a simple interface for writing audio samples to WAV files
+/// with any channel count and sample rate. Samples are written as 16-, 24- or 32-bit
+/// integers or 32-bit floats, see [`SampleDepth`]. [`AudioWriter::new`] and
+/// [`AudioWriter::from_writer`] write 32-bit floats, [`AudioWriter::from_reader`]
+/// keeps the depth of the source as chosen by [`SampleDepth::from_reader`] and
+/// [`AudioWriter::append`] keeps the depth of the existing file.
 /// 
 /// # Example
 /// 
@@ -24,15 +107,128 @@ use crate::io::AudioReader;
 /// writer.write_samples(&samples).unwrap();
 /// writer.finalize().unwrap();
 /// ```
-pub struct AudioWriter {
-    writer: WavWriter<std::io::BufWriter<std::fs::File>>,
+/// 
+/// Besides files, the writer can target any sink implementing `Write + Seek`, see
+/// [`AudioWriter::from_writer`].
+pub struct AudioWriter<W: Write + Seek = BufWriter<File>> {
+    writer: WavWriter<SharedSink<W>>,
+    /// Second handle on the sink to append the metadata chunks after hound is done
+    sink: SharedSink<W>,
+    /// Position of the RIFF header in the sink
+    start: u64,
+    depth: SampleDepth,
+    sample_rate: u32,
+    cue_points: Vec<(u64, String)>,
+    bwf: Option<BwfMetadata>,
+    /// Whether non-finite samples are replaced with silence
+    sanitize: bool,
+    /// Number of samples replaced so far
+    sanitized: u64,
+}
+
+/// A sink shared between the hound writer and the [`AudioWriter`].
+struct SharedSink<W>(Rc<RefCell<W>>);
+
+impl<W> Clone for SharedSink<W> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<W: Write> Write for SharedSink<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+impl<W: Seek> Seek for SharedSink<W> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.0.borrow_mut().seek(pos)
+    }
+}
+
+/// Reading from a buffered file is needed to parse the header when appending.
+impl Read for SharedSink<BufWriter<File>> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut sink = self.0.borrow_mut();
+        sink.flush()?;
+        sink.get_mut().read(buf)
+    }
 }
 
 impl AudioWriter {
+    /// Opens an existing WAV file to write more samples after its audio.
+    /// 
+    /// The channel count, sample rate and sample depth are taken from the file, and
+    /// `finalize` updates the header to cover the old and new samples. Markers and
+    /// Broadcast Wave metadata of the file are kept, markers added with
+    /// [`AudioWriter::add_marker`] are added to them. Other chunks following the audio
+    /// data are dropped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Path of the WAV file to continue
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioWriter if successful, or an error if the file
+    /// doesn't exist, is not a WAV file or uses a sample format the writer doesn't
+    /// support (8-bit or 64-bit samples).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sonex::io::AudioWriter;
+    /// 
+    /// let mut writer = AudioWriter::append("recording.wav").unwrap();
+    /// writer.write_samples(&[0.0f32; 1000]).unwrap();
+    /// writer.finalize().unwrap();
+    /// ```
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self, hound::Error> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let metadata_chunks = read_chunks(BufReader::new(&file), &METADATA_CHUNK_IDS);
+
+        let mut file = file;
+        file.seek(SeekFrom::Start(0))?;
+        let sink = SharedSink(Rc::new(RefCell::new(BufWriter::new(file))));
+        // Reads the header and leaves the sink at the end of the audio data
+        let writer = WavWriter::new_append(sink.clone())?;
+        let spec = writer.spec();
+        let depth = SampleDepth::from_spec(&spec).ok_or(hound::Error::Unsupported)?;
+
+        // The metadata chunks after the audio are written again by finalize
+        {
+            let mut file = sink.0.borrow_mut();
+            let end = file.stream_position()?;
+            file.get_ref().set_len(end)?;
+        }
+
+        Ok(Self {
+            writer,
+            sink,
+            start: 0,
+            depth,
+            sample_rate: spec.sample_rate,
+            cue_points: parse_cue_points(&metadata_chunks),
+            bwf: metadata_chunks
+                .iter()
+                .find(|(id, _)| *id == BEXT_CHUNK_ID)
+                .and_then(|(_, data)| parse_bext(data)),
+            sanitize: true,
+            sanitized: 0,
+        })
+    }
+
     /// Creates a new AudioWriter from an existing AudioReader.
     /// 
     /// This is useful when you want to write processed audio with the same
-    /// specifications as the input file.
+    /// specifications as the input file. The sample depth follows the source as
+    /// chosen by [`SampleDepth::from_reader`], e.g. a 16-bit input is written as
+    /// 16-bit again. Use [`AudioWriter::from_reader_with_depth`] to override it.
     /// 
     /// # Arguments
     /// 
@@ -43,14 +239,27 @@ impl AudioWriter {
     /// 
     /// Returns a Result containing the AudioWriter if successful.
     pub fn from_reader<P: AsRef<Path>>(path: P, reader: &AudioReader) -> Result<Self, hound::Error> {
-        let spec = WavSpec {
-            channels: reader.channels() as u16,
-            sample_rate: reader.sample_rate(),
-            bits_per_sample: 32,
-            sample_format: SampleFormat::Float,
-        };
-        let writer = WavWriter::create(path, spec)?;
-        Ok(Self { writer })
+        Self::from_reader_with_depth(path, reader, SampleDepth::from_reader(reader))
+    }
+
+    /// Creates a new AudioWriter from an existing AudioReader with the given sample depth.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Output file path
+    /// * `reader` - Reference to an AudioReader to copy the channels and sample rate from
+    /// * `depth` - Sample format of the written file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioWriter if successful.
+    pub fn from_reader_with_depth<P: AsRef<Path>>(
+        path: P,
+        reader: &AudioReader,
+        depth: SampleDepth,
+    ) -> Result<Self, hound::Error> {
+        let sink = BufWriter::new(File::create(path)?);
+        Self::with_depth(sink, reader.channels() as u16, reader.sample_rate(), depth)
     }
 
     /// Creates a new AudioWriter with specified parameters.
@@ -69,20 +278,89 @@ impl AudioWriter {
         channels: u16,
         sample_rate: u32,
     ) -> Result<Self, hound::Error> {
-        let spec = WavSpec {
-            channels,
+        let sink = BufWriter::new(File::create(path)?);
+        Self::with_depth(sink, channels, sample_rate, SampleDepth::Float32)
+    }
+}
+
+impl<W: Write + Seek> AudioWriter<W> {
+    /// Creates a new AudioWriter that writes into an arbitrary sink.
+    /// 
+    /// This allows writing a WAV file into memory, e.g. a `Cursor<Vec<u8>>`, or any
+    /// other destination. The sink must implement `Seek` because the WAV header holds
+    /// the data length, which is only known and written back by `finalize`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sink` - Destination of the WAV data
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a Result containing the AudioWriter if successful.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use std::io::Cursor;
+    /// use sonex::io::AudioWriter;
+    /// 
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// let mut writer = AudioWriter::from_writer(&mut buffer, 1, 48000).unwrap();
+    /// writer.write_samples(&[0.0, 0.5, -0.5]).unwrap();
+    /// writer.finalize().unwrap();
+    /// let wav_bytes = buffer.into_inner();
+    /// ```
+    pub fn from_writer(sink: W, channels: u16, sample_rate: u32) -> Result<Self, hound::Error> {
+        Self::with_depth(sink, channels, sample_rate, SampleDepth::Float32)
+    }
+
+    fn with_depth(
+        mut sink: W,
+        channels: u16,
+        sample_rate: u32,
+        depth: SampleDepth,
+    ) -> Result<Self, hound::Error> {
+        let start = sink.stream_position()?;
+        let sink = SharedSink(Rc::new(RefCell::new(sink)));
+        let writer = WavWriter::new(sink.clone(), depth.spec(channels, sample_rate))?;
+        Ok(Self {
+            writer,
+            sink,
+            start,
+            depth,
             sample_rate,
-            bits_per_sample: 32,
-            sample_format: SampleFormat::Float,
-        };
-        let writer = WavWriter::create(path, spec)?;
-        Ok(Self { writer })
+            cue_points: Vec::new(),
+            bwf: None,
+            sanitize: true,
+            sanitized: 0,
+        })
+    }
+
+    /// Adds a labeled cue marker, e.g. the start of a podcast chapter.
+    /// 
+    /// The markers are written as `cue ` and `LIST` chunks by `finalize` and can be
+    /// read back with [`AudioReader::markers`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `time_sec` - Position of the marker in seconds, rounded to the nearest frame
+    /// * `label` - Label of the marker
+    pub fn add_marker(&mut self, time_sec: f64, label: &str) {
+        let frame = (time_sec.max(0.0) * self.sample_rate as f64).round() as u64;
+        self.cue_points.push((frame, label.to_string()));
     }
 
     /// Writes a slice of audio samples to the file.
     /// 
     /// The samples should be interleaved if multi-channel (e.g., [L,R,L,R,...] for stereo).
-    /// Each sample should be in the range [-1.0, 1.0].
+    /// Each sample should be in the range [-1.0, 1.0]. For integer sample depths the
+    /// samples are scaled by 2^(bits - 1) and values out of range are clipped.
+    /// 
+    /// NaN and infinite samples, e.g. from a bug in a processing chain, are written as
+    /// 0.0 and counted in [`AudioWriter::sanitized_count`], unless disabled with
+    /// [`AudioWriter::set_sanitize`].
     /// 
     /// # Arguments
     /// 
@@ -92,12 +370,68 @@ impl AudioWriter {
     /// 
     /// Returns Ok(()) if successful, or an error if the write failed.
     pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), hound::Error> {
-        for &sample in samples {
-            self.writer.write_sample(sample)?;
+        let sanitize = self.sanitize;
+        if sanitize {
+            self.sanitized += samples.iter().filter(|sample| !sample.is_finite()).count() as u64;
+        }
+        let clean = |sample: f32| if sanitize && !sample.is_finite() { 0.0 } else { sample };
+
+        match self.depth {
+            SampleDepth::Float32 => {
+                for &sample in samples {
+                    self.writer.write_sample(clean(sample))?;
+                }
+            }
+            SampleDepth::Int16 => {
+                for &sample in samples {
+                    self.writer.write_sample(quantize(clean(sample), 16) as i16)?;
+                }
+            }
+            SampleDepth::Int24 | SampleDepth::Int32 => {
+                let bits = self.depth.bits_per_sample() as u32;
+                for &sample in samples {
+                    self.writer.write_sample(quantize(clean(sample), bits) as i32)?;
+                }
+            }
         }
         Ok(())
     }
 
+    /// Returns whether NaN and infinite samples are replaced with 0.0.
+    pub fn sanitize(&self) -> bool {
+        self.sanitize
+    }
+
+    /// Enables or disables replacing NaN and infinite samples with 0.0.
+    /// 
+    /// Sanitizing is on by default, as most players fail on a float file holding such
+    /// values. With it off the samples are written unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - Whether to sanitize written samples
+    pub fn set_sanitize(&mut self, enabled: bool) {
+        self.sanitize = enabled;
+    }
+
+    /// Returns the number of NaN and infinite samples replaced with 0.0 so far.
+    pub fn sanitized_count(&self) -> u64 {
+        self.sanitized
+    }
+
+    /// Sets the Broadcast Wave metadata, turning the file into a BWF file.
+    /// 
+    /// The `bext` chunk is written by `finalize`. As the samples are streamed to the
+    /// sink first, the chunk follows the audio data rather than preceding it, which
+    /// BWF readers accept as RIFF chunks may appear in any order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `metadata` - Contents of the `bext` chunk, replacing any previously set metadata
+    pub fn set_bwf_metadata(&mut self, metadata: BwfMetadata) {
+        self.bwf = Some(metadata);
+    }
+
     /// Finalizes the WAV file and ensures all data is written.
     /// 
     /// This method must be called when you're done writing samples to ensure
@@ -108,6 +442,289 @@ impl AudioWriter {
     /// 
     /// Returns Ok(()) if successful, or an error if the finalization failed.
     pub fn finalize(self) -> Result<(), hound::Error> {
-        self.writer.finalize()
+        self.writer.finalize()?;
+        if self.cue_points.is_empty() && self.bwf.is_none() {
+            return Ok(());
+        }
+
+        let mut sink = self.sink.0.borrow_mut();
+        let end = sink.seek(SeekFrom::End(0))?;
+        let mut chunks = Vec::new();
+        // RIFF chunks start at even offsets, hound leaves an odd-sized data chunk unpadded
+        if (end - self.start) % 2 == 1 {
+            chunks.push(0);
+        }
+        if let Some(bwf) = &self.bwf {
+            chunks.extend(bext_chunk(bwf));
+        }
+        if !self.cue_points.is_empty() {
+            chunks.extend(cue_chunks(&self.cue_points));
+        }
+        sink.write_all(&chunks)?;
+
+        // The RIFF size written by hound only covers the audio data
+        let riff_size = (end + chunks.len() as u64 - self.start - 8) as u32;
+        sink.seek(SeekFrom::Start(self.start + 4))?;
+        sink.write_all(&riff_size.to_le_bytes())?;
+        sink.seek(SeekFrom::End(0))?;
+        sink.flush()?;
+        Ok(())
+    }
+}
+
+/// Converts a floating-point sample to a signed integer of the given bit depth.
+/// 
+/// This is the inverse of the decoder's integer to float conversion, so integer
+/// samples survive a read and write round trip unchanged.
+pub(crate) fn quantize(sample: f32, bits: u32) -> i64 {
+    let scale = 1_i64 << (bits - 1);
+    ((sample as f64 * scale as f64).round() as i64).clamp(-scale, scale - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use rstest::*;
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    fn test_in_memory_round_trip(#[case] channels: u16) {
+        let input: Vec<f32> = (0..4000).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = AudioWriter::from_writer(&mut buffer, channels, 22050).unwrap();
+        writer.write_samples(&input).unwrap();
+        writer.finalize().unwrap();
+
+        let mut reader = AudioReader::from_bytes(buffer.into_inner()).unwrap();
+        assert_eq!(reader.channels(), channels as usize);
+        assert_eq!(reader.sample_rate(), 22050);
+
+        let mut output = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            output.extend(packet);
+        }
+        // 32-bit float WAV is lossless
+        assert_eq!(output, input);
+    }
+
+    #[rstest]
+    fn test_markers_round_trip() {
+        let input = vec![0.25f32; 48000 * 2 * 3];
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = AudioWriter::from_writer(&mut buffer, 2, 48000).unwrap();
+        writer.write_samples(&input).unwrap();
+        writer.add_marker(2.5, "Interview");
+        writer.add_marker(0.75, "Intro");
+        writer.finalize().unwrap();
+
+        let bytes = buffer.into_inner();
+        let riff_size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        assert_eq!(riff_size as usize, bytes.len() - 8);
+
+        let mut reader = AudioReader::from_bytes(bytes).unwrap();
+        let markers = reader.markers();
+        assert_eq!(markers.len(), 2);
+        assert_eq!((markers[0].frame, markers[0].label.as_str()), (36000, "Intro"));
+        assert_eq!((markers[1].frame, markers[1].label.as_str()), (120000, "Interview"));
+        assert_eq!(markers[0].time_sec, 0.75);
+        assert_eq!(markers[1].time_sec, 2.5);
+
+        // The audio is unaffected by the trailing chunks
+        let mut output = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            output.extend(packet);
+        }
+        assert_eq!(output, input);
+    }
+
+    #[rstest]
+    fn test_markers_after_odd_sized_data() {
+        let reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        assert!(reader.markers().is_empty());
+        let path = std::env::temp_dir().join("sonex_writer_markers.wav");
+
+        // 3 mono 24-bit samples leave a 9 byte data chunk that needs padding
+        let mut writer = AudioWriter::from_reader_with_depth(&path, &reader, SampleDepth::Int24).unwrap();
+        writer.write_samples(&[0.5, -0.5, 0.25]).unwrap();
+        writer.add_marker(0.0, "Start");
+        writer.finalize().unwrap();
+
+        let markers = AudioReader::new(&path).unwrap().markers();
+        let written = hound::WavReader::open(&path).unwrap();
+        assert_eq!(written.len(), 3);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(markers.len(), 1);
+        assert_eq!((markers[0].frame, markers[0].label.as_str()), (0, "Start"));
+    }
+
+    #[rstest]
+    fn test_bwf_metadata_round_trip() {
+        let metadata = BwfMetadata {
+            description: "Episode 12 master".to_string(),
+            originator: "sonex".to_string(),
+            origination_date: "2024-03-01".to_string(),
+            origination_time: "09:15:00".to_string(),
+            time_reference: 48000 * 60,
+            ..Default::default()
+        };
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = AudioWriter::from_writer(&mut buffer, 1, 48000).unwrap();
+        writer.write_samples(&[0.5; 4800]).unwrap();
+        writer.set_bwf_metadata(metadata.clone());
+        writer.add_marker(0.05, "Cue");
+        writer.finalize().unwrap();
+
+        let reader = AudioReader::from_bytes(buffer.into_inner()).unwrap();
+        let read = reader.bwf_metadata().unwrap();
+        assert_eq!(read.description, "Episode 12 master");
+        assert_eq!(read.origination_date, "2024-03-01");
+        assert_eq!(read, metadata);
+        assert_eq!(reader.markers()[0].label, "Cue");
+
+        let plain = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        assert_eq!(plain.bwf_metadata(), None);
+    }
+
+    #[rstest]
+    fn test_from_reader_preserves_16_bit() {
+        let source = "audio/sin_100Hz_-3dBFS_3s.wav";
+        let path = std::env::temp_dir().join("sonex_writer_16bit.wav");
+
+        let mut reader = AudioReader::new(source).unwrap();
+        assert_eq!(reader.bits_per_sample(), Some(16));
+        assert_eq!(SampleDepth::from_reader(&reader), SampleDepth::Int16);
+
+        let mut writer = AudioWriter::from_reader(&path, &reader).unwrap();
+        while let Ok(Some(samples)) = reader.read_packet() {
+            writer.write_samples(&samples).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let original = hound::WavReader::open(source).unwrap();
+        let written = hound::WavReader::open(&path).unwrap();
+        assert_eq!(written.spec(), original.spec());
+        let original: Vec<i16> = original.into_samples().map(Result::unwrap).collect();
+        let written: Vec<i16> = written.into_samples().map(Result::unwrap).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, original);
+    }
+
+    #[rstest]
+    fn test_non_finite_samples_sanitized() {
+        let input = [0.5, f32::NAN, -0.25, f32::INFINITY, f32::NEG_INFINITY, 0.125];
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = AudioWriter::from_writer(&mut buffer, 2, 48000).unwrap();
+        assert!(writer.sanitize());
+        writer.write_samples(&input).unwrap();
+        writer.write_samples(&[f32::NAN, 1.0]).unwrap();
+        assert_eq!(writer.sanitized_count(), 4);
+        writer.finalize().unwrap();
+
+        let written = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+        let samples: Vec<f32> = written.into_samples().map(Result::unwrap).collect();
+        assert_eq!(samples, vec![0.5, 0.0, -0.25, 0.0, 0.0, 0.125, 0.0, 1.0]);
+    }
+
+    #[rstest]
+    fn test_sanitize_disabled() {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = AudioWriter::from_writer(&mut buffer, 1, 48000).unwrap();
+        writer.set_sanitize(false);
+        writer.write_samples(&[f32::NAN, f32::INFINITY]).unwrap();
+        assert_eq!(writer.sanitized_count(), 0);
+        writer.finalize().unwrap();
+
+        let written = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+        let samples: Vec<f32> = written.into_samples().map(Result::unwrap).collect();
+        assert!(samples[0].is_nan());
+        assert_eq!(samples[1], f32::INFINITY);
+    }
+
+    #[rstest]
+    fn test_depth_override() {
+        let reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        let path = std::env::temp_dir().join("sonex_writer_override.wav");
+
+        let mut writer = AudioWriter::from_reader_with_depth(&path, &reader, SampleDepth::Int24).unwrap();
+        writer.write_samples(&[0.5, -1.0, 2.0]).unwrap();
+        writer.finalize().unwrap();
+
+        let written = hound::WavReader::open(&path).unwrap();
+        assert_eq!(written.spec().bits_per_sample, 24);
+        assert_eq!(written.spec().sample_format, SampleFormat::Int);
+        let samples: Vec<i32> = written.into_samples().map(Result::unwrap).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(samples, vec![1 << 22, -(1 << 23), (1 << 23) - 1]);
+    }
+
+    #[rstest]
+    fn test_append_continues_file() {
+        let path = std::env::temp_dir().join("sonex_writer_append.wav");
+        let input: Vec<f32> = (0..2000).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+
+        let mut writer = AudioWriter::new(&path, 1, 44100).unwrap();
+        writer.write_samples(&input[..1000]).unwrap();
+        writer.finalize().unwrap();
+
+        let mut writer = AudioWriter::append(&path).unwrap();
+        writer.write_samples(&input[1000..]).unwrap();
+        writer.finalize().unwrap();
+
+        let mut reader = AudioReader::new(&path).unwrap();
+        let mut output = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            output.extend(packet);
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.sample_rate(), 44100);
+        assert_eq!(output.len(), 2000);
+        assert_eq!(output, input);
+    }
+
+    #[rstest]
+    fn test_append_keeps_depth_and_markers() {
+        let path = std::env::temp_dir().join("sonex_writer_append_markers.wav");
+
+        // A 9 byte data chunk is followed by a pad byte and the marker chunks
+        let mut writer = AudioWriter::with_depth(
+            BufWriter::new(File::create(&path).unwrap()), 1, 48000, SampleDepth::Int24,
+        ).unwrap();
+        writer.write_samples(&[0.5, -0.5, 0.25]).unwrap();
+        writer.add_marker(0.0, "Start");
+        writer.finalize().unwrap();
+
+        let mut writer = AudioWriter::append(&path).unwrap();
+        writer.write_samples(&[0.125, -0.25]).unwrap();
+        writer.add_marker(3.0 / 48000.0, "Resumed");
+        writer.finalize().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let written = hound::WavReader::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let riff_size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        assert_eq!(riff_size as usize, bytes.len() - 8);
+        assert_eq!(written.spec().bits_per_sample, 24);
+        let samples: Vec<i32> = written.into_samples().map(Result::unwrap).collect();
+        assert_eq!(samples, vec![1 << 22, -(1 << 22), 1 << 21, 1 << 20, -(1 << 21)]);
+
+        let markers = AudioReader::from_bytes(bytes).unwrap().markers();
+        let markers: Vec<(u64, &str)> = markers.iter().map(|m| (m.frame, m.label.as_str())).collect();
+        assert_eq!(markers, vec![(0, "Start"), (3, "Resumed")]);
+    }
+
+    #[rstest]
+    fn test_append_errors() {
+        let missing = std::env::temp_dir().join("sonex_writer_append_missing.wav");
+        assert!(matches!(AudioWriter::append(&missing), Err(hound::Error::IoError(_))));
+
+        let garbage = std::env::temp_dir().join("sonex_writer_append_garbage.wav");
+        std::fs::write(&garbage, [0u8; 64]).unwrap();
+        let result = AudioWriter::append(&garbage);
+        std::fs::remove_file(&garbage).unwrap();
+        assert!(result.is_err());
     }
 }
@@ -46,7 +46,11 @@
 //! - [`io`]: Audio input/output operations
 //! - [`process`]: Audio processing nodes and effects
 //! - [`analytic`]: Audio analysis tools
+//! - [`synth`]: Test signal generation
+//! - [`buffer`]: Audio samples with their channel count and sample rate
 
+pub mod buffer;
 pub mod io;
 pub mod analytic;
-pub mod process;
\ No newline at end of file
+pub mod process;
+pub mod synth;
\ No newline at end of file
@@ -0,0 +1,229 @@
+//! Allpass filter node.
+//!
+//! An allpass filter passes every frequency at its original level but delays them by
+//! different amounts, i.e. it only changes the phase. It is used to align the phase of
+//! signals recorded with several microphones, to build reverbs and phasers, and to
+//! match the phase shift of filters applied to another signal path.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AllpassNode, AudioNode};
+//!
+//! // 2nd-order allpass: 180° phase shift at 1 kHz, steep around it
+//! let node = AllpassNode::new(1000.0, 2.0, 48000.0).with_channels(2);
+//!
+//! // 1st-order allpass: 90° phase shift at 1 kHz
+//! let gentle = AllpassNode::first_order(1000.0, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::biquad::Biquad;
+use super::node::AudioNode;
+
+/// An audio processing node with a flat magnitude response and a frequency-dependent phase.
+///
+/// The phase shift grows from 0° at DC to 360° (2nd-order) or 180° (1st-order) at
+/// Nyquist, passing half of it at the center frequency. For the 2nd-order filter the
+/// Q sets how quickly the phase turns around the center frequency.
+///
+/// The node keeps its filter state between calls to `process`, use
+/// [`AllpassNode::reset`] before processing unrelated audio. For interleaved
+/// multi-channel audio set the channel count with [`AllpassNode::with_channels`],
+/// each channel is then filtered independently.
+#[derive(Clone)]
+pub struct AllpassNode {
+    frequency: f32,
+    q: Option<f32>,
+    filters: RefCell<Vec<Biquad>>,
+    channel: Cell<usize>,
+}
+
+impl AllpassNode {
+    /// Creates a new mono 2nd-order allpass node.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - Center frequency in Hz, where the phase shift is 180°. Clamped to
+    ///   [1.0, 0.49 * `sample_rate`].
+    /// * `q` - Quality factor, higher values concentrate the phase shift around the center
+    ///   frequency. Clamped to at least 0.01.
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(frequency: f32, q: f32, sample_rate: f32) -> Self {
+        let frequency = clamp_frequency(frequency, sample_rate);
+        let q = q.max(0.01);
+        Self::from_prototype(frequency, Some(q), Biquad::allpass(frequency, q, sample_rate))
+    }
+
+    /// Creates a new mono 1st-order allpass node.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - Center frequency in Hz, where the phase shift is 90°. Clamped to
+    ///   [1.0, 0.49 * `sample_rate`].
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn first_order(frequency: f32, sample_rate: f32) -> Self {
+        let frequency = clamp_frequency(frequency, sample_rate);
+        Self::from_prototype(frequency, None, Biquad::first_order_allpass(frequency, sample_rate))
+    }
+
+    fn from_prototype(frequency: f32, q: Option<f32>, prototype: Biquad) -> Self {
+        Self {
+            frequency,
+            q,
+            filters: RefCell::new(vec![prototype]),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the filter state.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let prototype = self.filters.get_mut()[0].clone();
+        self.filters = RefCell::new(vec![prototype; channels.max(1)]);
+        self.reset();
+        self
+    }
+
+    /// Returns the center frequency in Hz.
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Returns the quality factor, or None for a 1st-order allpass.
+    pub fn q(&self) -> Option<f32> {
+        self.q
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&self) {
+        self.filters.borrow_mut().iter_mut().for_each(Biquad::clear);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut filters = self.filters.borrow_mut();
+        let channel = self.channel.get();
+        self.channel.set((channel + 1) % filters.len());
+        filters[channel].process(sample as f64) as f32
+    }
+}
+
+fn clamp_frequency(frequency: f32, sample_rate: f32) -> f32 {
+    frequency.clamp(1.0, (0.49 * sample_rate).max(1.0))
+}
+
+impl AudioNode for AllpassNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "allpass"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.filters.borrow_mut().iter_mut().for_each(|filter| filter.set_flush_denormals(enabled));
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.filters.borrow().len())
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use crate::process::test_util::impulse;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(AllpassNode::new(1000.0, 0.707, 48000.0))]
+    #[case(AllpassNode::new(200.0, 5.0, 44100.0))]
+    #[case(AllpassNode::first_order(3000.0, 48000.0))]
+    fn test_flat_magnitude_changed_phase(#[case] node: AllpassNode) {
+        let input = impulse(16384);
+        let output = node.process(&input);
+        assert_ne!(output, input);
+
+        let expected = magnitude_spectrum(&input, WindowType::Rectangular);
+        let spectrum = magnitude_spectrum(&output, WindowType::Rectangular);
+        for (bin, (mag, flat)) in spectrum.iter().zip(&expected).enumerate() {
+            assert!((mag / flat - 1.0).abs() < 1e-3, "bin {}: {} vs {}", bin, mag, flat);
+        }
+    }
+
+    #[rstest]
+    fn test_phase_at_center_frequency() {
+        let input = sine(1000.0, 0.5, 0.5, 48000);
+        // Settled after a few thousand samples
+        let settled = 4800..input.len();
+
+        // 180°: the output is the inverted input
+        let output = AllpassNode::new(1000.0, 1.0, 48000.0).process(&input);
+        for i in settled.clone() {
+            assert!((output[i] + input[i]).abs() < 1e-3, "sample {}", i);
+        }
+
+        // 90°: the output lags a quarter period (12 samples) behind the input
+        let output = AllpassNode::first_order(1000.0, 48000.0).process(&input);
+        for i in settled {
+            assert!((output[i] - input[i - 12]).abs() < 1e-3, "sample {}", i);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_and_reset() {
+        let left = sine(500.0, 0.5, 0.1, 48000);
+        let input: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let node = AllpassNode::new(800.0, 0.7, 48000.0).with_channels(2);
+        let output = node.process(&input);
+        let mono = AllpassNode::new(800.0, 0.7, 48000.0).process(&left);
+        for (frame, expected) in output.chunks_exact(2).zip(&mono) {
+            assert_eq!(frame[0], *expected);
+            assert_eq!(frame[1], 0.0);
+        }
+
+        node.reset();
+        assert_eq!(node.process(&input), output);
+    }
+
+    #[rstest]
+    fn test_filter_state_flushed_to_zero() {
+        let node = AllpassNode::first_order(100.0, 48000.0).with_channels(2);
+        let input: Vec<f32> = impulse(20000).iter().flat_map(|&s| [s, s]).collect();
+        let output = node.process(&input);
+        assert!(output.iter().all(|s| !s.is_subnormal()));
+        assert!(output[30000..].iter().all(|&s| s == 0.0));
+    }
+
+    #[rstest]
+    fn test_parameters_clamped() {
+        let node = AllpassNode::new(30000.0, 0.0, 48000.0);
+        assert_eq!(node.frequency(), 0.49 * 48000.0);
+        assert_eq!(node.q(), Some(0.01));
+        assert_eq!(AllpassNode::first_order(-5.0, 48000.0).frequency(), 1.0);
+        assert_eq!(AllpassNode::first_order(100.0, 48000.0).q(), None);
+        assert_eq!(node.box_clone().node_type(), "allpass");
+    }
+}
@@ -0,0 +1,198 @@
+//! Gain automation processing node.
+//!
+//! This module applies a gain envelope defined by breakpoints, e.g. to fade music
+//! in and out under a voice-over or to duck an intro jingle.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, AutomatedGainNode};
+//!
+//! // Fade in over 2 seconds, hold, then fade out between 10 and 12 seconds
+//! let node = AutomatedGainNode::new(
+//!     vec![(0.0, -60.0), (2.0, 0.0), (10.0, 0.0), (12.0, -60.0)],
+//!     48000.0,
+//! );
+//!
+//! let input = vec![0.5f32; 48000 * 12];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::Cell;
+use super::node::AudioNode;
+
+/// An audio processing node that applies a gain envelope over time.
+///
+/// The envelope is given as `(time_sec, gain_db)` breakpoints. Between two
+/// breakpoints the gain in dB is interpolated linearly per frame; before the first
+/// breakpoint the first gain is used and past the last breakpoint the last gain is
+/// held. Without breakpoints the node has unity gain.
+///
+/// The node tracks the playback position across calls to `process`, so a stream can
+/// be processed block by block. Use [`AutomatedGainNode::reset`] to rewind to the
+/// start. For interleaved multi-channel audio set the channel count with
+/// [`AutomatedGainNode::with_channels`] so all channels of a frame get the same gain.
+#[derive(Clone)]
+pub struct AutomatedGainNode {
+    breakpoints: Vec<(f64, f32)>,
+    sample_rate: f32,
+    channels: usize,
+    frame: Cell<u64>,
+    channel: Cell<usize>,
+}
+
+impl AutomatedGainNode {
+    /// Creates a new mono automated gain node.
+    ///
+    /// # Arguments
+    ///
+    /// * `breakpoints` - `(time_sec, gain_db)` pairs, they are sorted by time if given out of order
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(breakpoints: Vec<(f64, f32)>, sample_rate: f32) -> Self {
+        let mut breakpoints = breakpoints;
+        breakpoints.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            breakpoints,
+            sample_rate,
+            channels: 1,
+            frame: Cell::new(0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the playback position.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Returns the breakpoints sorted by time.
+    pub fn breakpoints(&self) -> &[(f64, f32)] {
+        &self.breakpoints
+    }
+
+    /// Rewinds the playback position to the start of the envelope.
+    pub fn reset(&self) {
+        self.frame.set(0);
+        self.channel.set(0);
+    }
+
+    /// Returns the envelope gain in dB at the given time.
+    pub fn gain_db_at(&self, time_sec: f64) -> f32 {
+        let (first, last) = match (self.breakpoints.first(), self.breakpoints.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        if time_sec <= first.0 {
+            return first.1;
+        }
+        if time_sec >= last.0 {
+            return last.1;
+        }
+        let next = self.breakpoints.partition_point(|&(t, _)| t <= time_sec);
+        let (t0, g0) = self.breakpoints[next - 1];
+        let (t1, g1) = self.breakpoints[next];
+        let fraction = ((time_sec - t0) / (t1 - t0)) as f32;
+        g0 + (g1 - g0) * fraction
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let frame = self.frame.get();
+        let gain_db = self.gain_db_at(frame as f64 / self.sample_rate as f64);
+
+        let channel = self.channel.get() + 1;
+        if channel == self.channels {
+            self.channel.set(0);
+            self.frame.set(frame + 1);
+        } else {
+            self.channel.set(channel);
+        }
+
+        sample * 10.0_f32.powf(gain_db / 20.0)
+    }
+}
+
+impl AudioNode for AutomatedGainNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "automated_gain"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_ramp_reaches_unity() {
+        let node = AutomatedGainNode::new(vec![(0.0, -60.0), (1.0, 0.0)], 1000.0);
+        let output = node.process(&[1.0; 1500]);
+
+        assert!((output[0] - 0.001).abs() < 1e-6);
+        // Halfway through the ramp the gain is -30 dB
+        assert!((output[500] - 10.0_f32.powf(-1.5)).abs() < 1e-5);
+        assert!(output[999] < 1.0);
+        assert!((output[1000] - 1.0).abs() < 1e-6);
+        // The last value is held
+        assert!(output[1000..].iter().all(|&s| (s - 1.0).abs() < 1e-6));
+        assert!(output.windows(2).take(1000).all(|w| w[1] > w[0]));
+    }
+
+    #[rstest]
+    fn test_out_of_order_breakpoints_are_sorted() {
+        let node = AutomatedGainNode::new(vec![(1.0, 0.0), (0.0, -20.0), (0.5, -6.0)], 1000.0);
+        assert_eq!(node.breakpoints(), &[(0.0, -20.0), (0.5, -6.0), (1.0, 0.0)]);
+        assert_eq!(node.gain_db_at(0.25), -13.0);
+    }
+
+    #[rstest]
+    fn test_channels_share_gain_per_frame() {
+        let node = AutomatedGainNode::new(vec![(0.0, -20.0), (0.004, 0.0)], 1000.0).with_channels(2);
+        let output = node.process(&[1.0; 12]);
+        for frame in output.chunks_exact(2) {
+            assert_eq!(frame[0], frame[1]);
+        }
+        assert!((output[4] - 10.0_f32.powf(-0.5)).abs() < 1e-6);
+    }
+
+    #[rstest]
+    fn test_position_carries_over_and_reset() {
+        let node = AutomatedGainNode::new(vec![(0.0, -60.0), (1.0, 0.0)], 1000.0);
+        let whole = node.clone().process(&[1.0; 1000]);
+
+        let first = node.process(&[1.0; 400]);
+        let second = node.process(&[1.0; 600]);
+        assert_eq!([first, second].concat(), whole);
+
+        node.reset();
+        assert_eq!(node.process(&[1.0]), whole[..1]);
+    }
+
+    #[rstest]
+    fn test_empty_breakpoints_unity() {
+        let node = AutomatedGainNode::new(Vec::new(), 1000.0);
+        assert_eq!(node.process(&[0.5, -0.5]), vec![0.5, -0.5]);
+        assert_eq!(node.box_clone().node_type(), "automated_gain");
+    }
+}
@@ -0,0 +1,127 @@
+//! Biquad filter shared by the filtering nodes.
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use super::denormal::flush_denormals_f64;
+
+/// 2nd-order IIR filter in transposed direct form II.
+#[derive(Clone)]
+pub(super) struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+    flush_denormals: bool,
+}
+
+impl Biquad {
+    /// Creates a Butterworth low-pass or high-pass filter.
+    pub(super) fn butterworth(freq: f32, sample_rate: f32, high_pass: bool) -> Self {
+        let w0 = 2.0 * PI * freq as f64 / sample_rate as f64;
+        let cos = w0.cos();
+        let alpha = w0.sin() * FRAC_1_SQRT_2;
+        let a0 = 1.0 + alpha;
+        let (b0, b1) = if high_pass {
+            ((1.0 + cos) / 2.0, -(1.0 + cos))
+        } else {
+            ((1.0 - cos) / 2.0, 1.0 - cos)
+        };
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+            flush_denormals: true,
+        }
+    }
+
+    /// Creates a 2nd-order allpass filter with a phase shift of 180° at `freq`.
+    pub(super) fn allpass(freq: f32, q: f32, sample_rate: f32) -> Self {
+        let w0 = 2.0 * PI * freq as f64 / sample_rate as f64;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q as f64);
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 - alpha) / a0,
+            b1: -2.0 * cos / a0,
+            b2: 1.0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+            flush_denormals: true,
+        }
+    }
+
+    /// Creates a 1st-order allpass filter with a phase shift of 90° at `freq`.
+    pub(super) fn first_order_allpass(freq: f32, sample_rate: f32) -> Self {
+        let tan = (PI * freq as f64 / sample_rate as f64).tan();
+        let c = (tan - 1.0) / (tan + 1.0);
+        Self { b0: c, b1: 1.0, b2: 0.0, a1: c, a2: 0.0, z1: 0.0, z2: 0.0, flush_denormals: true }
+    }
+
+    /// Creates the high-shelf stage of the ITU-R BS.1770 K-weighting filter.
+    ///
+    /// The coefficients are derived from the analog prototype, so they match the
+    /// 48 kHz coefficients of the standard and carry over to other sample rates.
+    pub(super) fn k_weighting_shelf(sample_rate: f32) -> Self {
+        let k = (PI * 1681.974450955533 / sample_rate as f64).tan();
+        let q = 0.7071752369554196;
+        let vh = 10f64.powf(3.999843853973347 / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        Self {
+            b0: (vh + vb * k / q + k * k) / a0,
+            b1: 2.0 * (k * k - vh) / a0,
+            b2: (vh - vb * k / q + k * k) / a0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+            z1: 0.0,
+            z2: 0.0,
+            flush_denormals: true,
+        }
+    }
+
+    /// Creates the high-pass (RLB) stage of the ITU-R BS.1770 K-weighting filter.
+    pub(super) fn k_weighting_high_pass(sample_rate: f32) -> Self {
+        let k = (PI * 38.13547087602444 / sample_rate as f64).tan();
+        let q = 0.5003270373238773;
+        let a0 = 1.0 + k / q + k * k;
+        Self {
+            b0: 1.0,
+            b1: -2.0,
+            b2: 1.0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+            z1: 0.0,
+            z2: 0.0,
+            flush_denormals: true,
+        }
+    }
+
+    pub(super) fn process(&mut self, input: f64) -> f64 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        if self.flush_denormals {
+            self.z1 = flush_denormals_f64(self.z1);
+            self.z2 = flush_denormals_f64(self.z2);
+        }
+        output
+    }
+
+    /// Enables or disables flushing of denormal numbers in the filter state.
+    pub(super) fn set_flush_denormals(&mut self, enabled: bool) {
+        self.flush_denormals = enabled;
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
@@ -0,0 +1,197 @@
+//! Bitcrusher (bit-depth and sample-rate reduction) processing node.
+//!
+//! The bitcrusher produces lo-fi effects by quantizing samples to a small number of
+//! amplitude levels and by holding samples for several output samples, which
+//! lowers the effective sample rate.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, BitcrusherNode};
+//!
+//! // 6 bit resolution at a quarter of the sample rate
+//! let node = BitcrusherNode::new(6, 4);
+//!
+//! let input = vec![0.5f32; 1000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::node::AudioNode;
+
+/// An audio processing node that reduces bit depth and sample rate.
+///
+/// Quantization rounds every sample to one of `2^bit_depth` evenly spaced levels
+/// spanning [-1.0, 1.0]. Decimation holds each sample for `downsample_factor`
+/// frames (sample-and-hold). The hold position is kept between calls to `process`,
+/// use [`BitcrusherNode::reset`] to start over.
+///
+/// For interleaved multi-channel audio set the channel count with
+/// [`BitcrusherNode::with_channels`] so each channel is held independently.
+#[derive(Clone)]
+pub struct BitcrusherNode {
+    bit_depth: u32,
+    downsample_factor: usize,
+    channels: usize,
+    held: RefCell<Vec<f32>>,
+    counter: Cell<usize>,
+    channel: Cell<usize>,
+}
+
+impl BitcrusherNode {
+    /// Creates a new mono bitcrusher node.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_depth` - Number of bits to quantize to, clamped to [1, 24]
+    /// * `downsample_factor` - Number of frames each sample is held for, 1 disables decimation
+    pub fn new(bit_depth: u32, downsample_factor: usize) -> Self {
+        Self {
+            bit_depth: bit_depth.clamp(1, 24),
+            downsample_factor: downsample_factor.max(1),
+            channels: 1,
+            held: RefCell::new(vec![0.0]),
+            counter: Cell::new(0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the hold state.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.held = RefCell::new(vec![0.0; self.channels]);
+        self.reset();
+        self
+    }
+
+    /// Returns the bit depth.
+    pub fn bit_depth(&self) -> u32 {
+        self.bit_depth
+    }
+
+    /// Returns the downsample factor.
+    pub fn downsample_factor(&self) -> usize {
+        self.downsample_factor
+    }
+
+    /// Clears the hold state so the next sample is captured immediately.
+    pub fn reset(&self) {
+        self.held.borrow_mut().iter_mut().for_each(|s| *s = 0.0);
+        self.counter.set(0);
+        self.channel.set(0);
+    }
+
+    fn quantize(&self, sample: f32) -> f32 {
+        let steps = ((1u32 << self.bit_depth) - 1) as f32;
+        let level = ((sample.clamp(-1.0, 1.0) + 1.0) * 0.5 * steps).round();
+        level / steps * 2.0 - 1.0
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let channel = self.channel.get();
+        let counter = self.counter.get();
+        let mut held = self.held.borrow_mut();
+
+        if counter == 0 {
+            held[channel] = self.quantize(sample);
+        }
+        let output = held[channel];
+
+        if channel + 1 == self.channels {
+            self.channel.set(0);
+            self.counter.set((counter + 1) % self.downsample_factor);
+        } else {
+            self.channel.set(channel + 1);
+        }
+        output
+    }
+}
+
+impl AudioNode for BitcrusherNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "bitcrusher"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn ramp() -> Vec<f32> {
+        (-100..=100).map(|i| i as f32 / 100.0).collect()
+    }
+
+    fn distinct(values: &[f32]) -> Vec<f32> {
+        let mut values = values.to_vec();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values.dedup();
+        values
+    }
+
+    #[rstest]
+    fn test_one_bit_has_two_levels(ramp: Vec<f32>) {
+        let output = BitcrusherNode::new(1, 1).process(&ramp);
+        assert_eq!(distinct(&output), vec![-1.0, 1.0]);
+    }
+
+    #[rstest]
+    #[case(2, 4)]
+    #[case(3, 8)]
+    fn test_level_count(#[case] bits: u32, #[case] levels: usize, ramp: Vec<f32>) {
+        let output = BitcrusherNode::new(bits, 1).process(&ramp);
+        assert_eq!(distinct(&output).len(), levels);
+    }
+
+    #[rstest]
+    fn test_downsample_holds_values() {
+        let input: Vec<f32> = (0..16).map(|i| i as f32 / 16.0).collect();
+        let output = BitcrusherNode::new(24, 4).process(&input);
+        for (i, chunk) in output.chunks(4).enumerate() {
+            assert!(chunk.iter().all(|&s| s == chunk[0]));
+            assert!((chunk[0] - input[i * 4]).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    fn test_downsample_per_channel() {
+        let input = vec![0.5, -0.5, 0.1, -0.1, 0.2, -0.2, 0.3, -0.3];
+        let node = BitcrusherNode::new(24, 2).with_channels(2);
+        let output = node.process(&input);
+        let expected = [0.5, -0.5, 0.5, -0.5, 0.2, -0.2, 0.2, -0.2];
+        for (out, exp) in output.iter().zip(expected.iter()) {
+            assert!((out - exp).abs() < 1e-6, "{:?}", output);
+        }
+    }
+
+    #[rstest]
+    fn test_process_methods_and_type(ramp: Vec<f32>) {
+        let node = BitcrusherNode::new(4, 3);
+        let output = node.clone().process(&ramp);
+        let mut buffer = ramp.clone();
+        node.clone().process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+        assert_eq!(node.box_clone().node_type(), "bitcrusher");
+    }
+}
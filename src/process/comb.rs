@@ -0,0 +1,223 @@
+//! Comb filter node.
+//!
+//! A comb filter adds a delayed copy of the signal to itself. Frequencies whose period
+//! divides the delay add up and others cancel, which gives a magnitude response with
+//! evenly spaced peaks and notches, `1 / delay` Hz apart, like the teeth of a comb.
+//! Short swept delays give flangers, feedback combs tuned to a pitch ring like a
+//! plucked string or a resonator.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, CombFilterNode};
+//!
+//! // Resonator ringing at 200 Hz and its harmonics
+//! let resonator = CombFilterNode::new(1.0 / 200.0, 0.95, 0.0, 48000.0);
+//!
+//! // Notches at 250, 750, 1250 Hz, ...
+//! let notches = CombFilterNode::new(0.002, 0.0, 1.0, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = resonator.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::delay::MAX_FEEDBACK;
+use super::denormal::flush_denormals;
+use super::node::AudioNode;
+
+/// An audio processing node computing `y[n] = x[n] + feedforward * x[n - D] + feedback * y[n - D]`.
+///
+/// With only `feedforward` set the node is a FIR comb with notches, with only
+/// `feedback` set an IIR comb with resonant peaks. Positive gains put the peaks at
+/// multiples of `1 / delay` Hz and the notches halfway between them, negative gains
+/// swap them.
+///
+/// The node keeps its delay line between calls to `process`, use
+/// [`CombFilterNode::reset`] before processing unrelated audio. For interleaved
+/// multi-channel audio set the channel count with [`CombFilterNode::with_channels`],
+/// each channel is then filtered independently.
+#[derive(Clone)]
+pub struct CombFilterNode {
+    feedback: f32,
+    feedforward: f32,
+    delay_frames: usize,
+    channels: usize,
+    /// Past (input, output) pairs, interleaved by channel
+    buffer: RefCell<Vec<(f32, f32)>>,
+    position: Cell<usize>,
+    flush_denormals: Cell<bool>,
+}
+
+impl CombFilterNode {
+    /// Creates a new mono comb filter node.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_sec` - Delay time in seconds, rounded to whole samples and at least one
+    /// * `feedback` - Gain of the delayed output. Clamped to ±`MAX_FEEDBACK` so the filter
+    ///   stays stable.
+    /// * `feedforward` - Gain of the delayed input, clamped to [-1.0, 1.0]
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(delay_sec: f32, feedback: f32, feedforward: f32, sample_rate: f32) -> Self {
+        let delay_frames = ((delay_sec * sample_rate).round() as usize).max(1);
+        Self {
+            feedback: feedback.clamp(-MAX_FEEDBACK, MAX_FEEDBACK),
+            feedforward: feedforward.clamp(-1.0, 1.0),
+            delay_frames,
+            channels: 1,
+            buffer: RefCell::new(vec![(0.0, 0.0); delay_frames]),
+            position: Cell::new(0),
+            flush_denormals: Cell::new(true),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the delay line.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.buffer = RefCell::new(vec![(0.0, 0.0); self.delay_frames * self.channels]);
+        self.position.set(0);
+        self
+    }
+
+    /// Returns the delay time in frames.
+    pub fn delay_frames(&self) -> usize {
+        self.delay_frames
+    }
+
+    /// Returns the feedback gain.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Returns the feedforward gain.
+    pub fn feedforward(&self) -> f32 {
+        self.feedforward
+    }
+
+    /// Clears the delay line.
+    pub fn reset(&self) {
+        self.buffer.borrow_mut().iter_mut().for_each(|s| *s = (0.0, 0.0));
+        self.position.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut buffer = self.buffer.borrow_mut();
+        let position = self.position.get();
+
+        // As in DelayNode the slot being overwritten is one delay time old for the
+        // same channel
+        let (delayed_input, delayed_output) = buffer[position];
+        let output = sample + self.feedforward * delayed_input + self.feedback * delayed_output;
+        let stored = if self.flush_denormals.get() { flush_denormals(output) } else { output };
+        buffer[position] = (sample, stored);
+        self.position.set((position + 1) % buffer.len());
+        output
+    }
+}
+
+impl AudioNode for CombFilterNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "comb_filter"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.flush_denormals.set(enabled);
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use rstest::*;
+
+    /// Magnitude response relative to a flat response, in 1 Hz bins at 48 kHz.
+    fn response(node: &CombFilterNode) -> Vec<f32> {
+        let mut impulse = vec![0.0; 48000];
+        impulse[0] = 1.0;
+        let flat = magnitude_spectrum(&impulse, WindowType::Rectangular)[1];
+        magnitude_spectrum(&node.process(&impulse), WindowType::Rectangular)
+            .iter()
+            .map(|mag| mag / flat)
+            .collect()
+    }
+
+    #[rstest]
+    // Feedforward: |1 + g|, |1 - g|
+    #[case(0.0, 1.0, 2.0, 0.0)]
+    #[case(0.0, 0.5, 1.5, 0.5)]
+    // Feedback: 1 / |1 - g|, 1 / |1 + g|
+    #[case(0.9, 0.0, 10.0, 1.0 / 1.9)]
+    // Negative gains swap peaks and notches
+    #[case(-0.5, 0.0, 1.0 / 1.5, 2.0)]
+    fn test_peaks_and_notches(
+        #[case] feedback: f32,
+        #[case] feedforward: f32,
+        #[case] at_multiples: f32,
+        #[case] halfway: f32,
+    ) {
+        // 1 ms delay: multiples of 1 kHz and halfway at 500 Hz, 1.5 kHz, ...
+        let node = CombFilterNode::new(0.001, feedback, feedforward, 48000.0);
+        assert_eq!(node.delay_frames(), 48);
+        let response = response(&node);
+        for k in 1..10 {
+            let peak = response[k * 1000];
+            let between = response[k * 1000 + 500];
+            assert!((peak - at_multiples).abs() < 0.01 * at_multiples, "{} Hz: {}", k * 1000, peak);
+            assert!((between - halfway).abs() < 0.01 * halfway.max(1.0), "{} Hz: {}", k * 1000 + 500, between);
+        }
+    }
+
+    #[rstest]
+    fn test_feedback_clamped_stable() {
+        let node = CombFilterNode::new(0.001, 1.5, 2.0, 48000.0);
+        assert_eq!(node.feedback(), MAX_FEEDBACK);
+        assert_eq!(node.feedforward(), 1.0);
+
+        let output = node.process(&vec![0.5; 96000]);
+        assert!(output.iter().all(|s| s.is_finite() && s.abs() < 200.0));
+    }
+
+    #[rstest]
+    fn test_channels_and_reset() {
+        let input: Vec<f32> = (0..480).flat_map(|i| [if i == 0 { 1.0 } else { 0.0 }, 0.25]).collect();
+        let node = CombFilterNode::new(0.001, 0.5, 0.0, 48000.0).with_channels(2);
+        let output = node.process(&input);
+        // Echoes of the left impulse every 48 frames, halving each time
+        assert_eq!(output[0], 1.0);
+        assert_eq!(output[2 * 48], 0.5);
+        assert_eq!(output[2 * 96], 0.25);
+        assert_eq!(output[2 * 96 + 2], 0.0);
+        // The constant right channel builds up to 0.25 / (1 - 0.5)
+        assert!((output[959] - 0.5).abs() < 0.001);
+
+        node.reset();
+        assert_eq!(node.process(&input), output);
+        assert_eq!(node.box_clone().node_type(), "comb_filter");
+    }
+}
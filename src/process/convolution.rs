@@ -0,0 +1,344 @@
+//! Convolution node for applying impulse responses.
+//!
+//! This module convolves audio with a measured or designed impulse response (IR),
+//! e.g. a guitar cabinet or a small room. Long IRs are split into partitions that
+//! are convolved in the frequency domain, so the cost per sample grows with the
+//! logarithm of the partition size rather than with the length of the IR.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, ConvolutionNode};
+//!
+//! // Stereo audio through a 0.3 s impulse response
+//! let impulse_response = vec![0.0f32; 14400];
+//! let node = ConvolutionNode::new(impulse_response, 48000.0, 2);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let output = node.process(&input);
+//! assert_eq!(output.len(), input.len());
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use super::node::AudioNode;
+
+/// Approximate length of a partition in seconds.
+const PARTITION_SEC: f32 = 0.005;
+/// Smallest partition size in frames.
+const MIN_PARTITION_SIZE: usize = 64;
+
+/// Convolution state of one channel.
+#[derive(Clone)]
+struct ChannelState {
+    /// Input of the partition being collected
+    input: Vec<f32>,
+    /// Output of the previous partition, played while the next one is collected
+    output: Vec<f32>,
+    /// Second half of the last convolved block, added to the next one
+    overlap: Vec<f32>,
+    /// Spectra of the most recent input partitions, newest first
+    spectra: VecDeque<Vec<Complex<f32>>>,
+}
+
+/// An audio processing node that convolves audio with an impulse response.
+///
+/// The impulse response is split into partitions of [`ConvolutionNode::partition_size`]
+/// frames. Input is collected a partition at a time, and each full partition is
+/// convolved with all IR partitions using FFTs of twice the partition size
+/// (uniformly partitioned overlap-add). The output is therefore delayed by one
+/// partition, which the node reports as [`AudioNode::latency_samples`], and is
+/// silent until that latency has passed.
+///
+/// The same impulse response is applied to every channel. The node keeps its state
+/// between calls to `process`, use [`ConvolutionNode::reset`] before processing
+/// unrelated audio.
+#[derive(Clone)]
+pub struct ConvolutionNode {
+    channels: usize,
+    partition_size: usize,
+    ir_len: usize,
+    ir_spectra: Vec<Vec<Complex<f32>>>,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    states: RefCell<Vec<ChannelState>>,
+    position: Cell<usize>,
+    channel: Cell<usize>,
+}
+
+impl ConvolutionNode {
+    /// Creates a new convolution node.
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse_response` - Mono impulse response, at the sample rate of the audio.
+    ///   An empty IR is treated as a single zero and mutes the audio.
+    /// * `sample_rate` - Sample rate in Hz, which sets the partition size to about 5 ms
+    /// * `channels` - Number of interleaved audio channels
+    pub fn new(impulse_response: Vec<f32>, sample_rate: f32, channels: usize) -> Self {
+        let partition_size = ((sample_rate.max(1.0) * PARTITION_SEC) as usize)
+            .next_power_of_two()
+            .max(MIN_PARTITION_SIZE);
+        Self::with_partition_size(impulse_response, partition_size, channels)
+    }
+
+    /// Creates a new convolution node with a given partition size.
+    ///
+    /// Smaller partitions lower the latency, larger ones lower the cost per sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse_response` - Mono impulse response, at the sample rate of the audio
+    /// * `partition_size` - Partition size in frames, rounded up to a power of two
+    /// * `channels` - Number of interleaved audio channels
+    pub fn with_partition_size(impulse_response: Vec<f32>, partition_size: usize, channels: usize) -> Self {
+        let partition_size = partition_size.max(1).next_power_of_two();
+        let fft_size = 2 * partition_size;
+        let mut planner = FftPlanner::new();
+        let fft = planner.plan_fft_forward(fft_size);
+        let ifft = planner.plan_fft_inverse(fft_size);
+
+        let ir_len = impulse_response.len().max(1);
+        let ir_spectra: Vec<Vec<Complex<f32>>> = if impulse_response.is_empty() {
+            vec![vec![Complex::new(0.0, 0.0); fft_size]]
+        } else {
+            impulse_response
+                .chunks(partition_size)
+                .map(|partition| spectrum(fft.as_ref(), partition, fft_size))
+                .collect()
+        };
+
+        let channels = channels.max(1);
+        let state = ChannelState {
+            input: vec![0.0; partition_size],
+            output: vec![0.0; partition_size],
+            overlap: vec![0.0; partition_size],
+            spectra: vec![vec![Complex::new(0.0, 0.0); fft_size]; ir_spectra.len()].into(),
+        };
+        Self {
+            channels,
+            partition_size,
+            ir_len,
+            ir_spectra,
+            fft,
+            ifft,
+            states: RefCell::new(vec![state; channels]),
+            position: Cell::new(0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Returns the partition size in frames.
+    pub fn partition_size(&self) -> usize {
+        self.partition_size
+    }
+
+    /// Returns the length of the impulse response in samples.
+    pub fn impulse_response_len(&self) -> usize {
+        self.ir_len
+    }
+
+    /// Clears all buffered audio and the reverberation tail.
+    pub fn reset(&self) {
+        for state in self.states.borrow_mut().iter_mut() {
+            state.input.iter_mut().for_each(|s| *s = 0.0);
+            state.output.iter_mut().for_each(|s| *s = 0.0);
+            state.overlap.iter_mut().for_each(|s| *s = 0.0);
+            state.spectra.iter_mut().flatten().for_each(|c| *c = Complex::new(0.0, 0.0));
+        }
+        self.position.set(0);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let channel = self.channel.get();
+        let position = self.position.get();
+        let output = {
+            let state = &mut self.states.borrow_mut()[channel];
+            state.input[position] = sample;
+            state.output[position]
+        };
+
+        if channel + 1 < self.channels {
+            self.channel.set(channel + 1);
+        } else {
+            self.channel.set(0);
+            if position + 1 < self.partition_size {
+                self.position.set(position + 1);
+            } else {
+                self.position.set(0);
+                self.states.borrow_mut().iter_mut().for_each(|state| self.convolve(state));
+            }
+        }
+        output
+    }
+
+    /// Convolves the collected input partition of a channel with the impulse response.
+    fn convolve(&self, state: &mut ChannelState) {
+        let fft_size = 2 * self.partition_size;
+        let mut newest = state.spectra.pop_back().unwrap_or_default();
+        newest.clear();
+        newest.extend(state.input.iter().map(|&s| Complex::new(s, 0.0)));
+        newest.resize(fft_size, Complex::new(0.0, 0.0));
+        self.fft.process(&mut newest);
+        state.spectra.push_front(newest);
+
+        // Input partition k blocks ago meets IR partition k
+        let mut buffer = vec![Complex::new(0.0, 0.0); fft_size];
+        for (input, ir) in state.spectra.iter().zip(&self.ir_spectra) {
+            buffer.iter_mut()
+                .zip(input.iter().zip(ir))
+                .for_each(|(out, (x, h))| *out += x * h);
+        }
+        self.ifft.process(&mut buffer);
+
+        let scale = 1.0 / fft_size as f32;
+        let (first, second) = buffer.split_at(self.partition_size);
+        for (i, (out, overlap)) in state.output.iter_mut().zip(state.overlap.iter_mut()).enumerate() {
+            *out = first[i].re * scale + *overlap;
+            *overlap = second[i].re * scale;
+        }
+    }
+}
+
+/// Returns the FFT of `samples` zero-padded to `fft_size`.
+fn spectrum(fft: &dyn Fft<f32>, samples: &[f32], fft_size: usize) -> Vec<Complex<f32>> {
+    let mut buffer: Vec<Complex<f32>> = samples.iter().map(|&s| Complex::new(s, 0.0)).collect();
+    buffer.resize(fft_size, Complex::new(0.0, 0.0));
+    fft.process(&mut buffer);
+    buffer
+}
+
+impl AudioNode for ConvolutionNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "convolution"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    /// Input is convolved a partition at a time, so the output lags by one partition.
+    fn latency_samples(&self) -> usize {
+        self.partition_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::white_noise;
+    use rstest::*;
+
+    fn direct_convolution(input: &[f32], ir: &[f32]) -> Vec<f32> {
+        (0..input.len())
+            .map(|n| (0..ir.len()).filter(|&k| k <= n).map(|k| input[n - k] * ir[k]).sum())
+            .collect()
+    }
+
+    fn assert_delayed(output: &[f32], expected: &[f32], latency: usize) {
+        assert!(output[..latency].iter().all(|&s| s == 0.0));
+        for (i, (&out, &exp)) in output[latency..].iter().zip(expected).enumerate() {
+            assert!((out - exp).abs() < 1e-4, "sample {}: {} != {}", i, out, exp);
+        }
+    }
+
+    #[rstest]
+    #[case(1000.0)]
+    #[case(48000.0)]
+    fn test_unit_impulse_passes_through(#[case] sample_rate: f32) {
+        let node = ConvolutionNode::new(vec![1.0], sample_rate, 1);
+        let latency = node.latency_samples();
+        assert_eq!(latency, node.partition_size());
+
+        let input = white_noise(1.0, 2.0, 1000, 1);
+        let output = node.process(&input);
+        assert_eq!(output.len(), input.len());
+        assert_delayed(&output, &input, latency);
+    }
+
+    #[rstest]
+    fn test_two_tap_delayed_sum() {
+        let node = ConvolutionNode::with_partition_size(vec![0.5, 0.0, 0.0, 0.25], 4, 1);
+        let mut input = vec![0.0; 16];
+        input[0] = 1.0;
+        input[5] = -1.0;
+
+        let output = node.process(&input);
+        let mut expected = vec![0.0; 16];
+        expected[4] = 0.5;
+        expected[7] = 0.25;
+        expected[9] = -0.5;
+        expected[12] = -0.25;
+        for (i, (&out, &exp)) in output.iter().zip(&expected).enumerate() {
+            assert!((out - exp).abs() < 1e-6, "sample {} = {}", i, out);
+        }
+    }
+
+    #[rstest]
+    #[case(8)]
+    #[case(64)]
+    fn test_long_ir_matches_direct_convolution(#[case] partition_size: usize) {
+        // An IR spanning many partitions, processed in blocks of an unrelated size
+        let ir: Vec<f32> = white_noise(1.0, 0.3, 1000, 2).iter().enumerate().map(|(i, s)| s * (-(i as f32) / 60.0).exp()).collect();
+        let input = white_noise(1.0, 1.5, 1000, 3);
+        let node = ConvolutionNode::with_partition_size(ir.clone(), partition_size, 1);
+
+        let output: Vec<f32> = input.chunks(37).flat_map(|block| node.process(block)).collect();
+        assert_delayed(&output, &direct_convolution(&input, &ir), partition_size);
+    }
+
+    #[rstest]
+    fn test_channels_convolved_independently() {
+        let node = ConvolutionNode::with_partition_size(vec![1.0, 0.5], 2, 2);
+        // Impulse on the right channel only
+        let input = vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let output = node.process(&input);
+        let expected = [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.5];
+        assert!(output.iter().zip(expected).all(|(&out, exp)| (out - exp).abs() < 1e-6), "{:?}", output);
+    }
+
+    #[rstest]
+    fn test_state_carries_over_and_reset() {
+        let node1 = ConvolutionNode::with_partition_size(vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.8], 4, 1);
+        let node2 = node1.clone();
+        let input = white_noise(1.0, 0.064, 1000, 4);
+
+        let output = node1.process(&input);
+        let mut buffer = input.clone();
+        node2.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+
+        node1.reset();
+        assert!(node1.process(&[0.0; 16]).iter().all(|&s| s == 0.0));
+        assert_eq!(node1.impulse_response_len(), 6);
+        assert_eq!(node1.box_clone().node_type(), "convolution");
+    }
+
+    #[rstest]
+    fn test_empty_ir_mutes() {
+        let node = ConvolutionNode::with_partition_size(Vec::new(), 4, 1);
+        assert!(node.process(&white_noise(1.0, 0.032, 1000, 5)).iter().all(|&s| s == 0.0));
+    }
+}
@@ -0,0 +1,265 @@
+//! Linkwitz-Riley crossover.
+//!
+//! This module splits audio into frequency bands for multiband processing. Each
+//! crossover point uses 4th-order Linkwitz-Riley filters (two cascaded 2nd-order
+//! Butterworth filters): the low-pass and high-pass outputs are in phase at every
+//! frequency and sum to an allpass, so the bands add back up to the input with a
+//! flat magnitude response and only a phase shift.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::LrCrossover;
+//!
+//! // Three bands: below 200 Hz, 200 Hz to 2 kHz and above 2 kHz
+//! let crossover = LrCrossover::new(&[200.0, 2000.0], 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let bands = crossover.split(&input);
+//! assert_eq!(bands.len(), 3);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::biquad::Biquad;
+
+/// 4th-order Linkwitz-Riley low-pass and high-pass pair at one frequency.
+#[derive(Clone)]
+struct LrSplit {
+    low: [Biquad; 2],
+    high: [Biquad; 2],
+}
+
+impl LrSplit {
+    fn new(freq: f32, sample_rate: f32) -> Self {
+        let low = Biquad::butterworth(freq, sample_rate, false);
+        let high = Biquad::butterworth(freq, sample_rate, true);
+        Self { low: [low.clone(), low], high: [high.clone(), high] }
+    }
+
+    /// Returns the (low, high) outputs.
+    fn process(&mut self, input: f64) -> (f64, f64) {
+        let low = self.low.iter_mut().fold(input, |signal, stage| stage.process(signal));
+        let high = self.high.iter_mut().fold(input, |signal, stage| stage.process(signal));
+        (low, high)
+    }
+
+    /// Returns the sum of the low and high outputs, the allpass response of the split.
+    fn allpass(&mut self, input: f64) -> f64 {
+        let (low, high) = self.process(input);
+        low + high
+    }
+
+    fn clear(&mut self) {
+        self.low.iter_mut().chain(self.high.iter_mut()).for_each(Biquad::clear);
+    }
+}
+
+/// Filter state of one channel.
+#[derive(Clone)]
+struct ChannelFilters {
+    /// One split per crossover frequency, each fed by the high output of the previous
+    splits: Vec<LrSplit>,
+    /// Allpasses applied to each band to match the phase shift of the higher crossovers
+    compensation: Vec<Vec<LrSplit>>,
+}
+
+impl ChannelFilters {
+    fn new(freqs: &[f32], sample_rate: f32) -> Self {
+        Self {
+            splits: freqs.iter().map(|&freq| LrSplit::new(freq, sample_rate)).collect(),
+            compensation: (0..freqs.len())
+                .map(|band| freqs[band + 1..].iter().map(|&freq| LrSplit::new(freq, sample_rate)).collect())
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, sample: f32, bands: &mut [Vec<f32>]) {
+        let mut rest = sample as f64;
+        for (band, (split, compensation)) in self.splits.iter_mut().zip(&mut self.compensation).enumerate() {
+            let (low, high) = split.process(rest);
+            rest = high;
+            let low = compensation.iter_mut().fold(low, |signal, allpass| allpass.allpass(signal));
+            bands[band].push(low as f32);
+        }
+        bands[self.splits.len()].push(rest as f32);
+    }
+
+    fn clear(&mut self) {
+        self.splits.iter_mut().chain(self.compensation.iter_mut().flatten()).for_each(LrSplit::clear);
+    }
+}
+
+/// Splits audio into frequency bands with Linkwitz-Riley crossover filters.
+///
+/// `N` crossover frequencies give `N + 1` bands, from the lowest to the highest.
+/// Every band is phase-compensated for the crossovers it doesn't pass through, so
+/// summing all bands gives the input with the allpass phase response of the
+/// crossovers and a flat magnitude response.
+///
+/// The filters keep their state between calls to [`LrCrossover::split`], use
+/// [`LrCrossover::reset`] before processing unrelated audio. For interleaved
+/// multi-channel audio set the channel count with [`LrCrossover::with_channels`];
+/// the bands are then interleaved in the same way as the input.
+#[derive(Clone)]
+pub struct LrCrossover {
+    crossover_freqs: Vec<f32>,
+    sample_rate: f32,
+    filters: RefCell<Vec<ChannelFilters>>,
+    channel: Cell<usize>,
+}
+
+impl LrCrossover {
+    /// Creates a new mono crossover.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_freqs` - Crossover frequencies in Hz, sorted into ascending order.
+    ///   Frequencies outside (0, Nyquist) are dropped.
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(crossover_freqs: &[f32], sample_rate: f32) -> Self {
+        let mut crossover_freqs: Vec<f32> = crossover_freqs
+            .iter()
+            .copied()
+            .filter(|&freq| freq > 0.0 && freq < sample_rate / 2.0)
+            .collect();
+        crossover_freqs.sort_by(f32::total_cmp);
+
+        Self {
+            filters: RefCell::new(vec![ChannelFilters::new(&crossover_freqs, sample_rate)]),
+            crossover_freqs,
+            sample_rate,
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the crossover for interleaved audio with the given number of channels.
+    ///
+    /// This resets the filter state.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let filters = ChannelFilters::new(&self.crossover_freqs, self.sample_rate);
+        self.filters = RefCell::new(vec![filters; channels.max(1)]);
+        self.channel.set(0);
+        self
+    }
+
+    /// Returns the crossover frequencies in ascending order.
+    pub fn crossover_freqs(&self) -> &[f32] {
+        &self.crossover_freqs
+    }
+
+    /// Returns the number of bands, one more than the number of crossover frequencies.
+    pub fn bands(&self) -> usize {
+        self.crossover_freqs.len() + 1
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&self) {
+        self.filters.borrow_mut().iter_mut().for_each(ChannelFilters::clear);
+        self.channel.set(0);
+    }
+
+    /// Splits a buffer into frequency bands.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Audio samples, interleaved when configured for multiple channels
+    ///
+    /// # Returns
+    ///
+    /// One buffer per band from the lowest to the highest, each as long as the input.
+    pub fn split(&self, input: &[f32]) -> Vec<Vec<f32>> {
+        let mut bands = vec![Vec::with_capacity(input.len()); self.bands()];
+        let mut filters = self.filters.borrow_mut();
+        let mut channel = self.channel.get();
+        for &sample in input {
+            filters[channel].process(sample, &mut bands);
+            channel = (channel + 1) % filters.len();
+        }
+        self.channel.set(channel);
+        bands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use crate::process::test_util::rms;
+    use crate::synth::sine;
+    use rstest::*;
+
+    fn sum_bands(bands: &[Vec<f32>]) -> Vec<f32> {
+        (0..bands[0].len()).map(|i| bands.iter().map(|band| band[i]).sum()).collect()
+    }
+
+    #[rstest]
+    #[case(&[1000.0])]
+    #[case(&[200.0, 2000.0])]
+    #[case(&[8000.0, 100.0, 1000.0])]
+    fn test_bands_sum_to_allpass(#[case] freqs: &[f32]) {
+        let crossover = LrCrossover::new(freqs, 48000.0);
+        let mut impulse = vec![0.0f32; 8192];
+        impulse[0] = 1.0;
+        let bands = crossover.split(&impulse);
+        assert_eq!(bands.len(), freqs.len() + 1);
+
+        // The summed impulse response has a flat magnitude response
+        let magnitudes = magnitude_spectrum(&sum_bands(&bands), WindowType::Rectangular);
+        let scale = impulse.len() as f32 / 2.0;
+        for (bin, magnitude) in magnitudes.iter().enumerate().skip(1).take(magnitudes.len() - 2) {
+            assert!((magnitude * scale - 1.0).abs() < 1e-3, "bin {}: {}", bin, magnitude * scale);
+        }
+    }
+
+    #[rstest]
+    fn test_two_way_split() {
+        let sample_rate = 48000.0;
+        let crossover = LrCrossover::new(&[1000.0], sample_rate);
+        let input = sine(100.0, 0.5, 1.0, sample_rate as u32);
+        let bands = crossover.split(&input);
+
+        // Skip the filter settling time
+        let steady = 4800..;
+        let low = rms(&bands[0][steady.clone()]);
+        let high = rms(&bands[1][steady.clone()]);
+        let summed = rms(&sum_bands(&bands)[steady.clone()]);
+        assert!(low > 100.0 * high, "low {}, high {}", low, high);
+        assert!((summed - rms(&input[steady])).abs() < 1e-3);
+    }
+
+    #[rstest]
+    fn test_crossover_point_is_minus_six_db() {
+        let sample_rate = 48000.0;
+        let crossover = LrCrossover::new(&[1000.0], sample_rate);
+        let input = sine(1000.0, 1.0, 1.0, sample_rate as u32);
+        let bands = crossover.split(&input);
+        for band in &bands {
+            let gain_db = 20.0 * (rms(&band[4800..]) / rms(&input[4800..])).log10();
+            assert!((gain_db + 6.02).abs() < 0.05, "gain {} dB", gain_db);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_and_reset() {
+        let crossover = LrCrossover::new(&[500.0], 44100.0).with_channels(2);
+        let left = sine(50.0, 0.5, 0.5, 44100);
+        let right = sine(5000.0, 0.5, 0.5, 44100);
+        let input: Vec<f32> = left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect();
+
+        let bands = crossover.split(&input);
+        let low_left: Vec<f32> = bands[0].iter().step_by(2).copied().collect();
+        let low_right: Vec<f32> = bands[0].iter().skip(1).step_by(2).copied().collect();
+        assert!(rms(&low_left[4410..]) > 0.3);
+        assert!(rms(&low_right[4410..]) < 0.01);
+
+        crossover.reset();
+        assert_eq!(crossover.split(&input), bands);
+    }
+
+    #[rstest]
+    fn test_invalid_frequencies_dropped() {
+        let crossover = LrCrossover::new(&[30000.0, 2000.0, 0.0, 200.0], 48000.0);
+        assert_eq!(crossover.crossover_freqs(), &[200.0, 2000.0]);
+        assert_eq!(crossover.bands(), 3);
+    }
+}
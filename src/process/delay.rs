@@ -0,0 +1,261 @@
+//! Delay (echo) processing node.
+//!
+//! This module provides a feedback delay line: the input is delayed by a fixed time
+//! and fed back into itself, producing a series of decaying echoes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, DelayNode};
+//!
+//! // 250 ms echo with 40% feedback, mixed 30% wet
+//! let node = DelayNode::new(0.25, 0.4, 0.3, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::delayline::DelayLine;
+use super::denormal::flush_denormals;
+use super::node::AudioNode;
+
+/// Largest feedback amount accepted, higher values are clamped to avoid runaway.
+pub const MAX_FEEDBACK: f32 = 0.99;
+
+/// An audio processing node that adds echoes using a feedback delay line.
+///
+/// The node keeps its delay line between calls to `process`, so consecutive blocks
+/// of a stream are processed seamlessly. Use [`DelayNode::reset`] to clear the
+/// echoes before processing unrelated audio.
+///
+/// For interleaved multi-channel audio set the channel count with
+/// [`DelayNode::with_channels`], each channel is then delayed independently.
+///
+/// The echoes are the effect rather than processing delay, so the node reports no
+/// [`latency`](AudioNode::latency_samples) for any mix. Otherwise a host aligning
+/// parallel paths, or a [`DryWetNode`](super::DryWetNode), would delay the dry
+/// signal along with the echoes.
+#[derive(Clone)]
+pub struct DelayNode {
+    feedback: f32,
+    mix: f32,
+    delay_frames: usize,
+    lines: RefCell<Vec<DelayLine>>,
+    channel: Cell<usize>,
+    flush_denormals: Cell<bool>,
+}
+
+impl DelayNode {
+    /// Creates a new mono delay node.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_sec` - Delay time in seconds
+    /// * `feedback` - Amount of the delayed signal fed back into the delay line, in [0.0, 1.0).
+    ///   Values outside this range are clamped to [0.0, `MAX_FEEDBACK`].
+    /// * `mix` - Dry/wet balance, 0.0 is fully dry and 1.0 fully wet. Clamped to [0.0, 1.0].
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(delay_sec: f32, feedback: f32, mix: f32, sample_rate: f32) -> Self {
+        let delay_frames = ((delay_sec * sample_rate).round() as usize).max(1);
+        Self {
+            feedback: feedback.clamp(0.0, MAX_FEEDBACK),
+            mix: mix.clamp(0.0, 1.0),
+            delay_frames,
+            lines: RefCell::new(vec![DelayLine::new(delay_frames)]),
+            channel: Cell::new(0),
+            flush_denormals: Cell::new(true),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the delay line.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.lines = RefCell::new(vec![DelayLine::new(self.delay_frames); channels.max(1)]);
+        self.channel.set(0);
+        self
+    }
+
+    /// Returns the delay time in frames.
+    pub fn delay_frames(&self) -> usize {
+        self.delay_frames
+    }
+
+    /// Returns the feedback amount.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Returns the dry/wet mix.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Clears the delay line, removing any pending echoes.
+    pub fn reset(&self) {
+        self.lines.borrow_mut().iter_mut().for_each(DelayLine::clear);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut lines = self.lines.borrow_mut();
+        let channel = self.channel.get();
+        self.channel.set((channel + 1) % lines.len());
+
+        let line = &mut lines[channel];
+        let delayed = line.read(self.delay_frames as f32);
+        let stored = sample + self.feedback * delayed;
+        line.push(if self.flush_denormals.get() { flush_denormals(stored) } else { stored });
+
+        (1.0 - self.mix) * sample + self.mix * delayed
+    }
+}
+
+impl AudioNode for DelayNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let channels = self.lines.borrow().len();
+        buffer.chunks_mut(channels).for_each(|frame| self.process_frame(frame));
+    }
+
+    fn process_frame(&self, frame: &mut [f32]) {
+        frame.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "delay"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.flush_denormals.set(enabled);
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.lines.borrow().len())
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::test_util::impulse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_impulse_decaying_echoes() {
+        // 10 frame delay at 1 kHz, fully wet
+        let node = DelayNode::new(0.01, 0.5, 1.0, 1000.0);
+        let output = node.process(&impulse(50));
+
+        for (i, &sample) in output.iter().enumerate() {
+            let expected = match i {
+                10 => 1.0,
+                20 => 0.5,
+                30 => 0.25,
+                40 => 0.125,
+                _ => 0.0,
+            };
+            assert!((sample - expected).abs() < 1e-6, "sample {} = {}", i, sample);
+        }
+    }
+
+    #[rstest]
+    fn test_no_feedback_single_copy() {
+        let node = DelayNode::new(0.01, 0.0, 0.5, 1000.0);
+        let output = node.process(&impulse(50));
+
+        assert_eq!(output[0], 0.5);
+        assert_eq!(output[10], 0.5);
+        assert_eq!(output.iter().filter(|&&s| s != 0.0).count(), 2);
+    }
+
+    #[rstest]
+    fn test_feedback_is_clamped() {
+        let node = DelayNode::new(0.01, 1.5, 1.0, 1000.0);
+        assert_eq!(node.feedback(), MAX_FEEDBACK);
+
+        let output = node.process(&impulse(10_000));
+        assert!(output.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[rstest]
+    fn test_channels_delayed_independently() {
+        let node = DelayNode::new(0.002, 0.0, 1.0, 1000.0).with_channels(2);
+        // Impulse on the right channel only
+        let input = vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let output = node.process(&input);
+        assert_eq!(output, vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[rstest]
+    fn test_frame_by_frame_matches_buffer() {
+        let node = DelayNode::new(0.003, 0.5, 0.5, 1000.0).with_channels(2);
+        let input: Vec<f32> = impulse(40).iter().flat_map(|&s| [s, -0.5 * s]).collect();
+        let framed = node.clone();
+        let output = node.process(&input);
+
+        let mut frames = input.clone();
+        frames.chunks_exact_mut(2).for_each(|frame| framed.process_frame(frame));
+        assert_eq!(frames, output);
+        assert_eq!(output[6..8], [0.5, -0.25]);
+    }
+
+    #[rstest]
+    fn test_state_carries_over_and_reset() {
+        let node = DelayNode::new(0.01, 0.0, 1.0, 1000.0);
+        node.process(&impulse(5));
+        let continued = node.process(&[0.0; 10]);
+        assert_eq!(continued[5], 1.0);
+
+        node.process(&impulse(5));
+        node.reset();
+        let after_reset = node.process(&[0.0; 10]);
+        assert!(after_reset.iter().all(|&s| s == 0.0));
+    }
+
+    #[rstest]
+    fn test_decaying_feedback_flushed_to_zero() {
+        // Halving a 1 frame echo reaches the subnormal range after 126 repeats
+        let node = DelayNode::new(0.001, 0.5, 1.0, 1000.0);
+        let output = node.process(&impulse(300));
+
+        assert!(output.iter().all(|s| !s.is_subnormal()));
+        assert_eq!(output[127], f32::MIN_POSITIVE);
+        assert!(output[128..].iter().all(|&s| s == 0.0));
+        let lines = node.lines.borrow();
+        assert!((1..=node.delay_frames()).all(|delay| lines[0].read(delay as f32) == 0.0));
+    }
+
+    #[rstest]
+    fn test_process_methods_and_type() {
+        let node1 = DelayNode::new(0.01, 0.3, 0.5, 1000.0);
+        let node2 = node1.clone();
+        let input = impulse(100);
+
+        let output = node1.process(&input);
+        let mut buffer = input.clone();
+        node2.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+
+        assert_eq!(node1.node_type(), "delay");
+        assert_eq!(node1.latency_samples(), 0);
+        assert_eq!(DelayNode::new(0.01, 0.3, 1.0, 1000.0).latency_samples(), 0);
+        assert_eq!(node1.box_clone().node_type(), "delay");
+    }
+}
@@ -0,0 +1,189 @@
+//! Fractional delay line.
+//!
+//! [`DelayLine`] is the building block of delay-based effects: a ring buffer of past
+//! samples that can be read at any delay, including fractions of a sample. It is not an
+//! [`AudioNode`](super::AudioNode) itself, nodes own one line per channel and decide how
+//! to mix, modulate and feed back what they read.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{DelayInterpolation, DelayLine};
+//!
+//! let mut line = DelayLine::new(480).with_interpolation(DelayInterpolation::Cubic);
+//! for sample in [0.1, 0.2, 0.3, 0.4] {
+//!     // Read before pushing the current sample: 2.5 samples behind it
+//!     let delayed = line.read(2.5);
+//!     line.push(sample);
+//! }
+//! ```
+
+/// How a [`DelayLine`] reads between two stored samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DelayInterpolation {
+    /// Straight line between the two neighbouring samples
+    #[default]
+    Linear,
+    /// 4-point cubic Hermite curve through the surrounding samples, smoother for
+    /// modulated delays at the cost of two more taps
+    Cubic,
+}
+
+/// A mono ring buffer that can be read at fractional delays.
+///
+/// Delays are counted relative to the next sample to be pushed: reading at a delay
+/// of 1.0 returns the sample pushed last, so a node reads its delayed signal first
+/// and then pushes the current input.
+#[derive(Debug, Clone)]
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    write: usize,
+    interpolation: DelayInterpolation,
+}
+
+impl DelayLine {
+    /// Creates an empty delay line with linear interpolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_delay` - Longest delay that can be read, in samples. At least 1.
+    pub fn new(max_delay: usize) -> Self {
+        Self {
+            buffer: vec![0.0; max_delay.max(1) + 1],
+            write: 0,
+            interpolation: DelayInterpolation::Linear,
+        }
+    }
+
+    /// Sets how the line reads between two stored samples.
+    pub fn with_interpolation(mut self, interpolation: DelayInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Returns the longest delay that can be read, in samples.
+    pub fn max_delay(&self) -> usize {
+        self.buffer.len() - 1
+    }
+
+    /// Returns the interpolation used for fractional delays.
+    pub fn interpolation(&self) -> DelayInterpolation {
+        self.interpolation
+    }
+
+    /// Sets all stored samples to zero.
+    pub fn clear(&mut self) {
+        self.buffer.iter_mut().for_each(|s| *s = 0.0);
+        self.write = 0;
+    }
+
+    /// Stores a sample, dropping the oldest one.
+    pub fn push(&mut self, x: f32) {
+        self.buffer[self.write] = x;
+        self.write = (self.write + 1) % self.buffer.len();
+    }
+
+    /// Reads the signal at a delay behind the next sample to be pushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_samples` - Delay in samples, clamped to [1.0, `max_delay`]. Whole
+    ///   numbers return a stored sample exactly.
+    ///
+    /// # Returns
+    ///
+    /// The stored or interpolated sample at that delay.
+    pub fn read(&self, delay_samples: f32) -> f32 {
+        let delay = delay_samples.clamp(1.0, self.max_delay() as f32);
+        let whole = delay.floor() as usize;
+        let fraction = delay - whole as f32;
+        if fraction == 0.0 {
+            return self.tap(whole);
+        }
+
+        match self.interpolation {
+            DelayInterpolation::Linear => {
+                let (x0, x1) = (self.tap(whole), self.tap(whole + 1));
+                x0 + fraction * (x1 - x0)
+            }
+            DelayInterpolation::Cubic => {
+                let x0 = self.tap(whole);
+                let x1 = self.tap(whole + 1);
+                // Taps outside the stored range are extrapolated in a straight line
+                let newer = if whole > 1 { self.tap(whole - 1) } else { 2.0 * x0 - x1 };
+                let older = if whole + 2 <= self.buffer.len() { self.tap(whole + 2) } else { 2.0 * x1 - x0 };
+
+                let c1 = 0.5 * (x1 - newer);
+                let c2 = newer - 2.5 * x0 + 2.0 * x1 - 0.5 * older;
+                let c3 = 0.5 * (older - newer) + 1.5 * (x0 - x1);
+                ((c3 * fraction + c2) * fraction + c1) * fraction + x0
+            }
+        }
+    }
+
+    /// Returns the sample pushed `delay` pushes ago, for `delay` in [1, buffer length].
+    fn tap(&self, delay: usize) -> f32 {
+        let len = self.buffer.len();
+        self.buffer[(self.write + len - delay) % len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// A line holding the ramp 0, 1, ..., 9, most recent last.
+    fn ramp(interpolation: DelayInterpolation) -> DelayLine {
+        let mut line = DelayLine::new(8).with_interpolation(interpolation);
+        (0..10).for_each(|i| line.push(i as f32));
+        line
+    }
+
+    #[rstest]
+    #[case(DelayInterpolation::Linear)]
+    #[case(DelayInterpolation::Cubic)]
+    fn test_integer_delays_exact(#[case] interpolation: DelayInterpolation) {
+        let line = ramp(interpolation);
+        for delay in 1..=8 {
+            assert_eq!(line.read(delay as f32), (10 - delay) as f32);
+        }
+        // Clamped to the available range
+        assert_eq!(line.read(0.0), 9.0);
+        assert_eq!(line.read(20.0), 2.0);
+    }
+
+    #[rstest]
+    #[case(DelayInterpolation::Linear)]
+    #[case(DelayInterpolation::Cubic)]
+    fn test_half_sample_delays_midpoint(#[case] interpolation: DelayInterpolation) {
+        let line = ramp(interpolation);
+        for delay in 1..8 {
+            let expected = (10 - delay) as f32 - 0.5;
+            assert!((line.read(delay as f32 + 0.5) - expected).abs() < 1e-6, "delay {}.5", delay);
+        }
+    }
+
+    #[rstest]
+    fn test_cubic_follows_curve() {
+        // Samples of x², the cubic curve lands closer to the parabola than the straight line
+        let mut linear = DelayLine::new(8);
+        let mut cubic = DelayLine::new(8).with_interpolation(DelayInterpolation::Cubic);
+        for i in 0..8 {
+            linear.push((i * i) as f32);
+            cubic.push((i * i) as f32);
+        }
+        // 3.5 samples behind the next push is x = 4.5
+        let exact = 4.5 * 4.5;
+        assert!((cubic.read(3.5) - exact).abs() < (linear.read(3.5) - exact).abs());
+    }
+
+    #[rstest]
+    fn test_clear() {
+        let mut line = ramp(DelayInterpolation::Linear);
+        line.clear();
+        assert!((1..=8).all(|delay| line.read(delay as f32) == 0.0));
+        assert_eq!(line.max_delay(), 8);
+        assert_eq!(DelayLine::new(0).max_delay(), 1);
+    }
+}
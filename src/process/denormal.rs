@@ -0,0 +1,61 @@
+//! Denormal number handling for feedback paths.
+//!
+//! A signal decaying in a feedback loop (a delay line, a reverb tail, an IIR filter)
+//! ends up in the subnormal range of floating point numbers, where arithmetic is
+//! many times slower on most CPUs. Nodes with feedback flush such values to zero
+//! before storing them, which can be switched off per node or per chain with
+//! [`AudioNode::set_flush_denormals`](super::AudioNode::set_flush_denormals).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::flush_denormals;
+//!
+//! assert_eq!(flush_denormals(1e-40), 0.0);
+//! assert_eq!(flush_denormals(0.5), 0.5);
+//! ```
+
+/// Flushes a subnormal sample to zero.
+///
+/// # Arguments
+///
+/// * `x` - Sample to flush
+///
+/// # Returns
+///
+/// 0.0 if `x` is smaller in magnitude than the smallest normal f32, otherwise `x` unchanged.
+#[inline]
+pub fn flush_denormals(x: f32) -> f32 {
+    if x.abs() < f32::MIN_POSITIVE {
+        0.0
+    } else {
+        x
+    }
+}
+
+/// Flushes f64 filter state that can't produce a normal f32 sample to zero.
+#[inline]
+pub(super) fn flush_denormals_f64(x: f64) -> f64 {
+    if x.abs() < f32::MIN_POSITIVE as f64 {
+        0.0
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(1e-40, 0.0)]
+    #[case(-1e-40, 0.0)]
+    #[case(f32::MIN_POSITIVE, f32::MIN_POSITIVE)]
+    #[case(-0.25, -0.25)]
+    #[case(0.0, 0.0)]
+    fn test_flush_denormals(#[case] x: f32, #[case] expected: f32) {
+        assert_eq!(flush_denormals(x), expected);
+        assert_eq!(flush_denormals_f64(x as f64), expected as f64);
+    }
+}
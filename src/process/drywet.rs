@@ -0,0 +1,221 @@
+//! Dry/wet blend for any node.
+//!
+//! Many effects sound best blended with the unprocessed signal, e.g. parallel
+//! compression or a subtle saturation. [`DryWetNode`] wraps any [`AudioNode`] and
+//! mixes its output with the original input, so individual nodes don't need a mix
+//! parameter of their own.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, DryWetNode, ShapeFunction, WaveshaperNode};
+//!
+//! // 30% saturated, 70% dry
+//! let node = DryWetNode::new(WaveshaperNode::new(ShapeFunction::Tanh, 4.0), 0.3);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use super::node::AudioNode;
+
+/// Blends the output of a wrapped node (wet) with its input (dry).
+///
+/// The dry signal is delayed by the wrapped node's
+/// [`latency_samples`](AudioNode::latency_samples), so both paths stay aligned and
+/// blending doesn't cause comb filtering. For interleaved multi-channel audio set the
+/// channel count with [`DryWetNode::with_channels`], as the latency is counted in
+/// frames.
+///
+/// The wet path must produce as many samples as it receives; if it doesn't (e.g. it
+/// changes the channel count) the output is truncated to the shorter length.
+pub struct DryWetNode {
+    node: Box<dyn AudioNode>,
+    mix: f32,
+    channels: usize,
+    /// Delays the dry signal by the latency of the wet path
+    dry_delay: RefCell<VecDeque<f32>>,
+}
+
+impl DryWetNode {
+    /// Creates a dry/wet blend around a node, for mono audio.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node producing the wet signal
+    /// * `mix` - Dry/wet balance, 0.0 is fully dry and 1.0 fully wet. Clamped to [0.0, 1.0].
+    pub fn new<T: AudioNode + 'static>(node: T, mix: f32) -> Self {
+        let wrapper = Self {
+            node: Box::new(node),
+            mix: mix.clamp(0.0, 1.0),
+            channels: 1,
+            dry_delay: RefCell::new(VecDeque::new()),
+        };
+        wrapper.reset();
+        wrapper
+    }
+
+    /// Configures the dry path for interleaved audio with the given number of channels.
+    ///
+    /// This resets the dry delay line. The wrapped node must be configured for the
+    /// same channel count.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Returns the dry/wet mix.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Sets the dry/wet mix, clamped to [0.0, 1.0].
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix.clamp(0.0, 1.0);
+    }
+
+    /// Returns the wrapped node.
+    pub fn node(&self) -> &dyn AudioNode {
+        self.node.as_ref()
+    }
+
+    /// Clears the dry delay line.
+    ///
+    /// The wrapped node keeps its state, reset it before wrapping if needed.
+    pub fn reset(&self) {
+        let mut dry_delay = self.dry_delay.borrow_mut();
+        dry_delay.clear();
+        dry_delay.resize(self.node.latency_samples() * self.channels, 0.0);
+    }
+}
+
+impl Clone for DryWetNode {
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node.box_clone(),
+            mix: self.mix,
+            channels: self.channels,
+            dry_delay: self.dry_delay.clone(),
+        }
+    }
+}
+
+impl AudioNode for DryWetNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let wet = self.node.process(input);
+        let mut dry_delay = self.dry_delay.borrow_mut();
+        input
+            .iter()
+            .zip(wet)
+            .map(|(&sample, wet)| {
+                dry_delay.push_back(sample);
+                let dry = dry_delay.pop_front().unwrap();
+                (1.0 - self.mix) * dry + self.mix * wet
+            })
+            .collect()
+    }
+
+    /// The blend is written to the start of the buffer. If it is shorter than the
+    /// buffer the remaining samples are set to silence.
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let mix = self.process(buffer);
+        let len = mix.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&mix[..len]);
+        buffer[len..].iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "dry_wet"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        self.node.input_channels()
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.node.set_flush_denormals(enabled);
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    /// Both paths are delayed by the latency of the wrapped node.
+    fn latency_samples(&self) -> usize {
+        self.node.latency_samples()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{GainNode, LimiterNode};
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[fixture]
+    fn test_input() -> Vec<f32> {
+        vec![1.0, 0.5, -0.5, -1.0, 0.25]
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(0.5)]
+    #[case(1.0)]
+    fn test_gain_blend(test_input: Vec<f32>, #[case] mix: f32) {
+        let node = DryWetNode::new(GainNode::new(6.0), mix);
+        let output = node.process(&test_input);
+        for (out, dry) in output.iter().zip(&test_input) {
+            let wet = dry * 1.995262;
+            let expected = match mix {
+                0.0 => *dry,
+                1.0 => wet,
+                _ => (dry + wet) / 2.0,
+            };
+            assert!((out - expected).abs() < 1e-5, "{} vs {}", out, expected);
+        }
+
+        let mut buffer = test_input.clone();
+        DryWetNode::new(GainNode::new(6.0), mix).process_in_place(&mut buffer);
+        assert_eq!(buffer, output);
+    }
+
+    #[rstest]
+    fn test_dry_aligned_with_latency() {
+        // Below the threshold the limiter only delays the signal, so with an aligned
+        // dry path the blend is the delayed input
+        let input = sine(1000.0, 0.1, 0.1, 48000);
+        let limiter = LimiterNode::new(-1.0, 0.1, 0.005, 48000.0);
+        let latency = limiter.latency_samples();
+        let node = DryWetNode::new(limiter, 0.5);
+        assert_eq!(node.latency_samples(), latency);
+
+        let output: Vec<f32> = input.chunks(100).flat_map(|block| node.process(block)).collect();
+        assert!(output[..latency].iter().all(|&s| s == 0.0));
+        for (out, expected) in output[latency..].iter().zip(&input) {
+            assert!((out - expected).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    fn test_channels() {
+        // Stereo limiter below its threshold: the latency is 48 frames, i.e. 96 samples
+        let limiter = LimiterNode::new(0.0, 0.1, 0.001, 48000.0).with_channels(2);
+        let node = DryWetNode::new(limiter, 0.25).with_channels(2);
+        let input: Vec<f32> = (0..400).map(|i| i as f32 / 1000.0).collect();
+        let output = node.process(&input);
+        assert!(output[..96].iter().all(|&s| s == 0.0));
+        for (out, expected) in output[96..].iter().zip(&input) {
+            assert!((out - expected).abs() < 1e-6);
+        }
+
+        let mut node = node.clone();
+        node.set_mix(2.0);
+        assert_eq!(node.mix(), 1.0);
+        assert_eq!(node.node().node_type(), "limiter");
+        assert_eq!(node.box_clone().node_type(), "dry_wet");
+    }
+}
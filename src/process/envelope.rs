@@ -0,0 +1,189 @@
+//! Envelope follower for dynamics processing.
+//!
+//! Dynamics processors such as limiters, compressors and gates react to the level of
+//! the signal rather than to individual samples. [`EnvelopeFollower`] smooths the
+//! rectified signal with separate attack and release time constants: the envelope
+//! rises with the attack time when the level goes up and falls with the release time
+//! when it goes down.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{DetectionMode, EnvelopeFollower};
+//!
+//! // 5 ms attack, 100 ms release, following the RMS level
+//! let mut follower = EnvelopeFollower::new(0.005, 0.1, 48000.0).with_mode(DetectionMode::Rms);
+//!
+//! let input = vec![0.5f32; 4800];
+//! for &sample in &input {
+//!     let level = follower.process_sample(sample);
+//! }
+//! println!("Level: {:.3}", follower.envelope());
+//! ```
+
+/// How an [`EnvelopeFollower`] measures the level of the signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetectionMode {
+    /// Follows the absolute sample value, reacting to every peak
+    #[default]
+    Peak,
+    /// Follows the mean square and returns its root, which tracks perceived loudness
+    /// more closely than peaks
+    Rms,
+}
+
+/// One-pole envelope follower with separate attack and release times.
+///
+/// The time constants are the time the envelope needs to cover 63% of a step in
+/// level. An attack or release time of 0.0 makes the envelope jump to the level
+/// immediately.
+#[derive(Debug, Clone)]
+pub struct EnvelopeFollower {
+    attack_coeff: f32,
+    release_coeff: f32,
+    /// Smoothed level, squared in RMS mode
+    envelope: f32,
+    mode: DetectionMode,
+}
+
+impl EnvelopeFollower {
+    /// Creates a new peak envelope follower.
+    ///
+    /// # Arguments
+    ///
+    /// * `attack_sec` - Time constant for the envelope to rise, 0.0 for an instantaneous attack
+    /// * `release_sec` - Time constant for the envelope to fall, 0.0 for an instantaneous release
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(attack_sec: f32, release_sec: f32, sample_rate: f32) -> Self {
+        Self {
+            attack_coeff: time_constant_coeff(attack_sec, sample_rate),
+            release_coeff: time_constant_coeff(release_sec, sample_rate),
+            envelope: 0.0,
+            mode: DetectionMode::Peak,
+        }
+    }
+
+    /// Sets the detection mode.
+    pub fn with_mode(mut self, mode: DetectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the detection mode.
+    pub fn mode(&self) -> DetectionMode {
+        self.mode
+    }
+
+    /// Returns the smoothing coefficient applied while the level rises.
+    pub fn attack_coeff(&self) -> f32 {
+        self.attack_coeff
+    }
+
+    /// Returns the smoothing coefficient applied while the level falls.
+    pub fn release_coeff(&self) -> f32 {
+        self.release_coeff
+    }
+
+    /// Returns the current envelope as a linear level.
+    pub fn envelope(&self) -> f32 {
+        match self.mode {
+            DetectionMode::Peak => self.envelope,
+            DetectionMode::Rms => self.envelope.sqrt(),
+        }
+    }
+
+    /// Resets the envelope to silence.
+    pub fn reset(&mut self) {
+        self.envelope = 0.0;
+    }
+
+    /// Feeds a sample to the follower.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input sample, only its magnitude matters
+    ///
+    /// # Returns
+    ///
+    /// The updated envelope as a linear level, see [`EnvelopeFollower::envelope`].
+    pub fn process_sample(&mut self, x: f32) -> f32 {
+        let level = match self.mode {
+            DetectionMode::Peak => x.abs(),
+            DetectionMode::Rms => x * x,
+        };
+        let coeff = if level > self.envelope { self.attack_coeff } else { self.release_coeff };
+        self.envelope = coeff * self.envelope + (1.0 - coeff) * level;
+        self.envelope()
+    }
+}
+
+/// One-pole coefficient reaching 63% of a step after `time_sec`, 0.0 for an instant response.
+fn time_constant_coeff(time_sec: f32, sample_rate: f32) -> f32 {
+    if time_sec > 0.0 {
+        (-1.0 / (sample_rate * time_sec)).exp()
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(DetectionMode::Peak)]
+    #[case(DetectionMode::Rms)]
+    fn test_attack_and_release_time_constants(#[case] mode: DetectionMode) {
+        // 10 ms attack and 50 ms release at 10 kHz: 100 and 500 samples
+        let mut follower = EnvelopeFollower::new(0.01, 0.05, 10000.0).with_mode(mode);
+        let rise: Vec<f32> = (0..500).map(|_| follower.process_sample(1.0)).collect();
+        assert!(rise.windows(2).all(|w| w[1] >= w[0]));
+        let fall: Vec<f32> = (0..2500).map(|_| follower.process_sample(0.0)).collect();
+        assert!(fall.windows(2).all(|w| w[1] <= w[0]));
+
+        // After one time constant 63% of the step is covered. In RMS mode this holds
+        // for the mean square, the returned level is its root.
+        let rms = mode == DetectionMode::Rms;
+        let to_level = |square: f32| if rms { square.sqrt() } else { square };
+        let to_square = |level: f32| if rms { level * level } else { level };
+        assert!((rise[99] - to_level(0.632)).abs() < 0.01, "attack {}", rise[99]);
+        assert!((rise[499] - 1.0).abs() < 0.01);
+        let released = to_level(to_square(rise[499]) * 0.368);
+        assert!((fall[499] - released).abs() < 0.01, "release {}", fall[499]);
+        assert!(fall[2499] < 0.1);
+    }
+
+    #[rstest]
+    fn test_rms_and_peak_of_sine() {
+        let sine = sine(100.0, 0.8, 1.0, 48000);
+
+        // An instant attack with a slow release holds the peaks
+        let mut peak = EnvelopeFollower::new(0.0, 1.0, 48000.0);
+        sine.iter().for_each(|&s| { peak.process_sample(s); });
+        assert!(peak.envelope() > 0.79 && peak.envelope() <= 0.8, "peak {}", peak.envelope());
+
+        // With slow ballistics the peak follower averages the rectified sine (2/π of
+        // the amplitude), the RMS follower settles at 1/√2 of it
+        let mut average = EnvelopeFollower::new(0.05, 0.05, 48000.0);
+        let mut rms = EnvelopeFollower::new(0.05, 0.05, 48000.0).with_mode(DetectionMode::Rms);
+        sine.iter().for_each(|&s| {
+            average.process_sample(s);
+            rms.process_sample(s);
+        });
+        assert!((average.envelope() - 0.8 * 2.0 / std::f32::consts::PI).abs() < 0.02);
+        assert!((rms.envelope() - 0.8 * std::f32::consts::FRAC_1_SQRT_2).abs() < 0.02);
+    }
+
+    #[rstest]
+    fn test_instant_and_reset() {
+        let mut follower = EnvelopeFollower::new(0.0, 0.0, 48000.0);
+        assert_eq!(follower.mode(), DetectionMode::Peak);
+        assert_eq!(follower.attack_coeff(), 0.0);
+        assert_eq!(follower.process_sample(-0.7), 0.7);
+        assert_eq!(follower.process_sample(0.2), 0.2);
+        follower.reset();
+        assert_eq!(follower.envelope(), 0.0);
+    }
+}
@@ -17,7 +17,8 @@
 //! let output = node.process(&input);  // Samples will be amplified by ~1.995
 //! ```
 
-use super::node::AudioNode;
+use std::cell::Cell;
+use super::node::{for_each_chunk, AudioNode};
 
 /// An audio processing node that applies gain adjustment in decibels.
 /// 
@@ -43,6 +44,16 @@ use super::node::AudioNode;
 #[derive(Clone)]
 pub struct GainNode {
     db: f32,
+    channel_db: Option<Vec<f32>>,
+    channels: Option<usize>,
+    smoothing: Option<Smoothing>,
+}
+
+/// State of a smoothed gain: the linear gain ramps toward the target per frame.
+#[derive(Clone)]
+struct Smoothing {
+    coeff: f32,
+    current: Cell<f32>,
 }
 
 impl GainNode {
@@ -60,46 +71,278 @@ impl GainNode {
     /// let node = GainNode::new(6.0);  // +6 dB gain
     /// ```
     pub fn new(db: f32) -> Self {
-        Self { db }
+        Self { db, channel_db: None, channels: None, smoothing: None }
+    }
+
+    /// Creates a new gain node from a linear multiplier.
+    /// 
+    /// The gain is stored in dB, so [`GainNode::db`] returns `20 * log10(multiplier)`.
+    /// A multiplier of 0.0 mutes the signal and maps to negative infinity dB.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `multiplier` - Linear gain, 1.0 for unity. Negative values are clamped to 0.0.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use sonex::process::GainNode;
+    /// 
+    /// let node = GainNode::from_linear(0.5);  // ≈ -6 dB
+    /// ```
+    pub fn from_linear(multiplier: f32) -> Self {
+        Self::new(linear_to_db(multiplier))
+    }
+
+    /// Creates a new gain node that smoothly ramps to new gain values.
+    /// 
+    /// When the gain is changed with [`GainNode::set_db`] the applied linear gain
+    /// follows the new value with a one-pole smoother instead of jumping, which avoids
+    /// clicks ("zipper noise") when adjusting the gain of live audio. The gain covers
+    /// 99% of a change within `smoothing_time_sec`.
+    /// 
+    /// Smoothing makes the node stateful, use [`GainNode::reset`] to jump straight to
+    /// the target gain. The smoother advances once per frame and applies the same gain
+    /// to all samples of the frame, set the channel count of interleaved audio with
+    /// [`GainNode::with_channels`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `db` - Initial gain in decibels
+    /// * `smoothing_time_sec` - Time in seconds for the gain to settle after a change
+    /// * `sample_rate` - Sample rate in Hz
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use sonex::process::GainNode;
+    /// 
+    /// let mut node = GainNode::with_smoothing(0.0, 0.05, 48000.0);
+    /// node.set_db(-12.0);  // Ramps down over 50 ms
+    /// ```
+    pub fn with_smoothing(db: f32, smoothing_time_sec: f32, sample_rate: f32) -> Self {
+        let smoothing_samples = (smoothing_time_sec * sample_rate).max(1.0);
+        Self {
+            db,
+            channel_db: None,
+            channels: None,
+            smoothing: Some(Smoothing {
+                coeff: 0.01_f32.powf(1.0 / smoothing_samples),
+                current: Cell::new(10.0_f32.powf(db / 20.0)),
+            }),
+        }
+    }
+
+    /// Creates a new gain node with a separate dB value for each channel.
+    /// 
+    /// The gains are applied to the interleaved positions of their channel, e.g.
+    /// `gains_db[0]` to the left and `gains_db[1]` to the right channel of stereo audio.
+    /// If fewer gains than channels are given the remaining channels use 0 dB, extra
+    /// gains are ignored. A trailing partial frame in the processed buffer is passed
+    /// through unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `gains_db` - Gain in decibels for each channel
+    /// * `channels` - Number of interleaved audio channels
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use sonex::process::GainNode;
+    /// 
+    /// // -3 dB on the left, +2 dB on the right channel
+    /// let node = GainNode::per_channel(vec![-3.0, 2.0], 2);
+    /// ```
+    pub fn per_channel(gains_db: Vec<f32>, channels: usize) -> Self {
+        let mut gains_db = gains_db;
+        gains_db.resize(channels.max(1), 0.0);
+        Self { db: 0.0, channel_db: Some(gains_db), channels: Some(channels.max(1)), smoothing: None }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    /// 
+    /// A smoothed node then ramps its gain once per frame, so the ramp takes the
+    /// same time regardless of the channel count. The per-channel gains of a node
+    /// created with [`GainNode::per_channel`] are padded with 0 dB or truncated to
+    /// the new channel count.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use sonex::process::GainNode;
+    /// 
+    /// let node = GainNode::with_smoothing(0.0, 0.05, 48000.0).with_channels(2);
+    /// ```
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let channels = channels.max(1);
+        if let Some(channel_db) = &mut self.channel_db {
+            channel_db.resize(channels, 0.0);
+        }
+        self.channels = Some(channels);
+        self
     }
     
     /// Returns the current gain setting in dB.
+    /// 
+    /// For a per-channel gain node this is the uniform gain (0 dB), see
+    /// [`GainNode::channel_db`] for the individual channel gains.
     pub fn db(&self) -> f32 {
         self.db
     }
+
+    /// Returns the current gain setting as a linear multiplier.
+    pub fn linear(&self) -> f32 {
+        10.0_f32.powf(self.db / 20.0)
+    }
+
+    /// Returns the gain of each channel in dB, or None if the gain is uniform.
+    pub fn channel_db(&self) -> Option<&[f32]> {
+        self.channel_db.as_deref()
+    }
     
     /// Sets a new gain value in dB.
     /// 
+    /// This applies the gain uniformly to all channels, replacing any per-channel gains.
+    /// 
     /// # Arguments
     /// 
     /// * `db` - New gain value in decibels
     pub fn set_db(&mut self, db: f32) {
         self.db = db;
+        self.channel_db = None;
+    }
+
+    /// Sets a new gain value as a linear multiplier.
+    /// 
+    /// Like [`GainNode::set_db`] this replaces any per-channel gains, and a smoothed
+    /// node ramps to the new gain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `multiplier` - Linear gain, 1.0 for unity. Negative values are clamped to 0.0,
+    ///   which maps to negative infinity dB.
+    pub fn set_linear(&mut self, multiplier: f32) {
+        self.set_db(linear_to_db(multiplier));
+    }
+
+    /// Jumps the smoothed gain straight to the current target.
+    /// 
+    /// This has no effect on nodes created without smoothing.
+    pub fn reset(&self) {
+        if let Some(smoothing) = &self.smoothing {
+            smoothing.current.set(10.0_f32.powf(self.db / 20.0));
+        }
     }
 }
 
 impl AudioNode for GainNode {
     fn process(&self, input: &[f32]) -> Vec<f32> {
-        let linear_gain = 10.0_f32.powf(self.db / 20.0);
-        input.iter()
-            .map(|&sample| sample * linear_gain)
-            .collect()
+        if self.channel_db.is_some() || self.smoothing.is_some() {
+            let mut output = input.to_vec();
+            self.process_in_place(&mut output);
+            return output;
+        }
+        let mut output = input.to_vec();
+        apply_gain(&mut output, 10.0_f32.powf(self.db / 20.0));
+        output
     }
     
     fn process_in_place(&self, buffer: &mut [f32]) {
+        if let Some(channel_db) = &self.channel_db {
+            let linear_gains: Vec<f32> = channel_db.iter()
+                .map(|db| 10.0_f32.powf(db / 20.0))
+                .collect();
+            buffer.chunks_exact_mut(linear_gains.len()).for_each(|frame| {
+                frame.iter_mut()
+                    .zip(linear_gains.iter())
+                    .for_each(|(sample, gain)| *sample *= gain);
+            });
+            return;
+        }
         let linear_gain = 10.0_f32.powf(self.db / 20.0);
-        buffer.iter_mut().for_each(|sample| {
-            *sample *= linear_gain;
-        });
+        if let Some(smoothing) = &self.smoothing {
+            let mut current = smoothing.current.get();
+            buffer.chunks_mut(self.channels.unwrap_or(1)).for_each(|frame| {
+                current = linear_gain + smoothing.coeff * (current - linear_gain);
+                frame.iter_mut().for_each(|sample| *sample *= current);
+            });
+            smoothing.current.set(current);
+            return;
+        }
+        apply_gain(buffer, linear_gain);
     }
     
     fn node_type(&self) -> &'static str {
         "gain"
     }
     
+    /// Known only for nodes configured with a channel count, otherwise any layout works.
+    fn input_channels(&self) -> Option<usize> {
+        self.channels
+    }
+
     fn box_clone(&self) -> Box<dyn AudioNode> {
         Box::new(self.clone())
     }
+
+    fn is_stateless(&self) -> bool {
+        self.smoothing.is_none()
+    }
+
+    fn process_parallel(&self, buffer: &mut [f32]) {
+        if self.smoothing.is_some() {
+            self.process_in_place(buffer);
+            return;
+        }
+        let linear_gains: Vec<f32> = match &self.channel_db {
+            Some(channel_db) => channel_db.iter().map(|db| 10.0_f32.powf(db / 20.0)).collect(),
+            None => {
+                let linear_gain = 10.0_f32.powf(self.db / 20.0);
+                for_each_chunk(buffer, 1, |chunk| apply_gain(chunk, linear_gain));
+                return;
+            }
+        };
+        for_each_chunk(buffer, linear_gains.len(), |chunk| {
+            chunk.chunks_exact_mut(linear_gains.len()).for_each(|frame| {
+                frame.iter_mut()
+                    .zip(linear_gains.iter())
+                    .for_each(|(sample, gain)| *sample *= gain);
+            });
+        });
+    }
+}
+
+/// Converts a linear multiplier to dB, negative infinity for silence.
+fn linear_to_db(multiplier: f32) -> f32 {
+    20.0 * multiplier.max(0.0).log10()
+}
+
+/// Multiplies every sample by a linear gain.
+/// 
+/// With the `simd` feature enabled the samples are processed 8 lanes at a time,
+/// followed by a scalar loop over the remaining samples.
+#[cfg(feature = "simd")]
+fn apply_gain(buffer: &mut [f32], linear_gain: f32) {
+    use wide::f32x8;
+
+    let gain = f32x8::splat(linear_gain);
+    let mut lanes = buffer.chunks_exact_mut(8);
+    for chunk in &mut lanes {
+        let scaled = f32x8::new(chunk.try_into().unwrap()) * gain;
+        chunk.copy_from_slice(&scaled.to_array());
+    }
+    apply_gain_scalar(lanes.into_remainder(), linear_gain);
+}
+
+/// Multiplies every sample by a linear gain.
+#[cfg(not(feature = "simd"))]
+fn apply_gain(buffer: &mut [f32], linear_gain: f32) {
+    apply_gain_scalar(buffer, linear_gain);
+}
+
+fn apply_gain_scalar(buffer: &mut [f32], linear_gain: f32) {
+    buffer.iter_mut().for_each(|sample| *sample *= linear_gain);
 }
 
 /// Convenience function to apply gain adjustment to samples.
@@ -132,7 +375,40 @@ pub fn gain_db_in_place(samples: &mut [f32], db: f32) {
     node.process_in_place(samples);
 }
 
+/// Applies gain in-place to a range of frames only.
+/// 
+/// All channels of the frames in `[start_frame, end_frame)` are scaled, every other
+/// sample is left untouched. The range is clamped to the whole frames in the buffer,
+/// so a range reaching past the end stops at the last frame and an empty or
+/// reversed range changes nothing.
+/// 
+/// # Arguments
+/// 
+/// * `samples` - Mutable slice of interleaved audio samples to adjust
+/// * `channels` - Number of interleaved channels
+/// * `start_frame` - First frame to adjust
+/// * `end_frame` - Frame after the last frame to adjust
+/// * `db` - Gain adjustment in decibels
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use sonex::process::gain_db_range;
+/// 
+/// // Duck one second of stereo audio at 48 kHz, starting at 2 s, by 12 dB
+/// let mut samples = vec![0.5f32; 48000 * 2 * 5];
+/// gain_db_range(&mut samples, 2, 96000, 144000, -12.0);
+/// ```
+pub fn gain_db_range(samples: &mut [f32], channels: usize, start_frame: usize, end_frame: usize, db: f32) {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let end_frame = end_frame.min(frames);
+    let start_frame = start_frame.min(end_frame);
+    apply_gain(&mut samples[start_frame * channels..end_frame * channels], 10.0_f32.powf(db / 20.0));
+}
+
 #[cfg(test)]
+#[allow(clippy::legacy_numeric_constants)]
 mod tests {
     use super::*;
     use rstest::*;
@@ -202,6 +478,30 @@ mod tests {
         }
     }
 
+    #[rstest]
+    fn test_from_linear_matches_db(test_input: Vec<f32>) {
+        let node = GainNode::from_linear(2.0);
+        assert!((node.db() - 6.0206).abs() < 1e-4);
+        assert!((node.linear() - 2.0).abs() < 1e-6);
+        let expected = GainNode::new(6.0206).process(&test_input);
+        for (actual, expected) in node.process(&test_input).iter().zip(&expected) {
+            assert!((actual - expected).abs() < 1e-4);
+        }
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(-1.0)]
+    fn test_linear_zero_mutes(test_input: Vec<f32>, #[case] multiplier: f32) {
+        let mut node = GainNode::from_linear(multiplier);
+        assert_eq!(node.db(), f32::NEG_INFINITY);
+        assert!(node.process(&test_input).iter().all(|&s| s == 0.0));
+
+        node.set_linear(0.5);
+        assert!((node.db() + 6.0206).abs() < 1e-4);
+        assert!((node.process(&[1.0])[0] - 0.5).abs() < 1e-6);
+    }
+
     #[rstest]
     fn test_convenience_functions(test_input: Vec<f32>) {
         let db = 6.0;
@@ -213,6 +513,151 @@ mod tests {
         assert_eq!(output1, input2);
     }
 
+    #[rstest]
+    fn test_per_channel_stereo() {
+        let node = GainNode::per_channel(vec![-6.0, 6.0], 2);
+        let input = vec![1.0, 1.0, 0.5, -0.5];
+        let output = node.process(&input);
+
+        let expected = [0.501187, 1.995262, 0.250594, -0.997631];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 0.0001);
+        }
+
+        let mut buffer = input.clone();
+        node.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+    }
+
+    #[rstest]
+    fn test_per_channel_partial_frame() {
+        let node = GainNode::per_channel(vec![-6.0, 6.0], 2);
+        let output = node.process(&[1.0, 1.0, 1.0]);
+        assert!((output[0] - 0.501187).abs() < 0.0001);
+        assert!((output[1] - 1.995262).abs() < 0.0001);
+        assert_eq!(output[2], 1.0);
+    }
+
+    #[rstest]
+    fn test_per_channel_settings() {
+        let mut node = GainNode::per_channel(vec![3.0], 2);
+        assert_eq!(node.channel_db(), Some(&[3.0, 0.0][..]));
+        node = node.with_channels(3);
+        assert_eq!(node.channel_db(), Some(&[3.0, 0.0, 0.0][..]));
+
+        node.set_db(-3.0);
+        assert_eq!(node.channel_db(), None);
+        assert_eq!(node.db(), -3.0);
+    }
+
+    #[rstest]
+    fn test_smoothing_ramps_to_target() {
+        // 10 ms at 1 kHz = 10 samples
+        let mut node = GainNode::with_smoothing(0.0, 0.01, 1000.0);
+        assert_eq!(node.process(&[1.0; 4]), vec![1.0; 4]);
+
+        node.set_db(-40.0);
+        let output = node.process(&[1.0; 20]);
+        let target = 0.01;
+
+        // No jump: the first sample is still close to the old gain
+        assert!(output[0] > 0.5);
+        assert!(output.windows(2).all(|w| w[1] < w[0]));
+        // Within the smoothing time 99% of the change is covered
+        assert!((output[9] - target) <= 0.01 * (1.0 - target) + 1e-6);
+        assert!((output[19] - target).abs() < 0.001);
+    }
+
+    #[rstest]
+    fn test_smoothing_per_frame() {
+        let mut mono = GainNode::with_smoothing(0.0, 0.01, 1000.0);
+        let mut stereo = GainNode::with_smoothing(0.0, 0.01, 1000.0).with_channels(2);
+        mono.set_db(-40.0);
+        stereo.set_db(-40.0);
+
+        let ramp = mono.process(&[1.0; 20]);
+        let output = stereo.process(&[1.0; 40]);
+        for (frame, expected) in output.chunks_exact(2).zip(&ramp) {
+            assert_eq!(frame[0], frame[1]);
+            assert_eq!(frame[0], *expected);
+        }
+    }
+
+    #[rstest]
+    fn test_smoothing_reset() {
+        let mut node = GainNode::with_smoothing(0.0, 0.1, 1000.0);
+        node.set_db(-6.0);
+        node.reset();
+        let output = node.process(&[1.0; 3]);
+        assert!(output.iter().all(|&s| (s - 0.501187).abs() < 0.0001));
+
+        let mut buffer = vec![1.0; 3];
+        node.process_in_place(&mut buffer);
+        assert_eq!(buffer, output);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(7)]
+    #[case(8)]
+    #[case(1027)]
+    #[cfg(feature = "simd")]
+    fn test_vectorized_matches_scalar(#[case] len: usize) {
+        let input: Vec<f32> = (0..len).map(|i| (i as f32 * 0.37).sin()).collect();
+        let linear_gain = 10.0_f32.powf(-7.5 / 20.0);
+
+        let mut expected = input.clone();
+        apply_gain_scalar(&mut expected, linear_gain);
+        let mut buffer = input.clone();
+        apply_gain(&mut buffer, linear_gain);
+
+        assert_eq!(buffer.len(), expected.len());
+        for (out, exp) in buffer.iter().zip(expected.iter()) {
+            assert!((out - exp).abs() <= f32::EPSILON);
+        }
+        assert_eq!(GainNode::new(-7.5).process(&input), buffer);
+    }
+
+    #[rstest]
+    #[case(GainNode::new(-4.5))]
+    #[case(GainNode::per_channel(vec![-3.0, 2.0], 2))]
+    fn test_parallel_matches_serial(#[case] node: GainNode) {
+        let input: Vec<f32> = (0..200_001).map(|i| (i as f32 * 0.001).sin()).collect();
+
+        let mut serial = input.clone();
+        node.process_in_place(&mut serial);
+        let mut parallel = input.clone();
+        node.process_parallel(&mut parallel);
+
+        assert!(node.is_stateless());
+        assert_eq!(parallel, serial);
+    }
+
+    #[rstest]
+    fn test_smoothed_gain_is_stateful() {
+        assert!(!GainNode::with_smoothing(0.0, 0.01, 1000.0).is_stateless());
+    }
+
+    #[rstest]
+    #[case::inside(2, 5, 2..5)]
+    #[case::past_end(7, 100, 7..8)]
+    #[case::reversed(5, 2, 0..0)]
+    #[case::empty(3, 3, 0..0)]
+    fn test_gain_db_range_respects_frames(#[case] start: usize, #[case] end: usize, #[case] scaled: std::ops::Range<usize>) {
+        // 8 stereo frames and a trailing partial frame
+        let input: Vec<f32> = (0..17).map(|i| i as f32 + 1.0).collect();
+        let mut output = input.clone();
+        gain_db_range(&mut output, 2, start, end, -6.0);
+
+        for (i, (out, original)) in output.iter().zip(&input).enumerate() {
+            if scaled.contains(&(i / 2)) && i < 16 {
+                assert!((out - original * 0.501187).abs() < 1e-4, "sample {}", i);
+            } else {
+                assert_eq!(out, original, "sample {}", i);
+            }
+        }
+    }
+
     #[rstest]
     fn test_box_clone(test_input: Vec<f32>) {
         let node = GainNode::new(6.0);
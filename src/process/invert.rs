@@ -0,0 +1,105 @@
+//! Polarity inversion processing node.
+//!
+//! Inverting the polarity multiplies every sample by -1. It is mostly used for phase
+//! checks, e.g. summing a processed signal with the inverted original to hear only
+//! what the processing changed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, InvertNode};
+//!
+//! let node = InvertNode::new();
+//! let output = node.process(&[0.5, -0.25]);
+//! assert_eq!(output, vec![-0.5, 0.25]);
+//! ```
+
+use super::node::{for_each_chunk, AudioNode};
+
+/// An audio processing node that inverts the polarity of every sample.
+#[derive(Clone, Default)]
+pub struct InvertNode;
+
+impl InvertNode {
+    /// Creates a new polarity inversion node.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AudioNode for InvertNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| -sample).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        invert_polarity(buffer);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "invert"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn process_parallel(&self, buffer: &mut [f32]) {
+        for_each_chunk(buffer, 1, invert_polarity);
+    }
+}
+
+/// Convenience function to invert the polarity of samples in-place.
+///
+/// # Arguments
+///
+/// * `samples` - Mutable slice of audio samples to invert
+pub fn invert_polarity(samples: &mut [f32]) {
+    samples.iter_mut().for_each(|sample| *sample = -*sample);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::AudioNodeChain;
+    use rstest::*;
+
+    #[fixture]
+    fn test_input() -> Vec<f32> {
+        vec![1.0, 0.5, -0.5, -1.0, 0.0]
+    }
+
+    #[rstest]
+    fn test_single_invert_negates(test_input: Vec<f32>) {
+        let output = InvertNode::new().process(&test_input);
+        for (out, inp) in output.iter().zip(test_input.iter()) {
+            assert_eq!(*out, -inp);
+        }
+
+        let mut buffer = test_input.clone();
+        invert_polarity(&mut buffer);
+        assert_eq!(buffer, output);
+    }
+
+    #[rstest]
+    fn test_double_invert_is_identity(test_input: Vec<f32>) {
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(InvertNode::new());
+        chain.add_node(InvertNode::new());
+
+        assert_eq!(chain.process(&test_input), test_input);
+
+        let mut buffer = test_input.clone();
+        chain.process_in_place(&mut buffer);
+        assert_eq!(buffer, test_input);
+    }
+
+    #[rstest]
+    fn test_node_type_and_clone() {
+        assert_eq!(InvertNode::new().box_clone().node_type(), "invert");
+    }
+}
@@ -0,0 +1,169 @@
+//! K-weighting filter node.
+//!
+//! K-weighting is the frequency weighting that ITU-R BS.1770 (and EBU R128) applies
+//! before measuring loudness: a high shelf of about +4 dB above 2 kHz modelling the
+//! head, followed by a high-pass (the "revised low-frequency B-curve") that removes
+//! the lowest octaves. [`Meter`](crate::analytic::Meter) applies it internally, the
+//! node is for custom metering built on the weighted signal.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, KWeightingNode};
+//!
+//! let node = KWeightingNode::new(48000.0).with_channels(2);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let weighted = node.process(&input);
+//! let mean_square = weighted.iter().map(|s| s * s).sum::<f32>() / weighted.len() as f32;
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::biquad::Biquad;
+use super::node::AudioNode;
+
+/// An audio processing node applying the two-stage K-weighting filter of ITU-R BS.1770.
+///
+/// The node keeps its filter state between calls to `process`, use
+/// [`KWeightingNode::reset`] before processing unrelated audio. For interleaved
+/// multi-channel audio set the channel count with [`KWeightingNode::with_channels`],
+/// each channel is then filtered independently.
+#[derive(Clone)]
+pub struct KWeightingNode {
+    sample_rate: f32,
+    filters: RefCell<Vec<[Biquad; 2]>>,
+    channel: Cell<usize>,
+}
+
+impl KWeightingNode {
+    /// Creates a new mono K-weighting node.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(sample_rate: f32) -> Self {
+        let stages = [Biquad::k_weighting_shelf(sample_rate), Biquad::k_weighting_high_pass(sample_rate)];
+        Self {
+            sample_rate,
+            filters: RefCell::new(vec![stages]),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the filter state.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let stages = self.filters.get_mut()[0].clone();
+        self.filters = RefCell::new(vec![stages; channels.max(1)]);
+        self.reset();
+        self
+    }
+
+    /// Returns the sample rate in Hz.
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Clears the filter state.
+    pub fn reset(&self) {
+        self.filters.borrow_mut().iter_mut().flatten().for_each(Biquad::clear);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut filters = self.filters.borrow_mut();
+        let channel = self.channel.get();
+        self.channel.set((channel + 1) % filters.len());
+        let [shelf, high_pass] = &mut filters[channel];
+        high_pass.process(shelf.process(sample as f64)) as f32
+    }
+}
+
+impl AudioNode for KWeightingNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "k_weighting"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.filters.borrow_mut().iter_mut().flatten().for_each(|filter| filter.set_flush_denormals(enabled));
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.filters.borrow().len())
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use crate::synth::sine;
+    use rstest::*;
+
+    /// Magnitude response in dB at 1 Hz resolution, from one second of impulse response.
+    fn response_db(node: &KWeightingNode, sample_rate: usize) -> Vec<f32> {
+        let mut impulse = vec![0.0; sample_rate];
+        impulse[0] = 1.0;
+        let flat = magnitude_spectrum(&impulse, WindowType::Rectangular);
+        let spectrum = magnitude_spectrum(&node.process(&impulse), WindowType::Rectangular);
+        spectrum.iter().zip(&flat).map(|(mag, flat)| 20.0 * (mag / flat).log10()).collect()
+    }
+
+    // Reference curve: the 48 kHz filter coefficients given in ITU-R BS.1770
+    #[rstest]
+    #[case(48000, 20, -13.28)]
+    #[case(48000, 50, -3.93)]
+    #[case(48000, 100, -1.13)]
+    #[case(48000, 1000, 0.70)]
+    #[case(48000, 2000, 3.07)]
+    #[case(48000, 10000, 4.04)]
+    #[case(48000, 20000, 4.04)]
+    #[case(44100, 50, -3.93)]
+    #[case(44100, 1000, 0.70)]
+    #[case(44100, 10000, 4.04)]
+    #[case(96000, 100, -1.13)]
+    #[case(96000, 10000, 4.04)]
+    fn test_matches_reference_curve(#[case] sample_rate: usize, #[case] frequency: usize, #[case] expected_db: f32) {
+        let response = response_db(&KWeightingNode::new(sample_rate as f32), sample_rate);
+        let db = response[frequency];
+        assert!((db - expected_db).abs() < 0.05, "{} Hz at {} Hz: {} dB", frequency, sample_rate, db);
+    }
+
+    #[rstest]
+    fn test_channels_and_reset() {
+        let left = sine(60.0, 0.5, 0.1, 48000);
+        let input: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let node = KWeightingNode::new(48000.0).with_channels(2);
+        let output = node.process(&input);
+        let mono = KWeightingNode::new(48000.0).process(&left);
+        for (frame, expected) in output.chunks_exact(2).zip(&mono) {
+            assert_eq!(frame[0], *expected);
+            assert_eq!(frame[1], 0.0);
+        }
+
+        node.reset();
+        let mut buffer = input.clone();
+        node.process_in_place(&mut buffer);
+        assert_eq!(buffer, output);
+        assert_eq!(node.box_clone().node_type(), "k_weighting");
+    }
+}
@@ -1,65 +1,347 @@
+//! Lookahead peak limiter processing node.
+//! 
+//! The limiter delays the audio by a short lookahead time. The gain is computed from
+//! the incoming (future) samples, so by the time a peak reaches the output the gain
+//! has already been reduced and the peak is caught without overshoot.
+
 use std::collections::VecDeque;
 use std::cell::{Cell, RefCell};
+use super::envelope::EnvelopeFollower;
 use super::node::AudioNode;
+use crate::analytic::{polyphase_filter, TAPS_PER_PHASE};
+
+/// Oversampling factor used to detect true peaks.
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
 
+/// Number of gain reduction values a [`LimiterNode`] keeps, older values are dropped.
+pub const GAIN_REDUCTION_HISTORY_LEN: usize = 1 << 16;
 
+
+/// An audio processing node that limits peaks above a threshold.
+/// 
+/// The output is delayed by the lookahead time (`lookahead_samples`). The envelope
+/// follows the loudest sample within the lookahead window and falls back with the
+/// release time once the peak has passed. By default the envelope rises instantly;
+/// an attack time (see [`LimiterNode::with_attack`]) makes gain reduction set in
+/// more gently, at the cost of letting the start of fast peaks through.
+/// 
+/// An optional makeup gain (see [`LimiterNode::set_makeup_db`]) raises the level
+/// after limiting. As a final safety net every output sample is clamped to
+/// `threshold + makeup_db`.
+/// 
+/// By default the limiter works on sample values: the reconstructed analog signal can
+/// still peak between samples (inter-sample peaks), so the true peak may exceed the
+/// ceiling slightly. In true-peak mode (see [`LimiterNode::set_true_peak`]) the
+/// envelope follows the 4x oversampled signal instead, which keeps the true peak of
+/// the output at the ceiling, e.g. for -1 dBTP delivery targets.
+/// 
+/// For interleaved multi-channel audio set the channel count with
+/// [`LimiterNode::with_channels`]. By default the channels are linked: the envelope
+/// follows the loudest channel of each frame and all channels of a frame get the same
+/// gain, so the stereo image stays in place. See [`LimiterNode::set_link_channels`]
+/// to limit each channel independently.
+/// 
+/// For metering, the gain reduction can be recorded with
+/// [`LimiterNode::record_gain_reduction`].
 #[derive(Clone)]
 pub struct LimiterNode {
     threshold: f32,
-    release_coeff: f32,
-    envelope: Cell<f32>,
-    lookahead_buffer: RefCell<VecDeque<f32>>,
+    makeup_db: f32,
+    /// One envelope per channel, only the first is used when the channels are linked
+    envelopes: RefCell<Vec<EnvelopeFollower>>,
+    /// Delayed samples with the level the envelope follows for each of them, interleaved
+    lookahead_buffer: RefCell<VecDeque<(f32, f32)>>,
     lookahead_samples: usize,
+    channels: usize,
+    link_channels: bool,
+    channel: Cell<usize>,
+    /// Limited frame being output while the next frame arrives, only used when the
+    /// channels are linked
+    output_frame: RefCell<Vec<f32>>,
+    /// Interpolation filter, only set in true-peak mode
+    oversampling_filter: Option<Vec<Vec<f32>>>,
+    /// Latest input samples of each channel feeding the interpolation filter
+    history: RefCell<Vec<VecDeque<f32>>>,
+    /// Recorded gain reduction in dB, only set while recording
+    gain_reduction: RefCell<Option<VecDeque<f32>>>,
 }
 
 impl LimiterNode {
+    /// Creates a new mono limiter node with an instantaneous attack.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threshold` - Maximum output level in dBFS
+    /// * `release_time_sec` - Time constant for the gain to recover after a peak
+    /// * `lookahead_sec` - Lookahead time, which is also the latency of the node
+    /// * `sample_rate` - Sample rate in Hz
     pub fn new(
         threshold: f32,
         release_time_sec: f32,
         lookahead_sec: f32,
         sample_rate: f32
     ) -> Self {
-        let release_coeff = (-1.0 / (sample_rate * release_time_sec)).exp();
-        let lookahead_samples = (lookahead_sec * sample_rate) as usize;
+        Self::with_attack(threshold, 0.0, release_time_sec, lookahead_sec, sample_rate)
+    }
 
-        Self {
+    /// Creates a new mono limiter node with an attack time.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threshold` - Maximum output level in dBFS
+    /// * `attack_time_sec` - Time constant for the envelope to rise toward a peak, 0.0 for
+    ///   an instantaneous attack
+    /// * `release_time_sec` - Time constant for the gain to recover after a peak
+    /// * `lookahead_sec` - Lookahead time, which is also the latency of the node
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn with_attack(
+        threshold: f32,
+        attack_time_sec: f32,
+        release_time_sec: f32,
+        lookahead_sec: f32,
+        sample_rate: f32
+    ) -> Self {
+        let limiter = Self {
             threshold,
-            release_coeff,
-            envelope: Cell::new(0.0),
-            lookahead_buffer: RefCell::new(VecDeque::with_capacity(lookahead_samples)),
-            lookahead_samples,
+            makeup_db: 0.0,
+            envelopes: RefCell::new(vec![EnvelopeFollower::new(attack_time_sec, release_time_sec, sample_rate)]),
+            lookahead_buffer: RefCell::new(VecDeque::new()),
+            lookahead_samples: (lookahead_sec * sample_rate) as usize,
+            channels: 1,
+            link_channels: true,
+            channel: Cell::new(0),
+            output_frame: RefCell::new(Vec::new()),
+            oversampling_filter: None,
+            history: RefCell::new(Vec::new()),
+            gain_reduction: RefCell::new(None),
+        };
+        limiter.reset();
+        limiter
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    /// 
+    /// The lookahead and latency are then counted in frames. This resets the delay
+    /// line and the envelope.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        let envelope = self.envelopes.get_mut()[0].clone();
+        *self.envelopes.get_mut() = vec![envelope; self.channels];
+        self.reset();
+        self
+    }
+
+    /// Returns the lookahead time in samples, i.e. the delay introduced by the limiter.
+    /// 
+    /// For multi-channel audio this is counted in frames.
+    pub fn lookahead_samples(&self) -> usize {
+        self.lookahead_samples
+    }
+
+    /// Returns the makeup gain in dB.
+    pub fn makeup_db(&self) -> f32 {
+        self.makeup_db
+    }
+
+    /// Sets the makeup gain in dB applied after limiting.
+    /// 
+    /// The output ceiling becomes `threshold + makeup_db`.
+    pub fn set_makeup_db(&mut self, makeup_db: f32) {
+        self.makeup_db = makeup_db;
+    }
+
+    /// Returns whether the limiter follows true peaks instead of sample peaks.
+    pub fn true_peak(&self) -> bool {
+        self.oversampling_filter.is_some()
+    }
+
+    /// Enables or disables true-peak limiting.
+    /// 
+    /// In true-peak mode the signal is oversampled 4x with the interpolation filter of
+    /// [`true_peak_dbtp`](crate::analytic::true_peak_dbtp) and the envelope follows the
+    /// peaks between the samples as well. The interpolation adds half the filter
+    /// length (6 samples) to the latency, see [`AudioNode::latency_samples`].
+    /// 
+    /// Changing the mode clears the delay line and the envelope.
+    pub fn set_true_peak(&mut self, enabled: bool) {
+        self.oversampling_filter = enabled.then(|| polyphase_filter(TRUE_PEAK_OVERSAMPLING));
+        self.reset();
+    }
+
+    /// Returns whether all channels of a frame get the same gain.
+    pub fn link_channels(&self) -> bool {
+        self.link_channels
+    }
+
+    /// Links or unlinks the channels of multi-channel audio.
+    /// 
+    /// Linked channels share one envelope following the loudest channel of each frame,
+    /// so a peak in one channel lowers the others as well and the stereo image doesn't
+    /// shift. The level of a frame is only known once all its channels have arrived, so
+    /// with a lookahead below one frame the latency is one frame.
+    /// 
+    /// Unlinked channels are limited independently, each with its own envelope, which
+    /// keeps quiet channels untouched by peaks in others.
+    /// 
+    /// This has no effect on mono audio. Changing the mode clears the delay line and
+    /// the envelope.
+    pub fn set_link_channels(&mut self, linked: bool) {
+        self.link_channels = linked;
+        self.reset();
+    }
+
+    /// Returns whether the gain reduction is recorded.
+    pub fn records_gain_reduction(&self) -> bool {
+        self.gain_reduction.borrow().is_some()
+    }
+
+    /// Starts or stops recording the gain reduction, e.g. to draw a meter.
+    /// 
+    /// While recording, every gain the limiter applies is stored as gain reduction in
+    /// dB: 0.0 while the level is below the threshold and positive while limiting, not
+    /// including the makeup gain. Linked channels share one value per frame, otherwise
+    /// there is one value per sample. Only the latest [`GAIN_REDUCTION_HISTORY_LEN`]
+    /// values are kept. Stopping discards the recorded values.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - Whether to record the gain reduction
+    pub fn record_gain_reduction(&mut self, enabled: bool) {
+        let history = self.gain_reduction.get_mut();
+        match (enabled, history.is_some()) {
+            (true, false) => *history = Some(VecDeque::new()),
+            (false, true) => *history = None,
+            _ => {}
         }
     }
 
-    pub fn process_sample(&self, sample: f32) -> f32 {
-        let mut buffer = self.lookahead_buffer.borrow_mut();
-        buffer.push_back(sample);
+    /// Returns the recorded gain reduction in dB, oldest first.
+    /// 
+    /// Values are in the order the limited samples are output, see
+    /// [`LimiterNode::record_gain_reduction`]. Empty when not recording.
+    pub fn gain_reduction_history(&self) -> Vec<f32> {
+        self.gain_reduction.borrow().iter().flatten().copied().collect()
+    }
 
-        if buffer.len() < self.lookahead_samples {
-            return sample;  // Pass through input while filling buffer
+    /// Discards the recorded gain reduction, recording continues if enabled.
+    pub fn clear_gain_reduction_history(&self) {
+        if let Some(history) = self.gain_reduction.borrow_mut().as_mut() {
+            history.clear();
         }
+    }
 
-        let future_idx = buffer.len() - 1;
-        let future_sample = buffer[future_idx];
-        
-        let input_lvl = future_sample.abs();
-        let mut envelope = self.envelope.get();
-        
-        if input_lvl > envelope {
-            envelope = input_lvl;
+    /// Clears the delay line, the interpolation filter, the envelope and the recorded
+    /// gain reduction.
+    pub fn reset(&self) {
+        let mut buffer = self.lookahead_buffer.borrow_mut();
+        buffer.clear();
+        buffer.resize(self.delay_frames() * self.channels, (0.0, 0.0));
+        self.envelopes.borrow_mut().iter_mut().for_each(EnvelopeFollower::reset);
+        let history_len = if self.true_peak() { TAPS_PER_PHASE } else { 0 };
+        *self.history.borrow_mut() = vec![VecDeque::from(vec![0.0; history_len]); self.channels];
+        *self.output_frame.borrow_mut() = vec![0.0; self.channels];
+        self.channel.set(0);
+        self.clear_gain_reduction_history();
+    }
+
+    fn linked(&self) -> bool {
+        self.link_channels && self.channels > 1
+    }
+
+    /// Frames of silence the delay line starts with.
+    ///
+    /// Linked frames are limited once complete and output during the next frame,
+    /// which adds a frame of delay the delay line makes up for.
+    fn delay_frames(&self) -> usize {
+        if self.linked() {
+            self.lookahead_samples.saturating_sub(1)
         } else {
-            envelope = self.release_coeff * envelope + (1.0 - self.release_coeff) * input_lvl;
+            self.lookahead_samples
         }
-        self.envelope.set(envelope);
+    }
+
+    /// Feeds a sample to the interpolation filter of its channel and returns the
+    /// sample half a filter length earlier together with the true peak around it.
+    fn true_peak_level(&self, sample: f32, channel: usize, filter: &[Vec<f32>]) -> (f32, f32) {
+        let mut histories = self.history.borrow_mut();
+        let history = &mut histories[channel];
+        history.pop_front();
+        history.push_back(sample);
+
+        // Phase 0 reproduces the center sample, the other phases interpolate the
+        // points between it and the next sample
+        let center = history[TAPS_PER_PHASE / 2 - 1];
+        let level = filter[1..]
+            .iter()
+            .map(|coefficients| {
+                let interpolated: f32 = coefficients.iter().zip(history.iter()).map(|(c, s)| c * s).sum();
+                interpolated.abs()
+            })
+            .fold(center.abs(), f32::max);
+        (center, level)
+    }
+
+    /// Feeds the loudest of `levels` to an envelope and returns the resulting gain.
+    fn gain<'a>(&self, levels: impl Iterator<Item = &'a (f32, f32)>, envelope: usize) -> f32 {
+        let input_lvl = levels.fold(0.0_f32, |peak, &(_, level)| peak.max(level));
+        let envelope = self.envelopes.borrow_mut()[envelope].process_sample(input_lvl);
 
-        let gain = if envelope > self.threshold {
-            10.0_f32.powf(self.threshold/20.0) / envelope  // Convert threshold to gain factor
+        let threshold_lin = 10.0_f32.powf(self.threshold / 20.0);
+        let gain = if envelope > threshold_lin {
+            threshold_lin / envelope
         } else {
             1.0
         };
 
-        let output_sample = buffer.pop_front().unwrap() * gain;
-        output_sample
+        if let Some(history) = self.gain_reduction.borrow_mut().as_mut() {
+            if history.len() == GAIN_REDUCTION_HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(-20.0 * gain.log10());
+        }
+        gain
+    }
+
+    /// Applies the gain and the makeup gain, clamped to the ceiling.
+    fn limit(&self, sample: f32, gain: f32) -> f32 {
+        let makeup = 10.0_f32.powf(self.makeup_db / 20.0);
+        let ceiling = 10.0_f32.powf(self.threshold / 20.0) * makeup;
+        (sample * gain * makeup).clamp(-ceiling, ceiling)
+    }
+
+    /// Processes a single sample and returns the limited sample from
+    /// [`AudioNode::latency_samples`] earlier.
+    /// 
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let channel = self.channel.get();
+        self.channel.set((channel + 1) % self.channels);
+        let (sample, level) = match &self.oversampling_filter {
+            Some(filter) => self.true_peak_level(sample, channel, filter),
+            None => (sample, sample.abs()),
+        };
+        let mut buffer = self.lookahead_buffer.borrow_mut();
+        buffer.push_back((sample, level));
+
+        if self.linked() {
+            let mut output_frame = self.output_frame.borrow_mut();
+            let output = output_frame[channel];
+            if channel == self.channels - 1 {
+                // The frame is complete: one gain from the loudest sample of all
+                // channels within the window limits the oldest frame
+                let gain = self.gain(buffer.iter(), 0);
+                for out in output_frame.iter_mut() {
+                    *out = self.limit(buffer.pop_front().unwrap().0, gain);
+                }
+            }
+            return output;
+        }
+
+        // The window spans from the delayed output sample to the newest input of the
+        // same channel, so every sample has been seen by the envelope before it is output.
+        let gain = self.gain(buffer.iter().step_by(self.channels), channel);
+        self.limit(buffer.pop_front().unwrap().0, gain)
     }
 
 
@@ -67,16 +349,19 @@ impl LimiterNode {
 
 impl AudioNode for LimiterNode {
     fn process(&self, input_buffer: &[f32]) -> Vec<f32> {
-        let mut out = Vec::with_capacity(input_buffer.len());
-        for &sample in input_buffer {
-            let limited = self.process_sample(sample);
-            out.push(limited);
-        }
+        let mut out = input_buffer.to_vec();
+        self.process_in_place(&mut out);
         out
     }
     
     fn process_in_place(&self, buffer: &mut [f32]) {
-        buffer.iter_mut().for_each(|sample| {
+        buffer.chunks_mut(self.channels).for_each(|frame| self.process_frame(frame));
+    }
+
+    /// Limits one frame and replaces it with the frame from
+    /// [`AudioNode::latency_samples`] earlier.
+    fn process_frame(&self, frame: &mut [f32]) {
+        frame.iter_mut().for_each(|sample| {
             *sample = self.process_sample(*sample);
         });
     }
@@ -85,15 +370,29 @@ impl AudioNode for LimiterNode {
         "limiter"
     }
     
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
     fn box_clone(&self) -> Box<dyn AudioNode> {
         Box::new(self.clone())
     }
-    
+
+    fn latency_samples(&self) -> usize {
+        let frames = if self.linked() { self.delay_frames() + 1 } else { self.lookahead_samples };
+        if self.true_peak() {
+            frames + TAPS_PER_PHASE / 2
+        } else {
+            frames
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analytic::true_peak_dbtp;
+    use crate::synth::{sine, white_noise};
     use rstest::*;
 
     #[fixture]
@@ -109,27 +408,34 @@ mod tests {
     #[rstest]
     fn test_initial_state(test_limiter: LimiterNode) {
         assert_eq!(test_limiter.threshold, -6.0);
-        assert_eq!(test_limiter.envelope.get(), 0.0);
-        assert_eq!(test_limiter.lookahead_buffer.borrow().len(), 0);
+        assert_eq!(test_limiter.envelopes.borrow()[0].envelope(), 0.0);
+        assert_eq!(test_limiter.lookahead_buffer.borrow().len(), test_limiter.lookahead_samples);
     }
 
     #[rstest]
-    fn test_lookahead_buffer(test_limiter: LimiterNode) {
+    fn test_lookahead_delay(test_limiter: LimiterNode) {
         let lookahead_samples = (0.001 * 44100.0) as usize;
-        
-        // First phase: Buffer filling
-        // Should pass through input samples while buffer fills
-        for _ in 0..lookahead_samples-1 {
-            let output = test_limiter.process_sample(1.0);
-            assert_eq!(output, 1.0, "Should pass through input while buffer is filling");
-        }
-        
-        // Second phase: Test actual limiting behavior
-        // Feed a large peak and verify the limiter starts reducing gain before the peak
-        let peak_value = 2.0; // Above threshold
-        let output_at_peak_start = test_limiter.process_sample(peak_value);
-        assert!(output_at_peak_start < peak_value, "Limiter should start reducing gain before peak");
-        assert!(output_at_peak_start > 0.0, "Output should not be completely silenced");
+        let input: Vec<f32> = (0..100).map(|i| 0.004 * i as f32).collect();
+        let output = test_limiter.process(&input);
+
+        assert!(output[..lookahead_samples].iter().all(|&s| s == 0.0));
+        assert_eq!(output[lookahead_samples..], input[..100 - lookahead_samples]);
+    }
+
+    #[rstest]
+    fn test_peak_caught_on_emergence(test_limiter: LimiterNode) {
+        let lookahead_samples = (0.001 * 44100.0) as usize;
+        let threshold = 10.0_f32.powf(-6.0 / 20.0);
+
+        let mut input = vec![0.0f32; 200];
+        input[100] = 2.0;
+        let output = test_limiter.process(&input);
+
+        // The peak emerges after the lookahead delay and is already attenuated
+        let emerged = output[100 + lookahead_samples];
+        assert!(emerged > 0.0, "Output should not be completely silenced");
+        assert!(emerged <= threshold + 1e-6, "Peak should be limited when it emerges, got {}", emerged);
+        assert!(output.iter().all(|s| s.is_finite() && s.abs() <= threshold + 1e-6));
     }
 
     #[rstest]
@@ -158,17 +464,172 @@ mod tests {
         
         // Send one loud sample
         test_limiter.process_sample(2.0);
-        let envelope_peak = test_limiter.envelope.get();
+        let envelope_peak = test_limiter.envelopes.borrow()[0].envelope();
         
         // Process more samples and check envelope decreases
         for _ in 0..100 {
             test_limiter.process_sample(0.0);
         }
         
-        assert!(test_limiter.envelope.get() < envelope_peak, 
+        assert!(test_limiter.envelopes.borrow()[0].envelope() < envelope_peak, 
             "Envelope should decrease during release phase");
     }
 
+    #[rstest]
+    fn test_attack_time() {
+        let sample_rate = 44100.0;
+        let attack_samples = (0.01 * sample_rate) as usize;
+        let limiter = LimiterNode::with_attack(-6.0, 0.01, 0.1, 0.0, sample_rate);
+
+        // Step input: the envelope rises gradually instead of jumping
+        let mut envelopes = Vec::new();
+        for _ in 0..attack_samples * 5 {
+            limiter.process_sample(1.0);
+            envelopes.push(limiter.envelopes.borrow()[0].envelope());
+        }
+        assert!(envelopes[0] < 0.01);
+        assert!(envelopes.windows(2).all(|w| w[1] >= w[0]));
+        // One attack time constant covers about 63% of the step
+        assert!((envelopes[attack_samples - 1] - 0.632).abs() < 0.01);
+        assert!(envelopes[attack_samples * 5 - 1] > 0.99);
+
+        // The default limiter attacks instantly
+        let instant = LimiterNode::new(-6.0, 0.1, 0.0, sample_rate);
+        instant.process_sample(1.0);
+        assert_eq!(instant.envelopes.borrow()[0].envelope(), 1.0);
+    }
+
+    #[rstest]
+    fn test_makeup_gain_restores_level() {
+        let sample_rate = 44100.0;
+        let mut limiter = LimiterNode::new(-6.0, 0.1, 0.001, sample_rate);
+        limiter.set_makeup_db(6.0);
+        let ceiling = 1.0;  // -6 dB threshold + 6 dB makeup = 0 dBFS
+
+        // Steady 0 dBFS tone, 6 dB above the threshold, with a transient on top
+        let mut input: Vec<f32> = (0..44100)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate).sin())
+            .collect();
+        input[30000] = 4.0;
+        let output = limiter.process(&input);
+
+        let steady_peak = output[10000..20000].iter().fold(0.0_f32, |p, s| p.max(s.abs()));
+        assert!((steady_peak - 1.0).abs() < 0.02, "steady peak {}", steady_peak);
+        assert!(output.iter().all(|s| s.abs() <= ceiling + 1e-6));
+        assert_eq!(limiter.makeup_db(), 6.0);
+    }
+
+    #[rstest]
+    #[case(44100.0, -1.0)]
+    #[case(48000.0, -1.0)]
+    #[case(48000.0, -3.0)]
+    fn test_true_peak_mode(#[case] sample_rate: f32, #[case] threshold: f32) {
+        // A quarter of the sample rate at 45° phase: the samples stay at -3 dBFS while
+        // the waveform peaks at 0 dBTP between them. A burst of noise and a tone follow.
+        let mut input: Vec<f32> = (0..sample_rate as usize / 2)
+            .map(|i| (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin())
+            .collect();
+        input.extend(white_noise(1.0, 0.5, sample_rate as u32, 3));
+        input.extend(sine(3000.0, 1.5, 0.5, sample_rate as u32));
+
+        let sample_limiter = LimiterNode::new(threshold, 0.1, 0.001, sample_rate);
+        let output = sample_limiter.process(&input);
+        let sample_peak = true_peak_dbtp(&output[..sample_rate as usize / 2], 1, sample_rate as u32)[0];
+        assert!(sample_peak > threshold + 0.5, "sample peak limiter gives {} dBTP", sample_peak);
+
+        let mut limiter = LimiterNode::new(threshold, 0.1, 0.001, sample_rate);
+        limiter.set_true_peak(true);
+        assert!(limiter.true_peak());
+        let output = limiter.process(&input);
+        let peak = true_peak_dbtp(&output, 1, sample_rate as u32)[0];
+        assert!(peak <= threshold + 0.01, "true peak {} dBTP above {} dBTP", peak, threshold);
+        assert!(peak > threshold - 0.5, "true peak {} dBTP", peak);
+    }
+
+    #[rstest]
+    fn test_true_peak_latency(mut test_limiter: LimiterNode) {
+        let lookahead_samples = test_limiter.lookahead_samples();
+        test_limiter.set_true_peak(true);
+        let latency = test_limiter.latency_samples();
+        assert_eq!(latency, lookahead_samples + TAPS_PER_PHASE / 2);
+
+        // Quiet input passes unchanged after the latency
+        let input: Vec<f32> = (0..200).map(|i| 0.001 * i as f32).collect();
+        let output = test_limiter.process(&input);
+        assert!(output[..latency].iter().all(|&s| s == 0.0));
+        for (out, inp) in output[latency..].iter().zip(&input) {
+            assert!((out - inp).abs() < 1e-6);
+        }
+
+        test_limiter.set_true_peak(false);
+        assert_eq!(test_limiter.latency_samples(), lookahead_samples);
+    }
+
+    /// Stereo input with a loud left and a quiet right channel.
+    fn stereo_input() -> Vec<f32> {
+        let left = sine(1000.0, 1.0, 0.1, 48000);
+        let right = sine(300.0, 0.3, 0.1, 48000);
+        left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect()
+    }
+
+    #[rstest]
+    fn test_linked_channels_share_gain() {
+        let limiter = LimiterNode::new(-6.0, 0.05, 0.001, 48000.0).with_channels(2);
+        assert!(limiter.link_channels());
+        let latency = limiter.latency_samples();
+        assert_eq!(latency, 48);
+
+        let input = stereo_input();
+        let output = limiter.process(&input);
+        assert!(output[..latency * 2].iter().all(|&s| s == 0.0));
+
+        let threshold = 10.0_f32.powf(-6.0 / 20.0);
+        let mut reduced = 0;
+        for (out, inp) in output[latency * 2..].chunks_exact(2).zip(input.chunks_exact(2)) {
+            assert!(out[0].abs() <= threshold + 1e-6);
+            if inp[0].abs() > 0.01 && inp[1].abs() > 0.01 {
+                let (left_gain, right_gain) = (out[0] / inp[0], out[1] / inp[1]);
+                assert!((left_gain - right_gain).abs() < 1e-5, "{} vs {}", left_gain, right_gain);
+                if left_gain < 0.9 {
+                    reduced += 1;
+                }
+            }
+        }
+        // The quiet right channel follows the gain reduction of the left
+        assert!(reduced > 1000);
+    }
+
+    #[rstest]
+    fn test_unlinked_channels_independent() {
+        let mut limiter = LimiterNode::new(-6.0, 0.05, 0.001, 48000.0).with_channels(2);
+        limiter.set_link_channels(false);
+        let latency = limiter.latency_samples();
+        assert_eq!(latency, 48);
+
+        let input = stereo_input();
+        let output = limiter.process(&input);
+        let threshold = 10.0_f32.powf(-6.0 / 20.0);
+        for (out, inp) in output[latency * 2..].chunks_exact(2).zip(input.chunks_exact(2)) {
+            assert!(out[0].abs() <= threshold + 1e-6);
+            assert_eq!(out[1], inp[1]);
+        }
+    }
+
+    #[rstest]
+    fn test_linked_without_lookahead() {
+        // The gain of a frame is known once it is complete, one frame later
+        let limiter = LimiterNode::new(-6.0, 0.05, 0.0, 48000.0).with_channels(2);
+        assert_eq!(limiter.latency_samples(), 1);
+        let output = limiter.process(&[0.1, 1.0, 0.2, 0.2]);
+        let threshold = 10.0_f32.powf(-6.0 / 20.0);
+        assert_eq!(&output[..2], &[0.0, 0.0]);
+        assert!((output[2] - 0.1 * threshold).abs() < 1e-6);
+        assert!((output[3] - threshold).abs() < 1e-6);
+
+        limiter.reset();
+        assert_eq!(limiter.process(&[0.1, 1.0, 0.2, 0.2]), output);
+    }
+
     #[rstest]
     fn test_process_methods(test_limiter: LimiterNode) {
         let input = vec![0.5f32; 1000];
@@ -188,6 +649,60 @@ mod tests {
         assert_eq!(output1, buffer);
     }
 
+    #[rstest]
+    #[case(1, true, false)]
+    #[case(2, true, false)]
+    #[case(2, false, false)]
+    #[case(2, true, true)]
+    fn test_frame_by_frame_matches_buffer(#[case] channels: usize, #[case] linked: bool, #[case] true_peak: bool) {
+        let mut limiter = LimiterNode::new(-6.0, 0.05, 0.001, 48000.0).with_channels(channels);
+        limiter.set_link_channels(linked);
+        limiter.set_true_peak(true_peak);
+        let input: Vec<f32> = if channels == 2 { stereo_input() } else { sine(1000.0, 1.0, 0.1, 48000) };
+
+        let framed = limiter.clone();
+        let output = limiter.process(&input);
+        let mut frames = input.clone();
+        for frame in frames.chunks_exact_mut(channels) {
+            framed.process_frame(frame);
+        }
+        assert_eq!(frames, output);
+    }
+
+    #[rstest]
+    fn test_gain_reduction_history(mut test_limiter: LimiterNode) {
+        assert!(!test_limiter.records_gain_reduction());
+        test_limiter.process(&[1.0; 10]);
+        assert!(test_limiter.gain_reduction_history().is_empty());
+
+        test_limiter.record_gain_reduction(true);
+        test_limiter.reset();
+        let lookahead = test_limiter.lookahead_samples();
+        // A 2.0 transient is 12.02 dB above the -6 dB threshold
+        let mut input = vec![0.1f32; 2000];
+        input[500] = 2.0;
+        let output = test_limiter.process(&input);
+
+        // One value per output sample, reducing from the moment the transient enters
+        // the lookahead window until it has been output
+        let history = test_limiter.gain_reduction_history();
+        assert_eq!(history.len(), input.len());
+        assert!(history[..500].iter().all(|&db| db == 0.0));
+        assert!(history[500..=500 + lookahead].iter().all(|&db| (db - 12.02).abs() < 0.01));
+        assert!(history.iter().all(|&db| db < 12.03));
+        assert!((output[500 + lookahead] - 10.0_f32.powf(-6.0 / 20.0)).abs() < 1e-6);
+        // and then releases
+        assert!(history[1999] < history[1000] && history[1000] < history[501 + lookahead]);
+
+        test_limiter.clear_gain_reduction_history();
+        assert!(test_limiter.gain_reduction_history().is_empty());
+        test_limiter.process(&vec![0.1; GAIN_REDUCTION_HISTORY_LEN + 10]);
+        assert_eq!(test_limiter.gain_reduction_history().len(), GAIN_REDUCTION_HISTORY_LEN);
+
+        test_limiter.record_gain_reduction(false);
+        assert!(test_limiter.gain_reduction_history().is_empty());
+    }
+
     #[rstest]
     fn test_node_type_and_clone(test_limiter: LimiterNode) {
         assert_eq!(test_limiter.node_type(), "limiter");
@@ -0,0 +1,158 @@
+//! Mid/side encoding and decoding nodes.
+//!
+//! Mid/side (M/S) represents a stereo signal as its sum (mid, what both channels
+//! share) and difference (side, what differs between them). Processing the two
+//! components separately is a common mastering technique, e.g. to tame a harsh
+//! centre vocal without touching the stereo ambience.
+//!
+//! Both nodes operate on interleaved 2-channel buffers: [`MidSideEncodeNode`] turns
+//! [L,R,L,R,...] into [M,S,M,S,...] and [`MidSideDecodeNode`] performs the inverse.
+//! The 1/√2 scaling makes the transform its own inverse and preserves energy.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNodeChain, GainNode, MidSideDecodeNode, MidSideEncodeNode};
+//!
+//! let mut chain = AudioNodeChain::new();
+//! chain.add_node(MidSideEncodeNode::new());
+//! // ... process the mid/side signal here ...
+//! chain.add_node(MidSideDecodeNode::new());
+//!
+//! let stereo = vec![0.5f32, 0.25, 0.5, 0.25];
+//! let output = chain.process(&stereo);
+//! ```
+
+use std::f32::consts::FRAC_1_SQRT_2;
+use super::node::AudioNode;
+
+/// Converts one (a, b) pair with the orthonormal sum/difference transform.
+fn sum_difference(frame: &mut [f32]) {
+    let (a, b) = (frame[0], frame[1]);
+    frame[0] = (a + b) * FRAC_1_SQRT_2;
+    frame[1] = (a - b) * FRAC_1_SQRT_2;
+}
+
+/// An audio processing node that encodes interleaved L/R stereo into M/S.
+///
+/// M = (L + R) / √2, S = (L - R) / √2
+#[derive(Clone, Default)]
+pub struct MidSideEncodeNode;
+
+impl MidSideEncodeNode {
+    /// Creates a new mid/side encoder.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AudioNode for MidSideEncodeNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.chunks_exact_mut(2).for_each(sum_difference);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "mid_side_encode"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+/// An audio processing node that decodes interleaved M/S back into L/R stereo.
+///
+/// L = (M + S) / √2, R = (M - S) / √2
+#[derive(Clone, Default)]
+pub struct MidSideDecodeNode;
+
+impl MidSideDecodeNode {
+    /// Creates a new mid/side decoder.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AudioNode for MidSideDecodeNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.chunks_exact_mut(2).for_each(sum_difference);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "mid_side_decode"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::AudioNodeChain;
+    use rstest::*;
+
+    #[fixture]
+    fn stereo() -> Vec<f32> {
+        vec![1.0, -0.5, 0.25, 0.75, -0.3, -0.9, 0.0, 0.6]
+    }
+
+    #[rstest]
+    fn test_round_trip(stereo: Vec<f32>) {
+        let encoded = MidSideEncodeNode::new().process(&stereo);
+        let decoded = MidSideDecodeNode::new().process(&encoded);
+        for (a, b) in decoded.iter().zip(stereo.iter()) {
+            assert!((a - b).abs() <= 2.0 * f32::EPSILON, "{} != {}", a, b);
+        }
+    }
+
+    #[rstest]
+    fn test_round_trip_chain(stereo: Vec<f32>) {
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(MidSideEncodeNode::new());
+        chain.add_node(MidSideDecodeNode::new());
+
+        let mut buffer = stereo.clone();
+        chain.process_in_place(&mut buffer);
+        for (a, b) in buffer.iter().zip(stereo.iter()) {
+            assert!((a - b).abs() <= 2.0 * f32::EPSILON);
+        }
+    }
+
+    #[rstest]
+    fn test_mono_has_no_side() {
+        let mono: Vec<f32> = [0.5, -0.25, 0.8].iter().flat_map(|&s| [s, s]).collect();
+        let encoded = MidSideEncodeNode::new().process(&mono);
+        for frame in encoded.chunks_exact(2) {
+            assert_eq!(frame[1], 0.0);
+        }
+        assert!((encoded[0] - 0.5 * 2.0 * FRAC_1_SQRT_2).abs() < f32::EPSILON);
+    }
+
+    #[rstest]
+    fn test_node_types() {
+        assert_eq!(MidSideEncodeNode::new().box_clone().node_type(), "mid_side_encode");
+        assert_eq!(MidSideDecodeNode::new().box_clone().node_type(), "mid_side_decode");
+    }
+}
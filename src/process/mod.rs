@@ -1,8 +1,74 @@
 mod gain;
 mod node;
 mod limiter;
+mod normalize;
+mod trim;
+mod delay;
+mod delayline;
+mod pan;
+mod midside;
+mod width;
+mod waveshaper;
+mod bitcrusher;
+mod invert;
+mod automation;
+mod parallel;
+mod resample;
+mod tremolo;
+mod ringmod;
+mod moddelay;
+mod reverb;
+mod crossfade;
+mod remap;
+mod crossover;
+mod pitchshift;
+mod timestretch;
+mod vocoder;
+mod biquad;
+mod allpass;
+mod kweighting;
+mod comb;
+mod envelope;
+mod drywet;
+mod convolution;
+mod oversample;
+mod rangegain;
+mod denormal;
+#[cfg(test)]
+mod test_util;
 
 pub use gain::*;
 pub use node::*;
 pub use limiter::*;
+pub use normalize::*;
+pub use trim::*;
+pub use delay::*;
+pub use delayline::*;
+pub use pan::*;
+pub use midside::*;
+pub use width::*;
+pub use waveshaper::*;
+pub use bitcrusher::*;
+pub use invert::*;
+pub use automation::*;
+pub use parallel::*;
+pub use resample::*;
+pub use tremolo::*;
+pub use ringmod::*;
+pub use moddelay::*;
+pub use reverb::*;
+pub use crossfade::*;
+pub use remap::*;
+pub use crossover::*;
+pub use pitchshift::*;
+pub use timestretch::*;
+pub use allpass::*;
+pub use kweighting::*;
+pub use comb::*;
+pub use envelope::*;
+pub use drywet::*;
+pub use convolution::*;
+pub use oversample::*;
+pub use rangegain::*;
+pub use denormal::*;
 
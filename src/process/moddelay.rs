@@ -0,0 +1,280 @@
+//! Modulated delay (chorus / flanger) processing node.
+//!
+//! A short delay whose delay time is swept by a low-frequency oscillator. Mixed with
+//! the dry signal the moving copy creates the shimmering detune of a chorus (around
+//! 15-30 ms of delay) or the jet-like comb filter sweep of a flanger (around 1-5 ms,
+//! usually with feedback).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, ModDelayNode};
+//!
+//! // Chorus: 20 ms delay swept by ±5 ms at 0.8 Hz, half wet
+//! let chorus = ModDelayNode::new(0.02, 0.005, 0.8, 0.0, 0.5, 48000.0);
+//!
+//! // Flanger: 3 ms delay swept by ±2 ms at 0.25 Hz with feedback
+//! let flanger = ModDelayNode::new(0.003, 0.002, 0.25, 0.6, 0.5, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = chorus.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::f64::consts::TAU;
+use super::delay::MAX_FEEDBACK;
+use super::denormal::flush_denormals;
+use super::node::AudioNode;
+
+/// An audio processing node with an LFO-modulated fractional delay line.
+///
+/// The delay time follows `base_delay + depth * sin(2π * rate * t)`. Delay times
+/// between two samples are read with linear interpolation, so the sweep is smooth.
+/// Like [`DelayNode`](super::DelayNode) the delayed signal can be fed back into the
+/// line and mixed with the dry input.
+///
+/// The node keeps its delay line and LFO phase between calls to `process`, use
+/// [`ModDelayNode::reset`] to clear them. For interleaved multi-channel audio set the
+/// channel count with [`ModDelayNode::with_channels`], each channel is then delayed
+/// independently with a shared LFO.
+///
+/// As for [`DelayNode`](super::DelayNode) the delay is part of the effect, so the
+/// node reports no [`latency`](AudioNode::latency_samples) for any mix.
+#[derive(Clone)]
+pub struct ModDelayNode {
+    base_delay: f64,
+    depth: f64,
+    rate_hz: f32,
+    feedback: f32,
+    mix: f32,
+    sample_rate: f32,
+    channels: usize,
+    buffer_frames: usize,
+    buffer: RefCell<Vec<f32>>,
+    position: Cell<usize>,
+    channel: Cell<usize>,
+    phase: Cell<f64>,
+    flush_denormals: Cell<bool>,
+}
+
+impl ModDelayNode {
+    /// Creates a new mono modulated delay node.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_delay_sec` - Center delay time in seconds
+    /// * `depth_sec` - Maximum deviation from the center delay in seconds, clamped to
+    ///   `base_delay_sec`
+    /// * `rate_hz` - LFO frequency in Hz
+    /// * `feedback` - Amount of the delayed signal fed back into the delay line, clamped to
+    ///   [0.0, `MAX_FEEDBACK`]
+    /// * `mix` - Dry/wet balance, 0.0 is fully dry and 1.0 fully wet. Clamped to [0.0, 1.0].
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(
+        base_delay_sec: f32,
+        depth_sec: f32,
+        rate_hz: f32,
+        feedback: f32,
+        mix: f32,
+        sample_rate: f32,
+    ) -> Self {
+        let base_delay = (base_delay_sec.max(0.0) * sample_rate) as f64;
+        let depth = ((depth_sec.max(0.0) * sample_rate) as f64).min(base_delay);
+        // Room for the longest delay plus the second interpolation tap
+        let buffer_frames = (base_delay + depth).ceil() as usize + 2;
+        Self {
+            base_delay,
+            depth,
+            rate_hz: rate_hz.max(0.0),
+            feedback: feedback.clamp(0.0, MAX_FEEDBACK),
+            mix: mix.clamp(0.0, 1.0),
+            sample_rate,
+            channels: 1,
+            buffer_frames,
+            buffer: RefCell::new(vec![0.0; buffer_frames]),
+            position: Cell::new(0),
+            channel: Cell::new(0),
+            phase: Cell::new(0.0),
+            flush_denormals: Cell::new(true),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the delay line.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.buffer = RefCell::new(vec![0.0; self.buffer_frames * self.channels]);
+        self.reset();
+        self
+    }
+
+    /// Returns the feedback amount.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Returns the dry/wet mix.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Clears the delay line and restarts the LFO.
+    pub fn reset(&self) {
+        self.buffer.borrow_mut().iter_mut().for_each(|s| *s = 0.0);
+        self.position.set(0);
+        self.channel.set(0);
+        self.phase.set(0.0);
+    }
+
+    /// Returns the current delay time in frames.
+    fn current_delay(&self) -> f64 {
+        let delay = self.base_delay + self.depth * (TAU * self.phase.get()).sin();
+        delay.max(1.0)
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut buffer = self.buffer.borrow_mut();
+        let position = self.position.get();
+        let channel = self.channel.get();
+
+        // Fractional read position behind the write position, wrapped into the ring
+        let read = position as f64 + self.buffer_frames as f64 - self.current_delay();
+        let index = read.floor() as usize;
+        let fraction = (read - read.floor()) as f32;
+        let tap = |frame: usize| buffer[(frame % self.buffer_frames) * self.channels + channel];
+        let delayed = tap(index) + fraction * (tap(index + 1) - tap(index));
+
+        let stored = sample + self.feedback * delayed;
+        buffer[position * self.channels + channel] =
+            if self.flush_denormals.get() { flush_denormals(stored) } else { stored };
+
+        if channel + 1 == self.channels {
+            self.channel.set(0);
+            self.position.set((position + 1) % self.buffer_frames);
+            let next = self.phase.get() + self.rate_hz as f64 / self.sample_rate as f64;
+            self.phase.set(next.fract());
+        } else {
+            self.channel.set(channel + 1);
+        }
+
+        (1.0 - self.mix) * sample + self.mix * delayed
+    }
+}
+
+impl AudioNode for ModDelayNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "mod_delay"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.flush_denormals.set(enabled);
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0.02, 0.005, 2.0)]
+    #[case(0.003, 0.002, 5.0)]
+    fn test_delay_oscillates_around_base(
+        #[case] base_sec: f32,
+        #[case] depth_sec: f32,
+        #[case] rate_hz: f32,
+    ) {
+        let sample_rate = 1000.0;
+        let node = ModDelayNode::new(base_sec, depth_sec, rate_hz, 0.0, 1.0, sample_rate);
+
+        // Linear interpolation of a ramp is exact, so the output reveals the delay
+        let input: Vec<f32> = (0..2200).map(|i| i as f32).collect();
+        let output = node.process(&input);
+        // Skip past the initial fill of the delay line, the rest spans whole LFO periods
+        let skip = 200;
+        let delays: Vec<f32> = input[skip..].iter()
+            .zip(output[skip..].iter())
+            .map(|(x, y)| x - y)
+            .collect();
+
+        let base = base_sec * sample_rate;
+        let depth = depth_sec * sample_rate;
+        let max = delays.iter().cloned().fold(f32::MIN, f32::max);
+        let min = delays.iter().cloned().fold(f32::MAX, f32::min);
+        assert!((max - (base + depth)).abs() < 0.05, "max {}", max);
+        assert!((min - (base - depth).max(1.0)).abs() < 0.05, "min {}", min);
+        let mean = delays.iter().sum::<f32>() / delays.len() as f32;
+        assert!((mean - base).abs() < 0.1, "mean {}", mean);
+
+        // The delay peaks once per LFO period
+        let peaks: Vec<usize> = (1..delays.len() - 1)
+            .filter(|&i| delays[i] > delays[i - 1] && delays[i] >= delays[i + 1])
+            .collect();
+        let period = sample_rate / rate_hz;
+        for pair in peaks.windows(2) {
+            assert!(((pair[1] - pair[0]) as f32 - period).abs() <= 1.0);
+        }
+        assert_eq!(peaks.len(), ((delays.len() as f32) / period).round() as usize);
+    }
+
+    #[rstest]
+    fn test_dry_mix_passes_through() {
+        let node = ModDelayNode::new(0.02, 0.005, 1.0, 0.5, 0.0, 1000.0);
+        let input: Vec<f32> = (0..500).map(|i| (i as f32 * 0.1).sin()).collect();
+        assert_eq!(node.process(&input), input);
+        assert_eq!(node.latency_samples(), 0);
+    }
+
+    #[rstest]
+    fn test_without_modulation_matches_delay() {
+        let node = ModDelayNode::new(0.01, 0.0, 1.0, 0.5, 1.0, 1000.0);
+        let delay = crate::process::DelayNode::new(0.01, 0.5, 1.0, 1000.0);
+        let mut impulse = vec![0.0; 60];
+        impulse[0] = 1.0;
+
+        let output = node.process(&impulse);
+        let expected = delay.process(&impulse);
+        for (out, exp) in output.iter().zip(expected.iter()) {
+            assert!((out - exp).abs() < 1e-6);
+        }
+        assert_eq!(node.latency_samples(), 0);
+    }
+
+    #[rstest]
+    fn test_channels_and_reset() {
+        let node = ModDelayNode::new(0.005, 0.002, 3.0, 0.3, 0.7, 1000.0).with_channels(2);
+        // Signal on the left channel only
+        let input: Vec<f32> = (0..400)
+            .flat_map(|i| [(i as f32 * 0.05).sin(), 0.0])
+            .collect();
+        let output = node.process(&input);
+        assert!(output.iter().skip(1).step_by(2).all(|&s| s == 0.0));
+
+        node.reset();
+        assert_eq!(node.process(&input), output);
+        assert_eq!(node.box_clone().node_type(), "mod_delay");
+    }
+}
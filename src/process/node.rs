@@ -16,11 +16,118 @@
 //! // Add processing nodes
 //! chain.add_node(GainNode::new(6.0));  // +6 dB gain
 //! 
+//! // Or build the chain in one expression
+//! let preset = AudioNodeChain::builder()
+//!     .gain(6.0)
+//!     .limiter(-1.0, 0.1, 0.005, 48000.0)
+//!     .build();
+//! 
 //! // Process audio
 //! let input = vec![0.5f32; 1000];
 //! let output = chain.process(&input);
 //! ```
 
+use std::error::Error;
+use std::fmt;
+use crate::buffer::AudioBuffer;
+use super::{AllpassNode, DelayNode, GainNode, LimiterNode};
+
+/// Errors a node can report from [`AudioNode::try_process`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessError {
+    /// The number of samples is not a multiple of the channel count
+    PartialFrame {
+        /// Number of samples passed
+        samples: usize,
+        /// Channel count of the node
+        channels: usize,
+    },
+    /// The input is shorter than the node needs
+    InputTooShort {
+        /// Number of samples passed
+        samples: usize,
+        /// Minimum number of samples
+        required: usize,
+    },
+    /// A node in a chain receives a different channel count than it expects
+    ChannelMismatch {
+        /// Position of the node in the chain
+        node: usize,
+        /// Channel count the node expects
+        expected: usize,
+        /// Channel count produced by the nodes before it
+        channels: usize,
+    },
+    /// A node in a chain processed in place changes the number of samples
+    InPlaceUnsupported {
+        /// Position of the node in the chain
+        node: usize,
+    },
+    /// Any other failure, with a description
+    Other(String),
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessError::PartialFrame { samples, channels } => write!(
+                f,
+                "{} samples are not a whole number of {}-channel frames",
+                samples, channels
+            ),
+            ProcessError::InputTooShort { samples, required } => write!(
+                f,
+                "input of {} samples is too short, at least {} are required",
+                samples, required
+            ),
+            ProcessError::ChannelMismatch { node, expected, channels } => write!(
+                f,
+                "node {} expects {}-channel input but receives {} channels",
+                node, expected, channels
+            ),
+            ProcessError::InPlaceUnsupported { node } => write!(
+                f,
+                "node {} changes the number of samples and cannot process in place",
+                node
+            ),
+            ProcessError::Other(message) => write!(f, "processing failed: {}", message),
+        }
+    }
+}
+
+impl Error for ProcessError {}
+
+/// Format of the audio passing through a node.
+/// 
+/// An [`AudioNodeChain`] builds the context from an [`AudioBuffer`] and updates it
+/// after every node with [`AudioNode::output_context`], so nodes that need the
+/// channel count or sample rate receive it in [`AudioNode::process_ctx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioContext {
+    /// Number of interleaved channels
+    pub channels: u32,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+}
+
+impl AudioContext {
+    /// Creates a new context.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `channels` - Number of interleaved channels
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(channels: u32, sample_rate: u32) -> Self {
+        Self { channels, sample_rate }
+    }
+}
+
+impl From<&AudioBuffer> for AudioContext {
+    fn from(buffer: &AudioBuffer) -> Self {
+        Self::new(buffer.channels, buffer.sample_rate)
+    }
+}
+
 /// Represents an audio processing node that can be chained with other nodes.
 /// 
 /// This trait defines the interface for all audio processing nodes in the system.
@@ -68,6 +175,24 @@ pub trait AudioNode {
     /// 
     /// A new vector containing the processed samples
     fn process(&self, input: &[f32]) -> Vec<f32>;
+
+    /// Process audio samples, reporting invalid input as an error.
+    /// 
+    /// Nodes that can't process every input (e.g. a resampler given a partial frame)
+    /// override this to return a [`ProcessError`] instead of silently adjusting the
+    /// input. The default wraps [`AudioNode::process`] in `Ok`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - Slice of input samples to process
+    /// 
+    /// # Returns
+    /// 
+    /// A new vector containing the processed samples, or a ProcessError if the node
+    /// can't process the input.
+    fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+        Ok(self.process(input))
+    }
     
     /// Process audio samples in-place.
     /// 
@@ -92,6 +217,170 @@ pub trait AudioNode {
     /// Clone trait. It allows nodes to be cloned when needed by the processing
     /// chain.
     fn box_clone(&self) -> Box<dyn AudioNode>;
+
+    /// Get the latency introduced by this node in samples.
+    /// 
+    /// Nodes that delay their output (e.g. a lookahead limiter) report the delay
+    /// here so hosts can align parallel signal paths. The default is no latency.
+    fn latency_samples(&self) -> usize {
+        0
+    }
+
+    /// Get the number of interleaved channels this node expects as input.
+    /// 
+    /// Nodes built for a fixed layout (e.g. a stereo width control) or configured
+    /// for a channel count report it here, so an [`AudioNodeChain`] can detect
+    /// incompatible neighbours. The default is None, meaning any channel count.
+    fn input_channels(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get the number of interleaved channels `process` returns.
+    /// 
+    /// Nodes that change the channel count (e.g. a panner turning mono into stereo)
+    /// override this. The default is [`AudioNode::input_channels`], i.e. the channel
+    /// count passes through unchanged; None means the output has as many channels as
+    /// the input.
+    fn output_channels(&self) -> Option<usize> {
+        self.input_channels()
+    }
+
+    /// Whether the output of this node depends only on the current sample.
+    /// 
+    /// Stateless nodes (e.g. a fixed gain) can process any part of a buffer
+    /// independently, which allows [`AudioNode::process_parallel`] to split the work
+    /// across threads. Nodes with internal state such as envelopes or delay lines must
+    /// return false, which is the default.
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    /// Whether this node can process a buffer in place.
+    /// 
+    /// Nodes whose output has a different number of samples than their input (e.g. a
+    /// resampler) can't, and panic in [`AudioNode::process_in_place`]. They return
+    /// false here so an [`AudioNodeChain`] can report them with
+    /// [`AudioNodeChain::validate_in_place`] before processing. The default is true.
+    fn supports_in_place(&self) -> bool {
+        true
+    }
+
+    /// Enables or disables flushing of denormal numbers in the feedback paths of this node.
+    /// 
+    /// Nodes with feedback (delays, reverbs, IIR filters) flush values decaying into
+    /// the subnormal range to zero with [`flush_denormals`](super::flush_denormals),
+    /// which keeps the CPU load steady while a tail fades out. This is enabled by
+    /// default. Nodes wrapping other nodes pass the setting on, the default
+    /// implementation does nothing for nodes without feedback.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - Whether to flush denormals
+    fn set_flush_denormals(&self, _enabled: bool) {}
+
+    /// Process audio samples knowing their format.
+    /// 
+    /// Nodes that depend on the channel count or sample rate of the stream override
+    /// this, the default ignores the context and calls [`AudioNode::process`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - Interleaved samples to process
+    /// * `_ctx` - Format of `input`
+    /// 
+    /// # Returns
+    /// 
+    /// A vector containing the processed samples
+    fn process_ctx(&self, input: &[f32], _ctx: &AudioContext) -> Vec<f32> {
+        self.process(input)
+    }
+
+    /// Returns the format of the audio this node produces from audio of the given format.
+    /// 
+    /// The default keeps the sample rate and takes the channel count reported by
+    /// [`AudioNode::output_channels`], or that of the input if the node doesn't report
+    /// one. Nodes that change the sample rate override this.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ctx` - Format of the input
+    fn output_context(&self, ctx: &AudioContext) -> AudioContext {
+        AudioContext {
+            channels: self.output_channels().map_or(ctx.channels, |channels| channels as u32),
+            sample_rate: ctx.sample_rate,
+        }
+    }
+
+    /// Process an [`AudioBuffer`], keeping track of its format.
+    /// 
+    /// The samples are processed with [`AudioNode::process_ctx`] and the output
+    /// format is given by [`AudioNode::output_context`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - The audio to process
+    /// 
+    /// # Returns
+    /// 
+    /// A new buffer holding the processed audio and its format
+    fn process_buffer(&self, input: &AudioBuffer) -> AudioBuffer {
+        let ctx = AudioContext::from(input);
+        let output = self.output_context(&ctx);
+        AudioBuffer::from_interleaved(self.process_ctx(&input.samples, &ctx), output.channels, output.sample_rate)
+    }
+
+    /// Process a single frame of interleaved audio in-place.
+    /// 
+    /// This is meant for real-time callbacks that hand over one frame at a time: the
+    /// frame holds one sample per channel and the node's state advances by exactly
+    /// one frame. Processing a buffer frame by frame gives the same result as
+    /// processing it at once. The default calls [`AudioNode::process_in_place`], nodes
+    /// that keep state per frame override this and build their buffer methods on it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `frame` - One sample per channel, in channel order
+    fn process_frame(&self, frame: &mut [f32]) {
+        self.process_in_place(frame);
+    }
+
+    /// Process audio samples in-place, using multiple threads where possible.
+    /// 
+    /// With the `rayon` feature enabled, stateless nodes override this to split the
+    /// buffer into chunks processed in parallel. The default simply calls
+    /// [`AudioNode::process_in_place`]. The result must be identical to the serial path.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buffer` - Mutable slice of samples to process in-place
+    fn process_parallel(&self, buffer: &mut [f32]) {
+        self.process_in_place(buffer);
+    }
+}
+
+/// Number of samples each thread processes at a time in parallel processing.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_SIZE: usize = 1 << 14;
+
+/// Applies `f` to chunks of the buffer, in parallel when the `rayon` feature is enabled.
+/// 
+/// Chunks are a multiple of `frame_size` samples long so that interleaved frames
+/// are never split. `f` must not depend on the position of the chunk in the buffer.
+pub(crate) fn for_each_chunk<F>(buffer: &mut [f32], frame_size: usize, f: F)
+where
+    F: Fn(&mut [f32]) + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        let chunk_size = PARALLEL_CHUNK_SIZE.div_ceil(frame_size.max(1)) * frame_size.max(1);
+        buffer.par_chunks_mut(chunk_size).for_each(f);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let _ = frame_size;
+        f(buffer);
+    }
 }
 
 /// A chain of audio processing nodes that can be executed sequentially.
@@ -115,9 +404,30 @@ pub trait AudioNode {
 /// let input = vec![0.5f32; 1000];
 /// let output = chain.process(&input);
 /// ```
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct AudioNodeChain {
-    nodes: Vec<Box<dyn AudioNode>>,
+    nodes: Vec<ChainEntry>,
+}
+
+/// A node in an [`AudioNodeChain`] together with its bypass state.
+struct ChainEntry {
+    node: Box<dyn AudioNode>,
+    bypassed: bool,
+}
+
+impl ChainEntry {
+    fn new(node: Box<dyn AudioNode>) -> Self {
+        Self { node, bypassed: false }
+    }
+}
+
+impl Clone for ChainEntry {
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node.box_clone(),
+            bypassed: self.bypassed,
+        }
+    }
 }
 
 impl AudioNodeChain {
@@ -125,6 +435,13 @@ impl AudioNodeChain {
     pub fn new() -> Self {
         Self { nodes: Vec::new() }
     }
+
+    /// Creates a builder to assemble a chain in one expression.
+    /// 
+    /// See [`AudioNodeChainBuilder`].
+    pub fn builder() -> AudioNodeChainBuilder {
+        AudioNodeChainBuilder::default()
+    }
     
     /// Adds a node to the end of the processing chain.
     /// 
@@ -134,13 +451,155 @@ impl AudioNodeChain {
     /// 
     /// * `node` - The node to add to the chain
     pub fn add_node<T: AudioNode + 'static>(&mut self, node: T) {
-        self.nodes.push(Box::new(node));
+        self.nodes.push(ChainEntry::new(Box::new(node)));
+    }
+
+    /// Inserts a node at the given position in the chain.
+    /// 
+    /// Nodes at and after `index` are shifted back. An `index` past the end of the
+    /// chain appends the node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Position of the new node
+    /// * `node` - The node to insert
+    pub fn insert_node<T: AudioNode + 'static>(&mut self, index: usize, node: T) {
+        let index = index.min(self.nodes.len());
+        self.nodes.insert(index, ChainEntry::new(Box::new(node)));
+    }
+
+    /// Removes the node at the given position from the chain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Position of the node to remove
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Some(node) with the removed node, or None if `index` is out of range.
+    pub fn remove_node(&mut self, index: usize) -> Option<Box<dyn AudioNode>> {
+        if index < self.nodes.len() {
+            Some(self.nodes.remove(index).node)
+        } else {
+            None
+        }
+    }
+
+    /// Enables or disables bypass for the node at the given position.
+    /// 
+    /// A bypassed node stays in the chain but is skipped during processing, so its
+    /// input is passed on unchanged. Out-of-range indices are ignored.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Position of the node
+    /// * `bypassed` - Whether the node should be skipped
+    pub fn set_bypassed(&mut self, index: usize, bypassed: bool) {
+        if let Some(entry) = self.nodes.get_mut(index) {
+            entry.bypassed = bypassed;
+        }
+    }
+
+    /// Returns true if the node at the given position is bypassed.
+    /// 
+    /// Returns false for out-of-range indices.
+    pub fn is_bypassed(&self, index: usize) -> bool {
+        self.nodes.get(index).is_some_and(|entry| entry.bypassed)
+    }
+
+    /// Returns an iterator over the nodes in processing order.
+    /// 
+    /// Bypassed nodes are included, use [`AudioNodeChain::is_bypassed`] to check their state.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn AudioNode> + '_ {
+        self.nodes.iter().map(|entry| entry.node.as_ref())
+    }
+
+    /// Returns the type identifier of each node in processing order.
+    /// 
+    /// This is useful for logging or displaying a chain, e.g. "gain → limiter".
+    pub fn node_types(&self) -> Vec<&'static str> {
+        self.iter().map(|node| node.node_type()).collect()
+    }
+
+    /// Returns the total latency of the chain in samples.
+    /// 
+    /// This is the sum of [`AudioNode::latency_samples`] over all nodes that are not
+    /// bypassed.
+    pub fn total_latency_samples(&self) -> usize {
+        self.nodes.iter()
+            .filter(|entry| !entry.bypassed)
+            .map(|entry| entry.node.latency_samples())
+            .sum()
+    }
+
+    /// Checks that each node receives the channel count it expects.
+    /// 
+    /// The channel count is followed through all nodes that are not bypassed, using
+    /// [`AudioNode::input_channels`] and [`AudioNode::output_channels`]. Nodes that
+    /// accept any channel count pass on whatever they receive, so only nodes with a
+    /// known channel count are compared.
+    /// 
+    /// # Returns
+    /// 
+    /// Ok(()) if all nodes are compatible, or `ProcessError::ChannelMismatch` for the
+    /// first node receiving a channel count it doesn't expect.
+    pub fn validate_channels(&self) -> Result<(), ProcessError> {
+        let mut channels = None;
+        for (index, entry) in self.nodes.iter().enumerate().filter(|(_, entry)| !entry.bypassed) {
+            if let (Some(actual), Some(expected)) = (channels, entry.node.input_channels()) {
+                if actual != expected {
+                    return Err(ProcessError::ChannelMismatch { node: index, expected, channels: actual });
+                }
+            }
+            channels = entry.node.output_channels().or(channels);
+        }
+        Ok(())
+    }
+
+    /// Checks that every node can process in place.
+    /// 
+    /// [`AudioNodeChain::process_in_place`] and the methods built on it
+    /// ([`AudioNodeChain::process_parallel`], [`AudioNodeChain::process_blocks`] and
+    /// [`AudioNodeChain::process_frame`]) panic on nodes that change the number of
+    /// samples. Bypassed nodes are not checked.
+    /// 
+    /// # Returns
+    /// 
+    /// Ok(()) if the chain can process in place, or `ProcessError::InPlaceUnsupported`
+    /// for the first node reporting false from [`AudioNode::supports_in_place`].
+    pub fn validate_in_place(&self) -> Result<(), ProcessError> {
+        match self.nodes.iter().position(|entry| !entry.bypassed && !entry.node.supports_in_place()) {
+            Some(node) => Err(ProcessError::InPlaceUnsupported { node }),
+            None => Ok(()),
+        }
+    }
+
+    /// Enables or disables flushing of denormal numbers in all nodes of the chain.
+    /// 
+    /// The setting is passed to every node, including bypassed ones, with
+    /// [`AudioNode::set_flush_denormals`]. Nodes added afterwards keep their own setting.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - Whether to flush denormals
+    pub fn set_flush_denormals(&self, enabled: bool) {
+        self.nodes.iter().for_each(|entry| entry.node.set_flush_denormals(enabled));
+    }
+
+    /// Returns the number of nodes in the chain.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if the chain contains no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
     }
     
     /// Processes audio through the entire chain.
     /// 
     /// Each node in the chain processes the audio sequentially, with each node's
-    /// output becoming the input for the next node.
+    /// output becoming the input for the next node. Bypassed nodes are skipped.
     /// 
     /// # Arguments
     /// 
@@ -151,12 +610,121 @@ impl AudioNodeChain {
     /// A new vector containing the processed samples
     pub fn process(&self, input: &[f32]) -> Vec<f32> {
         let mut buffer = input.to_vec();
-        for node in &self.nodes {
-            buffer = node.process(&buffer);
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            buffer = entry.node.process(&buffer);
         }
         buffer
     }
     
+    /// Processes audio through the entire chain, stopping at the first error.
+    /// 
+    /// Like [`AudioNodeChain::process`], but each node is run through
+    /// [`AudioNode::try_process`] and the first error is returned without processing
+    /// the remaining nodes. Before any processing the channel counts of the nodes
+    /// are checked with [`AudioNodeChain::validate_channels`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - The input samples to process
+    /// 
+    /// # Returns
+    /// 
+    /// A new vector containing the processed samples, or the ProcessError of the
+    /// first failing node.
+    pub fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+        self.validate_channels()?;
+        let mut buffer = input.to_vec();
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            buffer = entry.node.try_process(&buffer)?;
+        }
+        Ok(buffer)
+    }
+
+    /// Processes audio through the entire chain in-place, parallelizing stateless nodes.
+    /// 
+    /// Nodes reporting [`AudioNode::is_stateless`] are run through
+    /// [`AudioNode::process_parallel`], all other nodes are processed serially. The
+    /// result is identical to [`AudioNodeChain::process_in_place`]. Without the `rayon`
+    /// feature everything runs on the calling thread.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buffer` - Mutable slice of samples to process
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if a node can't process in place, see [`AudioNodeChain::validate_in_place`].
+    pub fn process_parallel(&self, buffer: &mut [f32]) {
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            if entry.node.is_stateless() {
+                entry.node.process_parallel(buffer);
+            } else {
+                entry.node.process_in_place(buffer);
+            }
+        }
+    }
+
+    /// Processes audio through the chain in fixed-size blocks.
+    /// 
+    /// The input is copied block by block into a reusable scratch buffer which is then
+    /// processed in-place, so apart from the output the memory used stays bounded by
+    /// `block_size` regardless of the input length. Stateful nodes (e.g. a limiter)
+    /// see one continuous stream, so the result is identical to processing the whole
+    /// buffer at once.
+    /// 
+    /// As processing is done in-place, nodes that change the number of samples are
+    /// not supported.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - The input samples to process
+    /// * `block_size` - Number of samples per block, should be a multiple of the channel
+    ///   count for nodes that work on whole frames
+    /// 
+    /// # Returns
+    /// 
+    /// A new vector containing the processed samples
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if a node can't process in place, see [`AudioNodeChain::validate_in_place`].
+    pub fn process_blocks(&self, input: &[f32], block_size: usize) -> Vec<f32> {
+        let block_size = block_size.max(1);
+        let mut output = Vec::with_capacity(input.len());
+        let mut scratch = vec![0.0; block_size];
+        for block in input.chunks(block_size) {
+            let scratch = &mut scratch[..block.len()];
+            scratch.copy_from_slice(block);
+            self.process_in_place(scratch);
+            output.extend_from_slice(scratch);
+        }
+        output
+    }
+
+    /// Processes an [`AudioBuffer`] through the entire chain.
+    /// 
+    /// Like [`AudioNodeChain::process`], but an [`AudioContext`] built from the buffer
+    /// is passed to each node's [`AudioNode::process_ctx`] and updated with its
+    /// [`AudioNode::output_context`], so the output carries the channel count and
+    /// sample rate left by the last node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - The audio to process
+    /// 
+    /// # Returns
+    /// 
+    /// A new buffer holding the processed audio and its format
+    pub fn process_buffer(&self, input: &AudioBuffer) -> AudioBuffer {
+        let mut ctx = AudioContext::from(input);
+        let mut samples = input.samples.clone();
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            samples = entry.node.process_ctx(&samples, &ctx);
+            ctx = entry.node.output_context(&ctx);
+        }
+        AudioBuffer::from_interleaved(samples, ctx.channels, ctx.sample_rate)
+    }
+
     /// Processes audio through the entire chain in-place.
     /// 
     /// Similar to `process`, but modifies the input buffer directly instead
@@ -166,16 +734,117 @@ impl AudioNodeChain {
     /// # Arguments
     /// 
     /// * `buffer` - Mutable slice of samples to process
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if a node can't process in place, see [`AudioNodeChain::validate_in_place`]
+    /// and [`AudioNodeChain::try_process_in_place`].
     pub fn process_in_place(&self, buffer: &mut [f32]) {
-        for node in &self.nodes {
-            node.process_in_place(buffer);
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            entry.node.process_in_place(buffer);
+        }
+    }
+
+    /// Processes audio through the entire chain in-place, reporting unsupported nodes.
+    /// 
+    /// Like [`AudioNodeChain::process_in_place`], but the chain is first checked with
+    /// [`AudioNodeChain::validate_in_place`] and the buffer is left untouched if a node
+    /// can't process in place.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buffer` - Mutable slice of samples to process
+    /// 
+    /// # Returns
+    /// 
+    /// Ok(()) once the buffer is processed, or `ProcessError::InPlaceUnsupported` for
+    /// the first node that changes the number of samples.
+    pub fn try_process_in_place(&self, buffer: &mut [f32]) -> Result<(), ProcessError> {
+        self.validate_in_place()?;
+        self.process_in_place(buffer);
+        Ok(())
+    }
+
+    /// Processes a single frame through the entire chain in-place.
+    /// 
+    /// Each node gets the frame through [`AudioNode::process_frame`], so a real-time
+    /// callback can run the chain one frame at a time. The nodes must not change the
+    /// number of samples.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `frame` - One sample per channel, in channel order
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if a node can't process in place, see [`AudioNodeChain::validate_in_place`].
+    pub fn process_frame(&self, frame: &mut [f32]) {
+        for entry in self.nodes.iter().filter(|entry| !entry.bypassed) {
+            entry.node.process_frame(frame);
         }
     }
 }
 
+/// Fluent builder for an [`AudioNodeChain`].
+/// 
+/// Each method appends a node, in the same order as [`AudioNodeChain::add_node`]
+/// would. Common nodes have shortcuts taking their constructor arguments, any other
+/// node is added with [`AudioNodeChainBuilder::node`].
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use sonex::process::{AudioNodeChain, ShapeFunction, WaveshaperNode};
+/// 
+/// let chain = AudioNodeChain::builder()
+///     .gain(6.0)
+///     .node(WaveshaperNode::new(ShapeFunction::Tanh, 2.0))
+///     .allpass(1000.0, 0.7, 48000.0)
+///     .limiter(-1.0, 0.1, 0.005, 48000.0)
+///     .build();
+/// ```
+#[derive(Default, Clone)]
+pub struct AudioNodeChainBuilder {
+    chain: AudioNodeChain,
+}
+
+impl AudioNodeChainBuilder {
+    /// Appends any node.
+    pub fn node<T: AudioNode + 'static>(mut self, node: T) -> Self {
+        self.chain.add_node(node);
+        self
+    }
+
+    /// Appends a [`GainNode`], see [`GainNode::new`].
+    pub fn gain(self, db: f32) -> Self {
+        self.node(GainNode::new(db))
+    }
+
+    /// Appends a mono [`LimiterNode`], see [`LimiterNode::new`].
+    pub fn limiter(self, threshold: f32, release_time_sec: f32, lookahead_sec: f32, sample_rate: f32) -> Self {
+        self.node(LimiterNode::new(threshold, release_time_sec, lookahead_sec, sample_rate))
+    }
+
+    /// Appends a mono [`DelayNode`], see [`DelayNode::new`].
+    pub fn delay(self, delay_sec: f32, feedback: f32, mix: f32, sample_rate: f32) -> Self {
+        self.node(DelayNode::new(delay_sec, feedback, mix, sample_rate))
+    }
+
+    /// Appends a mono 2nd-order [`AllpassNode`], see [`AllpassNode::new`].
+    pub fn allpass(self, frequency: f32, q: f32, sample_rate: f32) -> Self {
+        self.node(AllpassNode::new(frequency, q, sample_rate))
+    }
+
+    /// Returns the assembled chain.
+    pub fn build(self) -> AudioNodeChain {
+        self.chain
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::{InvertNode, PannerNode, ParallelChain, ResampleNode, ResampleQuality, StereoWidthNode};
     use rstest::*;
 
     #[derive(Clone)]
@@ -264,6 +933,382 @@ mod tests {
         assert_eq!(buffer, expected);
     }
 
+    #[rstest]
+    fn test_chain_remove_middle(test_input: Vec<f32>) {
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(TestNode::new(2.0));
+        chain.add_node(TestNode::new(3.0));
+        chain.add_node(TestNode::new(5.0));
+        assert_eq!(chain.len(), 3);
+
+        let removed = chain.remove_node(1).unwrap();
+        assert_eq!(removed.process(&[1.0]), vec![3.0]);
+        assert_eq!(chain.len(), 2);
+
+        let mut expected_chain = AudioNodeChain::new();
+        expected_chain.add_node(TestNode::new(2.0));
+        expected_chain.add_node(TestNode::new(5.0));
+        assert_eq!(chain.process(&test_input), expected_chain.process(&test_input));
+    }
+
+    #[rstest]
+    fn test_chain_remove_out_of_range() {
+        let mut chain = AudioNodeChain::new();
+        assert!(chain.is_empty());
+        assert!(chain.remove_node(0).is_none());
+
+        chain.add_node(TestNode::new(2.0));
+        assert!(chain.remove_node(1).is_none());
+        assert_eq!(chain.len(), 1);
+    }
+
+    #[rstest]
+    fn test_chain_insert(test_input: Vec<f32>) {
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(TestNode::new(2.0));
+        chain.insert_node(0, TestNode::new(3.0));
+        chain.insert_node(10, TestNode::new(0.5));
+        assert_eq!(chain.len(), 3);
+        assert!(!chain.is_empty());
+        assert_eq!(chain.process(&test_input), vec![3.0, 6.0, 9.0]);
+    }
+
+    #[rstest]
+    fn test_chain_bypass(test_input: Vec<f32>) {
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(GainNode::new(6.0));
+        chain.add_node(TestNode::new(2.0));
+
+        chain.set_bypassed(0, true);
+        assert!(chain.is_bypassed(0));
+        assert!(!chain.is_bypassed(1));
+        assert!(!chain.is_bypassed(5));
+        assert_eq!(chain.process(&test_input), vec![2.0, 4.0, 6.0]);
+
+        chain.set_bypassed(1, true);
+        let mut buffer = test_input.clone();
+        chain.process_in_place(&mut buffer);
+        assert_eq!(buffer, test_input);
+
+        chain.set_bypassed(0, false);
+        let output = chain.process(&test_input);
+        for (out, inp) in output.iter().zip(test_input.iter()) {
+            assert!((out - inp * 1.995262).abs() < 0.0001);
+        }
+    }
+
+    #[rstest]
+    fn test_chain_node_types() {
+        let mut chain = AudioNodeChain::new();
+        assert!(chain.node_types().is_empty());
+
+        chain.add_node(GainNode::new(3.0));
+        chain.add_node(TestNode::new(2.0));
+        chain.add_node(LimiterNode::new(-1.0, 0.1, 0.001, 44100.0));
+        assert_eq!(chain.node_types(), vec!["gain", "test", "limiter"]);
+
+        let outputs: Vec<Vec<f32>> = chain.iter()
+            .skip(1)
+            .take(1)
+            .map(|node| node.process(&[1.0]))
+            .collect();
+        assert_eq!(outputs, vec![vec![2.0]]);
+    }
+
+    #[rstest]
+    fn test_chain_latency() {
+        let mut chain = AudioNodeChain::new();
+        assert_eq!(chain.total_latency_samples(), 0);
+
+        chain.add_node(GainNode::new(3.0));
+        chain.add_node(LimiterNode::new(-1.0, 0.1, 0.001, 44100.0));
+        assert_eq!(chain.total_latency_samples(), 44);
+
+        chain.add_node(LimiterNode::new(-1.0, 0.1, 0.001, 44100.0));
+        assert_eq!(chain.total_latency_samples(), 88);
+
+        chain.set_bypassed(2, true);
+        assert_eq!(chain.total_latency_samples(), 44);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(64)]
+    #[case(1000)]
+    #[case(4096)]
+    fn test_process_blocks_matches_whole(#[case] block_size: usize) {
+        let build = || {
+            let mut chain = AudioNodeChain::new();
+            chain.add_node(GainNode::new(6.0));
+            chain.add_node(LimiterNode::new(-3.0, 0.05, 0.001, 44100.0));
+            chain.add_node(DelayNode::new(0.01, 0.5, 0.3, 44100.0));
+            chain
+        };
+        let input: Vec<f32> = (0..10_000)
+            .map(|i| (i as f32 * 0.05).sin() * if i % 3000 < 100 { 1.0 } else { 0.3 })
+            .collect();
+
+        let whole = build().process(&input);
+        let blocks = build().process_blocks(&input, block_size);
+        assert_eq!(whole, blocks);
+    }
+
+    #[rstest]
+    fn test_chain_process_parallel_matches_serial() {
+        let build = || {
+            let mut chain = AudioNodeChain::new();
+            chain.add_node(GainNode::new(-3.0));
+            chain.add_node(LimiterNode::new(-6.0, 0.05, 0.001, 44100.0));
+            chain.add_node(InvertNode::new());
+            chain
+        };
+        let input: Vec<f32> = (0..100_000).map(|i| (i as f32 * 0.01).sin()).collect();
+
+        let mut serial = input.clone();
+        build().process_in_place(&mut serial);
+        let mut parallel = input.clone();
+        build().process_parallel(&mut parallel);
+        assert_eq!(parallel, serial);
+    }
+
+    #[rstest]
+    fn test_builder_matches_add_node() {
+        let built = AudioNodeChain::builder()
+            .gain(6.0)
+            .node(TestNode::new(0.5))
+            .allpass(800.0, 0.7, 44100.0)
+            .delay(0.01, 0.5, 0.3, 44100.0)
+            .limiter(-3.0, 0.05, 0.001, 44100.0)
+            .build();
+
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(GainNode::new(6.0));
+        chain.add_node(TestNode::new(0.5));
+        chain.add_node(AllpassNode::new(800.0, 0.7, 44100.0));
+        chain.add_node(DelayNode::new(0.01, 0.5, 0.3, 44100.0));
+        chain.add_node(LimiterNode::new(-3.0, 0.05, 0.001, 44100.0));
+
+        assert_eq!(built.node_types(), chain.node_types());
+        assert_eq!(built.total_latency_samples(), chain.total_latency_samples());
+        let input: Vec<f32> = (0..5000).map(|i| (i as f32 * 0.05).sin()).collect();
+        assert_eq!(built.process(&input), chain.process(&input));
+        assert!(AudioNodeChain::builder().build().is_empty());
+    }
+
+    /// Fails on inputs shorter than `min_len`.
+    #[derive(Clone)]
+    struct FailingNode {
+        min_len: usize,
+    }
+
+    impl AudioNode for FailingNode {
+        fn process(&self, input: &[f32]) -> Vec<f32> {
+            input.to_vec()
+        }
+
+        fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+            if input.len() < self.min_len {
+                return Err(ProcessError::InputTooShort { samples: input.len(), required: self.min_len });
+            }
+            Ok(self.process(input))
+        }
+
+        fn process_in_place(&self, _buffer: &mut [f32]) {}
+
+        fn node_type(&self) -> &'static str {
+            "failing"
+        }
+
+        fn box_clone(&self) -> Box<dyn AudioNode> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[rstest]
+    fn test_chain_try_process_propagates_error(test_input: Vec<f32>) {
+        let chain = AudioNodeChain::builder()
+            .node(TestNode::new(2.0))
+            .node(FailingNode { min_len: 4 })
+            .node(TestNode::new(3.0))
+            .build();
+
+        let error = chain.try_process(&test_input).unwrap_err();
+        assert_eq!(error, ProcessError::InputTooShort { samples: 3, required: 4 });
+        assert_eq!(error.to_string(), "input of 3 samples is too short, at least 4 are required");
+
+        assert_eq!(chain.try_process(&[1.0; 4]).unwrap(), vec![6.0; 4]);
+        assert_eq!(TestNode::new(2.0).try_process(&test_input).unwrap(), vec![2.0, 4.0, 6.0]);
+
+        // A bypassed node can't fail
+        let mut chain = chain;
+        chain.set_bypassed(1, true);
+        assert_eq!(chain.try_process(&test_input).unwrap(), chain.process(&test_input));
+    }
+
+    #[rstest]
+    fn test_chain_rejects_channel_mismatch() {
+        // The second panner expects mono but receives the stereo output of the first
+        let mut chain = AudioNodeChain::builder()
+            .node(PannerNode::new(-0.5))
+            .gain(-6.0)
+            .node(PannerNode::new(0.5))
+            .build();
+
+        let error = chain.try_process(&[0.5; 8]).unwrap_err();
+        assert_eq!(error, ProcessError::ChannelMismatch { node: 2, expected: 1, channels: 2 });
+        assert_eq!(error.to_string(), "node 2 expects 1-channel input but receives 2 channels");
+
+        // A stereo node after the panner is compatible
+        chain.remove_node(2);
+        chain.add_node(StereoWidthNode::new(1.5));
+        assert_eq!(chain.validate_channels(), Ok(()));
+        assert_eq!(chain.try_process(&[0.5; 8]).unwrap().len(), 16);
+
+        // Bypassed nodes don't take part in the check
+        chain.insert_node(1, PannerNode::new(0.0));
+        assert!(chain.validate_channels().is_err());
+        chain.set_bypassed(1, true);
+        assert!(chain.validate_channels().is_ok());
+
+        assert_eq!(TestNode::new(1.0).input_channels(), None);
+        assert_eq!(TestNode::new(1.0).output_channels(), None);
+    }
+
+    #[rstest]
+    fn test_chain_validates_configured_channels() {
+        // Stateful nodes report the channel count they are configured for
+        let mut chain = AudioNodeChain::builder()
+            .node(PannerNode::new(0.0))
+            .node(LimiterNode::new(-1.0, 0.1, 0.001, 1000.0).with_channels(2))
+            .node(DelayNode::new(0.002, 0.3, 0.5, 1000.0))
+            .build();
+        assert_eq!(chain.validate_channels(), Err(ProcessError::ChannelMismatch { node: 2, expected: 1, channels: 2 }));
+
+        chain.remove_node(2);
+        chain.add_node(DelayNode::new(0.002, 0.3, 0.5, 1000.0).with_channels(2));
+        assert_eq!(chain.validate_channels(), Ok(()));
+        assert_eq!(chain.try_process(&[0.5; 8]).unwrap().len(), 16);
+        assert_eq!(GainNode::per_channel(vec![-3.0, 2.0], 2).output_channels(), Some(2));
+        assert_eq!(GainNode::new(-3.0).input_channels(), None);
+    }
+
+    #[rstest]
+    fn test_chain_validates_in_place() {
+        let mut chain = AudioNodeChain::builder()
+            .gain(-6.0)
+            .node(ResampleNode::new(48000, 16000, 1, ResampleQuality::Low))
+            .build();
+        let error = chain.validate_in_place().unwrap_err();
+        assert_eq!(error, ProcessError::InPlaceUnsupported { node: 1 });
+        assert_eq!(error.to_string(), "node 1 changes the number of samples and cannot process in place");
+
+        let mut buffer = vec![1.0; 48];
+        assert_eq!(chain.try_process_in_place(&mut buffer), Err(error));
+        assert_eq!(buffer, vec![1.0; 48]);
+
+        // Bypassed nodes don't take part in the check
+        chain.set_bypassed(1, true);
+        assert_eq!(chain.try_process_in_place(&mut buffer), Ok(()));
+        assert_eq!(buffer, GainNode::new(-6.0).process(&[1.0; 48]));
+        assert!(!PannerNode::new(0.0).supports_in_place());
+    }
+
+    #[rstest]
+    fn test_chain_denormal_flushing_toggle() {
+        // The setting reaches nodes nested in a parallel chain
+        let mut parallel = ParallelChain::new();
+        parallel.add_branch(AudioNodeChain::builder().delay(0.001, 0.5, 1.0, 1000.0).build(), 1.0);
+        let chain = AudioNodeChain::builder()
+            .delay(0.001, 0.5, 1.0, 1000.0)
+            .node(parallel)
+            .build();
+        let mut input = vec![0.0; 600];
+        input[0] = 1.0;
+        assert!(chain.process(&input).iter().all(|s| !s.is_subnormal()));
+
+        // Without flushing the echoes linger in the subnormal range before underflowing
+        chain.set_flush_denormals(false);
+        assert!(chain.process(&input).iter().any(|s| s.is_subnormal()));
+
+        chain.set_flush_denormals(true);
+        let output = chain.process(&input);
+        assert!(output.iter().all(|s| !s.is_subnormal()));
+        assert_eq!(output[599], 0.0);
+    }
+
+    #[rstest]
+    fn test_chain_process_buffer_tracks_format() {
+        let chain = AudioNodeChain::builder()
+            .gain(-6.0)
+            .node(PannerNode::new(0.0))
+            .node(ResampleNode::new(48000, 16000, 2, ResampleQuality::Low))
+            .build();
+        let input = AudioBuffer::from_interleaved(vec![0.5; 480], 1, 48000);
+        let output = chain.process_buffer(&input);
+
+        assert_eq!((output.channels, output.sample_rate), (2, 16000));
+        assert_eq!(output.n_frames(), 160);
+        assert_eq!(output.samples, chain.process(&input.samples));
+        assert_eq!(TestNode::new(2.0).process_buffer(&input).channels, 1);
+    }
+
+    #[rstest]
+    fn test_chain_process_frame() {
+        let chain = AudioNodeChain::builder()
+            .gain(-6.0)
+            .node(DelayNode::new(0.002, 0.3, 0.5, 1000.0).with_channels(2))
+            .node(InvertNode::new())
+            .build();
+        let input: Vec<f32> = (0..20).map(|i| (i as f32 * 0.7).sin()).collect();
+        let expected = chain.clone().process(&input);
+
+        let mut frames = input;
+        frames.chunks_exact_mut(2).for_each(|frame| chain.process_frame(frame));
+        assert_eq!(frames, expected);
+    }
+
+    /// Outputs the sample rate it is told about, one value per input sample.
+    #[derive(Clone)]
+    struct RateProbe;
+
+    impl AudioNode for RateProbe {
+        fn process(&self, input: &[f32]) -> Vec<f32> {
+            input.to_vec()
+        }
+
+        fn process_in_place(&self, _buffer: &mut [f32]) {}
+
+        fn process_ctx(&self, input: &[f32], ctx: &AudioContext) -> Vec<f32> {
+            vec![ctx.sample_rate as f32; input.len()]
+        }
+
+        fn node_type(&self) -> &'static str {
+            "rate_probe"
+        }
+
+        fn box_clone(&self) -> Box<dyn AudioNode> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[rstest]
+    fn test_chain_threads_context() {
+        let chain = AudioNodeChain::builder()
+            .gain(6.0)
+            .node(ResampleNode::new(44100, 22050, 2, ResampleQuality::Low))
+            .node(RateProbe)
+            .build();
+        let input = AudioBuffer::from_interleaved(vec![0.25; 1764], 2, 44100);
+        let output = chain.process_buffer(&input);
+
+        assert_eq!(AudioContext::from(&output), AudioContext::new(2, 22050));
+        assert_eq!(output.n_frames(), 441);
+        assert!(output.samples.iter().all(|&s| s == 22050.0));
+
+        let resampler = ResampleNode::new(44100, 22050, 2, ResampleQuality::Low);
+        assert_eq!(resampler.output_context(&AudioContext::new(2, 44100)), AudioContext::new(2, 22050));
+    }
+
     #[rstest]
     fn test_box_clone(test_node: TestNode, test_input: Vec<f32>) {
         let cloned = test_node.box_clone();
@@ -0,0 +1,245 @@
+//! Loudness normalization processing node.
+//!
+//! This module provides a node that brings audio to a target integrated loudness
+//! (LUFS), as required by most streaming and podcast platforms (e.g. -14 LUFS for
+//! Spotify, -16 LUFS for Apple Podcasts). For quick leveling without a loudness
+//! measurement, [`autogain_to_rms`] brings audio to a target RMS level instead.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, LoudnessNormalizeNode};
+//!
+//! // Normalize mono 48 kHz audio to -14 LUFS
+//! let node = LoudnessNormalizeNode::new(-14.0, 1, 48000);
+//!
+//! let input = vec![0.5f32; 48000 * 5];
+//! let output = node.process(&input);
+//! ```
+
+use super::gain::{gain_db_in_place, GainNode};
+use super::node::AudioNode;
+use crate::analytic::normalization_gain_db;
+use crate::analytic::stats::rms_dbfs;
+
+/// An audio processing node that normalizes audio to a target integrated loudness.
+///
+/// The integrated loudness of each buffer passed to the node is measured and a
+/// uniform gain is applied so that the result matches the target. Since integrated
+/// loudness describes a whole programme, the node should be given the complete
+/// audio rather than small blocks.
+///
+/// If the loudness cannot be measured (e.g. silence), the audio is passed through
+/// unchanged.
+#[derive(Clone)]
+pub struct LoudnessNormalizeNode {
+    target_lufs: f64,
+    channels: u32,
+    sample_rate: u32,
+}
+
+impl LoudnessNormalizeNode {
+    /// Creates a new loudness normalization node.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_lufs` - Target integrated loudness in LUFS
+    /// * `channels` - Number of audio channels
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(target_lufs: f64, channels: u32, sample_rate: u32) -> Self {
+        Self {
+            target_lufs,
+            channels,
+            sample_rate,
+        }
+    }
+
+    /// Returns the target loudness in LUFS.
+    pub fn target_lufs(&self) -> f64 {
+        self.target_lufs
+    }
+
+    /// Sets a new target loudness in LUFS.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_lufs` - New target loudness in LUFS
+    pub fn set_target_lufs(&mut self, target_lufs: f64) {
+        self.target_lufs = target_lufs;
+    }
+
+    fn gain_node(&self, input: &[f32]) -> Option<GainNode> {
+        normalization_gain_db(input, self.channels, self.sample_rate, self.target_lufs)
+            .map(|db| GainNode::new(db as f32))
+    }
+}
+
+impl AudioNode for LoudnessNormalizeNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        match self.gain_node(input) {
+            Some(node) => node.process(input),
+            None => input.to_vec(),
+        }
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        if let Some(node) = self.gain_node(buffer) {
+            node.process_in_place(buffer);
+        }
+    }
+
+    fn node_type(&self) -> &'static str {
+        "loudness_normalize"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels as usize)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+/// Levels audio in-place to a target RMS level.
+///
+/// Unlike peak normalization, which scales the loudest sample to a target, this
+/// targets the average level of the audio. The RMS is measured over all channels of
+/// the whole frames together and a uniform gain is applied, so the gain can push
+/// peaks above 0 dBFS. Silent or empty input is left unchanged.
+///
+/// # Arguments
+///
+/// * `samples` - Mutable slice of interleaved audio samples to level
+/// * `channels` - Number of interleaved channels
+/// * `target_rms_dbfs` - Target RMS level in dBFS
+///
+/// # Returns
+///
+/// The applied gain in dB, 0.0 if the input is silent.
+///
+/// # Example
+///
+/// ```no_run
+/// use sonex::process::autogain_to_rms;
+///
+/// let mut samples = vec![0.01f32; 48000 * 2];
+/// let applied_db = autogain_to_rms(&mut samples, 2, -20.0);
+/// ```
+pub fn autogain_to_rms(samples: &mut [f32], channels: usize, target_rms_dbfs: f32) -> f32 {
+    let frames = samples.len() / channels.max(1);
+    let rms = rms_dbfs(&samples[..frames * channels.max(1)]);
+    if !rms.is_finite() {
+        return 0.0;
+    }
+    let db = target_rms_dbfs - rms;
+    gain_db_in_place(samples, db);
+    db
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::Meter;
+    use crate::io::AudioReader;
+    use crate::process::gain_db;
+    use crate::synth::sine;
+    use rstest::*;
+
+    struct TestAudio {
+        samples: Vec<f32>,
+        channels: u32,
+        sample_rate: u32,
+    }
+
+    #[fixture]
+    fn test_audio() -> TestAudio {
+        let mut reader = AudioReader::new("audio/sin_100Hz_-3dBFS_3s.wav").unwrap();
+        let mut samples = Vec::new();
+        while let Ok(Some(packet)) = reader.read_packet() {
+            samples.extend(packet);
+        }
+        TestAudio {
+            samples,
+            channels: reader.channels() as u32,
+            sample_rate: reader.sample_rate(),
+        }
+    }
+
+    #[rstest]
+    #[case(-14.0)]
+    #[case(-23.0)]
+    fn test_normalization_gain_reaches_target(#[case] target: f64, test_audio: TestAudio) {
+        let gain = normalization_gain_db(
+            &test_audio.samples,
+            test_audio.channels,
+            test_audio.sample_rate,
+            target,
+        )
+        .unwrap();
+
+        let normalized = gain_db(&test_audio.samples, gain as f32);
+        let meter = Meter::from_samples(&normalized, test_audio.channels, test_audio.sample_rate).unwrap();
+        let lufs = meter.lufs_integrated().unwrap();
+        assert!((lufs - target).abs() < 0.1, "expected {} LUFS, got {}", target, lufs);
+    }
+
+    #[rstest]
+    fn test_normalization_gain_silence() {
+        let silence = vec![0.0f32; 44100 * 2];
+        assert_eq!(normalization_gain_db(&silence, 1, 44100, -14.0), None);
+    }
+
+    #[rstest]
+    fn test_node_reaches_target(test_audio: TestAudio) {
+        let node = LoudnessNormalizeNode::new(-14.0, test_audio.channels, test_audio.sample_rate);
+
+        let output = node.process(&test_audio.samples);
+        let mut buffer = test_audio.samples.clone();
+        node.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+
+        let meter = Meter::from_samples(&output, test_audio.channels, test_audio.sample_rate).unwrap();
+        assert!((meter.lufs_integrated().unwrap() + 14.0).abs() < 0.1);
+    }
+
+    #[rstest]
+    fn test_node_passes_through_silence() {
+        let node = LoudnessNormalizeNode::new(-14.0, 1, 44100);
+        let silence = vec![0.0f32; 44100];
+        assert_eq!(node.process(&silence), silence);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    fn test_autogain_to_rms(#[case] channels: usize) {
+        // A sine of amplitude A measures A / sqrt(2) RMS, so this tone is at -30 dBFS
+        let amplitude = 10f32.powf(-30.0 / 20.0) * std::f32::consts::SQRT_2;
+        let mut samples: Vec<f32> = sine(440.0, amplitude, 1.0, 48000)
+            .into_iter()
+            .flat_map(|s| std::iter::repeat_n(s, channels))
+            .collect();
+        assert!((rms_dbfs(&samples) + 30.0).abs() < 0.01);
+
+        let applied = autogain_to_rms(&mut samples, channels, -20.0);
+        assert!((applied - 10.0).abs() < 0.01, "applied {} dB", applied);
+        assert!((rms_dbfs(&samples) + 20.0).abs() < 0.01);
+    }
+
+    #[rstest]
+    fn test_autogain_leaves_silence() {
+        let mut silence = vec![0.0f32; 1000];
+        assert_eq!(autogain_to_rms(&mut silence, 2, -20.0), 0.0);
+        assert!(silence.iter().all(|&s| s == 0.0));
+        assert_eq!(autogain_to_rms(&mut [], 1, -20.0), 0.0);
+    }
+
+    #[rstest]
+    fn test_node_type_and_clone() {
+        let node = LoudnessNormalizeNode::new(-16.0, 2, 48000);
+        assert_eq!(node.node_type(), "loudness_normalize");
+        assert_eq!(node.box_clone().node_type(), "loudness_normalize");
+        assert_eq!(node.target_lufs(), -16.0);
+    }
+}
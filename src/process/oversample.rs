@@ -0,0 +1,352 @@
+//! Oversampling for non-linear nodes.
+//!
+//! Non-linear processing such as waveshaping creates harmonics above the Nyquist
+//! frequency, which fold back into the audible range as aliasing. [`OversampleWrapper`]
+//! runs a node at a multiple of the sample rate, so most of those harmonics are
+//! created above the original Nyquist frequency and filtered out before the audio
+//! is brought back to the original rate.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, OversampleFactor, OversampleWrapper, ShapeFunction, WaveshaperNode};
+//!
+//! // Hard clipping at 4x the sample rate
+//! let shaper = WaveshaperNode::new(ShapeFunction::HardClip, 4.0);
+//! let node = OversampleWrapper::new(shaper, OversampleFactor::X4);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! assert_eq!(output.len(), input.len());
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use super::node::{AudioNode, ProcessError};
+use super::resample::windowed_sinc;
+
+/// Zero crossings on each side of the anti-aliasing filter kernel.
+const ZERO_CROSSINGS: f64 = 32.0;
+/// Cutoff of the anti-aliasing filters relative to the original Nyquist frequency.
+const CUTOFF: f64 = 0.9;
+
+/// Oversampling factors supported by the [`OversampleWrapper`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversampleFactor {
+    /// Twice the sample rate
+    X2,
+    /// Four times the sample rate
+    X4,
+}
+
+impl OversampleFactor {
+    /// Returns the factor by which the sample rate is multiplied.
+    pub fn factor(&self) -> usize {
+        match self {
+            OversampleFactor::X2 => 2,
+            OversampleFactor::X4 => 4,
+        }
+    }
+}
+
+/// Filter state of one channel.
+#[derive(Clone)]
+struct ChannelState {
+    /// Recent input samples, newest first
+    input: VecDeque<f32>,
+    /// Recent oversampled output samples of the wrapped node, newest first
+    oversampled: VecDeque<f32>,
+}
+
+/// Runs a wrapped node at a multiple of the sample rate to reduce aliasing.
+///
+/// The input is upsampled by the [`OversampleFactor`], processed by the wrapped node
+/// and downsampled again. Both conversions use linear-phase windowed-sinc low-pass
+/// filters with a cutoff at 90% of the original Nyquist frequency, so content above
+/// about 20 kHz at 44.1 kHz is removed.
+///
+/// The wrapped node sees `factor` times as many frames as the wrapper, so nodes that
+/// depend on the sample rate must be created with the oversampled rate. The filters
+/// delay the audio, which the wrapper reports as [`AudioNode::latency_samples`] along
+/// with the latency of the wrapped node.
+///
+/// The wrapper keeps its filter state between calls to `process`. For interleaved
+/// multi-channel audio set the channel count with [`OversampleWrapper::with_channels`];
+/// blocks must then contain whole frames, a trailing partial frame is dropped.
+#[derive(Clone)]
+pub struct OversampleWrapper<N: AudioNode> {
+    node: N,
+    factor: OversampleFactor,
+    channels: usize,
+    /// Low-pass kernel at the oversampled rate, shared by both conversions
+    kernel: Vec<f32>,
+    states: RefCell<Vec<ChannelState>>,
+}
+
+impl<N: AudioNode> OversampleWrapper<N> {
+    /// Wraps a node to run it oversampled, for mono audio.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to run at the oversampled rate
+    /// * `factor` - Oversampling factor
+    pub fn new(node: N, factor: OversampleFactor) -> Self {
+        let l = factor.factor();
+        let cutoff = CUTOFF / l as f64;
+        // Center the kernel on a multiple of the factor, so the delay of the two
+        // filters adds up to whole frames at the original rate
+        let center = l * (ZERO_CROSSINGS / cutoff / l as f64).ceil() as usize;
+        let kernel = (0..=2 * center)
+            .map(|k| windowed_sinc(k as f64 - center as f64, cutoff, center as f64) as f32)
+            .collect();
+
+        let wrapper = Self {
+            node,
+            factor,
+            channels: 1,
+            kernel,
+            states: RefCell::new(Vec::new()),
+        };
+        wrapper.reset();
+        wrapper
+    }
+
+    /// Configures the wrapper for interleaved audio with the given number of channels.
+    ///
+    /// This resets the filters. The wrapped node must be configured for the same
+    /// channel count.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Returns the oversampling factor.
+    pub fn factor(&self) -> OversampleFactor {
+        self.factor
+    }
+
+    /// Returns the wrapped node.
+    pub fn node(&self) -> &N {
+        &self.node
+    }
+
+    /// Returns the wrapped node mutably, e.g. to change its parameters.
+    pub fn node_mut(&mut self) -> &mut N {
+        &mut self.node
+    }
+
+    /// Clears the filter state.
+    ///
+    /// The wrapped node keeps its state, reset it before wrapping if needed.
+    pub fn reset(&self) {
+        let l = self.factor.factor();
+        let state = ChannelState {
+            input: VecDeque::from(vec![0.0; self.kernel.len().div_ceil(l)]),
+            oversampled: VecDeque::from(vec![0.0; self.kernel.len()]),
+        };
+        *self.states.borrow_mut() = vec![state; self.channels];
+    }
+
+    /// Delay of the up- and downsampling filters in frames at the original rate.
+    fn filter_delay(&self) -> usize {
+        // The kernel is 2 * center + 1 taps long and each filter delays by center
+        (self.kernel.len() - 1) / self.factor.factor()
+    }
+
+    /// Upsamples whole frames of interleaved input.
+    fn upsample(&self, input: &[f32]) -> Vec<f32> {
+        let l = self.factor.factor();
+        let gain = l as f32;
+        let mut states = self.states.borrow_mut();
+        let mut output = vec![0.0; input.len() * l];
+
+        for (frame, samples) in input.chunks_exact(self.channels).enumerate() {
+            for (channel, (&sample, state)) in samples.iter().zip(states.iter_mut()).enumerate() {
+                state.input.pop_back();
+                state.input.push_front(sample);
+                // Polyphase interpolation: phase p only meets every l-th tap
+                for phase in 0..l {
+                    let value: f32 = self.kernel[phase..]
+                        .iter()
+                        .step_by(l)
+                        .zip(&state.input)
+                        .map(|(h, x)| h * x)
+                        .sum();
+                    output[((frame * l + phase) * self.channels) + channel] = value * gain;
+                }
+            }
+        }
+        output
+    }
+
+    /// Filters and decimates whole frames of interleaved oversampled audio.
+    fn downsample(&self, oversampled: &[f32]) -> Vec<f32> {
+        let l = self.factor.factor();
+        let mut states = self.states.borrow_mut();
+        let mut output = Vec::with_capacity(oversampled.len() / l);
+
+        for (frame, samples) in oversampled.chunks_exact(self.channels).enumerate() {
+            for (&sample, state) in samples.iter().zip(states.iter_mut()) {
+                state.oversampled.pop_back();
+                state.oversampled.push_front(sample);
+                if frame.is_multiple_of(l) {
+                    output.push(self.kernel.iter().zip(&state.oversampled).map(|(h, x)| h * x).sum());
+                }
+            }
+        }
+        output
+    }
+}
+
+impl<N: AudioNode + Clone + 'static> AudioNode for OversampleWrapper<N> {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let whole_frames = input.len() - input.len() % self.channels;
+        let mut oversampled = self.upsample(&input[..whole_frames]);
+        self.node.process_in_place(&mut oversampled);
+        self.downsample(&oversampled)
+    }
+
+    /// Fails with `ProcessError::PartialFrame` if the input is not a whole number of
+    /// frames, which `process` would drop.
+    fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+        if !input.len().is_multiple_of(self.channels) {
+            return Err(ProcessError::PartialFrame { samples: input.len(), channels: self.channels });
+        }
+        Ok(self.process(input))
+    }
+
+    /// The output is written to the start of the buffer. A trailing partial frame is
+    /// set to silence.
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let output = self.process(buffer);
+        let len = output.len();
+        buffer[..len].copy_from_slice(&output);
+        buffer[len..].iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "oversample"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.node.set_flush_denormals(enabled);
+    }
+
+    /// The wrapped node processes the oversampled audio in place.
+    fn supports_in_place(&self) -> bool {
+        self.node.supports_in_place()
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    /// The delay of the two filters plus the latency of the wrapped node, which is
+    /// counted at the oversampled rate and rounded up to whole frames.
+    fn latency_samples(&self) -> usize {
+        self.filter_delay() + self.node.latency_samples().div_ceil(self.factor.factor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use crate::process::{GainNode, LimiterNode, ShapeFunction, WaveshaperNode};
+    use crate::synth::sine;
+    use rstest::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    /// Energy and number of bins above -80 dB away from the harmonics of `frequency`.
+    fn aliasing(output: &[f32], frequency: f32) -> (f32, usize) {
+        let spectrum = magnitude_spectrum(output, WindowType::Blackman);
+        let bin_width = SAMPLE_RATE as f32 / output.len() as f32;
+        let spurious: Vec<f32> = spectrum
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let harmonic = (*i as f32 * bin_width / frequency).round();
+                (*i as f32 * bin_width - harmonic * frequency).abs() > 4.0 * bin_width
+            })
+            .map(|(_, &m)| m)
+            .collect();
+        let energy = spurious.iter().map(|m| m * m).sum();
+        (energy, spurious.iter().filter(|&&m| m > 1e-4).count())
+    }
+
+    #[rstest]
+    #[case(OversampleFactor::X2)]
+    #[case(OversampleFactor::X4)]
+    fn test_hard_clip_aliasing_reduced(#[case] factor: OversampleFactor) {
+        let frequency = 5000.0;
+        let input = sine(frequency, 1.0, 0.2, SAMPLE_RATE);
+        let shaper = WaveshaperNode::new(ShapeFunction::HardClip, 4.0);
+        let wrapped = OversampleWrapper::new(shaper.clone(), factor);
+
+        // Skip the start-up of the filters
+        let plain = shaper.process(&input)[4800..].to_vec();
+        let oversampled = wrapped.process(&input)[4800..].to_vec();
+
+        let (plain_energy, plain_bins) = aliasing(&plain, frequency);
+        let (energy, bins) = aliasing(&oversampled, frequency);
+        assert!(energy < plain_energy * 0.1, "{} vs {}", energy, plain_energy);
+        assert!(bins < plain_bins, "{} vs {} spurious bins", bins, plain_bins);
+    }
+
+    #[rstest]
+    #[case(OversampleFactor::X2)]
+    #[case(OversampleFactor::X4)]
+    fn test_linear_node_delayed_by_latency(#[case] factor: OversampleFactor) {
+        let input = sine(1000.0, 0.5, 0.1, SAMPLE_RATE);
+        let node = OversampleWrapper::new(GainNode::new(0.0), factor);
+        let latency = node.latency_samples();
+        assert!(latency > 0);
+
+        let output: Vec<f32> = input.chunks(100).flat_map(|block| node.process(block)).collect();
+        assert_eq!(output.len(), input.len());
+        for (out, expected) in output[latency..].iter().zip(&input).skip(latency) {
+            assert!((out - expected).abs() < 1e-3, "{} vs {}", out, expected);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_and_inner_latency() {
+        // 8 frames of lookahead at the oversampled rate are 4 frames at the original rate
+        let limiter = LimiterNode::new(0.0, 0.1, 0.001, 8000.0).with_channels(2);
+        let node = OversampleWrapper::new(limiter, OversampleFactor::X2).with_channels(2);
+        assert_eq!(node.latency_samples(), node.filter_delay() + 4);
+
+        // A low sine on the left channel only
+        let left = sine(200.0, 0.5, 0.1, SAMPLE_RATE);
+        let input: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let output = node.process(&input);
+        assert!(output.chunks_exact(2).all(|frame| frame[1] == 0.0));
+        assert!(output.chunks_exact(2).any(|frame| frame[0] > 0.49));
+
+        assert_eq!(node.try_process(&[0.0; 3]).unwrap_err(), ProcessError::PartialFrame { samples: 3, channels: 2 });
+        assert_eq!(node.factor(), OversampleFactor::X2);
+        assert_eq!(node.node().node_type(), "limiter");
+        assert_eq!(node.box_clone().node_type(), "oversample");
+    }
+
+    #[rstest]
+    fn test_process_in_place_and_reset() {
+        let input = sine(3000.0, 0.9, 0.01, SAMPLE_RATE);
+        let node1 = OversampleWrapper::new(WaveshaperNode::new(ShapeFunction::Tanh, 3.0), OversampleFactor::X4);
+        let node2 = node1.clone();
+
+        let output = node1.process(&input);
+        let mut buffer = input.clone();
+        node2.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+
+        node1.reset();
+        assert!(node1.process(&[0.0; 64]).iter().all(|&s| s == 0.0));
+    }
+}
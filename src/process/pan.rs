@@ -0,0 +1,344 @@
+//! Stereo panning and balance nodes.
+//!
+//! [`PannerNode`] places a mono source in the stereo field and therefore changes
+//! the channel count from 1 to 2. [`StereoPannerNode`] does the same for audio that
+//! is already stereo, by panning its mono downmix, and can process in place.
+//! [`BalanceNode`] adjusts the relative level of the left and right channel of
+//! stereo audio without mixing them.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, BalanceNode, PannerNode, StereoPannerNode};
+//!
+//! // Place a mono voice slightly to the left
+//! let panner = PannerNode::new(-0.3);
+//! let mono = vec![0.5f32; 1000];
+//! let stereo = panner.process(&mono);
+//! assert_eq!(stereo.len(), 2000);
+//!
+//! // Then nudge the whole mix to the right
+//! let balance = BalanceNode::new(0.2);
+//! let output = balance.process(&stereo);
+//!
+//! // Stereo audio can be panned in place
+//! let mut mix = output.clone();
+//! StereoPannerNode::new(0.5).process_in_place(&mut mix);
+//! ```
+
+use std::f32::consts::FRAC_PI_4;
+use super::node::AudioNode;
+
+/// An audio processing node that pans mono audio into stereo.
+///
+/// The node uses an equal-power pan law so the perceived loudness stays constant
+/// while moving the source: at the center both channels are at -3 dB.
+///
+/// `process` takes mono samples and returns interleaved stereo samples of twice the
+/// length (1 → 2 channels). As an in-place buffer cannot grow, the node can't process
+/// in place; use [`StereoPannerNode`] to pan audio that is already stereo.
+#[derive(Clone)]
+pub struct PannerNode {
+    pan: f32,
+}
+
+impl PannerNode {
+    /// Creates a new panner node.
+    ///
+    /// # Arguments
+    ///
+    /// * `pan` - Position in [-1.0, 1.0], where -1.0 is hard left, 0.0 center and 1.0
+    ///   hard right. Values outside the range are clamped.
+    pub fn new(pan: f32) -> Self {
+        Self { pan: pan.clamp(-1.0, 1.0) }
+    }
+
+    /// Returns the current pan position.
+    pub fn pan(&self) -> f32 {
+        self.pan
+    }
+
+    /// Sets a new pan position, clamped to [-1.0, 1.0].
+    pub fn set_pan(&mut self, pan: f32) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the linear (left, right) gains for the current pan position.
+    pub fn gains(&self) -> (f32, f32) {
+        let angle = (self.pan + 1.0) * FRAC_PI_4;
+        (angle.cos(), angle.sin())
+    }
+}
+
+impl AudioNode for PannerNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let (left, right) = self.gains();
+        input.iter()
+            .flat_map(|&sample| [sample * left, sample * right])
+            .collect()
+    }
+
+    /// # Panics
+    ///
+    /// Always panics, use `process` or a [`StereoPannerNode`] instead.
+    fn process_in_place(&self, _buffer: &mut [f32]) {
+        panic!(
+            "PannerNode turns mono into stereo and cannot process in place, use process or StereoPannerNode instead"
+        );
+    }
+
+    fn node_type(&self) -> &'static str {
+        "panner"
+    }
+
+    /// `process` turns mono into interleaved stereo.
+    fn input_channels(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn output_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn supports_in_place(&self) -> bool {
+        false
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+/// An audio processing node that pans stereo audio.
+///
+/// Each frame is downmixed to mono and placed in the stereo field with the same
+/// equal-power pan law as [`PannerNode`], so for dual-mono input the output matches
+/// that of a `PannerNode` given the mono signal. The input must be interleaved
+/// stereo and the channel count is preserved.
+#[derive(Clone)]
+pub struct StereoPannerNode {
+    panner: PannerNode,
+}
+
+impl StereoPannerNode {
+    /// Creates a new stereo panner node.
+    ///
+    /// # Arguments
+    ///
+    /// * `pan` - Position in [-1.0, 1.0], where -1.0 is hard left, 0.0 center and 1.0
+    ///   hard right. Values outside the range are clamped.
+    pub fn new(pan: f32) -> Self {
+        Self { panner: PannerNode::new(pan) }
+    }
+
+    /// Returns the current pan position.
+    pub fn pan(&self) -> f32 {
+        self.panner.pan()
+    }
+
+    /// Sets a new pan position, clamped to [-1.0, 1.0].
+    pub fn set_pan(&mut self, pan: f32) {
+        self.panner.set_pan(pan);
+    }
+
+    /// Returns the linear (left, right) gains for the current pan position.
+    pub fn gains(&self) -> (f32, f32) {
+        self.panner.gains()
+    }
+}
+
+impl AudioNode for StereoPannerNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let (left, right) = self.gains();
+        buffer.chunks_exact_mut(2).for_each(|frame| {
+            let mono = 0.5 * (frame[0] + frame[1]);
+            frame[0] = mono * left;
+            frame[1] = mono * right;
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "stereo_panner"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+/// An audio processing node that adjusts the left/right balance of stereo audio.
+///
+/// Unlike a panner, the balance control never mixes channels: moving the balance
+/// to one side attenuates the opposite channel and leaves the other unchanged.
+/// The input must be interleaved stereo and the channel count is preserved.
+#[derive(Clone)]
+pub struct BalanceNode {
+    balance: f32,
+}
+
+impl BalanceNode {
+    /// Creates a new balance node.
+    ///
+    /// # Arguments
+    ///
+    /// * `balance` - Balance in [-1.0, 1.0]. -1.0 silences the right channel, 0.0
+    ///   leaves both channels unchanged and 1.0 silences the left channel. Values
+    ///   outside the range are clamped.
+    pub fn new(balance: f32) -> Self {
+        Self { balance: balance.clamp(-1.0, 1.0) }
+    }
+
+    /// Returns the current balance.
+    pub fn balance(&self) -> f32 {
+        self.balance
+    }
+
+    /// Sets a new balance, clamped to [-1.0, 1.0].
+    pub fn set_balance(&mut self, balance: f32) {
+        self.balance = balance.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the linear (left, right) gains for the current balance.
+    pub fn gains(&self) -> (f32, f32) {
+        (
+            (1.0 - self.balance).min(1.0),
+            (1.0 + self.balance).min(1.0),
+        )
+    }
+}
+
+impl AudioNode for BalanceNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let (left, right) = self.gains();
+        buffer.chunks_exact_mut(2).for_each(|frame| {
+            frame[0] *= left;
+            frame[1] *= right;
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "balance"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn channel_energy(stereo: &[f32]) -> (f32, f32) {
+        stereo.chunks_exact(2).fold((0.0, 0.0), |(l, r), frame| {
+            (l + frame[0] * frame[0], r + frame[1] * frame[1])
+        })
+    }
+
+    #[fixture]
+    fn mono() -> Vec<f32> {
+        vec![1.0, -0.5, 0.25, 0.75]
+    }
+
+    #[rstest]
+    fn test_pan_hard_left(mono: Vec<f32>) {
+        let stereo = PannerNode::new(-1.0).process(&mono);
+        assert_eq!(stereo.len(), 2 * mono.len());
+        let (l, r) = channel_energy(&stereo);
+        assert!(l > 0.0);
+        assert!(r < 1e-10);
+    }
+
+    #[rstest]
+    fn test_pan_hard_right(mono: Vec<f32>) {
+        let stereo = PannerNode::new(1.0).process(&mono);
+        let (l, r) = channel_energy(&stereo);
+        assert!(l < 1e-10);
+        assert!(r > 0.0);
+    }
+
+    #[rstest]
+    fn test_pan_center_minus_3db(mono: Vec<f32>) {
+        let stereo = PannerNode::new(0.0).process(&mono);
+        for (frame, &sample) in stereo.chunks_exact(2).zip(mono.iter()) {
+            assert!((frame[0] - frame[1]).abs() < 1e-6);
+            let db = 20.0 * (frame[0] / sample).log10();
+            assert!((db - -3.0103).abs() < 0.01);
+        }
+    }
+
+    #[rstest]
+    #[case(-0.7)]
+    #[case(0.0)]
+    #[case(0.4)]
+    fn test_pan_constant_power(#[case] pan: f32) {
+        let (l, r) = PannerNode::new(pan).gains();
+        assert!((l * l + r * r - 1.0).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "cannot process in place")]
+    fn test_pan_in_place_panics(mono: Vec<f32>) {
+        PannerNode::new(0.3).process_in_place(&mut mono.clone());
+    }
+
+    #[rstest]
+    fn test_stereo_pan_dual_mono(mono: Vec<f32>) {
+        let dual_mono: Vec<f32> = mono.iter().flat_map(|&s| [s, s]).collect();
+        let expected = PannerNode::new(0.3).process(&mono);
+        assert_eq!(StereoPannerNode::new(0.3).process(&dual_mono), expected);
+    }
+
+    #[rstest]
+    fn test_stereo_pan_process_matches_in_place() {
+        let node = StereoPannerNode::new(-0.6);
+        let input = vec![1.0, 0.2, -0.5, 0.5, 0.25, -0.75];
+        let mut buffer = input.clone();
+        node.process_in_place(&mut buffer);
+        assert_eq!(node.process(&input), buffer);
+        assert_eq!((input.len(), node.input_channels(), node.output_channels()), (buffer.len(), Some(2), Some(2)));
+    }
+
+    #[rstest]
+    #[case(0.0, (1.0, 1.0))]
+    #[case(0.5, (0.5, 1.0))]
+    #[case(-1.0, (1.0, 0.0))]
+    fn test_balance_gains(#[case] balance: f32, #[case] expected: (f32, f32)) {
+        assert_eq!(BalanceNode::new(balance).gains(), expected);
+    }
+
+    #[rstest]
+    fn test_balance_process() {
+        let node = BalanceNode::new(0.5);
+        let output = node.process(&[1.0, 1.0, -0.5, 0.5]);
+        assert_eq!(output, vec![0.5, 1.0, -0.25, 0.5]);
+    }
+
+    #[rstest]
+    fn test_node_types() {
+        assert_eq!(PannerNode::new(0.0).box_clone().node_type(), "panner");
+        assert_eq!(StereoPannerNode::new(0.0).box_clone().node_type(), "stereo_panner");
+        assert_eq!(BalanceNode::new(0.0).box_clone().node_type(), "balance");
+    }
+}
@@ -0,0 +1,190 @@
+//! Parallel processing of several chains.
+//!
+//! A [`ParallelChain`] feeds the same input through multiple [`AudioNodeChain`]
+//! branches and sums their outputs, each scaled by its own gain. This covers
+//! wet/dry blends (one processed branch, one empty branch) as well as bus-style
+//! parallel processing such as parallel compression.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, AudioNodeChain, DelayNode, ParallelChain};
+//!
+//! let mut wet = AudioNodeChain::new();
+//! wet.add_node(DelayNode::new(0.25, 0.4, 1.0, 48000.0));
+//!
+//! let mut parallel = ParallelChain::new();
+//! parallel.add_branch(AudioNodeChain::new(), 0.8);  // Dry
+//! parallel.add_branch(wet, 0.2);                    // Wet
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = parallel.process(&input);
+//! ```
+
+use super::node::{AudioNode, AudioNodeChain};
+
+/// Runs the same input through several chains and mixes their outputs.
+///
+/// Each branch has a linear gain applied to its output before summing. If the
+/// branches produce outputs of different lengths (e.g. a branch changes the channel
+/// count or sample rate) the mix is truncated to the shortest output. Without any
+/// branch the output is silence.
+///
+/// `ParallelChain` implements [`AudioNode`] itself, so it can be nested inside an
+/// [`AudioNodeChain`].
+#[derive(Default, Clone)]
+pub struct ParallelChain {
+    branches: Vec<(AudioNodeChain, f32)>,
+}
+
+impl ParallelChain {
+    /// Creates a new parallel chain without branches.
+    pub fn new() -> Self {
+        Self { branches: Vec::new() }
+    }
+
+    /// Adds a branch to the parallel chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The chain processing this branch, an empty chain passes the input dry
+    /// * `gain` - Linear gain applied to the branch output before mixing
+    pub fn add_branch(&mut self, chain: AudioNodeChain, gain: f32) {
+        self.branches.push((chain, gain));
+    }
+
+    /// Returns the number of branches.
+    pub fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// Returns true if the parallel chain has no branches.
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// Sets the gain of the branch at the given position.
+    ///
+    /// Out-of-range indices are ignored.
+    pub fn set_branch_gain(&mut self, index: usize, gain: f32) {
+        if let Some(branch) = self.branches.get_mut(index) {
+            branch.1 = gain;
+        }
+    }
+}
+
+impl AudioNode for ParallelChain {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        if self.branches.is_empty() {
+            return vec![0.0; input.len()];
+        }
+        let outputs: Vec<(Vec<f32>, f32)> = self.branches.iter()
+            .map(|(chain, gain)| (chain.process(input), *gain))
+            .collect();
+        let len = outputs.iter().map(|(output, _)| output.len()).min().unwrap_or(0);
+
+        let mut mix = vec![0.0; len];
+        for (output, gain) in &outputs {
+            mix.iter_mut()
+                .zip(output.iter())
+                .for_each(|(m, &s)| *m += s * gain);
+        }
+        mix
+    }
+
+    /// The mix is written to the start of the buffer. If it is shorter than the
+    /// buffer the remaining samples are set to silence.
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let mix = self.process(buffer);
+        let len = mix.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&mix[..len]);
+        buffer[len..].iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    fn node_type(&self) -> &'static str {
+        "parallel"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.branches.iter().for_each(|(chain, _)| chain.set_flush_denormals(enabled));
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    /// Reports the latency of the slowest branch.
+    fn latency_samples(&self) -> usize {
+        self.branches.iter()
+            .map(|(chain, _)| chain.total_latency_samples())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{GainNode, PannerNode};
+    use rstest::*;
+
+    #[fixture]
+    fn test_input() -> Vec<f32> {
+        vec![1.0, 0.5, -0.5, -1.0]
+    }
+
+    #[rstest]
+    fn test_gain_branch_plus_dry(test_input: Vec<f32>) {
+        let mut wet = AudioNodeChain::new();
+        wet.add_node(GainNode::new(6.0));
+
+        let mut parallel = ParallelChain::new();
+        parallel.add_branch(AudioNodeChain::new(), 1.0);
+        parallel.add_branch(wet, 0.5);
+        assert_eq!(parallel.len(), 2);
+
+        let output = parallel.process(&test_input);
+        for (out, inp) in output.iter().zip(test_input.iter()) {
+            let expected = inp + 0.5 * inp * 1.995262;
+            assert!((out - expected).abs() < 0.0001);
+        }
+
+        let mut buffer = test_input.clone();
+        parallel.process_in_place(&mut buffer);
+        assert_eq!(buffer, output);
+    }
+
+    #[rstest]
+    fn test_truncates_to_shortest(test_input: Vec<f32>) {
+        let mut stereo = AudioNodeChain::new();
+        stereo.add_node(PannerNode::new(0.0));
+
+        let mut parallel = ParallelChain::new();
+        parallel.add_branch(stereo, 1.0);
+        parallel.add_branch(AudioNodeChain::new(), 1.0);
+
+        assert_eq!(parallel.process(&test_input).len(), test_input.len());
+    }
+
+    #[rstest]
+    fn test_nested_in_chain(test_input: Vec<f32>) {
+        let mut parallel = ParallelChain::new();
+        parallel.add_branch(AudioNodeChain::new(), 0.5);
+        parallel.add_branch(AudioNodeChain::new(), 0.5);
+        parallel.set_branch_gain(1, 1.5);
+
+        let mut chain = AudioNodeChain::new();
+        chain.add_node(parallel);
+        let output = chain.process(&test_input);
+        let expected: Vec<f32> = test_input.iter().map(|x| x * 2.0).collect();
+        assert_eq!(output, expected);
+        assert_eq!(chain.node_types(), vec!["parallel"]);
+    }
+
+    #[rstest]
+    fn test_empty_is_silent(test_input: Vec<f32>) {
+        let parallel = ParallelChain::new();
+        assert!(parallel.is_empty());
+        assert_eq!(parallel.process(&test_input), vec![0.0; 4]);
+    }
+}
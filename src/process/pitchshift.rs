@@ -0,0 +1,266 @@
+//! Pitch-shifting node.
+//!
+//! This module transposes audio without changing its duration. A phase vocoder first
+//! stretches the audio in time by the pitch ratio, keeping its pitch, and the stretched
+//! audio is then resampled back to the original length, which scales every frequency
+//! by the ratio.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, PitchShiftNode};
+//!
+//! // Stereo audio up a perfect fifth
+//! let node = PitchShiftNode::new(7.0, 48000.0, 2);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let output = node.process(&input);
+//! assert_eq!(output.len(), input.len());
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::node::AudioNode;
+use super::resample::windowed_sinc;
+use super::vocoder::{PhaseVocoder, VocoderState};
+
+/// Largest transposition in semitones, up or down.
+pub const MAX_PITCH_SHIFT_SEMITONES: f32 = 24.0;
+/// Zero crossings on each side of the resampling kernel.
+const ZERO_CROSSINGS: f64 = 8.0;
+
+/// An audio processing node that shifts the pitch of audio by a number of semitones.
+///
+/// Internally the node works on frames of [`PitchShiftNode::frame_size`] samples per
+/// channel (2048 at 44.1 and 48 kHz), overlapping by at least three quarters. Input
+/// is buffered until a frame is complete, so blocks of any length down to a single
+/// sample can be processed, but the audio is delayed by
+/// [`AudioNode::latency_samples`]: about one frame, up to two and a half frames when
+/// shifting down two octaves. The output has exactly as many samples as the input
+/// and is silent until the latency has passed.
+///
+/// Transients are smeared over a frame, which suits tonal material better than
+/// drums. The node keeps its state between calls to `process`, use
+/// [`PitchShiftNode::reset`] before processing unrelated audio.
+#[derive(Clone)]
+pub struct PitchShiftNode {
+    semitones: f32,
+    ratio: f64,
+    channels: usize,
+    vocoder: PhaseVocoder,
+    states: RefCell<Vec<VocoderState>>,
+    samples_in: Cell<usize>,
+    channel: Cell<usize>,
+}
+
+impl PitchShiftNode {
+    /// Creates a new pitch-shifting node.
+    ///
+    /// # Arguments
+    ///
+    /// * `semitones` - Transposition in semitones, positive shifts up. Clamped to
+    ///   ±`MAX_PITCH_SHIFT_SEMITONES`.
+    /// * `sample_rate` - Sample rate in Hz, which sets the frame size to about 40 ms
+    /// * `channels` - Number of interleaved audio channels
+    pub fn new(semitones: f32, sample_rate: f32, channels: usize) -> Self {
+        let semitones = semitones.clamp(-MAX_PITCH_SHIFT_SEMITONES, MAX_PITCH_SHIFT_SEMITONES);
+        let ratio = 2f64.powf(semitones as f64 / 12.0);
+        let vocoder = PhaseVocoder::new(ratio, sample_rate);
+        let channels = channels.max(1);
+        Self {
+            semitones,
+            ratio,
+            channels,
+            states: RefCell::new(vec![vocoder.state(); channels]),
+            vocoder,
+            samples_in: Cell::new(0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Returns the transposition in semitones.
+    pub fn semitones(&self) -> f32 {
+        self.semitones
+    }
+
+    /// Returns the length of the analysis frames in samples per channel.
+    pub fn frame_size(&self) -> usize {
+        self.vocoder.frame_size()
+    }
+
+    /// Clears all buffered audio.
+    pub fn reset(&self) {
+        self.states.borrow_mut().iter_mut().for_each(|state| *state = self.vocoder.state());
+        self.samples_in.set(0);
+        self.channel.set(0);
+    }
+
+    /// Half-width of the resampling kernel in stretched samples, with its cutoff.
+    fn kernel(&self) -> (f64, f64) {
+        let cutoff = (1.0 / self.ratio).min(1.0);
+        (ZERO_CROSSINGS / cutoff, cutoff)
+    }
+
+    /// Reads output sample `index` from the stretched audio with a sinc interpolator.
+    fn read_output(&self, state: &mut VocoderState, index: usize) -> f32 {
+        let (half_width, cutoff) = self.kernel();
+        let position = index as f64 * self.ratio;
+
+        let first = (position - half_width).ceil() as isize;
+        let last = (position + half_width).floor() as isize;
+        let output: f64 = (first..=last)
+            .map(|i| state.stretched_at(i) as f64 * windowed_sinc(position - i as f64, cutoff, half_width))
+            .sum();
+
+        // Free what the next output sample no longer needs
+        state.discard_before((position + self.ratio - half_width).floor() as isize);
+        output as f32
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node has more than one
+    /// channel. Returns the shifted sample from `latency_samples` frames earlier.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let channel = self.channel.get();
+        let samples_in = self.samples_in.get() + 1;
+        if channel + 1 == self.channels {
+            self.channel.set(0);
+            self.samples_in.set(samples_in);
+        } else {
+            self.channel.set(channel + 1);
+        }
+
+        let mut states = self.states.borrow_mut();
+        let state = &mut states[channel];
+        self.vocoder.push(state, sample);
+        match (samples_in - 1).checked_sub(self.latency_samples()) {
+            Some(index) => self.read_output(state, index),
+            None => 0.0,
+        }
+    }
+}
+
+impl AudioNode for PitchShiftNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "pitch_shift"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    /// Output sample `t` is read around stretched sample `t * ratio`, which is complete
+    /// once the last frame overlapping the resampling kernel has been stretched. A
+    /// stretched frame spans `frame_size / ratio` input samples, so shifting down
+    /// has more latency than shifting up.
+    fn latency_samples(&self) -> usize {
+        let (half_width, _) = self.kernel();
+        let half_frame = self.frame_size() as f64 / 2.0;
+        ((half_width + half_frame + 1.0) / self.ratio).ceil() as usize + self.frame_size() / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::peak_frequency;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(12.0, 880.0)]
+    #[case(-12.0, 220.0)]
+    #[case(7.0, 659.26)]
+    fn test_sine_shifted(#[case] semitones: f32, #[case] expected: f32) {
+        let input = sine(440.0, 0.5, 2.0, 48000);
+        let node = PitchShiftNode::new(semitones, 48000.0, 1);
+        let output = node.process(&input);
+        assert_eq!(output.len(), input.len());
+
+        // One second of settled output, bin resolution is 1 Hz
+        let start = node.latency_samples() + node.frame_size();
+        let settled = &output[start..start + 48000];
+        assert!((peak_frequency(settled, 48000) - expected).abs() <= 1.5);
+        let peak = settled.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.05, "peak {}", peak);
+    }
+
+    #[rstest]
+    fn test_no_shift_delays_input() {
+        let input = sine(330.0, 0.5, 0.5, 44100);
+        let node = PitchShiftNode::new(0.0, 44100.0, 1);
+        let latency = node.latency_samples();
+        assert_eq!(node.frame_size(), 2048);
+        // Half a frame to the first stretched frame, half a frame plus the 8-sample
+        // kernel and one sample of rounding until the kernel around it is complete
+        assert_eq!(latency, 1024 + 1024 + 8 + 1);
+
+        let output = node.process(&input);
+        assert!(output[..latency].iter().all(|&s| s == 0.0));
+        for (i, (out, expected)) in output[latency..].iter().zip(&input).enumerate().skip(node.frame_size()) {
+            assert!((out - expected).abs() < 1e-3, "sample {}: {} vs {}", i, out, expected);
+        }
+    }
+
+    #[rstest]
+    #[case(12.0)]
+    #[case(-24.0)]
+    #[case(24.0)]
+    fn test_latency_aligns_output(#[case] semitones: f32) {
+        // A tone starting after half a second starts half a second after the latency
+        let mut input = vec![0.0f32; 24000];
+        input.extend(sine(300.0, 0.5, 1.0, 48000));
+        let node = PitchShiftNode::new(semitones, 48000.0, 1);
+        let output = node.process(&input);
+
+        // The tone fades in over a stretched frame centered on its start, which spans
+        // more input time when shifting down
+        let onset = node.latency_samples() + 24000;
+        let ratio = 2f32.powf(semitones / 12.0);
+        let half_frame = (node.frame_size() as f32 / 2.0 / ratio.min(1.0)) as usize;
+        let level = |range: std::ops::Range<usize>| output[range].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(level(0..onset - half_frame) < 1e-3);
+        assert!(level(onset + half_frame..onset + 3 * half_frame) > 0.4);
+    }
+
+    #[rstest]
+    fn test_blocks_match_whole_buffer() {
+        let left = sine(440.0, 0.5, 0.5, 48000);
+        let right = sine(300.0, 0.3, 0.5, 48000);
+        let input: Vec<f32> = left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect();
+
+        let whole = PitchShiftNode::new(5.0, 48000.0, 2).process(&input);
+        let node = PitchShiftNode::new(5.0, 48000.0, 2);
+        let blocks: Vec<f32> = input.chunks(2 * 100).flat_map(|block| node.process(block)).collect();
+        assert_eq!(whole, blocks);
+
+        // Channels are shifted independently
+        node.reset();
+        let mono = PitchShiftNode::new(5.0, 48000.0, 1).process(&left);
+        let stereo = node.process(&input);
+        for (frame, expected) in stereo.chunks_exact(2).zip(&mono) {
+            assert_eq!(frame[0], *expected);
+        }
+    }
+
+    #[rstest]
+    fn test_semitones_clamped() {
+        let node = PitchShiftNode::new(40.0, 48000.0, 1);
+        assert_eq!(node.semitones(), MAX_PITCH_SHIFT_SEMITONES);
+        assert_eq!(node.box_clone().node_type(), "pitch_shift");
+    }
+}
@@ -0,0 +1,155 @@
+//! Range gain processing node.
+//!
+//! This module applies a gain to a fixed range of frames of a stream, e.g. to
+//! lower a cough or a loud laugh without touching the rest of a recording.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, RangeGainNode};
+//!
+//! // Lower seconds 2 to 3 of a stereo stream at 48 kHz by 12 dB
+//! let node = RangeGainNode::new(96000, 144000, -12.0).with_channels(2);
+//!
+//! let input = vec![0.5f32; 48000 * 2 * 5];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::Cell;
+use super::gain::gain_db_range;
+use super::node::AudioNode;
+
+/// An audio processing node that applies a gain to frames `[start_frame, end_frame)`
+/// of a stream and passes all other frames through unchanged.
+///
+/// The node tracks the stream position across calls to `process`, so a stream can
+/// be processed block by block and the range is counted from the first processed
+/// frame. Use [`RangeGainNode::reset`] to rewind to the start. For interleaved
+/// multi-channel audio set the channel count with [`RangeGainNode::with_channels`]
+/// so the range is counted in frames rather than samples.
+#[derive(Clone)]
+pub struct RangeGainNode {
+    start_frame: usize,
+    end_frame: usize,
+    db: f32,
+    channels: usize,
+    sample: Cell<u64>,
+}
+
+impl RangeGainNode {
+    /// Creates a new mono range gain node.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_frame` - First frame to adjust
+    /// * `end_frame` - Frame after the last frame to adjust
+    /// * `db` - Gain adjustment in decibels
+    pub fn new(start_frame: usize, end_frame: usize, db: f32) -> Self {
+        Self {
+            start_frame,
+            end_frame,
+            db,
+            channels: 1,
+            sample: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the stream position.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Returns the adjusted frame range as `(start_frame, end_frame)`.
+    pub fn range(&self) -> (usize, usize) {
+        (self.start_frame, self.end_frame)
+    }
+
+    /// Returns the gain in dB.
+    pub fn gain_db(&self) -> f32 {
+        self.db
+    }
+
+    /// Rewinds the stream position to the first frame.
+    pub fn reset(&self) {
+        self.sample.set(0);
+    }
+}
+
+impl AudioNode for RangeGainNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        // Blocks may end mid-frame, so the range is mapped onto the buffer in samples
+        let position = self.sample.get();
+        self.sample.set(position + buffer.len() as u64);
+
+        let to_buffer = |frame: usize| {
+            let sample = frame as u64 * self.channels as u64;
+            sample.saturating_sub(position).min(buffer.len() as u64) as usize
+        };
+        let start = to_buffer(self.start_frame);
+        let end = to_buffer(self.end_frame);
+        if start < end {
+            gain_db_range(&mut buffer[start..end], 1, 0, end - start, self.db);
+        }
+    }
+
+    fn node_type(&self) -> &'static str {
+        "range_gain"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case::whole_stream(1000)]
+    #[case::odd_blocks(7)]
+    #[case::mid_frame_blocks(3)]
+    fn test_only_range_is_scaled(#[case] block_size: usize) {
+        let node = RangeGainNode::new(10, 20, -20.0).with_channels(2);
+        let input: Vec<f32> = (0..60).map(|i| i as f32).collect();
+        let output: Vec<f32> = input.chunks(block_size).flat_map(|block| node.process(block)).collect();
+
+        for (i, (out, original)) in output.iter().zip(&input).enumerate() {
+            if (20..40).contains(&i) {
+                assert!((out - original * 0.1).abs() < 1e-4, "sample {}", i);
+            } else {
+                assert_eq!(out, original, "sample {}", i);
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_reset_and_accessors() {
+        let node = RangeGainNode::new(0, 2, -6.0);
+        let output = node.process(&[1.0; 4]);
+        assert!((output[1] - 0.501187).abs() < 1e-6);
+        assert_eq!(output[2], 1.0);
+        assert_eq!(node.process(&[1.0; 2]), vec![1.0; 2]);
+
+        node.reset();
+        assert!(node.process(&[1.0])[0] < 1.0);
+        assert_eq!(node.range(), (0, 2));
+        assert_eq!(node.gain_db(), -6.0);
+        assert_eq!(node.box_clone().node_type(), "range_gain");
+    }
+}
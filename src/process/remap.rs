@@ -0,0 +1,169 @@
+//! Channel up- and downmixing.
+//!
+//! This module converts interleaved audio between channel layouts, e.g. stereo
+//! recordings to mono for a voice-only feed, or a mono voice to stereo before mixing
+//! it with stereo music.
+//!
+//! Downmixing averages the source channels: each of the `N` channels contributes with
+//! a gain of `1/N` (-6 dB for stereo). Identical channels therefore come out at their
+//! original level and the result can never clip, at the cost of uncorrelated content
+//! ending up quieter than in the source. Upmixing duplicates the mono channel without
+//! any attenuation.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{downmix_to_mono, remap_channels, upmix_mono_to_stereo};
+//!
+//! let stereo = vec![0.5, 0.25, 0.5, 0.25];
+//! let mono = downmix_to_mono(&stereo, 2);
+//! assert_eq!(mono, vec![0.375, 0.375]);
+//!
+//! let dual_mono = upmix_mono_to_stereo(&mono);
+//! assert_eq!(dual_mono.len(), 4);
+//!
+//! // Swap left and right
+//! let swapped = remap_channels(&stereo, 2, 2, &[0.0, 1.0, 1.0, 0.0]);
+//! assert_eq!(swapped, vec![0.25, 0.5, 0.25, 0.5]);
+//! ```
+
+/// Mixes interleaved multi-channel audio down to mono.
+///
+/// Each output sample is the average of the channels of one frame, see the
+/// [module documentation](self) for the summing law. A trailing partial frame is
+/// dropped.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+///
+/// # Returns
+///
+/// A new vector with one sample per frame.
+pub fn downmix_to_mono(samples: &[f32], channels: usize) -> Vec<f32> {
+    let channels = channels.max(1);
+    let gain = 1.0 / channels as f32;
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() * gain)
+        .collect()
+}
+
+/// Converts mono audio to interleaved stereo by copying each sample to both channels.
+///
+/// # Arguments
+///
+/// * `samples` - Mono audio samples
+///
+/// # Returns
+///
+/// A new vector of interleaved stereo samples, twice the length of the input.
+pub fn upmix_mono_to_stereo(samples: &[f32]) -> Vec<f32> {
+    samples.iter().flat_map(|&sample| [sample, sample]).collect()
+}
+
+/// Converts interleaved audio between channel layouts with a mixing matrix.
+///
+/// Output channel `o` of every frame is `sum(matrix[o * from + i] * input[i])` over the
+/// input channels `i`, so the matrix has one row of `from` gains per output channel.
+/// A trailing partial input frame is dropped.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples with `from` channels
+/// * `from` - Number of input channels
+/// * `to` - Number of output channels
+/// * `matrix` - Row-major `to` x `from` matrix of gains
+///
+/// # Returns
+///
+/// A new vector of interleaved samples with `to` channels.
+///
+/// # Panics
+///
+/// Panics if `matrix` does not contain exactly `from * to` gains.
+pub fn remap_channels(samples: &[f32], from: usize, to: usize, matrix: &[f32]) -> Vec<f32> {
+    assert_eq!(
+        matrix.len(),
+        from * to,
+        "remap matrix must have {} rows of {} gains",
+        to,
+        from
+    );
+    if from == 0 {
+        return Vec::new();
+    }
+
+    let mut output = Vec::with_capacity(samples.len() / from * to);
+    for frame in samples.chunks_exact(from) {
+        output.extend(matrix.chunks_exact(from).map(|row| {
+            row.iter().zip(frame).map(|(gain, sample)| gain * sample).sum::<f32>()
+        }));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(vec![1.0, 0.0, 0.5, 0.5, -0.25, 0.75], 2, vec![0.5, 0.5, 0.25])]
+    #[case(vec![0.3, 0.6, 0.9, 0.0, 0.0, 0.3], 3, vec![0.6, 0.1])]
+    #[case(vec![0.1, 0.2, 0.3], 1, vec![0.1, 0.2, 0.3])]
+    fn test_downmix_averages_channels(
+        #[case] samples: Vec<f32>,
+        #[case] channels: usize,
+        #[case] expected: Vec<f32>,
+    ) {
+        let mono = downmix_to_mono(&samples, channels);
+        assert_eq!(mono.len(), expected.len());
+        for (out, exp) in mono.iter().zip(expected.iter()) {
+            assert!((out - exp).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    fn test_downmix_drops_partial_frame() {
+        assert_eq!(downmix_to_mono(&[1.0, 1.0, 0.5], 2), vec![1.0]);
+    }
+
+    #[rstest]
+    fn test_upmix_duplicates() {
+        let mono = vec![0.1, -0.2, 0.3];
+        let stereo = upmix_mono_to_stereo(&mono);
+        assert_eq!(stereo, vec![0.1, 0.1, -0.2, -0.2, 0.3, 0.3]);
+        // Mono survives the round trip unchanged
+        assert_eq!(downmix_to_mono(&stereo, 2), mono);
+    }
+
+    #[rstest]
+    fn test_remap_matches_up_and_downmix() {
+        let stereo = vec![1.0, 0.0, 0.5, 0.5, -0.25, 0.75];
+        assert_eq!(remap_channels(&stereo, 2, 1, &[0.5, 0.5]), downmix_to_mono(&stereo, 2));
+
+        let mono = vec![0.1, -0.2, 0.3];
+        assert_eq!(remap_channels(&mono, 1, 2, &[1.0, 1.0]), upmix_mono_to_stereo(&mono));
+    }
+
+    #[rstest]
+    fn test_remap_stereo_to_quad() {
+        // Front pair copied, rear pair swapped and attenuated
+        let matrix = [
+            1.0, 0.0,
+            0.0, 1.0,
+            0.0, 0.5,
+            0.5, 0.0,
+        ];
+        let output = remap_channels(&[0.8, 0.4], 2, 4, &matrix);
+        assert_eq!(output, vec![0.8, 0.4, 0.2, 0.4]);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "remap matrix must have 2 rows of 2 gains")]
+    fn test_remap_rejects_wrong_matrix_size() {
+        remap_channels(&[0.0; 4], 2, 2, &[1.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,259 @@
+//! Sample-rate conversion node.
+//!
+//! This module converts audio between sample rates with a windowed-sinc
+//! interpolator, e.g. to turn a 48 kHz recording into 44.1 kHz before writing it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, ResampleNode, ResampleQuality};
+//!
+//! let node = ResampleNode::new(48000, 44100, 2, ResampleQuality::High);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let output = node.process(&input);
+//! assert_eq!(output.len(), 44100 * 2);
+//! ```
+
+use std::f64::consts::PI;
+use super::node::{AudioContext, AudioNode, ProcessError};
+
+/// Interpolation quality of the [`ResampleNode`].
+///
+/// Higher quality uses a longer sinc kernel, which gives a steeper anti-aliasing
+/// filter at the cost of more computation per output sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// 8 zero crossings on each side of the kernel
+    Low,
+    /// 16 zero crossings on each side of the kernel
+    Medium,
+    /// 32 zero crossings on each side of the kernel
+    High,
+}
+
+impl ResampleQuality {
+    fn zero_crossings(&self) -> usize {
+        match self {
+            ResampleQuality::Low => 8,
+            ResampleQuality::Medium => 16,
+            ResampleQuality::High => 32,
+        }
+    }
+}
+
+/// An audio processing node that converts interleaved audio to another sample rate.
+///
+/// Each output sample is interpolated from the surrounding input samples with a
+/// Blackman-windowed sinc kernel. When downsampling the kernel cutoff is lowered to
+/// the new Nyquist frequency so content above it is filtered out instead of aliasing.
+///
+/// The node processes every buffer independently, so it is meant for converting
+/// whole signals rather than a stream of blocks. A buffer of `n` frames produces
+/// `ceil(n * to_rate / from_rate)` frames.
+///
+/// Because the number of samples changes, the node cannot work in place:
+/// `process_in_place` panics, use `process` instead.
+#[derive(Clone)]
+pub struct ResampleNode {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    quality: ResampleQuality,
+}
+
+impl ResampleNode {
+    /// Creates a new resampling node.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_rate` - Sample rate of the input in Hz
+    /// * `to_rate` - Sample rate of the output in Hz
+    /// * `channels` - Number of interleaved audio channels
+    /// * `quality` - Interpolation quality
+    pub fn new(from_rate: u32, to_rate: u32, channels: usize, quality: ResampleQuality) -> Self {
+        Self {
+            from_rate: from_rate.max(1),
+            to_rate: to_rate.max(1),
+            channels: channels.max(1),
+            quality,
+        }
+    }
+
+    /// Returns the input sample rate in Hz.
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    /// Returns the output sample rate in Hz.
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// Returns the number of output frames produced for the given number of input frames.
+    pub fn output_frames(&self, input_frames: usize) -> usize {
+        (input_frames as u64 * self.to_rate as u64).div_ceil(self.from_rate as u64) as usize
+    }
+
+    /// Interpolates one channel of the input at the fractional input frame `position`.
+    fn interpolate(&self, input: &[f32], channel: usize, position: f64) -> f32 {
+        let frames = input.len() / self.channels;
+        let cutoff = (self.to_rate as f64 / self.from_rate as f64).min(1.0);
+        let half_width = self.quality.zero_crossings() as f64 / cutoff;
+
+        let first = (position - half_width).ceil().max(0.0) as usize;
+        let last = ((position + half_width).floor() as usize).min(frames.saturating_sub(1));
+
+        let mut sum = 0.0;
+        for frame in first..=last {
+            let distance = position - frame as f64;
+            sum += input[frame * self.channels + channel] as f64
+                * windowed_sinc(distance, cutoff, half_width);
+        }
+        sum as f32
+    }
+}
+
+/// Low-pass sinc kernel with the given normalized cutoff, tapered by a Blackman window
+/// spanning `[-half_width, half_width]`.
+pub(super) fn windowed_sinc(distance: f64, cutoff: f64, half_width: f64) -> f64 {
+    if distance.abs() >= half_width {
+        return 0.0;
+    }
+    let x = distance * cutoff;
+    let sinc = if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+    let phase = PI * (distance / half_width + 1.0);
+    let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+    cutoff * sinc * window
+}
+
+impl AudioNode for ResampleNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return input.to_vec();
+        }
+        let input_frames = input.len() / self.channels;
+        let step = self.from_rate as f64 / self.to_rate as f64;
+
+        let mut output = Vec::with_capacity(self.output_frames(input_frames) * self.channels);
+        for frame in 0..self.output_frames(input_frames) {
+            let position = frame as f64 * step;
+            for channel in 0..self.channels {
+                output.push(self.interpolate(input, channel, position));
+            }
+        }
+        output
+    }
+
+    /// Fails with `ProcessError::PartialFrame` if the input is not a whole number of
+    /// frames, which `process` would drop.
+    fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+        if !input.len().is_multiple_of(self.channels) {
+            return Err(ProcessError::PartialFrame { samples: input.len(), channels: self.channels });
+        }
+        Ok(self.process(input))
+    }
+
+    /// Resampling changes the number of samples, which an in-place buffer cannot hold.
+    ///
+    /// # Panics
+    ///
+    /// Always panics, use `process` instead.
+    fn process_in_place(&self, _buffer: &mut [f32]) {
+        panic!(
+            "ResampleNode changes the number of samples and cannot process in place, use process instead"
+        );
+    }
+
+    fn node_type(&self) -> &'static str {
+        "resample"
+    }
+
+    /// The output has the target sample rate.
+    fn output_context(&self, _ctx: &AudioContext) -> AudioContext {
+        AudioContext::new(self.channels as u32, self.to_rate)
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn supports_in_place(&self) -> bool {
+        false
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::peak_frequency;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(ResampleQuality::Low)]
+    #[case(ResampleQuality::High)]
+    fn test_sine_48k_to_44k1(#[case] quality: ResampleQuality) {
+        let input = sine(1000.0, 1.0, 0.2, 48000);
+        let node = ResampleNode::new(48000, 44100, 1, quality);
+        let output = node.process(&input);
+
+        assert_eq!(output.len(), 8820);
+        let ratio = output.len() as f64 / input.len() as f64;
+        assert!((ratio - 44100.0 / 48000.0).abs() < 1e-9);
+
+        // Bin resolution is 44100 / 8820 = 5 Hz
+        assert!((peak_frequency(&output, 44100) - 1000.0).abs() <= 5.0);
+
+        // Away from the edges the amplitude is preserved
+        let peak = output[500..8300].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 1.0).abs() < 0.01, "peak {}", peak);
+    }
+
+    #[rstest]
+    fn test_downsampling_removes_content_above_nyquist() {
+        // 20 kHz is above the 11.025 kHz Nyquist frequency of the output
+        let input = sine(20000.0, 1.0, 0.1, 48000);
+        let output = ResampleNode::new(48000, 22050, 1, ResampleQuality::High).process(&input);
+        let peak = output[200..output.len() - 200].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak < 0.01, "peak {}", peak);
+    }
+
+    #[rstest]
+    fn test_channels_resampled_independently() {
+        let left = sine(440.0, 1.0, 0.1, 22050);
+        let input: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let output = ResampleNode::new(22050, 44100, 2, ResampleQuality::Medium).process(&input);
+
+        assert_eq!(output.len(), 4410 * 2);
+        assert!(output.chunks_exact(2).all(|frame| frame[1] == 0.0));
+        assert!(output.chunks_exact(2).any(|frame| frame[0].abs() > 0.9));
+    }
+
+    #[rstest]
+    fn test_same_rate_passes_through() {
+        let input = vec![0.1, -0.2, 0.3];
+        let node = ResampleNode::new(44100, 44100, 1, ResampleQuality::Low);
+        assert_eq!(node.process(&input), input);
+        assert_eq!(node.box_clone().node_type(), "resample");
+    }
+
+    #[rstest]
+    fn test_try_process_rejects_partial_frame() {
+        let node = ResampleNode::new(48000, 24000, 2, ResampleQuality::Low);
+        let error = node.try_process(&[0.0; 9]).unwrap_err();
+        assert_eq!(error, ProcessError::PartialFrame { samples: 9, channels: 2 });
+        assert_eq!(node.try_process(&[0.0; 8]).unwrap().len(), 4);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "cannot process in place")]
+    fn test_process_in_place_panics() {
+        let mut buffer = vec![0.0; 16];
+        ResampleNode::new(48000, 44100, 1, ResampleQuality::Low).process_in_place(&mut buffer);
+    }
+}
@@ -0,0 +1,318 @@
+//! Reverb processing node.
+//!
+//! This module provides an algorithmic reverb following the Freeverb design: eight
+//! parallel feedback comb filters with damping build a dense decaying tail, which
+//! four allpass filters in series diffuse further.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, ReverbNode};
+//!
+//! // Medium room, slightly dark, 25% wet
+//! let node = ReverbNode::new(0.5, 0.4, 0.25, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use super::denormal::flush_denormals;
+use super::node::AudioNode;
+
+/// Comb filter delays in samples at 44.1 kHz.
+const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+/// Allpass filter delays in samples at 44.1 kHz.
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+/// Extra delay in samples at 44.1 kHz added per channel to decorrelate the channels.
+const STEREO_SPREAD: usize = 23;
+/// Attenuation of the input feeding the parallel combs.
+const INPUT_GAIN: f32 = 0.015;
+/// Gain of the wet signal, compensating the input attenuation.
+const WET_GAIN: f32 = 3.0;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+/// Feedback comb filter with a one-pole low-pass in the feedback path.
+#[derive(Clone)]
+struct Comb {
+    buffer: Vec<f32>,
+    position: usize,
+    filter_state: f32,
+}
+
+impl Comb {
+    fn new(delay: usize) -> Self {
+        Self { buffer: vec![0.0; delay.max(1)], position: 0, filter_state: 0.0 }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32, flush: bool) -> f32 {
+        let output = self.buffer[self.position];
+        self.filter_state = output * (1.0 - damping) + self.filter_state * damping;
+        let stored = input + self.filter_state * feedback;
+        if flush {
+            self.filter_state = flush_denormals(self.filter_state);
+            self.buffer[self.position] = flush_denormals(stored);
+        } else {
+            self.buffer[self.position] = stored;
+        }
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+
+    fn clear(&mut self) {
+        self.buffer.iter_mut().for_each(|s| *s = 0.0);
+        self.position = 0;
+        self.filter_state = 0.0;
+    }
+}
+
+/// Schroeder allpass filter.
+#[derive(Clone)]
+struct Allpass {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl Allpass {
+    fn new(delay: usize) -> Self {
+        Self { buffer: vec![0.0; delay.max(1)], position: 0 }
+    }
+
+    fn process(&mut self, input: f32, flush: bool) -> f32 {
+        let delayed = self.buffer[self.position];
+        let stored = input + delayed * ALLPASS_FEEDBACK;
+        self.buffer[self.position] = if flush { flush_denormals(stored) } else { stored };
+        self.position = (self.position + 1) % self.buffer.len();
+        delayed - input
+    }
+
+    fn clear(&mut self) {
+        self.buffer.iter_mut().for_each(|s| *s = 0.0);
+        self.position = 0;
+    }
+}
+
+/// The comb and allpass filters of one channel.
+#[derive(Clone)]
+struct Tank {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Tank {
+    fn new(sample_rate: f32, spread: usize) -> Self {
+        let scale = |delay: usize| ((delay + spread) as f32 * sample_rate / 44100.0).round() as usize;
+        Self {
+            combs: COMB_TUNING.iter().map(|&d| Comb::new(scale(d))).collect(),
+            allpasses: ALLPASS_TUNING.iter().map(|&d| Allpass::new(scale(d))).collect(),
+        }
+    }
+
+    /// Processes a sample, flushing denormals in the filter state if `flush` is set.
+    fn process(&mut self, input: f32, feedback: f32, damping: f32, flush: bool) -> f32 {
+        let input = input * INPUT_GAIN;
+        let combed: f32 = self.combs.iter_mut()
+            .map(|comb| comb.process(input, feedback, damping, flush))
+            .sum();
+        self.allpasses.iter_mut().fold(combed, |signal, allpass| allpass.process(signal, flush))
+    }
+
+    fn clear(&mut self) {
+        self.combs.iter_mut().for_each(Comb::clear);
+        self.allpasses.iter_mut().for_each(Allpass::clear);
+    }
+}
+
+/// An audio processing node that adds reverberation.
+///
+/// `room_size` sets how long the tail rings out and `damping` how quickly the high
+/// frequencies in the tail die away. The node keeps its tail between calls to
+/// `process`, use [`ReverbNode::reset`] to silence it before processing unrelated
+/// audio.
+///
+/// For interleaved multi-channel audio set the channel count with
+/// [`ReverbNode::with_channels`]. Each channel gets its own reverb with slightly
+/// different delay lengths, so a mono source is widened into a decorrelated stereo
+/// tail.
+///
+/// The direct sound is not delayed, so the node reports no latency: the gap before
+/// the tail builds up is part of the effect.
+#[derive(Clone)]
+pub struct ReverbNode {
+    room_size: f32,
+    damping: f32,
+    mix: f32,
+    sample_rate: f32,
+    tanks: RefCell<Vec<Tank>>,
+    channel: Cell<usize>,
+    flush_denormals: Cell<bool>,
+}
+
+impl ReverbNode {
+    /// Creates a new mono reverb node.
+    ///
+    /// # Arguments
+    ///
+    /// * `room_size` - Size of the simulated room in [0.0, 1.0], larger rooms ring longer.
+    ///   Clamped to this range.
+    /// * `damping` - High frequency absorption in [0.0, 1.0], clamped to this range
+    /// * `mix` - Dry/wet balance, 0.0 is fully dry and 1.0 fully wet. Clamped to [0.0, 1.0].
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(room_size: f32, damping: f32, mix: f32, sample_rate: f32) -> Self {
+        Self {
+            room_size: room_size.clamp(0.0, 1.0),
+            damping: damping.clamp(0.0, 1.0),
+            mix: mix.clamp(0.0, 1.0),
+            sample_rate,
+            tanks: RefCell::new(vec![Tank::new(sample_rate, 0)]),
+            channel: Cell::new(0),
+            flush_denormals: Cell::new(true),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the reverb tail.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let tanks = (0..channels.max(1))
+            .map(|channel| Tank::new(self.sample_rate, channel * STEREO_SPREAD))
+            .collect();
+        self.tanks = RefCell::new(tanks);
+        self.channel.set(0);
+        self
+    }
+
+    /// Returns the room size.
+    pub fn room_size(&self) -> f32 {
+        self.room_size
+    }
+
+    /// Returns the damping.
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Returns the dry/wet mix.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Silences the reverb tail.
+    pub fn reset(&self) {
+        self.tanks.borrow_mut().iter_mut().for_each(Tank::clear);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let mut tanks = self.tanks.borrow_mut();
+        let channel = self.channel.get();
+        self.channel.set((channel + 1) % tanks.len());
+
+        let feedback = 0.7 + 0.28 * self.room_size;
+        let damping = 0.4 * self.damping;
+        let wet = tanks[channel].process(sample, feedback, damping, self.flush_denormals.get()) * WET_GAIN;
+
+        (1.0 - self.mix) * sample + self.mix * wet
+    }
+}
+
+impl AudioNode for ReverbNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "reverb"
+    }
+
+    fn set_flush_denormals(&self, enabled: bool) {
+        self.flush_denormals.set(enabled);
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.tanks.borrow().len())
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::test_util::{impulse, rms};
+    use rstest::*;
+
+    #[rstest]
+    #[case(0.3, 0.5)]
+    #[case(0.8, 0.2)]
+    fn test_impulse_decaying_tail(#[case] room_size: f32, #[case] damping: f32) {
+        let node = ReverbNode::new(room_size, damping, 1.0, 44100.0);
+        let output = node.process(&impulse(44100));
+
+        // Skip the build-up before the combs and allpasses have all fed back
+        let windows: Vec<f32> = output[4410..].chunks(4410).map(rms).collect();
+        assert!(windows[0] > 1e-4);
+        for pair in windows.windows(2) {
+            assert!(pair[1] < pair[0], "{:?}", windows);
+        }
+
+        // The tail is dense rather than a few discrete echoes
+        let tail = &output[4410..8820];
+        let nonzero = tail.iter().filter(|s| s.abs() > 1e-9).count();
+        assert!(nonzero as f32 > 0.9 * tail.len() as f32);
+    }
+
+    #[rstest]
+    fn test_larger_room_rings_longer() {
+        let small = ReverbNode::new(0.2, 0.5, 1.0, 44100.0).process(&impulse(44100));
+        let large = ReverbNode::new(0.9, 0.5, 1.0, 44100.0).process(&impulse(44100));
+        assert!(rms(&large[30000..]) > 2.0 * rms(&small[30000..]));
+    }
+
+    #[rstest]
+    fn test_dry_mix_passes_through() {
+        let node = ReverbNode::new(0.5, 0.5, 0.0, 44100.0);
+        let input: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.1).sin()).collect();
+        assert_eq!(node.process(&input), input);
+        assert_eq!(node.latency_samples(), 0);
+    }
+
+    #[rstest]
+    fn test_tail_flushed_to_zero() {
+        // At a tenth of the usual sample rate the short delay lines decay quickly
+        let node = ReverbNode::new(0.0, 0.0, 1.0, 4410.0);
+        let output = node.process(&impulse(100_000));
+        assert!(output[90_000..].iter().all(|&s| s == 0.0));
+
+        let tanks = node.tanks.borrow();
+        assert!(tanks[0].combs.iter().all(|comb| comb.filter_state == 0.0 && comb.buffer.iter().all(|&s| s == 0.0)));
+        assert!(tanks[0].allpasses.iter().all(|allpass| allpass.buffer.iter().all(|&s| s == 0.0)));
+    }
+
+    #[rstest]
+    fn test_channels_decorrelated_and_reset() {
+        let node = ReverbNode::new(0.5, 0.5, 1.0, 44100.0).with_channels(2);
+        let input: Vec<f32> = impulse(8000).iter().flat_map(|&s| [s, s]).collect();
+        let output = node.process(&input);
+
+        let left: Vec<f32> = output.iter().step_by(2).cloned().collect();
+        let right: Vec<f32> = output.iter().skip(1).step_by(2).cloned().collect();
+        assert_ne!(left, right);
+
+        node.reset();
+        assert_eq!(node.process(&input), output);
+        assert_eq!(node.box_clone().node_type(), "reverb");
+    }
+}
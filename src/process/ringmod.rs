@@ -0,0 +1,170 @@
+//! Ring modulator processing node.
+//!
+//! Ring modulation multiplies the signal by a sine carrier. Every frequency of the
+//! input is replaced by the sum and difference with the carrier frequency, which
+//! produces metallic, bell-like or "robot voice" sounds.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, RingModNode};
+//!
+//! // Robot voice with a 30 Hz carrier
+//! let node = RingModNode::new(30.0, 1.0, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::Cell;
+use std::f64::consts::TAU;
+use super::node::AudioNode;
+
+/// An audio processing node that multiplies the input by a sine carrier.
+///
+/// The carrier phase carries over between calls to `process`, use
+/// [`RingModNode::reset`] to restart it. For interleaved multi-channel audio set the
+/// channel count with [`RingModNode::with_channels`] so all channels of a frame are
+/// multiplied by the same carrier value.
+#[derive(Clone)]
+pub struct RingModNode {
+    carrier_hz: f32,
+    mix: f32,
+    sample_rate: f32,
+    channels: usize,
+    phase: Cell<f64>,
+    channel: Cell<usize>,
+}
+
+impl RingModNode {
+    /// Creates a new mono ring modulator node.
+    ///
+    /// # Arguments
+    ///
+    /// * `carrier_hz` - Frequency of the sine carrier in Hz
+    /// * `mix` - Dry/wet balance, 0.0 is fully dry and 1.0 fully wet. Clamped to [0.0, 1.0].
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(carrier_hz: f32, mix: f32, sample_rate: f32) -> Self {
+        Self {
+            carrier_hz: carrier_hz.max(0.0),
+            mix: mix.clamp(0.0, 1.0),
+            sample_rate,
+            channels: 1,
+            phase: Cell::new(0.0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the carrier phase.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Returns the carrier frequency in Hz.
+    pub fn carrier_hz(&self) -> f32 {
+        self.carrier_hz
+    }
+
+    /// Returns the dry/wet mix.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Restarts the carrier at phase zero.
+    pub fn reset(&self) {
+        self.phase.set(0.0);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let carrier = (TAU * self.phase.get()).sin() as f32;
+
+        let channel = self.channel.get() + 1;
+        if channel == self.channels {
+            self.channel.set(0);
+            let next = self.phase.get() + self.carrier_hz as f64 / self.sample_rate as f64;
+            self.phase.set(next.fract());
+        } else {
+            self.channel.set(channel);
+        }
+
+        (1.0 - self.mix) * sample + self.mix * sample * carrier
+    }
+}
+
+impl AudioNode for RingModNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "ring_mod"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::{magnitude_spectrum, WindowType};
+    use crate::synth::sine;
+    use rstest::*;
+
+    const SAMPLE_RATE: f32 = 8000.0;
+
+    #[rstest]
+    #[case(440.0, 100.0)]
+    #[case(1000.0, 330.0)]
+    fn test_sidebands_replace_original(#[case] input_hz: f32, #[case] carrier_hz: f32) {
+        // One second at 8 kHz gives 1 Hz bins
+        let node = RingModNode::new(carrier_hz, 1.0, SAMPLE_RATE);
+        let output = node.process(&sine(input_hz, 1.0, 1.0, SAMPLE_RATE as u32));
+        let spectrum = magnitude_spectrum(&output, WindowType::Hann);
+
+        let difference = spectrum[(input_hz - carrier_hz) as usize];
+        let sum = spectrum[(input_hz + carrier_hz) as usize];
+        let original = spectrum[input_hz as usize];
+        let carrier = spectrum[carrier_hz as usize];
+
+        // sin(a) * sin(b) = (cos(a - b) - cos(a + b)) / 2
+        assert!((difference - 0.5).abs() < 0.01, "difference {}", difference);
+        assert!((sum - 0.5).abs() < 0.01, "sum {}", sum);
+        assert!(original < 1e-3, "original {}", original);
+        assert!(carrier < 1e-3, "carrier {}", carrier);
+    }
+
+    #[rstest]
+    fn test_dry_mix_passes_through() {
+        let node = RingModNode::new(100.0, 0.0, SAMPLE_RATE);
+        let input = sine(440.0, 1.0, 0.125, SAMPLE_RATE as u32);
+        assert_eq!(node.process(&input), input);
+    }
+
+    #[rstest]
+    fn test_channels_share_carrier_and_reset() {
+        let node = RingModNode::new(50.0, 1.0, SAMPLE_RATE).with_channels(2);
+        let output = node.process(&[1.0; 800]);
+        assert!(output.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+
+        node.reset();
+        assert_eq!(node.process(&[1.0; 800]), output);
+        assert_eq!(node.box_clone().node_type(), "ring_mod");
+    }
+}
@@ -0,0 +1,13 @@
+//! Test signals and measurements shared by the node tests.
+
+/// Returns a unit impulse followed by `len - 1` zeros.
+pub(crate) fn impulse(len: usize) -> Vec<f32> {
+    let mut samples = vec![0.0; len];
+    samples[0] = 1.0;
+    samples
+}
+
+/// Returns the linear RMS level of the samples.
+pub(crate) fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
@@ -0,0 +1,209 @@
+//! Time-stretching node.
+//!
+//! This module changes the duration of audio without changing its pitch, e.g. to
+//! slow down a recording for transcription or fit a loop to a new tempo. It uses the
+//! same phase vocoder as [`PitchShiftNode`](super::PitchShiftNode), without the
+//! resampling step.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, TimeStretchNode};
+//!
+//! // Stereo audio at half speed
+//! let node = TimeStretchNode::new(2.0, 48000.0, 2);
+//!
+//! let input = vec![0.5f32; 48000 * 2];
+//! let output = node.process(&input);
+//! assert_eq!(output.len(), 2 * input.len());
+//! ```
+
+use super::node::{AudioNode, ProcessError};
+use super::vocoder::PhaseVocoder;
+
+/// Shortest stretch ratio accepted, four times faster.
+pub const MIN_STRETCH_RATIO: f32 = 0.25;
+/// Longest stretch ratio accepted, four times slower.
+pub const MAX_STRETCH_RATIO: f32 = 4.0;
+
+/// An audio processing node that stretches or compresses interleaved audio in time.
+///
+/// A buffer of `n` frames produces `round(n * ratio)` frames with the same pitch.
+/// Like [`ResampleNode`](super::ResampleNode) the node processes every buffer
+/// independently, so it is meant for whole signals rather than a stream of blocks.
+/// Transients are smeared over an analysis frame of about 40 ms.
+///
+/// Because the number of samples changes, the node cannot work in place:
+/// `process_in_place` panics, use `process` instead.
+#[derive(Clone)]
+pub struct TimeStretchNode {
+    ratio: f32,
+    channels: usize,
+    vocoder: PhaseVocoder,
+}
+
+impl TimeStretchNode {
+    /// Creates a new time-stretching node.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratio` - Output duration relative to the input, 2.0 is twice as long. Clamped to
+    ///   [`MIN_STRETCH_RATIO`, `MAX_STRETCH_RATIO`].
+    /// * `sample_rate` - Sample rate in Hz, which sets the frame size to about 40 ms
+    /// * `channels` - Number of interleaved audio channels
+    pub fn new(ratio: f32, sample_rate: f32, channels: usize) -> Self {
+        let ratio = ratio.clamp(MIN_STRETCH_RATIO, MAX_STRETCH_RATIO);
+        Self {
+            ratio,
+            channels: channels.max(1),
+            vocoder: PhaseVocoder::new(ratio as f64, sample_rate),
+        }
+    }
+
+    /// Returns the stretch ratio.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Returns the number of output frames produced for the given number of input frames.
+    pub fn output_frames(&self, input_frames: usize) -> usize {
+        (input_frames as f64 * self.ratio as f64).round() as usize
+    }
+
+    /// Stretches one channel of the input.
+    fn stretch_channel(&self, input: &[f32], channel: usize, output_frames: usize) -> Vec<f32> {
+        let mut state = self.vocoder.state();
+        input
+            .iter()
+            .skip(channel)
+            .step_by(self.channels)
+            .for_each(|&sample| self.vocoder.push(&mut state, sample));
+        // Flush with silence until the last output sample is complete
+        while self.vocoder.finished_before(&state) < output_frames as isize {
+            self.vocoder.push(&mut state, 0.0);
+        }
+        (0..output_frames as isize).map(|i| state.stretched_at(i)).collect()
+    }
+}
+
+impl AudioNode for TimeStretchNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let output_frames = self.output_frames(input.len() / self.channels);
+        let stretched: Vec<Vec<f32>> = (0..self.channels)
+            .map(|channel| self.stretch_channel(input, channel, output_frames))
+            .collect();
+        (0..output_frames)
+            .flat_map(|frame| stretched.iter().map(move |channel| channel[frame]))
+            .collect()
+    }
+
+    /// Fails with `ProcessError::PartialFrame` if the input is not a whole number of
+    /// frames, which `process` would drop.
+    fn try_process(&self, input: &[f32]) -> Result<Vec<f32>, ProcessError> {
+        if !input.len().is_multiple_of(self.channels) {
+            return Err(ProcessError::PartialFrame { samples: input.len(), channels: self.channels });
+        }
+        Ok(self.process(input))
+    }
+
+    /// Time stretching changes the number of samples, which an in-place buffer cannot hold.
+    ///
+    /// # Panics
+    ///
+    /// Always panics, use `process` instead.
+    fn process_in_place(&self, _buffer: &mut [f32]) {
+        panic!(
+            "TimeStretchNode changes the number of samples and cannot process in place, use process instead"
+        );
+    }
+
+    fn node_type(&self) -> &'static str {
+        "time_stretch"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(self.channels)
+    }
+
+    fn supports_in_place(&self) -> bool {
+        false
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::peak_frequency;
+    use crate::synth::sine;
+    use rstest::*;
+
+    #[rstest]
+    #[case(2.0)]
+    #[case(0.5)]
+    #[case(1.3)]
+    fn test_sine_keeps_frequency(#[case] ratio: f32) {
+        let input = sine(440.0, 0.5, 2.0, 48000);
+        let node = TimeStretchNode::new(ratio, 48000.0, 1);
+        let output = node.process(&input);
+        assert_eq!(output.len(), (96000.0 * ratio).round() as usize);
+
+        // Away from the edges, bin resolution is 2 Hz
+        let middle = output.len() / 2;
+        let settled = &output[middle - 12000..middle + 12000];
+        assert!((peak_frequency(settled, 48000) - 440.0).abs() <= 2.0);
+        let peak = settled.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.05, "peak {}", peak);
+    }
+
+    #[rstest]
+    fn test_unit_ratio_passes_through() {
+        let input = sine(330.0, 0.5, 0.5, 44100);
+        let output = TimeStretchNode::new(1.0, 44100.0, 1).process(&input);
+        assert_eq!(output.len(), input.len());
+        for (i, (out, expected)) in output.iter().zip(&input).enumerate() {
+            assert!((out - expected).abs() < 1e-3, "sample {}: {} vs {}", i, out, expected);
+        }
+    }
+
+    #[rstest]
+    fn test_channels_stretched_independently() {
+        let left = sine(440.0, 0.5, 0.5, 48000);
+        let input: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let output = TimeStretchNode::new(1.5, 48000.0, 2).process(&input);
+        let mono = TimeStretchNode::new(1.5, 48000.0, 1).process(&left);
+
+        assert_eq!(output.len(), 2 * 36000);
+        for (frame, expected) in output.chunks_exact(2).zip(&mono) {
+            assert_eq!(frame[0], *expected);
+            assert_eq!(frame[1], 0.0);
+        }
+    }
+
+    #[rstest]
+    fn test_ratio_clamped() {
+        let node = TimeStretchNode::new(10.0, 48000.0, 1);
+        assert_eq!(node.ratio(), MAX_STRETCH_RATIO);
+        assert_eq!(node.output_frames(100), 400);
+        assert_eq!(node.box_clone().node_type(), "time_stretch");
+        assert!(node.process(&[]).is_empty());
+    }
+
+    #[rstest]
+    fn test_try_process_rejects_partial_frame() {
+        let node = TimeStretchNode::new(2.0, 48000.0, 2);
+        let error = node.try_process(&[0.0; 9]).unwrap_err();
+        assert_eq!(error, ProcessError::PartialFrame { samples: 9, channels: 2 });
+        assert_eq!(node.try_process(&[0.0; 8]).unwrap().len(), 16);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "cannot process in place")]
+    fn test_process_in_place_panics() {
+        let mut buffer = vec![0.0; 16];
+        TimeStretchNode::new(2.0, 48000.0, 1).process_in_place(&mut buffer);
+    }
+}
@@ -0,0 +1,219 @@
+//! Tremolo (amplitude modulation) processing node.
+//!
+//! A tremolo periodically raises and lowers the volume with a low-frequency
+//! oscillator (LFO), a classic effect of vintage guitar amplifiers.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, TremoloNode};
+//!
+//! // 5 Hz tremolo dipping down to 40% of the volume
+//! let node = TremoloNode::new(5.0, 0.6, 48000.0);
+//!
+//! let input = vec![0.5f32; 48000];
+//! let output = node.process(&input);
+//! ```
+
+use std::cell::Cell;
+use std::f64::consts::TAU;
+use super::node::AudioNode;
+
+/// An audio processing node that modulates the amplitude with a sine LFO.
+///
+/// The gain swings between 1.0 and `1.0 - depth` at `rate_hz` cycles per second,
+/// starting at full gain. The LFO phase carries over between calls to `process`, use
+/// [`TremoloNode::reset`] to restart it.
+///
+/// For interleaved multi-channel audio set the channel count with
+/// [`TremoloNode::with_channels`]. All channels are modulated in phase unless an
+/// offset is set with [`TremoloNode::with_phase_offset`]; an offset of 180° on stereo
+/// audio moves the sound from side to side like an auto-panner.
+#[derive(Clone)]
+pub struct TremoloNode {
+    rate_hz: f32,
+    depth: f32,
+    sample_rate: f32,
+    channels: usize,
+    phase_offset_deg: f32,
+    phase: Cell<f64>,
+    channel: Cell<usize>,
+}
+
+impl TremoloNode {
+    /// Creates a new mono tremolo node.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_hz` - LFO frequency in Hz (e.g. 2.0 to 10.0)
+    /// * `depth` - Modulation amount in [0.0, 1.0], 0.0 leaves the signal unchanged and 1.0
+    ///   dips to silence. Clamped to this range.
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn new(rate_hz: f32, depth: f32, sample_rate: f32) -> Self {
+        Self {
+            rate_hz: rate_hz.max(0.0),
+            depth: depth.clamp(0.0, 1.0),
+            sample_rate,
+            channels: 1,
+            phase_offset_deg: 0.0,
+            phase: Cell::new(0.0),
+            channel: Cell::new(0),
+        }
+    }
+
+    /// Configures the node for interleaved audio with the given number of channels.
+    ///
+    /// This resets the LFO phase.
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self.reset();
+        self
+    }
+
+    /// Sets the LFO phase offset between adjacent channels in degrees.
+    ///
+    /// Channel `n` is modulated `n * offset_deg` degrees ahead of the first channel.
+    pub fn with_phase_offset(mut self, offset_deg: f32) -> Self {
+        self.phase_offset_deg = offset_deg;
+        self
+    }
+
+    /// Returns the LFO frequency in Hz.
+    pub fn rate_hz(&self) -> f32 {
+        self.rate_hz
+    }
+
+    /// Returns the modulation depth.
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Restarts the LFO at full gain.
+    pub fn reset(&self) {
+        self.phase.set(0.0);
+        self.channel.set(0);
+    }
+
+    /// Processes a single sample.
+    ///
+    /// Samples must be passed in interleaved order when the node is configured for
+    /// more than one channel.
+    pub fn process_sample(&self, sample: f32) -> f32 {
+        let channel = self.channel.get();
+        let phase = self.phase.get() + channel as f64 * self.phase_offset_deg as f64 / 360.0;
+        let lfo = (0.5 + 0.5 * (TAU * phase).cos()) as f32;
+        let gain = 1.0 - self.depth * (1.0 - lfo);
+
+        if channel + 1 == self.channels {
+            self.channel.set(0);
+            let next = self.phase.get() + self.rate_hz as f64 / self.sample_rate as f64;
+            self.phase.set(next.fract());
+        } else {
+            self.channel.set(channel + 1);
+        }
+
+        sample * gain
+    }
+}
+
+impl AudioNode for TremoloNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.process_sample(*sample);
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "tremolo"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// Returns the indices of the local maxima of the envelope.
+    fn peaks(envelope: &[f32]) -> Vec<usize> {
+        (1..envelope.len() - 1)
+            .filter(|&i| envelope[i] > envelope[i - 1] && envelope[i] >= envelope[i + 1])
+            .collect()
+    }
+
+    #[rstest]
+    #[case(5.0, 0.5)]
+    #[case(4.0, 1.0)]
+    #[case(10.0, 0.25)]
+    fn test_envelope_rate_and_range(#[case] rate_hz: f32, #[case] depth: f32) {
+        let node = TremoloNode::new(rate_hz, depth, 1000.0);
+        let envelope = node.process(&[1.0; 2000]);
+
+        let max = envelope.iter().cloned().fold(f32::MIN, f32::max);
+        let min = envelope.iter().cloned().fold(f32::MAX, f32::min);
+        assert!((max - 1.0).abs() < 1e-6);
+        assert!((min - (1.0 - depth)).abs() < 1e-3, "min {}", min);
+
+        let peaks = peaks(&envelope);
+        let period = 1000.0 / rate_hz;
+        assert_eq!(peaks.len(), (2.0 * rate_hz) as usize - 1);
+        for pair in peaks.windows(2) {
+            assert!(((pair[1] - pair[0]) as f32 - period).abs() <= 1.0);
+        }
+    }
+
+    #[rstest]
+    fn test_zero_depth_passes_through() {
+        let node = TremoloNode::new(5.0, 0.0, 1000.0);
+        let input: Vec<f32> = (0..100).map(|i| i as f32 / 100.0).collect();
+        assert_eq!(node.process(&input), input);
+    }
+
+    #[rstest]
+    fn test_phase_carries_over_and_reset() {
+        let node = TremoloNode::new(3.0, 0.8, 1000.0);
+        let whole = node.clone().process(&[1.0; 1000]);
+
+        let first = node.process(&[1.0; 300]);
+        let second = node.process(&[1.0; 700]);
+        assert_eq!([first, second].concat(), whole);
+
+        node.reset();
+        assert_eq!(node.process(&[1.0; 1000]), whole);
+    }
+
+    #[rstest]
+    fn test_stereo_in_phase_by_default() {
+        let node = TremoloNode::new(5.0, 0.5, 1000.0).with_channels(2);
+        let output = node.process(&[1.0; 400]);
+        assert!(output.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+
+        // The stereo envelope matches the mono one per frame
+        let mono = TremoloNode::new(5.0, 0.5, 1000.0).process(&[1.0; 200]);
+        let left: Vec<f32> = output.iter().step_by(2).cloned().collect();
+        assert_eq!(left, mono);
+    }
+
+    #[rstest]
+    fn test_stereo_phase_offset() {
+        let node = TremoloNode::new(5.0, 1.0, 1000.0)
+            .with_channels(2)
+            .with_phase_offset(180.0);
+        let output = node.process(&[1.0; 400]);
+
+        // Opposite phase: one channel is loud while the other is quiet
+        assert!((output[0] - 1.0).abs() < 1e-6);
+        assert!(output[1].abs() < 1e-6);
+        for frame in output.chunks_exact(2) {
+            assert!((frame[0] + frame[1] - 1.0).abs() < 1e-5);
+        }
+        assert_eq!(node.box_clone().node_type(), "tremolo");
+    }
+}
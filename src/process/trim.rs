@@ -0,0 +1,85 @@
+//! Silence trimming.
+//!
+//! This module removes leading and trailing silence from a recording, e.g. dead air
+//! before the host starts talking. Trimming is done on whole frames so the
+//! channel interleaving of the output is preserved.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::trim_silence;
+//!
+//! let samples = vec![0.0, 0.0, 0.5, -0.5, 0.0, 0.0];
+//! let trimmed = trim_silence(&samples, 2, -60.0);
+//! assert_eq!(trimmed, vec![0.5, -0.5]);
+//! ```
+
+use crate::analytic::silent_frames;
+
+/// Removes leading and trailing silence from interleaved audio.
+///
+/// A frame counts as silent when every channel is below `threshold_dbfs`. Silence
+/// in the middle of the audio is kept.
+///
+/// # Arguments
+///
+/// * `samples` - Interleaved audio samples
+/// * `channels` - Number of audio channels
+/// * `threshold_dbfs` - Level in dBFS below which a frame counts as silent (e.g. -60.0)
+///
+/// # Returns
+///
+/// A new vector containing the audio between the first and last non-silent frame,
+/// or an empty vector if the whole buffer is silent.
+pub fn trim_silence(samples: &[f32], channels: usize, threshold_dbfs: f32) -> Vec<f32> {
+    let channels = channels.max(1);
+    let silent = silent_frames(samples, channels, threshold_dbfs);
+    match silent.iter().position(|&s| !s) {
+        Some(first) => {
+            let last = silent.iter().rposition(|&s| !s).unwrap_or(first);
+            let end = ((last + 1) * channels).min(samples.len());
+            samples[first * channels..end].to_vec()
+        }
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn padded() -> Vec<f32> {
+        let mut samples = vec![0.0f32; 100 * 2];
+        samples.extend([0.5, -0.5, 0.0, 0.0, 0.25, 0.0]);
+        samples.extend(vec![0.0f32; 50 * 2]);
+        samples
+    }
+
+    #[rstest]
+    fn test_trims_leading_and_trailing(padded: Vec<f32>) {
+        let trimmed = trim_silence(&padded, 2, -60.0);
+        assert_eq!(trimmed, vec![0.5, -0.5, 0.0, 0.0, 0.25, 0.0]);
+    }
+
+    #[rstest]
+    fn test_preserves_channel_alignment() {
+        // Only the right channel of the first frame is loud
+        let samples = vec![0.0, 0.0, 0.0, 0.5, 0.0, 0.0];
+        let trimmed = trim_silence(&samples, 2, -60.0);
+        assert_eq!(trimmed, vec![0.0, 0.5]);
+    }
+
+    #[rstest]
+    fn test_all_silent() {
+        let samples = vec![0.0f32; 100];
+        assert!(trim_silence(&samples, 2, -60.0).is_empty());
+    }
+
+    #[rstest]
+    fn test_no_silence_unchanged() {
+        let samples = vec![0.5f32, -0.5, 0.5, -0.5];
+        assert_eq!(trim_silence(&samples, 1, -60.0), samples);
+    }
+}
@@ -0,0 +1,208 @@
+//! Phase vocoder time stretching shared by the pitch and time processing nodes.
+//!
+//! The input is cut into overlapping windowed frames. Each frame is placed `ratio`
+//! times further apart in the output than in the input, and the phase of every
+//! frequency bin is advanced by the bin's measured frequency times the new spacing,
+//! so the frames join up without changing the pitch.
+
+use std::collections::VecDeque;
+use std::f64::consts::{PI, TAU};
+use std::sync::Arc;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use crate::analytic::window::hann;
+
+/// Approximate length of an analysis frame in seconds.
+const FRAME_SEC: f32 = 0.04;
+/// Minimum number of stretched frames covering each output sample.
+const OVERLAP: usize = 4;
+
+/// Frame layout and FFTs of a phase vocoder, shared by all channels.
+#[derive(Clone)]
+pub(super) struct PhaseVocoder {
+    ratio: f64,
+    frame_size: usize,
+    hop: usize,
+    window: Vec<f32>,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+}
+
+/// Analysis and overlap-add state of one channel.
+///
+/// Stretched sample `i` corresponds to input position `i / ratio`. Before the first
+/// sample the input is silent.
+#[derive(Clone)]
+pub(super) struct VocoderState {
+    /// The last frame of input samples
+    input: VecDeque<f32>,
+    samples_in: usize,
+    frames: usize,
+    analysis_phase: Vec<f64>,
+    synthesis_phase: Vec<f64>,
+    /// Overlap-added stretched samples and summed squared window, starting at `stretched_start`
+    stretched: VecDeque<(f32, f32)>,
+    stretched_start: isize,
+}
+
+impl PhaseVocoder {
+    /// Creates a phase vocoder stretching time by `ratio`, with frames of about 40 ms.
+    pub(super) fn new(ratio: f64, sample_rate: f32) -> Self {
+        let frame_size = ((sample_rate.max(1.0) * FRAME_SEC) as usize).next_power_of_two().max(256);
+        // Frames are spread out by the ratio, so stretching needs a shorter analysis
+        // hop to keep the stretched frames overlapping
+        let hop = frame_size / OVERLAP / (ratio.ceil().max(1.0) as usize);
+        let mut planner = FftPlanner::new();
+        Self {
+            ratio,
+            frame_size,
+            hop,
+            window: hann(frame_size),
+            fft: planner.plan_fft_forward(frame_size),
+            ifft: planner.plan_fft_inverse(frame_size),
+        }
+    }
+
+    /// Returns the length of the analysis frames in samples.
+    pub(super) fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Returns an empty state for one channel.
+    pub(super) fn state(&self) -> VocoderState {
+        VocoderState {
+            input: VecDeque::from(vec![0.0; self.frame_size]),
+            samples_in: 0,
+            frames: 0,
+            analysis_phase: vec![0.0; self.frame_size / 2 + 1],
+            synthesis_phase: vec![0.0; self.frame_size / 2 + 1],
+            stretched: VecDeque::new(),
+            stretched_start: 0,
+        }
+    }
+
+    /// Position in the stretched audio of the first sample of a frame.
+    ///
+    /// Frame `k` ends with the input sample `(k + 1) * hop`, and its center is mapped to
+    /// the center of the stretched frame.
+    fn synthesis_position(&self, frame: usize) -> isize {
+        let start = ((frame + 1) * self.hop) as f64 - self.frame_size as f64;
+        let half = self.frame_size as f64 / 2.0;
+        ((start + half) * self.ratio - half).round() as isize
+    }
+
+    /// Returns the index below which the stretched audio of a channel is complete.
+    pub(super) fn finished_before(&self, state: &VocoderState) -> isize {
+        self.synthesis_position(state.frames)
+    }
+
+    /// Adds an input sample to a channel, stretching a frame whenever one is complete.
+    pub(super) fn push(&self, state: &mut VocoderState, sample: f32) {
+        state.input.pop_front();
+        state.input.push_back(sample);
+        state.samples_in += 1;
+        if state.samples_in.is_multiple_of(self.hop) {
+            self.process_frame(state);
+        }
+    }
+
+    /// Stretches the current input frame of a channel and overlap-adds it.
+    fn process_frame(&self, state: &mut VocoderState) {
+        let n = self.frame_size;
+        let mut buffer: Vec<Complex<f32>> = state.input
+            .iter()
+            .zip(&self.window)
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buffer);
+
+        let frame = state.frames;
+        let position = self.synthesis_position(frame);
+        let synthesis_hop = match frame.checked_sub(1) {
+            Some(prev) => (position - self.synthesis_position(prev)) as f64,
+            None => 0.0,
+        };
+        let bins = n / 2 + 1;
+        let magnitudes: Vec<f32> = buffer[..bins].iter().map(|c| c.norm()).collect();
+        let phases: Vec<f64> = buffer[..bins].iter().map(|c| c.arg() as f64).collect();
+        if frame == 0 {
+            state.synthesis_phase.copy_from_slice(&phases);
+        } else {
+            // Identity phase locking: only spectral peaks are propagated with their
+            // measured frequency, the bins around each peak keep their phase relative to
+            // it. Propagating every bin on its own lets the bins of one partial drift
+            // apart and partly cancel.
+            let peaks: Vec<usize> = (0..bins)
+                .filter(|&bin| {
+                    let neighbours = bin.saturating_sub(2)..(bin + 3).min(bins);
+                    neighbours.clone().all(|other| magnitudes[other] <= magnitudes[bin])
+                        && neighbours.clone().any(|other| magnitudes[other] < magnitudes[bin])
+                })
+                .collect();
+            let mut peak_phase = Vec::with_capacity(peaks.len());
+            for &peak in &peaks {
+                // The deviation from the bin's nominal phase advance gives the true frequency
+                let omega = TAU * peak as f64 / n as f64;
+                let deviation = phases[peak] - state.analysis_phase[peak] - omega * self.hop as f64;
+                let deviation = deviation - TAU * ((deviation + PI) / TAU).floor();
+                let frequency = omega + deviation / self.hop as f64;
+                peak_phase.push(state.synthesis_phase[peak] + frequency * synthesis_hop);
+            }
+
+            let mut nearest = 0;
+            for bin in 0..bins {
+                while nearest + 1 < peaks.len() && peaks[nearest + 1] - bin < bin.abs_diff(peaks[nearest]) {
+                    nearest += 1;
+                }
+                state.synthesis_phase[bin] = match peaks.get(nearest) {
+                    Some(&peak) => (peak_phase[nearest] + phases[bin] - phases[peak]).rem_euclid(TAU),
+                    None => phases[bin],
+                };
+            }
+        }
+        state.analysis_phase.copy_from_slice(&phases);
+
+        for bin in 0..bins {
+            let value = Complex::from_polar(magnitudes[bin], state.synthesis_phase[bin] as f32);
+            buffer[bin] = value;
+            if bin != 0 && bin != n / 2 {
+                buffer[n - bin] = value.conj();
+            }
+        }
+        self.ifft.process(&mut buffer);
+
+        if state.frames == 0 {
+            state.stretched_start = position;
+        }
+        let offset = (position - state.stretched_start).max(0) as usize;
+        if state.stretched.len() < offset + n {
+            state.stretched.resize(offset + n, (0.0, 0.0));
+        }
+        for (i, (value, &w)) in buffer.iter().zip(&self.window).enumerate() {
+            let (sum, norm) = &mut state.stretched[offset + i];
+            *sum += value.re / n as f32 * w;
+            *norm += w * w;
+        }
+        state.frames += 1;
+    }
+}
+
+impl VocoderState {
+    /// Returns the stretched sample at the given index, 0.0 outside the buffered range.
+    pub(super) fn stretched_at(&self, index: isize) -> f32 {
+        let Some(offset) = index.checked_sub(self.stretched_start).filter(|&offset| offset >= 0) else {
+            return 0.0;
+        };
+        match self.stretched.get(offset as usize) {
+            Some(&(sum, norm)) if norm > 1e-6 => sum / norm,
+            _ => 0.0,
+        }
+    }
+
+    /// Frees the stretched samples before the given index.
+    pub(super) fn discard_before(&mut self, index: isize) {
+        let count = (index - self.stretched_start).clamp(0, self.stretched.len() as isize);
+        self.stretched.drain(..count as usize);
+        self.stretched_start += count;
+    }
+}
@@ -0,0 +1,191 @@
+//! Waveshaper (soft clipping / distortion) processing node.
+//!
+//! A waveshaper passes every sample through a fixed non-linear transfer function.
+//! Gentle curves like `tanh` round off peaks and add warmth, while a hard clip
+//! produces harsh distortion.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, ShapeFunction, WaveshaperNode};
+//!
+//! // Warm tanh saturation with moderate drive
+//! let node = WaveshaperNode::new(ShapeFunction::Tanh, 3.0);
+//!
+//! let input = vec![0.5f32; 1000];
+//! let output = node.process(&input);
+//! ```
+
+use std::f32::consts::FRAC_PI_2;
+use super::node::{for_each_chunk, AudioNode};
+
+/// Smallest drive accepted, lower values are clamped.
+pub const MIN_DRIVE: f32 = 0.01;
+
+/// Transfer functions available to the [`WaveshaperNode`].
+///
+/// All functions are odd-symmetric, map their input into [-1.0, 1.0] and have a
+/// linear region around zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeFunction {
+    /// Hyperbolic tangent, smooth and symmetric saturation
+    Tanh,
+    /// Cubic soft clipper, saturates fully at an input of ±1
+    Cubic,
+    /// Hard clipping at ±1
+    HardClip,
+    /// Scaled arctangent, saturates more slowly than tanh
+    Arctan,
+}
+
+impl ShapeFunction {
+    /// Applies the transfer function to a single value.
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            ShapeFunction::Tanh => x.tanh(),
+            ShapeFunction::Cubic => {
+                if x.abs() >= 1.0 {
+                    x.signum()
+                } else {
+                    1.5 * (x - x * x * x / 3.0)
+                }
+            }
+            ShapeFunction::HardClip => x.clamp(-1.0, 1.0),
+            ShapeFunction::Arctan => (x * FRAC_PI_2).atan() / FRAC_PI_2,
+        }
+    }
+}
+
+/// An audio processing node that applies waveshaping distortion.
+///
+/// The input is multiplied by `drive` before shaping, so higher drive pushes more of
+/// the signal into the non-linear region. The output is then compensated by
+/// `1 / shape(drive)`, keeping a full-scale input at full scale regardless of the
+/// drive setting. Input within [-1.0, 1.0] therefore always produces output within
+/// [-1.0, 1.0].
+#[derive(Clone)]
+pub struct WaveshaperNode {
+    shape: ShapeFunction,
+    drive: f32,
+}
+
+impl WaveshaperNode {
+    /// Creates a new waveshaper node.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - Transfer function to apply
+    /// * `drive` - Linear pre-gain applied before shaping (e.g. 1.0 to 10.0), clamped to at
+    ///   least `MIN_DRIVE`
+    pub fn new(shape: ShapeFunction, drive: f32) -> Self {
+        Self {
+            shape,
+            drive: drive.max(MIN_DRIVE),
+        }
+    }
+
+    /// Returns the transfer function.
+    pub fn shape(&self) -> ShapeFunction {
+        self.shape
+    }
+
+    /// Returns the drive.
+    pub fn drive(&self) -> f32 {
+        self.drive
+    }
+
+    /// Sets a new drive, clamped to at least `MIN_DRIVE`.
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.max(MIN_DRIVE);
+    }
+
+    fn compensation(&self) -> f32 {
+        1.0 / self.shape.apply(self.drive)
+    }
+}
+
+impl AudioNode for WaveshaperNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        let compensation = self.compensation();
+        buffer.iter_mut().for_each(|sample| {
+            *sample = self.shape.apply(*sample * self.drive) * compensation;
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "waveshaper"
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn process_parallel(&self, buffer: &mut [f32]) {
+        let node = self.clone();
+        for_each_chunk(buffer, 1, move |chunk| node.process_in_place(chunk));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn full_scale_ramp() -> Vec<f32> {
+        (-100..=100).map(|i| i as f32 / 100.0).collect()
+    }
+
+    #[rstest]
+    #[case(ShapeFunction::Tanh)]
+    #[case(ShapeFunction::Cubic)]
+    #[case(ShapeFunction::HardClip)]
+    #[case(ShapeFunction::Arctan)]
+    fn test_bounded_for_large_drive(#[case] shape: ShapeFunction, full_scale_ramp: Vec<f32>) {
+        let node = WaveshaperNode::new(shape, 50.0);
+        let output = node.process(&full_scale_ramp);
+        assert!(output.iter().all(|s| s.abs() <= 1.0 + 1e-6));
+        assert!((output[200] - 1.0).abs() < 1e-6);
+        assert!((output[0] + 1.0).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[case(ShapeFunction::Tanh)]
+    #[case(ShapeFunction::Cubic)]
+    #[case(ShapeFunction::HardClip)]
+    #[case(ShapeFunction::Arctan)]
+    fn test_low_level_nearly_linear(#[case] shape: ShapeFunction) {
+        let node = WaveshaperNode::new(shape, 2.0);
+        let small = [0.001f32, 0.002, -0.004];
+        let output = node.process(&small);
+        let ratio = output[0] / small[0];
+        for (out, inp) in output.iter().zip(small.iter()) {
+            assert!((out / inp - ratio).abs() / ratio < 1e-3);
+        }
+    }
+
+    #[rstest]
+    fn test_process_methods_and_type(full_scale_ramp: Vec<f32>) {
+        let node = WaveshaperNode::new(ShapeFunction::Arctan, 4.0);
+        let output = node.process(&full_scale_ramp);
+        let mut buffer = full_scale_ramp.clone();
+        node.process_in_place(&mut buffer);
+        assert_eq!(output, buffer);
+        assert_eq!(node.box_clone().node_type(), "waveshaper");
+    }
+
+    #[rstest]
+    fn test_drive_is_clamped() {
+        assert_eq!(WaveshaperNode::new(ShapeFunction::Tanh, 0.0).drive(), MIN_DRIVE);
+    }
+}
@@ -0,0 +1,130 @@
+//! Stereo width processing node.
+//!
+//! This module widens or narrows the stereo image by scaling the side (difference)
+//! component of a mid/side representation while leaving the mid (sum) untouched.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::process::{AudioNode, StereoWidthNode};
+//!
+//! // Narrow the image to half its width
+//! let node = StereoWidthNode::new(0.5);
+//!
+//! let stereo = vec![0.5f32, 0.1, 0.4, -0.2];
+//! let output = node.process(&stereo);
+//! ```
+
+use super::node::AudioNode;
+
+/// Largest width accepted, higher values are clamped to keep the output bounded.
+pub const MAX_WIDTH: f32 = 2.0;
+
+/// An audio processing node that controls the width of interleaved stereo audio.
+///
+/// * `0.0` collapses the signal to mono (both channels carry (L + R) / 2)
+/// * `1.0` leaves the signal unchanged
+/// * values above `1.0` exaggerate the difference between the channels
+#[derive(Clone)]
+pub struct StereoWidthNode {
+    width: f32,
+}
+
+impl StereoWidthNode {
+    /// Creates a new stereo width node.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Stereo width, clamped to [0.0, `MAX_WIDTH`]
+    pub fn new(width: f32) -> Self {
+        Self { width: width.clamp(0.0, MAX_WIDTH) }
+    }
+
+    /// Returns the current width.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Sets a new width, clamped to [0.0, `MAX_WIDTH`].
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width.clamp(0.0, MAX_WIDTH);
+    }
+}
+
+impl AudioNode for StereoWidthNode {
+    fn process(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        self.process_in_place(&mut output);
+        output
+    }
+
+    fn process_in_place(&self, buffer: &mut [f32]) {
+        buffer.chunks_exact_mut(2).for_each(|frame| {
+            let mid = 0.5 * (frame[0] + frame[1]);
+            let side = 0.5 * (frame[0] - frame[1]) * self.width;
+            frame[0] = mid + side;
+            frame[1] = mid - side;
+        });
+    }
+
+    fn node_type(&self) -> &'static str {
+        "stereo_width"
+    }
+
+    fn input_channels(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn box_clone(&self) -> Box<dyn AudioNode> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn stereo() -> Vec<f32> {
+        vec![1.0, -0.5, 0.25, 0.75, -0.3, -0.9]
+    }
+
+    #[rstest]
+    fn test_zero_width_is_mono(stereo: Vec<f32>) {
+        let output = StereoWidthNode::new(0.0).process(&stereo);
+        for (out, inp) in output.chunks_exact(2).zip(stereo.chunks_exact(2)) {
+            assert_eq!(out[0], out[1]);
+            assert!((out[0] - 0.5 * (inp[0] + inp[1])).abs() < f32::EPSILON);
+        }
+    }
+
+    #[rstest]
+    fn test_unity_width_is_passthrough(stereo: Vec<f32>) {
+        let output = StereoWidthNode::new(1.0).process(&stereo);
+        for (a, b) in output.iter().zip(stereo.iter()) {
+            assert!((a - b).abs() <= f32::EPSILON);
+        }
+    }
+
+    #[rstest]
+    fn test_wider_increases_difference(stereo: Vec<f32>) {
+        let output = StereoWidthNode::new(1.5).process(&stereo);
+        for (out, inp) in output.chunks_exact(2).zip(stereo.chunks_exact(2)) {
+            assert!((out[0] - out[1]).abs() >= (inp[0] - inp[1]).abs());
+            assert!(((out[0] + out[1]) - (inp[0] + inp[1])).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    #[case(-1.0, 0.0)]
+    #[case(10.0, MAX_WIDTH)]
+    fn test_width_is_clamped(#[case] width: f32, #[case] expected: f32) {
+        assert_eq!(StereoWidthNode::new(width).width(), expected);
+    }
+
+    #[rstest]
+    fn test_node_type() {
+        assert_eq!(StereoWidthNode::new(1.0).box_clone().node_type(), "stereo_width");
+    }
+}
@@ -0,0 +1,165 @@
+//! Test signal generation.
+//!
+//! Generators for the synthetic signals commonly used in examples, tests and
+//! measurements. All functions return mono samples; the length is the duration
+//! multiplied by the sample rate, rounded to the nearest frame.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sonex::synth::{log_sweep, sine, silence, white_noise};
+//!
+//! let tone = sine(1000.0, 0.5, 1.0, 48000);
+//! let noise = white_noise(0.1, 1.0, 48000, 42);
+//! let sweep = log_sweep(20.0, 20000.0, 10.0, 48000);
+//! let gap = silence(0.5, 48000);
+//! println!("{} {} {} {}", tone.len(), noise.len(), sweep.len(), gap.len());
+//! ```
+
+use std::f64::consts::PI;
+
+/// Returns the number of samples of a signal of the given duration.
+fn sample_count(duration_sec: f32, sample_rate: u32) -> usize {
+    (duration_sec.max(0.0) as f64 * sample_rate as f64).round() as usize
+}
+
+/// Generates a sine wave starting at phase zero.
+///
+/// # Arguments
+///
+/// * `freq` - Frequency in Hz
+/// * `amplitude` - Peak amplitude (1.0 = full scale)
+/// * `duration_sec` - Duration in seconds
+/// * `sample_rate` - Sample rate in Hz
+pub fn sine(freq: f32, amplitude: f32, duration_sec: f32, sample_rate: u32) -> Vec<f32> {
+    let step = 2.0 * PI * freq as f64 / sample_rate as f64;
+    (0..sample_count(duration_sec, sample_rate))
+        .map(|i| amplitude * (step * i as f64).sin() as f32)
+        .collect()
+}
+
+/// Generates uniformly distributed white noise.
+///
+/// The same seed always gives the same samples, so tests using noise are
+/// reproducible. The RMS level of the noise is `amplitude / sqrt(3)`.
+///
+/// # Arguments
+///
+/// * `amplitude` - Peak amplitude, samples lie in `[-amplitude, amplitude]`
+/// * `duration_sec` - Duration in seconds
+/// * `sample_rate` - Sample rate in Hz
+/// * `seed` - Seed of the random number generator
+pub fn white_noise(amplitude: f32, duration_sec: f32, sample_rate: u32, seed: u64) -> Vec<f32> {
+    // xorshift64* generator; a zero state would only produce zeros
+    let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+    if state == 0 {
+        state = 1;
+    }
+    (0..sample_count(duration_sec, sample_rate))
+        .map(|_| {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            let random = state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40;
+            amplitude * (random as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+        })
+        .collect()
+}
+
+/// Generates a full-scale exponential sine sweep.
+///
+/// The frequency rises (or falls) exponentially from `f0` to `f1`, spending the
+/// same time in each octave. This is the sweep used for impulse response
+/// measurements.
+///
+/// # Arguments
+///
+/// * `f0` - Start frequency in Hz
+/// * `f1` - End frequency in Hz
+/// * `duration_sec` - Duration in seconds
+/// * `sample_rate` - Sample rate in Hz
+pub fn log_sweep(f0: f32, f1: f32, duration_sec: f32, sample_rate: u32) -> Vec<f32> {
+    let (f0, f1) = (f0 as f64, f1 as f64);
+    let duration = duration_sec as f64;
+    let ratio = (f1 / f0).ln();
+    if !ratio.is_finite() || ratio.abs() < 1e-12 {
+        return sine(f0 as f32, 1.0, duration_sec, sample_rate);
+    }
+
+    let scale = 2.0 * PI * f0 * duration / ratio;
+    (0..sample_count(duration_sec, sample_rate))
+        .map(|i| {
+            let t = i as f64 / sample_rate as f64;
+            (scale * ((t / duration * ratio).exp() - 1.0)).sin() as f32
+        })
+        .collect()
+}
+
+/// Generates digital silence.
+///
+/// # Arguments
+///
+/// * `duration_sec` - Duration in seconds
+/// * `sample_rate` - Sample rate in Hz
+pub fn silence(duration_sec: f32, sample_rate: u32) -> Vec<f32> {
+    vec![0.0; sample_count(duration_sec, sample_rate)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytic::spectrum::peak_frequency;
+    use crate::analytic::stats::rms_dbfs;
+    use rstest::*;
+
+    #[rstest]
+    #[case(100.0, 48000)]
+    #[case(1000.0, 44100)]
+    #[case(5000.0, 96000)]
+    fn test_sine_frequency(#[case] freq: f32, #[case] sample_rate: u32) {
+        let samples = sine(freq, 0.5, 1.0, sample_rate);
+        assert_eq!(samples.len(), sample_rate as usize);
+        // One second gives 1 Hz bins
+        assert!((peak_frequency(&samples, sample_rate) - freq).abs() <= 1.0);
+        let peak = samples.iter().fold(0.0f32, |p, s| p.max(s.abs()));
+        assert!((peak - 0.5).abs() < 1e-3);
+    }
+
+    #[rstest]
+    #[case(1.0)]
+    #[case(0.1)]
+    fn test_noise_rms(#[case] amplitude: f32) {
+        let samples = white_noise(amplitude, 2.0, 48000, 7);
+        assert!(samples.iter().all(|s| s.abs() <= amplitude));
+        let expected = 20.0 * (amplitude / 3f32.sqrt()).log10();
+        assert!((rms_dbfs(&samples) - expected).abs() < 0.1, "{} vs {}", rms_dbfs(&samples), expected);
+    }
+
+    #[rstest]
+    fn test_noise_is_reproducible() {
+        assert_eq!(white_noise(0.5, 0.1, 48000, 1), white_noise(0.5, 0.1, 48000, 1));
+        assert_ne!(white_noise(0.5, 0.1, 48000, 1), white_noise(0.5, 0.1, 48000, 2));
+    }
+
+    #[rstest]
+    fn test_sweep_frequency_rises() {
+        let sample_rate = 48000;
+        let samples = log_sweep(100.0, 10000.0, 2.0, sample_rate);
+        assert_eq!(samples.len(), 96000);
+
+        // Halfway through the sweep the frequency is the geometric mean, 1 kHz
+        let start = peak_frequency(&samples[..4800], sample_rate);
+        let middle = peak_frequency(&samples[48000 - 2400..48000 + 2400], sample_rate);
+        let end = peak_frequency(&samples[96000 - 4800..], sample_rate);
+        assert!(start < 300.0, "start {}", start);
+        assert!((middle - 1000.0).abs() < 100.0, "middle {}", middle);
+        assert!(end > 7000.0, "end {}", end);
+    }
+
+    #[rstest]
+    fn test_silence() {
+        let samples = silence(0.5, 44100);
+        assert_eq!(samples.len(), 22050);
+        assert!(samples.iter().all(|&s| s == 0.0));
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use rstest::*;
+use sonex::analytic::Meter;
+use sonex::io::AudioReader;
+
+const TEST_WAV: &str = "audio/sin_100Hz_-3dBFS_3s.wav";
+
+fn sonex(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sonex"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to run sonex")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// Parses the value of the "Integrated loudness: X LUFS" line.
+fn printed_lufs(stdout: &str) -> f64 {
+    let line = stdout.lines().find(|line| line.starts_with("Integrated loudness:")).unwrap();
+    line.split_whitespace().nth(2).unwrap().parse().unwrap()
+}
+
+fn file_lufs(path: &str) -> f64 {
+    let mut reader = AudioReader::new(path).unwrap();
+    let mut meter = Meter::new(reader.channels() as u32, reader.sample_rate()).unwrap();
+    while let Some(samples) = reader.read_packet().unwrap() {
+        meter.add_frames_f32(&samples).unwrap();
+    }
+    meter.lufs_integrated().unwrap()
+}
+
+fn temp_wav(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sonex_cli_{}_{}.wav", std::process::id(), name))
+}
+
+#[rstest]
+#[case("info")]
+#[case("loudness")]
+fn test_prints_lufs(#[case] command: &str) {
+    let stdout = stdout(&sonex(&[command, TEST_WAV]));
+    let expected = file_lufs(TEST_WAV);
+    assert!((printed_lufs(&stdout) - expected).abs() < 0.01, "{}", stdout);
+}
+
+#[rstest]
+fn test_info_prints_format() {
+    let stdout = stdout(&sonex(&["info", TEST_WAV]));
+    assert!(stdout.contains("Sample rate: 44100 Hz"), "{}", stdout);
+    assert!(stdout.contains("Channels: 1"), "{}", stdout);
+    assert!(stdout.contains("Duration: 3.000 s"), "{}", stdout);
+}
+
+#[rstest]
+fn test_gain_and_normalize_write_files() {
+    let gained = temp_wav("gain");
+    stdout(&sonex(&["gain", "--db", "-6", TEST_WAV, gained.to_str().unwrap()]));
+    let drop = file_lufs(TEST_WAV) - file_lufs(gained.to_str().unwrap());
+    assert!((drop - 6.0).abs() < 0.05, "dropped {} LU", drop);
+
+    let normalized = temp_wav("normalize");
+    let output = stdout(&sonex(&["normalize", "--lufs", "-14", TEST_WAV, normalized.to_str().unwrap()]));
+    assert!((printed_lufs(&output) + 14.0).abs() < 0.1, "{}", output);
+    assert!((file_lufs(normalized.to_str().unwrap()) + 14.0).abs() < 0.1);
+
+    std::fs::remove_file(gained).unwrap();
+    std::fs::remove_file(normalized).unwrap();
+}
+
+#[rstest]
+fn test_errors_are_reported() {
+    let output = sonex(&["loudness", "does_not_exist.wav"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error:"));
+
+    assert!(!sonex(&["gain", TEST_WAV]).status.success());
+}